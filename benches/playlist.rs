@@ -0,0 +1,134 @@
+//! Time the playlist and persistence hot paths with large playlists
+//!
+//! `cargo bench --bench playlist`
+use dashmap::DashMap;
+use rand::thread_rng;
+use serenity::all::{GuildId, UserId};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+use turto::{
+    models::{
+        data::Data, guild::data::GuildData, playlist::Playlist, playlist_item::PlaylistItem,
+        queue_item::QueueItemKind, youtube_playlist::YouTubePlaylist,
+    },
+    utils::queue::{add_to_playlist, QueueType},
+};
+
+const TRACKS: usize = 1000;
+const GUILDS: u64 = 100;
+/// Each benchmark runs for at least this long, so the fast ones are repeated enough
+const MIN_DURATION: Duration = Duration::from_secs(2);
+
+fn item(index: usize) -> PlaylistItem {
+    PlaylistItem {
+        url: format!("https://www.youtube.com/watch?v={:011}", index),
+        title: format!("Track {} - A title as long as the usual ones", index),
+        channel: "Some channel".to_owned(),
+        duration: Duration::from_secs(213),
+        thumbnail: format!("https://i.ytimg.com/vi/{:011}/hqdefault.jpg", index),
+        priority: 0,
+        requester: Some(UserId::new(index as u64 + 1)),
+    }
+}
+
+fn yt_playlist() -> YouTubePlaylist {
+    let mut yt_playlist = YouTubePlaylist::default();
+    yt_playlist.extend((0..TRACKS).map(item));
+    yt_playlist
+}
+
+fn guild_data() -> DashMap<GuildId, GuildData> {
+    let guilds = DashMap::<GuildId, GuildData>::new();
+    for guild_id in 1..=GUILDS {
+        guilds.entry(GuildId::new(guild_id)).or_default().playlist =
+            Playlist::from((0..TRACKS).map(item).collect::<Vec<_>>());
+    }
+    guilds
+}
+
+/// Run `routine` on a fresh input from `setup` repeatedly, only the routine is timed
+fn bench<T, R>(name: &str, mut setup: impl FnMut() -> T, mut routine: impl FnMut(T) -> R) {
+    let mut times = Vec::new();
+    let started = Instant::now();
+    while started.elapsed() < MIN_DURATION || times.len() < 10 {
+        let input = setup();
+        let start = Instant::now();
+        black_box(routine(black_box(input)));
+        times.push(start.elapsed());
+    }
+    times.sort_unstable();
+    let mean = times.iter().sum::<Duration>() / times.len() as u32;
+    println!(
+        "{:<40} mean {:>12?}  median {:>12?}  min {:>12?}  ({} runs)",
+        name,
+        mean,
+        times[times.len() / 2],
+        times[0],
+        times.len()
+    );
+}
+
+fn main() {
+    bench(
+        "import 1k tracks",
+        || (Data::default(), yt_playlist()),
+        |(data, yt_playlist)| {
+            add_to_playlist(
+                &data.guilds,
+                GuildId::new(1),
+                QueueItemKind::Playlist(yt_playlist),
+                0,
+                UserId::new(1),
+                QueueType::Back,
+            )
+        },
+    );
+    bench(
+        "import 1k tracks in front of 1k tracks",
+        || {
+            let data = Data::default();
+            data.guilds.entry(GuildId::new(1)).or_default().playlist =
+                Playlist::from((0..TRACKS).map(item).collect::<Vec<_>>());
+            (data, yt_playlist())
+        },
+        |(data, yt_playlist)| {
+            add_to_playlist(
+                &data.guilds,
+                GuildId::new(1),
+                QueueItemKind::Playlist(yt_playlist),
+                0,
+                UserId::new(1),
+                QueueType::Front,
+            )
+        },
+    );
+    bench(
+        "shuffle 1k tracks",
+        || Playlist::from((0..TRACKS).map(item).collect::<Vec<_>>()),
+        |mut playlist| {
+            playlist.shuffle(&mut thread_rng());
+            playlist
+        },
+    );
+
+    let guilds = guild_data();
+    let json = serde_json::to_string(&guilds).unwrap();
+    println!(
+        "guild data of {} guilds with {} tracks each is {} KiB",
+        GUILDS,
+        TRACKS,
+        json.len() / 1024
+    );
+    bench(
+        "serialize guild data",
+        || (),
+        |_| serde_json::to_string(&guilds).unwrap(),
+    );
+    bench(
+        "deserialize guild data",
+        || (),
+        |_| serde_json::from_str::<DashMap<GuildId, GuildData>>(&json).unwrap(),
+    );
+}
@@ -0,0 +1,20 @@
+use std::process::Command;
+
+/// Pass the commit and the target turto is built for to `/about`, the commit is left out when
+/// it's built without git, e.g. from a source archive
+fn main() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok());
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=TURTO_COMMIT={}", commit.trim());
+    }
+    if let Ok(target) = std::env::var("TARGET") {
+        println!("cargo:rustc-env=TURTO_TARGET={}", target);
+    }
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+}
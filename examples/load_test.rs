@@ -0,0 +1,27 @@
+//! Simulate many guilds playing at the same time and print the latencies
+//!
+//! `cargo run --release --features load-test --example load_test -- [guilds] [seconds] [commands per second]`
+use std::{env, time::Duration};
+use turto::load_test::{run_load_test, LoadTestConfig};
+
+#[tokio::main]
+async fn main() {
+    let mut args = env::args().skip(1).map(|arg| arg.parse::<u64>());
+    let mut config = LoadTestConfig::default();
+    if let Some(Ok(guilds)) = args.next() {
+        config.guilds = guilds;
+    }
+    if let Some(Ok(seconds)) = args.next() {
+        config.duration = Duration::from_secs(seconds);
+    }
+    if let Some(Ok(commands_per_second)) = args.next() {
+        config.commands_per_second = commands_per_second as u32;
+    }
+
+    println!(
+        "Simulating {} guilds for {:?}, {} commands per second in each",
+        config.guilds, config.duration, config.commands_per_second
+    );
+    let report = run_load_test(config).await;
+    println!("{}", report);
+}
@@ -1,26 +1,72 @@
+#[cfg(unix)]
+use crate::operator::OperatorState;
 use crate::{
     commands::create_commands,
     config::get_config,
-    handlers::{before::before, SerenityEventHandler},
-    models::{data::Data, guild::data::GuildData},
-    utils::json::{read_json, write_json},
+    handlers::{
+        after::after,
+        before::before,
+        error::on_error,
+        lifecycle::{guild_data_cleaner, record_playing, register_commands},
+        pre::pre,
+        track_hooks::{init_track_hooks, TrackHooks},
+        SerenityEventHandler,
+    },
+    http::HttpState,
+    models::{data::Data, guild::data::GuildData, playing::Playing, user_prefs::UserPrefs},
+    utils::{
+        cleanup::init_cleanup,
+        cluster::{init_cluster, lease_keeper},
+        idle::idle_tracker,
+        json::{read_json, write_json},
+        listening_log::init_listening_log,
+        metrics::init_uptime,
+        notify::init_notify,
+        player::init_player,
+        presence::presence_rotator,
+        redis::Redis,
+        report::init_report,
+        scheduler::session_scheduler,
+        script::init_scripts,
+        state::{export_state, import_state},
+        store::{init_store, load_guilds, store_syncer, sync_guilds},
+        temp_voice::temp_voice_cleaner,
+        tts::init_tts,
+        watchdog::watchdog,
+    },
 };
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use poise::{Framework, FrameworkOptions};
-use serenity::{all::ClientBuilder, model::prelude::GuildId, prelude::GatewayIntents, Client};
-use songbird::SerenityInit;
+use serde::{de::DeserializeOwned, Serialize};
+use serenity::{
+    all::ClientBuilder,
+    model::prelude::{GuildId, UserId},
+    prelude::GatewayIntents,
+    Client,
+};
+use songbird::{driver::DecodeMode, Config, SerenityInit, Songbird};
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
-use tokio::sync::oneshot::{self, Receiver, Sender};
+use tokio::sync::{
+    oneshot::{self, Receiver, Sender},
+    RwLock,
+};
 use tracing::{error, info, warn};
 
 pub struct Turto {
     client: Client,
     guild_data: Arc<DashMap<GuildId, GuildData>>,
     data_path: PathBuf,
+    blacklist: Arc<DashSet<GuildId>>,
+    blacklist_path: PathBuf,
+    users: Arc<DashMap<UserId, UserPrefs>>,
+    users_path: PathBuf,
+    playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+    voice_manager: Arc<Songbird>,
     auto_save_tx: Option<Sender<()>>,
 }
 
@@ -32,52 +78,218 @@ impl Turto {
         let options = FrameworkOptions {
             commands: create_commands(),
             command_check: Some(before),
+            pre_command: pre,
+            post_command: after,
+            on_error,
+            #[cfg(feature = "plugins")]
+            event_handler: |ctx, event, _, _| {
+                Box::pin(async move {
+                    for plugin in crate::plugin::plugins() {
+                        plugin.event(ctx, event).await;
+                    }
+                    Ok(())
+                })
+            },
             ..Default::default()
         };
 
         let data_path = data_path.into();
+        let blacklist_path = data_path.with_file_name("blacklist.json");
+        // the preferences of the users are not tied to any guild, so they're stored separately
+        let users_path = data_path.with_file_name("users.json");
 
-        let guild_data = Arc::new(load_data(&data_path));
+        let guild_data: Arc<DashMap<GuildId, GuildData>> = Arc::new(load_data(&data_path));
+        let blacklist: Arc<DashSet<GuildId>> = Arc::new(load_data(&blacklist_path));
+        let users: Arc<DashMap<UserId, UserPrefs>> = Arc::new(load_data(&users_path));
+        if let Some(address) = &get_config().redis_address {
+            init_store(Box::new(Redis::new(address)), guild_data.clone());
+            load_guilds().await;
+        }
+        let playing: Arc<RwLock<HashMap<GuildId, Playing>>> = Default::default();
+        let track_hooks =
+            TrackHooks::with_builtin(guild_data.clone(), users.clone(), playing.clone());
+        #[cfg(feature = "plugins")]
+        let track_hooks = track_hooks.extend(
+            crate::plugin::plugins()
+                .iter()
+                .flat_map(|plugin| plugin.track_hooks()),
+        );
+        init_track_hooks(track_hooks);
         let data = Data {
             guilds: guild_data.clone(),
+            blacklist: blacklist.clone(),
+            users: users.clone(),
+            playing: playing.clone(),
             ..Default::default()
         };
 
         let serenity_event_handler = SerenityEventHandler {
             playing: data.playing.clone(),
             guild_data: guild_data.clone(),
+            blacklist: blacklist.clone(),
+            users: users.clone(),
             voice_channel_counts: Default::default(),
+            greeting_cooldowns: Default::default(),
+            control_messages: data.control_messages.clone(),
+            voice_migrations: Default::default(),
         };
+        // kept to record the calls when shutting down, only who is speaking is needed from the
+        // received audio for `ducking`, so it isn't decrypted
+        let voice_manager =
+            Songbird::serenity_from_config(Config::default().decode_mode(DecodeMode::Pass));
+        let manager = voice_manager.clone();
         let framework = Framework::builder()
             .setup(|ctx, _ready, framework| {
                 Box::pin(async move {
-                    poise::builtins::register_globally(ctx, &framework.options().commands).await?;
+                    init_report(ctx.cache.clone(), ctx.http.clone());
+                    init_notify(ctx.http.clone(), data.users.clone());
+                    init_cleanup(ctx.http.clone());
+                    init_tts(ctx.http.clone());
+                    init_listening_log(ctx.http.clone());
+                    init_scripts(ctx.http.clone(), manager.clone());
+                    if let Some(address) = &get_config().etcd_address {
+                        init_cluster(address, instance_id(), get_config().cluster_lease);
+                        tokio::spawn(lease_keeper(manager.clone()));
+                    }
+                    if get_config().request_channels {
+                        init_player(
+                            ctx.http.clone(),
+                            data.guilds.clone(),
+                            data.playing.clone(),
+                            data.users.clone(),
+                        );
+                    }
+                    if get_config().redis_address.is_some() {
+                        tokio::spawn(store_syncer(manager.clone(), data.playing.clone()));
+                    }
+                    tokio::spawn(idle_tracker(
+                        ctx.cache.clone(),
+                        manager.clone(),
+                        data.guilds.clone(),
+                        data.playing.clone(),
+                    ));
+                    if get_config().watchdog_timeout > 0 {
+                        tokio::spawn(watchdog(
+                            ctx.http.clone(),
+                            manager.clone(),
+                            data.guilds.clone(),
+                            data.playing.clone(),
+                            data.users.clone(),
+                        ));
+                    }
+                    tokio::spawn(session_scheduler(
+                        ctx.http.clone(),
+                        manager.clone(),
+                        data.guilds.clone(),
+                        data.playing.clone(),
+                    ));
+                    tokio::spawn(temp_voice_cleaner(
+                        ctx.http.clone(),
+                        ctx.cache.clone(),
+                        manager.clone(),
+                        data.guilds.clone(),
+                        data.playing.clone(),
+                    ));
+                    if get_config().guild_data_retention > 0 {
+                        tokio::spawn(guild_data_cleaner(data.guilds.clone()));
+                    }
+                    // a single status is set once by the ready handler
+                    if get_config().activities.len() > 1 {
+                        tokio::spawn(presence_rotator(
+                            framework.shard_manager().clone(),
+                            ctx.cache.clone(),
+                            data.playing.clone(),
+                        ));
+                    }
+                    register_commands(ctx, &framework.options().commands).await?;
                     Ok(data)
                 })
             })
             .options(options)
             .build();
-        let intents = GatewayIntents::non_privileged();
+        let mut intents = GatewayIntents::non_privileged();
+        if get_config().request_channels {
+            // privileged, the messages in the request channels are empty without it
+            intents |= GatewayIntents::MESSAGE_CONTENT;
+        }
         let client = ClientBuilder::new(token, intents)
             .framework(framework)
             .event_handler(serenity_event_handler)
-            .register_songbird()
+            .register_songbird_with(voice_manager.clone())
             .await?;
         Ok(Self {
             client,
             guild_data,
             data_path,
+            blacklist,
+            blacklist_path,
+            users,
+            users_path,
+            playing,
+            voice_manager,
             auto_save_tx: None,
         })
     }
 
+    /// The state of the guilds for the endpoints of the HTTP server, which need the `token`
+    pub fn http_state(&self, token: Option<String>) -> HttpState {
+        HttpState {
+            cache: self.client.cache.clone(),
+            shard_manager: self.client.shard_manager.clone(),
+            guild_data: self.guild_data.clone(),
+            playing: self.playing.clone(),
+            voice_manager: self.voice_manager.clone(),
+            token,
+        }
+    }
+
+    /// The state of turto for the commands of the operator socket
+    #[cfg(unix)]
+    pub fn operator_state(&self) -> OperatorState {
+        OperatorState {
+            cache: self.client.cache.clone(),
+            guild_data: self.guild_data.clone(),
+            data_path: self.data_path.clone(),
+            blacklist: self.blacklist.clone(),
+            blacklist_path: self.blacklist_path.clone(),
+            users: self.users.clone(),
+            users_path: self.users_path.clone(),
+            playing: self.playing.clone(),
+            voice_manager: self.voice_manager.clone(),
+        }
+    }
+
+    /// Replace the data loaded from the files with the state exported by another instance, see
+    /// `--import-state`
+    pub fn import_state(&self, path: &Path) -> anyhow::Result<usize> {
+        import_state(path, &self.guild_data, &self.blacklist, &self.users)
+    }
+
+    /// Export the state for another instance to take over, see `--export-state`
+    pub async fn export_state(&self, path: &Path) -> anyhow::Result<usize> {
+        export_state(
+            path,
+            &self.guild_data,
+            &self.blacklist,
+            &self.users,
+            &self.voice_manager,
+            &self.playing,
+        )
+        .await
+    }
+
     pub async fn start(&mut self) -> Result<(), serenity::Error> {
+        init_uptime();
         if get_config().auto_save {
             let (tx, rx) = oneshot::channel::<()>();
             self.auto_save_tx = Some(tx);
             tokio::spawn(auto_save(
                 self.guild_data.clone(),
                 self.data_path.clone(),
+                self.blacklist.clone(),
+                self.blacklist_path.clone(),
+                self.users.clone(),
+                self.users_path.clone(),
                 rx,
             ));
         }
@@ -88,17 +300,21 @@ impl Turto {
         if let Some(tx) = self.auto_save_tx.take() {
             let _ = tx.send(());
         }
+        record_playing(&self.voice_manager, &self.guild_data, &self.playing).await;
+        sync_guilds(&self.voice_manager, &self.playing).await;
         self.client.shard_manager.shutdown_all().await;
-        save_data(self.guild_data.clone(), &self.data_path);
+        save_data(&*self.guild_data, &self.data_path);
+        save_data(&*self.blacklist, &self.blacklist_path);
+        save_data(&*self.users, &self.users_path);
     }
 }
 
-fn load_data(data_path: impl AsRef<Path>) -> DashMap<GuildId, GuildData> {
+fn load_data<T: DeserializeOwned + Default>(data_path: impl AsRef<Path>) -> T {
     match read_json(data_path.as_ref()) {
         Ok(data) => data,
         Err(err) => {
             warn!(
-                "Failed to load data from {}: {}, will initialize new guilds data",
+                "Failed to load data from {}: {}, will initialize new data",
                 data_path.as_ref().display(),
                 err
             );
@@ -110,6 +326,10 @@ fn load_data(data_path: impl AsRef<Path>) -> DashMap<GuildId, GuildData> {
 async fn auto_save(
     data: Arc<DashMap<GuildId, GuildData>>,
     data_path: PathBuf,
+    blacklist: Arc<DashSet<GuildId>>,
+    blacklist_path: PathBuf,
+    users: Arc<DashMap<UserId, UserPrefs>>,
+    users_path: PathBuf,
     mut rx: Receiver<()>,
 ) {
     let sleep_interval = Duration::from_secs(get_config().auto_save_interval);
@@ -119,7 +339,9 @@ async fn auto_save(
     loop {
         tokio::select! {
             _ = &mut sleep => {
-                save_data(data.clone(), &data_path);
+                save_data(&*data, &data_path);
+                save_data(&*blacklist, &blacklist_path);
+                save_data(&*users, &users_path);
                 let next = sleep.deadline() + sleep_interval;
                 sleep.as_mut().reset(next);
             },
@@ -128,9 +350,9 @@ async fn auto_save(
     }
 }
 
-fn save_data(data: Arc<DashMap<GuildId, GuildData>>, data_path: impl AsRef<Path>) {
+fn save_data<T: Serialize>(data: &T, data_path: impl AsRef<Path>) {
     let data_path = data_path.as_ref();
-    match write_json(&*data, data_path) {
+    match write_json(data, data_path) {
         Ok(bytes) => info!(
             "Data saved, {} bytes has been written to {}",
             bytes,
@@ -139,3 +361,13 @@ fn save_data(data: Arc<DashMap<GuildId, GuildData>>, data_path: impl AsRef<Path>
         Err(err) => error!("Failed to write data to {}: {:#}", data_path.display(), err),
     }
 }
+
+/// The name of the instance in the cluster, the host name is used unless it's set, so a restarted
+/// instance gets its guilds back
+fn instance_id() -> String {
+    get_config()
+        .instance_id
+        .clone()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_else(|| format!("{:016x}", rand::random::<u64>()))
+}
@@ -1,22 +1,73 @@
 use crate::{
     config::get_config,
     models::alias::{Context, Error},
-    utils::misc::sha256_now,
+    utils::{
+        get_locale,
+        metrics::uptime,
+        misc::{format_duration, sha256_now},
+    },
 };
 use serenity::{
+    all::{ApplicationId, Permissions},
     builder::{CreateEmbed, CreateEmbedAuthor},
     prelude::Mentionable,
 };
 
-#[poise::command(slash_command, guild_only)]
+/// What turto needs to join, play and reply with the reaction controls and the attachments
+const REQUIRED_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL
+    .union(Permissions::SEND_MESSAGES)
+    .union(Permissions::EMBED_LINKS)
+    .union(Permissions::ATTACH_FILES)
+    .union(Permissions::READ_MESSAGE_HISTORY)
+    .union(Permissions::ADD_REACTIONS)
+    .union(Permissions::CONNECT)
+    .union(Permissions::SPEAK);
+
+/// The version with the commit and the target it's built for, if they're known
+fn version() -> String {
+    let build = [option_env!("TURTO_COMMIT"), option_env!("TURTO_TARGET")]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    if build.is_empty() {
+        env!("CARGO_PKG_VERSION").to_owned()
+    } else {
+        format!("{} ({})", env!("CARGO_PKG_VERSION"), build.join(", "))
+    }
+}
+
+/// The link to invite turto with the slash commands and the permissions it needs
+fn invite_url(application_id: ApplicationId) -> String {
+    format!(
+        "https://discord.com/oauth2/authorize?client_id={}&scope={}&permissions={}",
+        application_id,
+        "bot+applications.commands",
+        REQUIRED_PERMISSIONS.bits()
+    )
+}
+
+#[poise::command(slash_command)]
 pub async fn about(ctx: Context<'_>) -> Result<(), Error> {
+    let shard = format!(
+        "{} of {}, {} ms",
+        ctx.serenity_context().shard_id.0 + 1,
+        ctx.cache().shard_count(),
+        ctx.ping().await.as_millis()
+    );
     let mut embed = CreateEmbed::new()
         .author(
             CreateEmbedAuthor::new("phoxwupsh")
                 .url("https://github.com/phoxwupsh")
                 .icon_url("https://avatars.githubusercontent.com/u/89735195"),
         )
-        .field("Version", env!("CARGO_PKG_VERSION"), true)
+        .field("Version", version(), true)
+        .field("Uptime", format_duration(uptime()), true)
+        .field("Shard", shard, true)
+        .field(
+            "Source",
+            format!("[GitHub]({})", env!("CARGO_PKG_REPOSITORY")),
+            true,
+        )
         .title(env!("CARGO_PKG_NAME"))
         .description(env!("CARGO_PKG_DESCRIPTION"))
         .url(env!("CARGO_PKG_REPOSITORY"))
@@ -24,14 +75,36 @@ pub async fn about(ctx: Context<'_>) -> Result<(), Error> {
             "https://opengraph.githubassets.com/{}/phoxwupsh/turto",
             sha256_now()
         ));
+    if let Some(application_id) = ctx.http().application_id() {
+        embed = embed.field(
+            "Invite",
+            format!("[Add to your server]({})", invite_url(application_id)),
+            true,
+        );
+    }
     if let Some(owner) = get_config().owner {
         embed = embed.field("Owner of this bot", owner.mention().to_string(), true);
     }
 
-    if let Some(locale) = ctx.locale() {
+    if let Some(locale) = get_locale(ctx) {
         println!("locale: {}", locale);
     }
 
     ctx.send(poise::CreateReply::default().embed(embed)).await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{invite_url, version};
+    use serenity::all::ApplicationId;
+
+    #[test]
+    fn test_about_info() {
+        let url = invite_url(ApplicationId::new(1234));
+        assert!(url.starts_with("https://discord.com/oauth2/authorize?client_id=1234&"));
+        assert!(url.contains("&scope=bot+applications.commands&"));
+        assert!(url.ends_with("&permissions=3263552"));
+        assert!(version().starts_with(env!("CARGO_PKG_VERSION")));
+    }
+}
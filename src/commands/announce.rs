@@ -0,0 +1,47 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::AnnounceDone},
+    models::alias::{ApplicationContext, Context, Error},
+    utils::{announce::broadcast, check_owner, get_locale},
+};
+use poise::execute_modal;
+use std::time::Duration;
+use tracing::info;
+
+/// Writing the release notes can take a while
+const ANNOUNCE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, poise::Modal)]
+#[name = "Announce"]
+struct AnnounceModal {
+    #[name = "Posted to every guild as it is"]
+    #[paragraph]
+    #[max_length = 2000]
+    content: String,
+}
+
+#[poise::command(slash_command)]
+pub async fn announce(ctx: ApplicationContext<'_>) -> Result<(), Error> {
+    if !check_owner(Context::from(ctx)).await? {
+        return Ok(());
+    }
+    let Some(modal) =
+        execute_modal::<_, _, AnnounceModal>(ctx, None, Some(ANNOUNCE_TIMEOUT)).await?
+    else {
+        return Ok(());
+    };
+    let ctx = Context::from(ctx);
+
+    let data = ctx.data();
+    let (sent, failed) = broadcast(ctx.http(), ctx.cache(), &data.guilds, &data.users, |_| {
+        modal.content.clone()
+    })
+    .await;
+    info!("Announced to {} guilds, {} failed", sent, failed);
+
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: AnnounceDone { sent, failed },
+    })
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::SetAnnouncements},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        toggle::ToggleOption,
+    },
+    utils::{check_admin, get_locale, guild::thread_parent},
+};
+use serenity::all::GuildChannel;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn announcements(
+    ctx: Context<'_>,
+    toggle: ToggleOption,
+    channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let enabled = match toggle {
+        ToggleOption::On => true,
+        ToggleOption::Off => false,
+    };
+    // the threads may be archived, so the announcements are posted to their channels
+    let channel = channel.map(|channel| thread_parent(&channel));
+    {
+        let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+        let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        guild_data.config.announcements = enabled;
+        guild_data.config.announcement_channel = channel;
+    }
+
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: SetAnnouncements { enabled, channel },
+    })
+    .await?;
+    Ok(())
+}
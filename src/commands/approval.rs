@@ -0,0 +1,36 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::SetApproval},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        toggle::ToggleOption,
+    },
+    utils::{check_admin, get_locale},
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn approval(ctx: Context<'_>, toggle: ToggleOption) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let toggle = match toggle {
+        ToggleOption::On => true,
+        ToggleOption::Off => false,
+    };
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    ctx.data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .config
+        .request_approval = toggle;
+    // the requests waiting already stay until a dj decides
+
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: SetApproval(toggle),
+    })
+    .await?;
+    Ok(())
+}
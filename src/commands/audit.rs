@@ -0,0 +1,72 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{EmptyAuditLog, SetAudit},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        toggle::ToggleOption,
+    },
+    utils::{check_admin, get_locale},
+};
+use poise::CreateReply;
+use serenity::{builder::CreateEmbed, prelude::Mentionable};
+
+// how many latest entries to display
+const DISPLAY_ENTRIES: usize = 20;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn audit(ctx: Context<'_>, toggle: Option<ToggleOption>) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let locale = get_locale(ctx);
+
+    if let Some(toggle) = toggle {
+        let toggle = match toggle {
+            ToggleOption::On => true,
+            ToggleOption::Off => false,
+        };
+        ctx.data().guilds.entry(guild_id).or_default().config.audit = toggle;
+        ctx.say(TurtoMessage {
+            locale,
+            kind: SetAudit(toggle),
+        })
+        .await?;
+        return Ok(());
+    }
+
+    let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    if guild_data.audit_log.is_empty() {
+        drop(guild_data);
+        ctx.say(TurtoMessage {
+            locale,
+            kind: EmptyAuditLog,
+        })
+        .await?;
+        return Ok(());
+    }
+    let description = guild_data
+        .audit_log
+        .latest()
+        .take(DISPLAY_ENTRIES)
+        .map(|entry| {
+            format!(
+                "<t:{}:f> {} `{}`",
+                entry.timestamp,
+                entry.user.mention(),
+                entry.command
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    drop(guild_data);
+
+    let embed = CreateEmbed::new().title("📋").description(description);
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
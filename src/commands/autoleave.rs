@@ -1,25 +1,68 @@
 use crate::{
-    messages::{TurtoMessage, TurtoMessageKind::SetAutoleave},
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{AutoleaveTimeouts, SetAutoleave},
+    },
     models::{
-        alias::{Context, Error}, autoleave::AutoleaveType
+        alias::{Context, Error},
+        autoleave::AutoleaveType,
+        error::TurtoError,
+        premium::Feature,
     },
+    utils::{get_locale, premium::check_feature},
 };
 
+/// The timeouts are capped at a day
+const MAX_TIMEOUT_MINUTES: u64 = 24 * 60;
+
 #[poise::command(slash_command, guild_only)]
-pub async fn autoleave(ctx: Context<'_> , toggle: AutoleaveType) -> Result<(), Error> {
+pub async fn autoleave(
+    ctx: Context<'_>,
+    toggle: AutoleaveType,
+    #[min = 0]
+    #[max = 1440]
+    idle_minutes: Option<u64>,
+    #[min = 0]
+    #[max = 1440]
+    alone_minutes: Option<u64>,
+) -> Result<(), Error> {
+    // staying in the voice channel for good can be premium
+    if toggle == AutoleaveType::Off && !check_feature(ctx, Feature::AlwaysOn).await? {
+        return Ok(());
+    }
     let mut guild_data = ctx
         .data()
         .guilds
-        .entry(ctx.guild_id().unwrap())
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
         .or_default();
     guild_data.config.auto_leave = toggle;
+    if let Some(idle_minutes) = idle_minutes {
+        guild_data.config.idle_timeout = idle_minutes.min(MAX_TIMEOUT_MINUTES);
+    }
+    if let Some(alone_minutes) = alone_minutes {
+        guild_data.config.alone_timeout = alone_minutes.min(MAX_TIMEOUT_MINUTES);
+    }
+    let idle = guild_data.config.idle_timeout;
+    let alone = guild_data.config.alone_timeout;
     drop(guild_data);
 
-    ctx.say(TurtoMessage {
-        locale: ctx.locale(),
+    let locale = get_locale(ctx);
+    let mut response = TurtoMessage {
+        locale,
         kind: SetAutoleave(toggle),
-    })
-    .await?;
+    }
+    .to_string();
+    if toggle != AutoleaveType::Off {
+        response.push('\n');
+        response.push_str(
+            &TurtoMessage {
+                locale,
+                kind: AutoleaveTimeouts { idle, alone },
+            }
+            .to_string(),
+        );
+    }
+    ctx.say(response).await?;
 
     Ok(())
-}
\ No newline at end of file
+}
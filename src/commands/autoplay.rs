@@ -0,0 +1,34 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::SetAutoplay},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        premium::Feature,
+        toggle::ToggleOption,
+    },
+    utils::{get_locale, premium::check_feature},
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn autoplay(ctx: Context<'_>, toggle: ToggleOption) -> Result<(), Error> {
+    if !check_feature(ctx, Feature::Autoplay).await? {
+        return Ok(());
+    }
+
+    let toggle = match toggle {
+        ToggleOption::On => true,
+        ToggleOption::Off => false,
+    };
+    ctx.data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default()
+        .config
+        .autoplay = toggle;
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: SetAutoplay(toggle),
+    })
+    .await?;
+    Ok(())
+}
@@ -1,35 +1,20 @@
 use crate::{
-    config::get_config,
-    messages::{
-        TurtoMessage,
-        TurtoMessageKind::{AdministratorOnly, Ban},
+    messages::{TurtoMessage, TurtoMessageKind::Ban},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
     },
-    models::alias::{Context, Error},
+    utils::{get_locale, is_admin},
 };
 use serenity::all::UserId;
 
 #[poise::command(slash_command, guild_only)]
 pub async fn ban(ctx: Context<'_>, user: UserId) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
-    let user_id = ctx.author().id;
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let locale = get_locale(ctx);
 
-    // Since this is a guild only command interaction
-    let is_admin = ctx
-        .author_member()
-        .await
-        .unwrap()
-        .permissions
-        .unwrap()
-        .administrator();
-    let locale = ctx.locale();
-
-    if !(is_admin || get_config().is_owner(&user_id)) {
-        ctx.say(TurtoMessage {
-            locale,
-            kind: AdministratorOnly,
-        })
-        .await?;
-        return Ok(());
+    if !is_admin(ctx).await {
+        return Err(TurtoError::AdministratorOnly.into());
     }
 
     let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
@@ -0,0 +1,31 @@
+use crate::{
+    messages::TurtoMessage,
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{bookmark::bookmark_playing, get_locale},
+};
+use poise::CreateReply;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn bookmark(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let data = ctx.data();
+    let kind = bookmark_playing(
+        ctx.http(),
+        &data.playing,
+        &data.users,
+        ctx.guild_id().ok_or(TurtoError::NotInGuild)?,
+        ctx.author().id,
+        locale,
+    )
+    .await;
+    ctx.send(
+        CreateReply::default()
+            .content(TurtoMessage { locale, kind })
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::SetMessageCleanup},
+    models::{
+        alias::{Context, Error},
+        cleanup::MessageCleanup,
+        error::TurtoError,
+    },
+    utils::get_locale,
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn cleanup(ctx: Context<'_>, policy: MessageCleanup) -> Result<(), Error> {
+    ctx.data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default()
+        .config
+        .message_cleanup = policy;
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: SetMessageCleanup(policy),
+    })
+    .await?;
+    Ok(())
+}
@@ -1,16 +1,17 @@
 use crate::{
-    messages::{
-        TurtoMessage,
-        TurtoMessageKind::RemoveAll,
+    messages::{TurtoMessage, TurtoMessageKind::RemoveAll},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
     },
-    models::alias::{Context, Error},
+    utils::get_locale,
 };
 
 #[poise::command(slash_command, prefix_command, guild_only)]
 pub async fn clear(ctx: Context<'_>) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
     let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
-    let locale = ctx.locale();
+    let locale = get_locale(ctx);
 
     guild_data.playlist.clear();
     drop(guild_data);
@@ -22,4 +23,4 @@ pub async fn clear(ctx: Context<'_>) -> Result<(), Error> {
     .await?;
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,80 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{ClipFailed, ClipReady},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{
+        clip::{clip_range, extract_clip},
+        get_locale,
+        misc::format_duration,
+        say,
+    },
+};
+use poise::CreateReply;
+use serenity::builder::CreateAttachment;
+use tracing::warn;
+
+const DEFAULT_CLIP_SECONDS: u64 = 30;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn clip(
+    ctx: Context<'_>,
+    #[min = 5]
+    #[max = 60]
+    seconds: Option<u64>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let (track_handle, url, title) = {
+        let playing_map = ctx.data().playing.read().await;
+        let playing = playing_map.get(&guild_id).ok_or(TurtoError::NotPlaying)?;
+        (
+            playing.track_handle.clone(),
+            playing.url.clone(),
+            playing.metadata.title.clone().unwrap_or_default(),
+        )
+    };
+    let position = track_handle
+        .get_info()
+        .await
+        .map_err(|_| TurtoError::NotPlaying)?
+        .position;
+    let (start, length) = clip_range(position, seconds.unwrap_or(DEFAULT_CLIP_SECONDS));
+
+    let locale = get_locale(ctx);
+    // yt-dlp and ffmpeg take longer than an interaction can wait
+    ctx.defer().await?;
+    let clip = match extract_clip(&url, start, length).await {
+        Ok(clip) => clip,
+        Err(err) => {
+            warn!("Failed to clip {}: {}", url, err);
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: ClipFailed,
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let message = TurtoMessage {
+        locale,
+        kind: ClipReady {
+            title: &title,
+            from: &format_duration(start),
+            to: &format_duration(position),
+        },
+    };
+    ctx.send(
+        CreateReply::default()
+            .content(message.to_string())
+            .attachment(CreateAttachment::bytes(clip, "clip.ogg")),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::{
+    config::{check::check_files, CONFIG_PATH, HELP_PATH, TEMPLATES_PATH},
+    messages::{TurtoMessage, TurtoMessageKind::ConfigCheckPassed},
+    models::alias::{Context, Error},
+    utils::{check_owner, get_locale},
+};
+use poise::CreateReply;
+use serenity::builder::CreateAttachment;
+
+#[poise::command(slash_command)]
+pub async fn configcheck(ctx: Context<'_>) -> Result<(), Error> {
+    if !check_owner(ctx).await? {
+        return Ok(());
+    }
+
+    let diagnostics = check_files(CONFIG_PATH, HELP_PATH, TEMPLATES_PATH);
+    let response = if diagnostics.is_empty() {
+        CreateReply::default().content(
+            TurtoMessage {
+                locale: get_locale(ctx),
+                kind: ConfigCheckPassed,
+            }
+            .to_string(),
+        )
+    } else {
+        let content = diagnostics
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let code_block = format!("```\n{}\n```", content);
+        // discord text message has a length limitation of 2000 unicode chars
+        if code_block.chars().count() <= 2000 {
+            CreateReply::default().content(code_block)
+        } else {
+            CreateReply::default().attachment(CreateAttachment::bytes(content, "configcheck.txt"))
+        }
+    };
+    ctx.send(response.ephemeral(true)).await?;
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::InvalidGuildId},
+    models::alias::{Context, Error},
+    utils::{check_owner, get_locale, guild::parse_guild_id, say},
+};
+use dashmap::try_result::TryResult;
+use poise::CreateReply;
+use serde_json::{json, Value};
+use serenity::{all::GuildId, builder::CreateAttachment};
+
+#[poise::command(slash_command, subcommands("guild"))]
+pub async fn debug(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
+// Dump the in-memory state of a guild as a json attachment, for diagnosing stuck players
+#[poise::command(slash_command)]
+pub async fn guild(ctx: Context<'_>, id: String) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+
+    if !check_owner(ctx).await? {
+        return Ok(());
+    }
+
+    let Some(guild_id) = parse_guild_id(&id) else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: InvalidGuildId { id: &id },
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    ctx.defer_ephemeral().await?;
+    let dump = dump_guild_state(ctx, guild_id).await;
+    let attachment = CreateAttachment::bytes(
+        serde_json::to_vec_pretty(&dump)?,
+        format!("guild_{}.json", guild_id),
+    );
+    ctx.send(
+        CreateReply::default()
+            .attachment(attachment)
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn dump_guild_state(ctx: Context<'_>, guild_id: GuildId) -> Value {
+    let data = ctx.data();
+
+    // use the non-blocking variants here, if the state is locked by a stuck task we
+    // want to know it instead of getting stuck as well
+    let (guild_data, guild_data_locked) = match data.guilds.try_get(&guild_id) {
+        TryResult::Present(guild_data) => (
+            json!({
+                "config": &guild_data.config,
+                "playlist_length": guild_data.playlist.len(),
+            }),
+            false,
+        ),
+        TryResult::Absent => (Value::Null, false),
+        TryResult::Locked => (Value::Null, true),
+    };
+
+    let (playing, playing_locked) = match data.playing.try_read() {
+        Ok(playing_map) => match playing_map.get(&guild_id) {
+            Some(playing) => {
+                let track_state = match playing.track_handle.get_info().await {
+                    Ok(state) => json!({
+                        "playing": format!("{:?}", state.playing),
+                        "volume": state.volume,
+                        "position": state.position.as_secs_f64(),
+                        "play_time": state.play_time.as_secs_f64(),
+                        "loops": format!("{:?}", state.loops),
+                        "ready": format!("{:?}", state.ready),
+                    }),
+                    Err(err) => json!({ "error": err.to_string() }),
+                };
+                let track = json!({
+                    "uuid": playing.track_handle.uuid().to_string(),
+                    "title": playing.metadata.title,
+                    "url": playing.metadata.source_url,
+                    "duration": playing.metadata.duration.map(|duration| duration.as_secs_f64()),
+                    "state": track_state,
+                });
+                (track, false)
+            }
+            None => (Value::Null, false),
+        },
+        Err(_) => (Value::Null, true),
+    };
+
+    let (voice, voice_locked) = match songbird::get(ctx.serenity_context())
+        .await
+        .and_then(|manager| manager.get(guild_id))
+    {
+        Some(call) => match call.try_lock() {
+            Ok(call) => {
+                let connection = call.current_connection().map(|connection| {
+                    json!({
+                        "channel_id": connection.channel_id.map(|channel| channel.0.get()),
+                        "endpoint": connection.endpoint,
+                    })
+                });
+                let voice = json!({
+                    "channel_id": call.current_channel().map(|channel| channel.0.get()),
+                    "connection": connection,
+                    "deaf": call.is_deaf(),
+                    "mute": call.is_mute(),
+                });
+                (voice, false)
+            }
+            Err(_) => (Value::Null, true),
+        },
+        None => (Value::Null, false),
+    };
+
+    json!({
+        "guild_id": guild_id.get(),
+        "guild_data": guild_data,
+        "playing": playing,
+        "voice": voice,
+        "locks_held": {
+            "guild_data": guild_data_locked,
+            "playing": playing_locked,
+            "call": voice_locked,
+        },
+    })
+}
@@ -0,0 +1,55 @@
+use crate::{
+    handlers::ducking::start_ducking,
+    messages::{TurtoMessage, TurtoMessageKind::SetDucking},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        toggle::ToggleOption,
+    },
+    utils::{check_admin, get_locale},
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn ducking(ctx: Context<'_>, toggle: ToggleOption) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let toggle = match toggle {
+        ToggleOption::On => true,
+        ToggleOption::Off => false,
+    };
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    ctx.data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .config
+        .ducking = toggle;
+
+    // the track playing is ducked from now on, the handler stops by itself when it's turned off
+    let playing = match ctx.data().playing.read().await.get(&guild_id) {
+        Some(playing) if toggle => Some((playing.track_handle.clone(), playing.url.clone())),
+        _ => None,
+    };
+    let call = match songbird::get(ctx.serenity_context()).await {
+        Some(manager) => manager.get(guild_id),
+        None => None,
+    };
+    if let (Some((track_handle, url)), Some(call)) = (playing, call) {
+        start_ducking(
+            &mut *call.lock().await,
+            ctx.data().guilds.clone(),
+            guild_id,
+            track_handle,
+            url,
+        );
+    }
+
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: SetDucking(toggle),
+    })
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use super::playlist::autocomplete_saved_playlist;
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            EventCreateFailed, EventCreated, InvalidEventStart, SavedPlaylistNotFound,
+        },
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        guild::scheduled_session::{parse_start, ScheduledSession},
+    },
+    utils::{check_admin, get_locale, say},
+};
+use chrono::Local;
+use serenity::{
+    all::{ChannelType, GuildChannel, ScheduledEventType, Timestamp},
+    builder::CreateScheduledEvent,
+};
+use tracing::warn;
+
+#[poise::command(slash_command, guild_only, subcommands("create"))]
+pub async fn event(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn create(
+    ctx: Context<'_>,
+    name: String,
+    #[autocomplete = "autocomplete_saved_playlist"] playlist: String,
+    #[channel_types("Voice", "Stage")] channel: GuildChannel,
+    start: String,
+) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let saved = ctx
+        .data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .saved_playlists
+        .get(&playlist)
+        .map(|saved| saved.items.len());
+    let Some(tracks) = saved else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: SavedPlaylistNotFound { name: &playlist },
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+    let Some(start_at) = parse_start(&start, Local::now()) else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: InvalidEventStart { start: &start },
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let kind = match channel.kind {
+        ChannelType::Stage => ScheduledEventType::StageInstance,
+        _ => ScheduledEventType::Voice,
+    };
+    let builder = CreateScheduledEvent::new(
+        kind,
+        &name,
+        Timestamp::from_unix_timestamp(start_at.timestamp())?,
+    )
+    .channel_id(channel.id)
+    .description(format!("🎶 {} ({})", playlist, tracks));
+    let event = match guild_id.create_scheduled_event(ctx, builder).await {
+        Ok(event) => event,
+        Err(err) => {
+            // turto needs the permission to manage events
+            warn!("Failed to create an event in guild {}: {}", guild_id, err);
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: EventCreateFailed,
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    ctx.data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .scheduled_sessions
+        .insert(
+            event.id,
+            ScheduledSession {
+                playlist: playlist.clone(),
+                channel: channel.id,
+                start: start_at.timestamp(),
+                creator: ctx.author().id,
+            },
+        );
+    ctx.say(TurtoMessage {
+        locale,
+        kind: EventCreated {
+            name: &name,
+            playlist: &playlist,
+            start: start_at.timestamp(),
+        },
+    })
+    .await?;
+    Ok(())
+}
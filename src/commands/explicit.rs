@@ -0,0 +1,43 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::SetExplicitFilter},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        toggle::ToggleOption,
+    },
+    utils::{check_admin, get_locale},
+};
+use serenity::all::Role;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn explicit(
+    ctx: Context<'_>,
+    toggle: ToggleOption,
+    bypass_role: Option<Role>,
+) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let enabled = match toggle {
+        ToggleOption::On => true,
+        ToggleOption::Off => false,
+    };
+    let bypass_role = bypass_role.map(|role| role.id);
+    {
+        let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+        let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        guild_data.config.explicit_filter = enabled;
+        guild_data.config.explicit_bypass_role = bypass_role;
+    }
+
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: SetExplicitFilter {
+            enabled,
+            bypass_role,
+        },
+    })
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{FilterOff, SetFilter},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        guild::filter::AudioFilter,
+        premium::Feature,
+    },
+    utils::{get_locale, premium::check_feature},
+};
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("karaoke", "vocals", "eight_d", "off")
+)]
+pub async fn filter(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn karaoke(
+    ctx: Context<'_>,
+    #[min = 0.0]
+    #[max = 1.0]
+    level: Option<f64>,
+) -> Result<(), Error> {
+    let level = level.unwrap_or(1.0);
+    set_filter(ctx, "karaoke", AudioFilter::Karaoke { level }).await
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn vocals(ctx: Context<'_>) -> Result<(), Error> {
+    set_filter(ctx, "vocals", AudioFilter::Vocals).await
+}
+
+#[poise::command(slash_command, guild_only, rename = "8d")]
+pub async fn eight_d(
+    ctx: Context<'_>,
+    #[min = 0.05]
+    #[max = 1.0]
+    speed: Option<f64>,
+) -> Result<(), Error> {
+    let speed = speed.unwrap_or(0.125);
+    set_filter(ctx, "8d", AudioFilter::EightD { speed }).await
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn off(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default()
+        .config
+        .filter = None;
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: FilterOff,
+    })
+    .await?;
+    Ok(())
+}
+
+async fn set_filter(ctx: Context<'_>, name: &str, filter: AudioFilter) -> Result<(), Error> {
+    if !check_feature(ctx, Feature::Filters).await? {
+        return Ok(());
+    }
+
+    ctx.data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default()
+        .config
+        .filter = Some(filter);
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: SetFilter { name },
+    })
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            EmptyGreetings, GreetingAdded, GreetingLimitReached, GreetingRemoved,
+            GreetingTargetRequired, InvalidGreetingIndex,
+        },
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        guild::greeting::{Greeting, GreetingEvent, GreetingTarget, GREETING_LIMIT},
+    },
+    utils::{check_admin, get_locale, say},
+};
+use poise::ChoiceParameter;
+use serenity::{
+    all::{Role, User},
+    prelude::Mentionable,
+};
+
+#[poise::command(slash_command, guild_only, subcommands("add", "remove", "list"))]
+pub async fn greeting(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn add(
+    ctx: Context<'_>,
+    event: GreetingEvent,
+    url: String,
+    user: Option<User>,
+    role: Option<Role>,
+) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    let target = match (user, role) {
+        (Some(user), None) => GreetingTarget::User(user.id),
+        (None, Some(role)) => GreetingTarget::Role(role.id),
+        _ => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: GreetingTargetRequired,
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let mut guild_data = ctx
+        .data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default();
+    let greetings = &mut guild_data.config.greetings;
+    // a target has at most one sound for each event
+    greetings.retain(|greeting| greeting.target != target || greeting.event != event);
+    let kind = if greetings.len() >= GREETING_LIMIT {
+        GreetingLimitReached {
+            limit: GREETING_LIMIT,
+        }
+    } else {
+        greetings.push(Greeting { target, event, url });
+        GreetingAdded
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn remove(ctx: Context<'_>, #[min = 1] index: usize) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    let mut guild_data = ctx
+        .data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default();
+    let greetings = &mut guild_data.config.greetings;
+    let kind = if (1..=greetings.len()).contains(&index) {
+        greetings.remove(index - 1);
+        GreetingRemoved
+    } else {
+        InvalidGreetingIndex {
+            length: greetings.len(),
+        }
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let guild_data = ctx
+        .data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default();
+    let lines = guild_data
+        .config
+        .greetings
+        .iter()
+        .enumerate()
+        .map(|(index, greeting)| {
+            let target = match greeting.target {
+                GreetingTarget::User(user_id) => user_id.mention().to_string(),
+                GreetingTarget::Role(role_id) => role_id.mention().to_string(),
+            };
+            format!(
+                "`{}.` {} `{}` <{}>",
+                index + 1,
+                target,
+                greeting.event.name(),
+                greeting.url
+            )
+        })
+        .collect::<Vec<_>>();
+    drop(guild_data);
+
+    let response = if lines.is_empty() {
+        TurtoMessage {
+            locale: get_locale(ctx),
+            kind: EmptyGreetings,
+        }
+        .to_string()
+    } else {
+        lines.join("\n")
+    };
+    ctx.say(response).await?;
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            GuildBlacklist, GuildLeave, GuildUnblacklist, InvalidGuildId, NoGuilds,
+        },
+    },
+    models::alias::{Context, Error},
+    utils::{check_owner, get_locale, guild::parse_guild_id, say},
+};
+use poise::CreateReply;
+use serenity::builder::CreateAttachment;
+use tracing::warn;
+
+#[poise::command(
+    slash_command,
+    subcommands("list", "leave", "blacklist", "unblacklist")
+)]
+pub async fn guilds(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
+#[poise::command(slash_command)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    if !check_owner(ctx).await? {
+        return Ok(());
+    }
+
+    let mut guild_ids = ctx.cache().guilds();
+    if guild_ids.is_empty() {
+        ctx.say(TurtoMessage {
+            locale: get_locale(ctx),
+            kind: NoGuilds,
+        })
+        .await?;
+        return Ok(());
+    }
+    guild_ids.sort_unstable();
+
+    let blacklist = &ctx.data().blacklist;
+    let lines = guild_ids
+        .into_iter()
+        .map(|guild_id| {
+            let (name, member_count) = guild_id
+                .to_guild_cached(ctx.cache())
+                .map(|guild| (guild.name.clone(), guild.member_count))
+                .unwrap_or_default();
+            let mark = if blacklist.contains(&guild_id) {
+                "⛔ "
+            } else {
+                ""
+            };
+            format!("{}`{}` {} ({})", mark, guild_id, name, member_count)
+        })
+        .collect::<Vec<_>>();
+    let content = lines.join("\n");
+
+    // discord text message has a length limitation of 2000 unicode chars
+    let response = if content.chars().count() <= 2000 {
+        CreateReply::default().content(content)
+    } else {
+        CreateReply::default().attachment(CreateAttachment::bytes(content, "guilds.txt"))
+    };
+    ctx.send(response.ephemeral(true)).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command)]
+pub async fn leave(ctx: Context<'_>, id: String) -> Result<(), Error> {
+    if !check_owner(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    let Some(guild_id) = parse_guild_id(&id) else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: InvalidGuildId { id: &id },
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let success = match guild_id.leave(ctx).await {
+        Ok(_) => true,
+        Err(err) => {
+            warn!("Failed to leave guild {}: {}", guild_id, err);
+            false
+        }
+    };
+    ctx.say(TurtoMessage {
+        locale,
+        kind: GuildLeave { success, guild_id },
+    })
+    .await?;
+    Ok(())
+}
+
+#[poise::command(slash_command)]
+pub async fn blacklist(ctx: Context<'_>, id: String) -> Result<(), Error> {
+    if !check_owner(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    let Some(guild_id) = parse_guild_id(&id) else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: InvalidGuildId { id: &id },
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let success = ctx.data().blacklist.insert(guild_id);
+    // leave right away if the bot is in that guild
+    if ctx.cache().guild(guild_id).is_some() {
+        if let Err(err) = guild_id.leave(ctx).await {
+            warn!("Failed to leave blacklisted guild {}: {}", guild_id, err);
+        }
+    }
+
+    ctx.say(TurtoMessage {
+        locale,
+        kind: GuildBlacklist { success, guild_id },
+    })
+    .await?;
+    Ok(())
+}
+
+#[poise::command(slash_command)]
+pub async fn unblacklist(ctx: Context<'_>, id: String) -> Result<(), Error> {
+    if !check_owner(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    let Some(guild_id) = parse_guild_id(&id) else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: InvalidGuildId { id: &id },
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let success = ctx.data().blacklist.remove(&guild_id).is_some();
+    ctx.say(TurtoMessage {
+        locale,
+        kind: GuildUnblacklist { success, guild_id },
+    })
+    .await?;
+    Ok(())
+}
@@ -1,71 +1,230 @@
 use crate::{
-    config::help::get_locale_help,
-    models::alias::{Context, Error},
+    config::help::{get_command_help, get_help_categories, get_locale_help},
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            CommandNotFound, HelpAliases, HelpExamples, HelpPage, HelpParameters, HelpPermissions,
+            HelpUsage,
+        },
+    },
+    models::{
+        alias::{Context, Error},
+        help::CommandHelp,
+    },
+    utils::{get_locale, say},
 };
-use poise::{ChoiceParameter, CreateReply};
-use serenity::builder::CreateEmbed;
+use poise::CreateReply;
+use serenity::{
+    builder::{
+        CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+        CreateInteractionResponseMessage,
+    },
+    collector::ComponentInteractionCollector,
+};
+use std::time::Duration;
 
-#[derive(ChoiceParameter)]
-enum HelpOption {
-    #[name = "about"]
-    About,
-    #[name = "autoleave"]
-    Autoleave,
-    #[name = "ban"]
-    Ban,
-    #[name = "join"]
-    Join,
-    #[name = "leave"]
-    Leave,
-    #[name = "pause"]
-    Pause,
-    #[name = "play"]
-    Play,
-    #[name = "playlist"]
-    Playlist,
-    #[name = "playwhat"]
-    Playwhat,
-    #[name = "queue"]
-    Queue,
-    #[name = "remove"]
-    Remove,
-    #[name = "repeat"]
-    Repeat,
-    #[name = "seek"]
-    Seek,
-    #[name = "shuffle"]
-    Shuffle,
-    #[name = "skip"]
-    Skip,
-    #[name = "stop"]
-    Stop,
-    #[name = "unban"]
-    Unban,
-    #[name = "volume"]
-    Volume,
-}
+#[poise::command(slash_command)]
+pub async fn help(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_command"] command: Option<String>,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+
+    let Some(command_name) = command else {
+        return help_pages(ctx).await;
+    };
 
-#[poise::command(slash_command, guild_only)]
-pub async fn help(ctx: Context<'_>, command: HelpOption) -> Result<(), Error> {
-    let helps = get_locale_help(ctx.locale());
-    let command_name = command.name();
+    let Some(target_help) = get_command_help(locale, &command_name) else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: CommandNotFound {
+                    command_name: &command_name,
+                },
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let embed = command_help_embed(locale, &command_name, target_help);
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
 
-    let target_help = helps
-        .get(command_name)
-        .unwrap_or(get_locale_help(None).get(command_name).unwrap()); // fallback to default language
+async fn autocomplete_command<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let mut command_names = get_locale_help(get_locale(ctx))
+        .keys()
+        .chain(get_locale_help(None).keys())
+        .filter(move |command_name| command_name.starts_with(partial))
+        .cloned()
+        .collect::<Vec<_>>();
+    command_names.sort_unstable();
+    command_names.dedup();
+    command_names.into_iter().take(25) // discord allows at most 25 choices
+}
 
+fn command_help_embed(locale: Option<&str>, command_name: &str, help: &CommandHelp) -> CreateEmbed {
+    let description = if help.description.is_empty() {
+        &help.short_description
+    } else {
+        &help.description
+    };
     let mut embed = CreateEmbed::new()
         .title(command_name)
-        .description(&target_help.description);
+        .description(description);
 
-    if let Some(parameters) = &target_help.parameters {
-        for (name, description) in parameters.iter() {
-            embed = embed.field(name, description, false);
-        }
+    if let Some(usage) = &help.usage {
+        embed = embed.field(
+            TurtoMessage {
+                locale,
+                kind: HelpUsage,
+            },
+            format!("`{}`", usage),
+            false,
+        );
+    }
+    if let Some(parameters) = &help.parameters {
+        let mut parameters = parameters.iter().collect::<Vec<_>>();
+        parameters.sort_unstable_by_key(|(name, _)| name.as_str());
+        let parameters_str = parameters
+            .into_iter()
+            .map(|(name, description)| format!("`{}`: {}", name, description))
+            .collect::<Vec<_>>()
+            .join("\n");
+        embed = embed.field(
+            TurtoMessage {
+                locale,
+                kind: HelpParameters,
+            },
+            parameters_str,
+            false,
+        );
+    }
+    if !help.examples.is_empty() {
+        let examples_str = help
+            .examples
+            .iter()
+            .map(|example| format!("`{}`", example))
+            .collect::<Vec<_>>()
+            .join("\n");
+        embed = embed.field(
+            TurtoMessage {
+                locale,
+                kind: HelpExamples,
+            },
+            examples_str,
+            false,
+        );
+    }
+    if !help.aliases.is_empty() {
+        embed = embed.field(
+            TurtoMessage {
+                locale,
+                kind: HelpAliases,
+            },
+            help.aliases.join(", "),
+            true,
+        );
+    }
+    if let Some(permissions) = &help.permissions {
+        embed = embed.field(
+            TurtoMessage {
+                locale,
+                kind: HelpPermissions,
+            },
+            permissions,
+            true,
+        );
     }
+    embed
+}
+
+fn help_page_embed(locale: Option<&str>, page: usize) -> (CreateEmbed, usize) {
+    let categories = get_help_categories(locale);
+    let total = categories.len();
+    let (category, commands) = &categories[page];
+    let description = commands
+        .iter()
+        .map(|(command_name, command_help)| {
+            format!("`/{}` {}", command_name, command_help.short_description)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let footer = TurtoMessage {
+        locale,
+        kind: HelpPage {
+            current: page + 1,
+            total,
+        },
+    };
+    let embed = CreateEmbed::new()
+        .title(*category)
+        .description(description)
+        .footer(CreateEmbedFooter::new(footer.to_string()));
+    (embed, total)
+}
+
+fn help_page_buttons(prev_id: &str, next_id: &str, page: usize, total: usize) -> CreateActionRow {
+    CreateActionRow::Buttons(vec![
+        CreateButton::new(prev_id).emoji('◀').disabled(page == 0),
+        CreateButton::new(next_id)
+            .emoji('▶')
+            .disabled(page + 1 >= total),
+    ])
+}
+
+async fn help_pages(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let prev_id = format!("{}_prev", ctx.id());
+    let next_id = format!("{}_next", ctx.id());
 
-    let response = CreateReply::default().embed(embed);
+    let mut page = 0;
+    let (embed, total) = help_page_embed(locale, page);
+    let mut response = CreateReply::default().embed(embed);
+    if total > 1 {
+        response = response.components(vec![help_page_buttons(&prev_id, &next_id, page, total)]);
+    }
+    let help_msg = ctx.send(response).await?;
+    if total <= 1 {
+        return Ok(());
+    }
+
+    let ctx_id = ctx.id().to_string();
+    while let Some(mci) = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .timeout(Duration::from_secs(60))
+        .filter({
+            let ctx_id = ctx_id.clone();
+            move |mci| mci.data.custom_id.starts_with(&ctx_id)
+        })
+        .await
+    {
+        if mci.data.custom_id == prev_id {
+            page = page.saturating_sub(1);
+        } else if mci.data.custom_id == next_id {
+            page = (page + 1).min(total - 1);
+        }
+        let (embed, total) = help_page_embed(locale, page);
+        mci.create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .components(vec![help_page_buttons(&prev_id, &next_id, page, total)]),
+            ),
+        )
+        .await?;
+    }
 
-    ctx.send(response).await?;
+    // remove the buttons after timeout
+    help_msg
+        .edit(ctx, CreateReply::default().components(vec![]))
+        .await?;
     Ok(())
 }
@@ -1,9 +1,18 @@
 use crate::{
     models::alias::{Context, Error},
-    utils::queue::{enqueue, QueueType},
+    utils::{
+        queue::{enqueue, QueueType},
+        suggest::autocomplete_search,
+    },
 };
 
 #[poise::command(slash_command, guild_only)]
-pub async fn insert(ctx: Context<'_>, #[rename = "url"] query: String) -> Result<(), Error> {
-    enqueue(ctx, query, QueueType::Front).await
+pub async fn insert(
+    ctx: Context<'_>,
+    #[rename = "url"]
+    #[autocomplete = "autocomplete_search"]
+    query: String,
+    items: Option<String>,
+) -> Result<(), Error> {
+    enqueue(ctx, query, items, QueueType::Front).await
 }
@@ -0,0 +1,79 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{JobCanceled, JobEntry, JobNoPermission, JobNotFound, NoJobs},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{
+        get_locale, is_admin,
+        jobs::{cancel_job, find_job, guild_jobs},
+        say,
+    },
+};
+use poise::CreateReply;
+use serenity::builder::CreateAllowedMentions;
+
+#[poise::command(slash_command, guild_only, subcommands("list", "cancel"))]
+pub async fn jobs(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let jobs = guild_jobs(guild_id);
+    if jobs.is_empty() {
+        ctx.say(TurtoMessage {
+            locale,
+            kind: NoJobs,
+        })
+        .await?;
+        return Ok(());
+    }
+    let content = jobs
+        .iter()
+        .map(|job| {
+            TurtoMessage {
+                locale,
+                kind: JobEntry {
+                    job: job.id,
+                    running: job.is_running(),
+                    done: job.done(),
+                    total: job.total,
+                    user: job.author,
+                },
+            }
+            .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    // the authors are mentioned without being pinged
+    ctx.send(
+        CreateReply::default()
+            .content(content)
+            .allowed_mentions(CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn cancel(ctx: Context<'_>, id: u64) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let kind = match find_job(guild_id, id) {
+        None => JobNotFound { job: id },
+        Some(job) if job.author != ctx.author().id && !is_admin(ctx).await => JobNoPermission,
+        Some(job) => {
+            cancel_job(&job);
+            JobCanceled { job: id }
+        }
+    };
+    say(ctx, TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
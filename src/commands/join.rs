@@ -1,40 +1,47 @@
 use crate::{
-    messages::{
-        TurtoMessage,
-        TurtoMessageKind::{DifferentVoiceChannel, UserNotInVoiceChannel},
+    messages::{TurtoMessage, TurtoMessageKind::UserNotInVoiceChannel},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{
+        get_locale,
+        guild::{GuildUtil, VoiceChannelState},
+        join_voice_channel, say,
+        vote::vote_move,
     },
-    models::alias::{Context, Error},
-    utils::{guild::{GuildUtil, VoiceChannelState}, join_voice_channel},
 };
 use tracing::error;
 
 #[poise::command(slash_command, guild_only)]
 pub async fn join(ctx: Context<'_>) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
     let bot_id = ctx.cache().current_user().id;
     let user_id = ctx.author().id;
-    let vc_stat = ctx.guild().unwrap().cmp_voice_channel(&bot_id, &user_id);
-    let locale = ctx.locale();
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &user_id);
+    let locale = get_locale(ctx);
 
     match vc_stat {
-        VoiceChannelState::Different(bot_vc, _) => {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: DifferentVoiceChannel { bot: bot_vc },
-            })
-            .await?;
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
             return Ok(());
         }
         VoiceChannelState::None | VoiceChannelState::OnlyFirst(_) => {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: UserNotInVoiceChannel,
-            })
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: UserNotInVoiceChannel,
+                },
+            )
             .await?;
             return Ok(());
         }
         VoiceChannelState::OnlySecond(user_vc) => {
-            if let Err (err) = join_voice_channel(ctx, locale, guild_id, user_vc).await {
+            if let Err(err) = join_voice_channel(ctx, locale, guild_id, user_vc).await {
                 error!("Failed to join voice channel {user_vc}: {err}");
             }
         }
@@ -0,0 +1,141 @@
+use crate::{
+    config::get_config,
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{BotNotInVoiceChannel, DifferentVoiceChannel, InvalidJumpIndex, Play},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        invocation::Phase::{Discord, Ytdl},
+        playlist_item::PlaylistItem,
+    },
+    utils::{
+        cleanup::track_reply,
+        get_locale,
+        guild::{GuildUtil, VoiceChannelState},
+        play::play_url,
+        say,
+        timing::timed,
+        vote::vote_move,
+    },
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn jump(ctx: Context<'_>, #[min = 1] index: usize) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let bot_id = ctx.cache().current_user().id;
+    let user_id = ctx.author().id;
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &user_id);
+    let locale = get_locale(ctx);
+
+    match vc_stat {
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlyFirst(bot_vc) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: DifferentVoiceChannel { bot: bot_vc },
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlySecond(_) | VoiceChannelState::None => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: BotNotInVoiceChannel,
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+        VoiceChannelState::Same(_) => (),
+    }
+    let Some(call) = songbird::get(ctx.serenity_context())
+        .await
+        .ok_or(TurtoError::VoiceUnavailable)?
+        .get(guild_id)
+    else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: BotNotInVoiceChannel,
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let data = ctx.data();
+    // the currently playing item goes back as the first one, it starts over when it's played again
+    let current = if get_config().jump_requeue {
+        data.playing.read().await.get(&guild_id).map(|playing| {
+            let mut playlist_item = PlaylistItem::from((*playing.metadata).clone());
+            playlist_item.url = playing.url.to_string();
+            playlist_item.requester = playing.requester;
+            playlist_item
+        })
+    } else {
+        None
+    };
+
+    let next = {
+        let mut guild_data = data.guilds.entry(guild_id).or_default();
+        let next = guild_data.playlist.remove(index - 1);
+        if let (Some(_), Some(current)) = (&next, current) {
+            guild_data.playlist.push_front(current);
+        }
+        next.ok_or(guild_data.playlist.len())
+    };
+    let next = match next {
+        Ok(next) => next,
+        Err(length) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: InvalidJumpIndex { length },
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    timed(ctx, Discord, ctx.defer()).await?;
+    let meta = timed(
+        ctx,
+        Ytdl,
+        play_url(
+            call,
+            data.guilds.clone(),
+            data.playing.clone(),
+            guild_id,
+            next.url,
+            next.requester,
+        ),
+    )
+    .await?;
+
+    let reply = ctx
+        .say(TurtoMessage {
+            locale,
+            kind: Play {
+                title: meta.title.as_deref().unwrap_or_default(),
+            },
+        })
+        .await?;
+    track_reply(ctx, &reply).await;
+    Ok(())
+}
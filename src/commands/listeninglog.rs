@@ -0,0 +1,46 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{ListeningLogOff, SetListeningLog},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        guild::listening_log::LogPeriod,
+    },
+    utils::{check_admin, get_locale},
+};
+use serenity::all::GuildChannel;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn listeninglog(
+    ctx: Context<'_>,
+    #[channel_types("Text", "Forum")] channel: Option<GuildChannel>,
+    period: Option<LogPeriod>,
+) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let channel = channel.map(|channel| channel.id);
+    let period = period.unwrap_or_default();
+    {
+        let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+        let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        guild_data.config.listening_log = channel;
+        guild_data.config.listening_log_period = period;
+        // the next track starts a new thread in the channel
+        guild_data.listening_log_thread = None;
+    }
+
+    let kind = match channel {
+        Some(channel) => SetListeningLog { channel, period },
+        None => ListeningLogOff,
+    };
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind,
+    })
+    .await?;
+    Ok(())
+}
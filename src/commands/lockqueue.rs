@@ -0,0 +1,43 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::LockQueue},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        guild::queue_lock::{LockReason, QueueLock},
+    },
+    utils::{check_admin, get_locale, misc::unix_now},
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn lockqueue(
+    ctx: Context<'_>,
+    #[min = 1]
+    #[max = 1440]
+    minutes: Option<u64>,
+) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    // it's kept until `/unlockqueue` if the minutes aren't given
+    let until = minutes.map(|minutes| {
+        let now = unix_now();
+        now + minutes * 60
+    });
+    let queue_lock = QueueLock {
+        reason: LockReason::Manual,
+        until,
+    };
+    ctx.data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default()
+        .queue_lock = Some(queue_lock);
+
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: LockQueue { minutes },
+    })
+    .await?;
+    Ok(())
+}
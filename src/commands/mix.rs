@@ -0,0 +1,38 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{EmptyPlaylist, Mixed},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        mix::MixOrder,
+    },
+    utils::get_locale,
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn mix(ctx: Context<'_>, order: Option<MixOrder>) -> Result<(), Error> {
+    let guild = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let mut guild_data = ctx.data().guilds.entry(guild).or_default();
+    let locale = get_locale(ctx);
+    if guild_data.playlist.is_empty() {
+        drop(guild_data);
+        ctx.say(TurtoMessage {
+            locale,
+            kind: EmptyPlaylist,
+        })
+        .await?;
+        return Ok(());
+    }
+    let order = order.unwrap_or(MixOrder::Interleave);
+    guild_data.playlist.mix(order);
+    drop(guild_data);
+
+    ctx.say(TurtoMessage {
+        locale,
+        kind: Mixed(order),
+    })
+    .await?;
+    Ok(())
+}
@@ -2,75 +2,260 @@ use std::time::Duration;
 
 use crate::{
     commands::{
-        about::about, autoleave::autoleave, ban::ban, clear::clear, help::help, insert::insert,
-        join::join, leave::leave, pause::pause, play::play, playlist::playlist, playwhat::playwhat,
-        queue::queue, remove::remove, repeat::repeat, seek::seek, shuffle::shuffle, skip::skip,
-        stop::stop, unban::unban, volume::volume,
+        about::about, announce::announce, announcements::announcements, approval::approval,
+        audit::audit, autoleave::autoleave, autoplay::autoplay, ban::ban, bookmark::bookmark,
+        cleanup::cleanup, clear::clear, clip::clip, configcheck::configcheck, debug::debug,
+        ducking::ducking, event::event, explicit::explicit, filter::filter, forward::forward,
+        greeting::greeting, guilds::guilds, help::help, insert::insert, jobs::jobs, join::join,
+        jump::jump, leave::leave, listeninglog::listeninglog, lockqueue::lockqueue, mix::mix,
+        neveragain::neveragain, overlay::overlay, party::party, pause::pause, play::play,
+        playlist::playlist, playwhat::playwhat, prefs::prefs, queue::queue, queue_this::queue_this,
+        queuelength::queuelength, remove::remove, repeat::repeat, replay::replay,
+        requestchannel::requestchannel, rewind::rewind, script::script, seek::seek,
+        settings::settings, setup::setup, shuffle::shuffle, skip::skip, stop::stop, summon::summon,
+        tempvoice::tempvoice, unban::unban, unlockqueue::unlockqueue, voiceannounce::voiceannounce,
+        voicestats::voicestats, volume::volume,
     },
     config::{
         get_config,
         help::{get_help, locale_list},
     },
+    handlers::pre::instrument,
     models::alias::Command,
+    utils::deprecation::active_deprecations,
 };
+use poise::framework::set_qualified_names;
 use tracing::warn;
 
 pub mod about;
+pub mod announce;
+pub mod announcements;
+pub mod approval;
+pub mod audit;
 pub mod autoleave;
+pub mod autoplay;
 pub mod ban;
+pub mod bookmark;
+pub mod cleanup;
 pub mod clear;
+pub mod clip;
+pub mod configcheck;
+pub mod debug;
+pub mod ducking;
+pub mod event;
+pub mod explicit;
+pub mod filter;
+pub mod forward;
+pub mod greeting;
+pub mod guilds;
 pub mod help;
 pub mod insert;
+pub mod jobs;
 pub mod join;
+pub mod jump;
 pub mod leave;
+pub mod listeninglog;
+pub mod lockqueue;
+pub mod mix;
+pub mod neveragain;
+pub mod overlay;
+pub mod party;
 pub mod pause;
 pub mod play;
 pub mod playlist;
 pub mod playwhat;
+pub mod prefs;
 pub mod queue;
+pub mod queue_this;
+pub mod queuelength;
 pub mod remove;
 pub mod repeat;
+pub mod replay;
+pub mod requestchannel;
+pub mod rewind;
+pub mod script;
 pub mod seek;
+pub mod settings;
+pub mod setup;
 pub mod shuffle;
 pub mod skip;
 pub mod stop;
+pub mod summon;
+pub mod tempvoice;
 pub mod unban;
+pub mod unlockqueue;
+pub mod voiceannounce;
+pub mod voicestats;
 pub mod volume;
 
 pub fn create_commands() -> Vec<Command> {
+    let mut commands = builtin_commands();
+    // the old names of the renamed commands are the commands built again and renamed
+    let mut aliases = Vec::new();
+    for deprecation in active_deprecations() {
+        if commands
+            .iter()
+            .any(|command| command.name == deprecation.old)
+        {
+            warn!(
+                "/{} is still a command, it's not redirected to /{}",
+                deprecation.old, deprecation.new
+            );
+            continue;
+        }
+        match builtin_commands()
+            .into_iter()
+            .find(|command| command.name == deprecation.new)
+        {
+            Some(command) => aliases.push((deprecation.old.clone(), command)),
+            None => warn!(
+                "/{} is not found, /{} is not redirected to it",
+                deprecation.new, deprecation.old
+            ),
+        }
+    }
+
+    prepare_commands(&mut commands);
+    for (old, mut alias) in aliases {
+        // the help info is looked up with the new name before the command is renamed
+        prepare_commands(std::slice::from_mut(&mut alias));
+        alias.name = old.clone();
+        alias.qualified_name = old;
+        set_qualified_names(std::slice::from_mut(&mut alias));
+        commands.push(alias);
+    }
+    commands
+}
+
+fn builtin_commands() -> Vec<Command> {
+    // only extended with the commands of the plugins
+    #[allow(unused_mut)]
     let mut commands = vec![
         about(),
+        announce(),
+        announcements(),
+        approval(),
+        audit(),
         autoleave(),
+        autoplay(),
         ban(),
+        bookmark(),
+        cleanup(),
         clear(),
+        clip(),
+        configcheck(),
+        debug(),
+        ducking(),
+        event(),
+        explicit(),
+        filter(),
+        forward(),
+        greeting(),
+        guilds(),
         help(),
         insert(),
+        jobs(),
         join(),
+        jump(),
         leave(),
+        listeninglog(),
+        lockqueue(),
+        mix(),
+        neveragain(),
+        overlay(),
+        party(),
         pause(),
         play(),
         playlist(),
         playwhat(),
+        prefs(),
         queue(),
+        queue_this(),
+        queuelength(),
         remove(),
         repeat(),
+        replay(),
+        requestchannel(),
+        rewind(),
+        script(),
         seek(),
+        settings(),
+        setup(),
         shuffle(),
         skip(),
         stop(),
+        summon(),
+        tempvoice(),
         unban(),
+        unlockqueue(),
+        voiceannounce(),
+        voicestats(),
         volume(),
     ];
+    #[cfg(feature = "plugins")]
+    commands.extend(
+        crate::plugin::plugins()
+            .iter()
+            .flat_map(|plugin| plugin.commands()),
+    );
+    commands
+}
 
+fn prepare_commands(commands: &mut [Command]) {
+    // qualified names are needed to look up the help info of subcommands
+    set_qualified_names(commands);
+
+    let command_cooldown = Duration::from_secs(get_config().command_delay);
     for command in commands.iter_mut() {
-        let help = get_help();
-        // add default short description
+        apply_help(command);
+        // set command cooldown for each command
+        command.cooldown_config.write().unwrap().guild = Some(command_cooldown);
+        instrument(command);
+    }
+}
+
+/// Fill in the descriptions of a command and its parameters from the help info, subcommands are
+/// looked up with their qualified name, like `debug guild`
+fn apply_help(command: &mut Command) {
+    let help = get_help();
+    // add default short description
+    if let Some(command_help) = help
+        .get("default")
+        .and_then(|default_help| default_help.get(&command.qualified_name))
+    {
+        command.description = Some(command_help.short_description.to_string());
+        // add default description for each parameter
+        for parameter in command.parameters.iter_mut() {
+            let Some(parameter_description) = command_help
+                .parameters
+                .as_ref()
+                .and_then(|parameters| parameters.get(&parameter.name))
+            else {
+                warn!(
+                    "Description of parameter {} of command {} not found",
+                    parameter.name, command.qualified_name
+                );
+                continue;
+            };
+            parameter.description = Some(parameter_description.to_string());
+        }
+    } else {
+        warn!(
+            "Short description of command {} not found",
+            command.qualified_name
+        );
+    }
+    // add short description for all available locales
+    for locale in locale_list() {
         if let Some(command_help) = help
-            .get("default")
-            .and_then(|default_help| default_help.get(&command.name))
+            .get(locale)
+            .and_then(|locale_help| locale_help.get(&command.qualified_name))
+        // .map(|command_help| command_help.short_description.as_str())
         {
-            command.description = Some(command_help.short_description.to_string());
-            // add default description for each parameter
+            command.description_localizations.insert(
+                locale.to_string(),
+                command_help.short_description.to_string(),
+            );
             for parameter in command.parameters.iter_mut() {
                 let Some(parameter_description) = command_help
                     .parameters
@@ -78,53 +263,23 @@ pub fn create_commands() -> Vec<Command> {
                     .and_then(|parameters| parameters.get(&parameter.name))
                 else {
                     warn!(
-                        "Description of parameter {} of command {} not found",
-                        parameter.name, command.name
+                        "Description of parameter {} of command {} for locale {} not found",
+                        parameter.name, command.qualified_name, locale
                     );
                     continue;
                 };
-                parameter.description = Some(parameter_description.to_string());
+                parameter
+                    .description_localizations
+                    .insert(locale.to_string(), parameter_description.to_string());
             }
         } else {
-            warn!("Short description of command {} not found", command.name);
+            warn!(
+                "Short description of command {} for locale {} not found",
+                command.qualified_name, locale
+            )
         }
-        // add short description for all available locales
-        for locale in locale_list() {
-            if let Some(command_help) = help
-                .get(locale)
-                .and_then(|locale_help| locale_help.get(&command.name))
-            // .map(|command_help| command_help.short_description.as_str())
-            {
-                command.description_localizations.insert(
-                    locale.to_string(),
-                    command_help.short_description.to_string(),
-                );
-                for parameter in command.parameters.iter_mut() {
-                    let Some(parameter_description) = command_help
-                        .parameters
-                        .as_ref()
-                        .and_then(|parameters| parameters.get(&parameter.name))
-                    else {
-                        warn!(
-                            "Description of parameter {} of command {} for locale {} not found",
-                            parameter.name, command.name, locale
-                        );
-                        continue;
-                    };
-                    parameter
-                        .description_localizations
-                        .insert(locale.to_string(), parameter_description.to_string());
-                }
-            } else {
-                warn!(
-                    "Short description of command {} for locale {} not found",
-                    command.name, locale
-                )
-            }
-        }
-        // set command cooldown for each command
-        let command_cooldown = Duration::from_secs(get_config().command_delay);
-        command.cooldown_config.write().unwrap().guild = Some(command_cooldown);
     }
-    commands
+    for subcommand in command.subcommands.iter_mut() {
+        apply_help(subcommand);
+    }
 }
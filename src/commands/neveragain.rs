@@ -0,0 +1,59 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{NeverAgainAdded, NeverAgainNotFound, NeverAgainRemoved},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{get_locale, say},
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn neveragain(ctx: Context<'_>, forget: Option<String>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let locale = get_locale(ctx);
+
+    if let Some(url) = forget {
+        let removed = ctx
+            .data()
+            .guilds
+            .entry(guild_id)
+            .or_default()
+            .never_again
+            .remove(url.trim());
+        let kind = if removed {
+            NeverAgainRemoved
+        } else {
+            NeverAgainNotFound
+        };
+        say(ctx, TurtoMessage { locale, kind }).await?;
+        return Ok(());
+    }
+
+    let (url, title) = {
+        let playing_map = ctx.data().playing.read().await;
+        let playing = playing_map.get(&guild_id).ok_or(TurtoError::NotPlaying)?;
+        // the link queued by the users rather than the one played with, if there's one
+        let url = playing
+            .metadata
+            .source_url
+            .clone()
+            .unwrap_or_else(|| playing.url.to_string());
+        (url, playing.metadata.title.clone().unwrap_or_default())
+    };
+    ctx.data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .never_again
+        .insert(&url);
+
+    ctx.say(TurtoMessage {
+        locale,
+        kind: NeverAgainAdded { title: &title },
+    })
+    .await?;
+    Ok(())
+}
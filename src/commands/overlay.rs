@@ -0,0 +1,68 @@
+use crate::{
+    config::get_config,
+    http::overlay::{overlay_link, refresh_overlay},
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{OverlayDisabled, OverlayLink, OverlayOff},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        toggle::ToggleOption,
+    },
+    utils::{check_admin, get_locale},
+};
+use poise::CreateReply;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn overlay(ctx: Context<'_>, toggle: ToggleOption) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    if !get_config().http_server {
+        ctx.say(TurtoMessage {
+            locale,
+            kind: OverlayDisabled,
+        })
+        .await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    // a new token is made every time, so the link shown on a stream by accident can be revoked
+    let token = match toggle {
+        ToggleOption::On => Some(format!("{:032x}", rand::random::<u128>())),
+        ToggleOption::Off => None,
+    };
+    ctx.data().guilds.entry(guild_id).or_default().overlay_token = token.clone();
+    // the overlays with the old token are closed
+    refresh_overlay(guild_id);
+
+    match token {
+        // only the administrator can see the link
+        Some(token) => {
+            let link = TurtoMessage {
+                locale,
+                kind: OverlayLink {
+                    url: &overlay_link(&token),
+                },
+            };
+            ctx.send(
+                CreateReply::default()
+                    .content(link.to_string())
+                    .ephemeral(true),
+            )
+            .await?;
+        }
+        None => {
+            ctx.say(TurtoMessage {
+                locale,
+                kind: OverlayOff,
+            })
+            .await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            BotNotInVoiceChannel, DifferentVoiceChannel, EmptyPlaylist, NotPlaying, PartyCountdown,
+            PartyStarted, PartySynced, UserNotInVoiceChannel,
+        },
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        invocation::Phase::Ytdl,
+    },
+    utils::{
+        cleanup::track_reply,
+        get_locale,
+        guild::{GuildUtil, VoiceChannelState},
+        join_voice_channel,
+        play::play_next,
+        say,
+        timing::timed,
+        vote::vote_move,
+    },
+};
+use poise::{CreateReply, ReplyHandle};
+use serenity::all::GuildId;
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
+use tracing::warn;
+
+/// How long the countdown before the party starts or the track restarts is
+const PARTY_COUNTDOWN: Duration = Duration::from_secs(5);
+
+#[poise::command(slash_command, guild_only, subcommands("start", "sync"))]
+pub async fn party(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn start(ctx: Context<'_>) -> Result<(), Error> {
+    // the next track of the playlist is loaded during the countdown and started when it reaches 0
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let bot_id = ctx.cache().current_user().id;
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &ctx.author().id);
+    let locale = get_locale(ctx);
+
+    let is_empty = ctx
+        .data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .playlist
+        .is_empty();
+    if is_empty {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: EmptyPlaylist,
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let call = match vc_stat {
+        VoiceChannelState::None | VoiceChannelState::OnlyFirst(_) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: UserNotInVoiceChannel,
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlySecond(user_vc) => {
+            join_voice_channel(ctx, locale, guild_id, user_vc).await?
+        }
+        VoiceChannelState::Same(_) => songbird::get(ctx.serenity_context())
+            .await
+            .ok_or(TurtoError::VoiceUnavailable)?
+            .get(guild_id)
+            .ok_or(TurtoError::BotNotInVoiceChannel)?,
+    };
+
+    let deadline = Instant::now() + PARTY_COUNTDOWN;
+    let reply = ctx
+        .say(TurtoMessage {
+            locale,
+            kind: PartyCountdown {
+                seconds: PARTY_COUNTDOWN.as_secs(),
+            },
+        })
+        .await?;
+
+    let data = ctx.data();
+    let next = play_next(call, data.guilds.clone(), data.playing.clone(), guild_id);
+    let meta = match timed(ctx, Ytdl, next).await {
+        Some(Ok(meta)) => meta,
+        Some(Err(err)) => return Err(err.into()),
+        // the playlist is cleared in the meantime
+        None => {
+            reply
+                .edit(
+                    ctx,
+                    CreateReply::default().content(
+                        TurtoMessage {
+                            locale,
+                            kind: EmptyPlaylist,
+                        }
+                        .to_string(),
+                    ),
+                )
+                .await?;
+            return Ok(());
+        }
+    };
+    // held until the countdown ends, so a slow extraction doesn't start it late
+    pause_playing(ctx, guild_id).await;
+    countdown(ctx, &reply, deadline).await?;
+    restart_playing(ctx, guild_id).await;
+
+    reply
+        .edit(
+            ctx,
+            CreateReply::default().content(
+                TurtoMessage {
+                    locale,
+                    kind: PartyStarted {
+                        title: meta.title.as_deref().unwrap_or_default(),
+                    },
+                }
+                .to_string(),
+            ),
+        )
+        .await?;
+    track_reply(ctx, &reply).await;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn sync(ctx: Context<'_>) -> Result<(), Error> {
+    // the track playing is restarted after the countdown, so the members who joined late hear it
+    // from the start with the others
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let bot_id = ctx.cache().current_user().id;
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &ctx.author().id);
+    let locale = get_locale(ctx);
+
+    match vc_stat {
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlyFirst(bot_vc) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: DifferentVoiceChannel { bot: bot_vc },
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlySecond(_) | VoiceChannelState::None => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: BotNotInVoiceChannel,
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+        VoiceChannelState::Same(_) => (),
+    }
+
+    let title = ctx
+        .data()
+        .playing
+        .read()
+        .await
+        .get(&guild_id)
+        .map(|playing| playing.metadata.title.clone().unwrap_or_default());
+    let Some(title) = title else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: NotPlaying,
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let deadline = Instant::now() + PARTY_COUNTDOWN;
+    let reply = ctx
+        .say(TurtoMessage {
+            locale,
+            kind: PartyCountdown {
+                seconds: PARTY_COUNTDOWN.as_secs(),
+            },
+        })
+        .await?;
+    pause_playing(ctx, guild_id).await;
+    countdown(ctx, &reply, deadline).await?;
+    restart_playing(ctx, guild_id).await;
+
+    reply
+        .edit(
+            ctx,
+            CreateReply::default().content(
+                TurtoMessage {
+                    locale,
+                    kind: PartySynced { title: &title },
+                }
+                .to_string(),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Update the countdown every second until the deadline
+async fn countdown(
+    ctx: Context<'_>,
+    reply: &ReplyHandle<'_>,
+    deadline: Instant,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let seconds = left.as_secs_f32().ceil() as u64;
+        if seconds == 0 {
+            return Ok(());
+        }
+        reply
+            .edit(
+                ctx,
+                CreateReply::default().content(
+                    TurtoMessage {
+                        locale,
+                        kind: PartyCountdown { seconds },
+                    }
+                    .to_string(),
+                ),
+            )
+            .await?;
+        sleep_until(deadline - Duration::from_secs(seconds - 1)).await;
+    }
+}
+
+async fn pause_playing(ctx: Context<'_>, guild_id: GuildId) {
+    if let Some(playing) = ctx.data().playing.read().await.get(&guild_id) {
+        if let Err(err) = playing.track_handle.pause() {
+            warn!("Failed to hold the track for the countdown: {}", err);
+        }
+    }
+}
+
+async fn restart_playing(ctx: Context<'_>, guild_id: GuildId) {
+    let playing_map = ctx.data().playing.read().await;
+    let Some(playing) = playing_map.get(&guild_id) else {
+        return;
+    };
+    // the live streams can't be seeked, they're resumed where they are
+    if let Err(err) = playing.track_handle.seek_async(Duration::ZERO).await {
+        warn!("Failed to restart the track: {}", err);
+    }
+    if let Err(err) = playing.track_handle.play() {
+        warn!("Failed to start the track after the countdown: {}", err);
+    }
+}
@@ -1,56 +1,53 @@
 use crate::{
-    messages::{
-        TurtoMessage,
-        TurtoMessageKind::{BotNotInVoiceChannel, DifferentVoiceChannel, NotPlaying, Pause},
+    messages::{TurtoMessage, TurtoMessageKind::Pause},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{
+        fade::fade_pause,
+        get_locale,
+        guild::{GuildUtil, VoiceChannelState},
+        vote::vote_move,
     },
-    models::alias::{Context, Error},
-    utils::guild::{GuildUtil, VoiceChannelState},
 };
 use tracing::error;
 
 #[poise::command(slash_command, guild_only)]
 pub async fn pause(ctx: Context<'_>) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
     let bot_id = ctx.cache().current_user().id;
     let user_id = ctx.author().id;
-    let vc_stat = ctx.guild().unwrap().cmp_voice_channel(&bot_id, &user_id);
-    let locale = ctx.locale();
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &user_id);
+    let locale = get_locale(ctx);
 
     match vc_stat {
         VoiceChannelState::None | VoiceChannelState::OnlySecond(_) => {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: BotNotInVoiceChannel,
-            })
-            .await?;
-            return Ok(());
+            return Err(TurtoError::BotNotInVoiceChannel.into());
         }
-        VoiceChannelState::Different(bot_vc, _) | VoiceChannelState::OnlyFirst(bot_vc) => {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: DifferentVoiceChannel { bot: bot_vc },
-            })
-            .await?;
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
             return Ok(());
         }
+        VoiceChannelState::OnlyFirst(bot_vc) => {
+            return Err(TurtoError::DifferentVoiceChannel(bot_vc).into());
+        }
         VoiceChannelState::Same(_) => (),
     }
 
     let playing_map = ctx.data().playing.read().await;
     let Some(playing) = playing_map.get(&guild_id) else {
-        ctx.say(TurtoMessage {
-            locale,
-            kind: NotPlaying,
-        })
-        .await?;
-        return Ok(());
+        return Err(TurtoError::NotPlaying.into());
     };
 
-    if let Err(why) = playing.track_handle.pause() {
+    if let Err(why) = fade_pause(&playing.track_handle).await {
         let uuid = playing.track_handle.uuid();
         error!("Failed to pause track {uuid}: {why}");
     }
-    let title = playing.metadata.title.as_ref().unwrap();
+    let title = playing.metadata.title.as_deref().unwrap_or_default();
     ctx.say(TurtoMessage {
         locale,
         kind: Pause { title },
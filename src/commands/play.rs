@@ -2,43 +2,80 @@ use crate::{
     messages::{
         TurtoMessage,
         TurtoMessageKind::{
-            DifferentVoiceChannel, InvalidUrl, Play, UserNotInVoiceChannel,
+            InvalidUrl, Play, TempVoiceDisabled, TempVoiceFailed, UserNotInVoiceChannel,
         },
     },
-    models::alias::{Context, Error},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        invocation::Phase::{Discord, Ytdl},
+    },
     utils::{
+        cleanup::track_reply,
+        context::TurtoContext,
+        fade::fade_play,
+        get_locale,
         guild::{GuildUtil, VoiceChannelState},
         join_voice_channel,
-        play::{play_next, play_url},
+        play::{play_next, play_prepared, prepare_track},
+        resolve_query,
+        resolver::MusicLink,
+        resolving::Placeholder,
+        say,
+        suggest::autocomplete_search,
+        temp_voice::temp_voice_channel,
+        timing::timed,
+        vote::vote_move,
     },
 };
-use songbird::tracks::PlayMode;
-use tracing::error;
+use serenity::all::GuildId;
+use songbird::{tracks::PlayMode, Call};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{error, warn};
 use url::Url;
 
 #[poise::command(slash_command, guild_only)]
-pub async fn play(ctx: Context<'_>, #[rename = "url"] query: Option<String>) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
+pub async fn play(
+    ctx: Context<'_>,
+    #[rename = "url"]
+    #[autocomplete = "autocomplete_search"]
+    query: Option<String>,
+    #[rename = "temp"] temp_channel: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
     let bot_id = ctx.cache().current_user().id;
     let user_id = ctx.author().id;
-    let vc_stat = ctx.guild().unwrap().cmp_voice_channel(&bot_id, &user_id);
-    let locale = ctx.locale();
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &user_id);
+    let locale = get_locale(ctx);
 
+    // the session is hosted where turto already is
+    let host_temp = temp_channel.unwrap_or_default()
+        && matches!(
+            vc_stat,
+            VoiceChannelState::None | VoiceChannelState::OnlySecond(_)
+        );
     let call = match vc_stat {
+        _ if host_temp => match host_temp_voice(ctx, guild_id, &vc_stat).await? {
+            Some(call) => call,
+            None => return Ok(()),
+        },
         VoiceChannelState::None | VoiceChannelState::OnlyFirst(_) => {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: UserNotInVoiceChannel,
-            })
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: UserNotInVoiceChannel,
+                },
+            )
             .await?;
             return Ok(());
         }
-        VoiceChannelState::Different(bot_vc, _) => {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: DifferentVoiceChannel { bot: bot_vc },
-            })
-            .await?;
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
             return Ok(());
         }
         VoiceChannelState::OnlySecond(user_vc) => {
@@ -52,41 +89,58 @@ pub async fn play(ctx: Context<'_>, #[rename = "url"] query: Option<String>) ->
         }
         VoiceChannelState::Same(_) => songbird::get(ctx.serenity_context())
             .await
-            .unwrap()
+            .ok_or(TurtoError::VoiceUnavailable)?
             .get(guild_id)
-            .unwrap(),
+            .ok_or(TurtoError::BotNotInVoiceChannel)?,
     };
 
     let data = ctx.data();
 
     if let Some(query) = query {
-        // If a valid url is provided then play the url
-        if Url::parse(&query).is_err() {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: InvalidUrl(None),
-            })
-            .await?;
+        let music_link = Url::parse(&query).ok().as_ref().and_then(MusicLink::parse);
+        let resolution = async {
+            let url = match music_link {
+                // the links yt-dlp can't play are searched with their artists and titles
+                Some(music_link) => match timed(ctx, Ytdl, music_link.search_query()).await {
+                    Ok(search_query) => search_query,
+                    Err(_) => return Err(TurtoError::ExtractionFailed(query.clone()).into()),
+                },
+                // the keywords are searched if it's not a url
+                None => resolve_query(ctx, &query),
+            };
+            timed(ctx, Ytdl, prepare_track(&data.guilds, guild_id, &url))
+                .await
+                .map_err(Error::from)
+        };
+        let placeholder = Placeholder::send(ctx, &query, false).await?;
+        // what's playing is kept if it's canceled
+        let Some(resolved) = placeholder.resolve(resolution).await? else {
             return Ok(());
-        }
-
-        ctx.defer().await?;
-        let meta = play_url(
+        };
+        let prepared = match resolved {
+            Ok(prepared) => prepared,
+            Err(err) => return placeholder.fail(err).await,
+        };
+        let meta = play_prepared(
             call,
             data.guilds.clone(),
             data.playing.clone(),
             guild_id,
-            query,
+            prepared,
+            Some(ctx.author().id),
+            false,
         )
-        .await?;
+        .await;
 
-        ctx.say(TurtoMessage {
-            locale,
-            kind: Play {
-                title: meta.title.as_ref().unwrap(),
-            },
-        })
-        .await?;
+        placeholder
+            .say(TurtoMessage {
+                locale,
+                kind: Play {
+                    title: meta.title.as_deref().unwrap_or_default(),
+                },
+            })
+            .await?;
+        track_reply(ctx, placeholder.reply()).await;
     } else {
         // If no url provided, check if there is a paused track or there is any song in the playlist
         let playing_map = data.playing.read().await;
@@ -94,14 +148,14 @@ pub async fn play(ctx: Context<'_>, #[rename = "url"] query: Option<String>) ->
             if let Ok(current_track_state) = playing.track_handle.get_info().await {
                 if current_track_state.playing == PlayMode::Pause {
                     // If there is a paused song then play it
-                    if let Err(why) = playing.track_handle.play() {
+                    if let Err(why) = fade_play(&playing.track_handle).await {
                         let uuid = playing.track_handle.uuid();
                         error!("Failed to play track {uuid}: {why}");
                     } else {
                         ctx.say(TurtoMessage {
                             locale,
                             kind: Play {
-                                title: playing.metadata.title.as_ref().unwrap(),
+                                title: playing.metadata.title.as_deref().unwrap_or_default(),
                             },
                         })
                         .await?;
@@ -112,27 +166,99 @@ pub async fn play(ctx: Context<'_>, #[rename = "url"] query: Option<String>) ->
         }
         drop(playing_map);
 
-        ctx.defer().await?;
-        if let Some(Ok(meta)) =
-            play_next(call, data.guilds.clone(), data.playing.clone(), guild_id).await
-        {
+        timed(ctx, Discord, ctx.defer()).await?;
+        let next = play_next(call, data.guilds.clone(), data.playing.clone(), guild_id);
+        if let Some(Ok(meta)) = timed(ctx, Ytdl, next).await {
             // if there is any song in the play list
-            ctx.say(TurtoMessage {
-                locale,
-                kind: Play {
-                    title: meta.title.as_ref().unwrap(),
-                },
-            })
-            .await?;
+            let reply = ctx
+                .say(TurtoMessage {
+                    locale,
+                    kind: Play {
+                        title: meta.title.as_deref().unwrap_or_default(),
+                    },
+                })
+                .await?;
+            track_reply(ctx, &reply).await;
         } else {
             // if the playlist is empty
-            ctx.say(TurtoMessage {
-                locale,
-                kind: InvalidUrl(None),
-            })
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: InvalidUrl(None),
+                },
+            )
             .await?;
         }
     }
 
     Ok(())
 }
+
+/// Join the temporary voice channel of the guild, which is created if there isn't one, and move
+/// the author into it. `None` if it can't be joined, which is replied
+async fn host_temp_voice(
+    ctx: Context<'_>,
+    guild_id: GuildId,
+    vc_stat: &VoiceChannelState,
+) -> Result<Option<Arc<Mutex<Call>>>, Error> {
+    let locale = get_locale(ctx);
+    let category = ctx
+        .data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .config
+        .temp_voice_category;
+    let Some(category) = category else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: TempVoiceDisabled,
+            },
+        )
+        .await?;
+        return Ok(None);
+    };
+    timed(ctx, Discord, ctx.defer()).await?;
+    let channel_id = match temp_voice_channel(ctx, guild_id, category).await {
+        Ok(channel_id) => channel_id,
+        Err(err) => {
+            // turto needs the permission to manage channels
+            warn!(
+                "Failed to create a voice channel in guild {}: {}",
+                guild_id, err
+            );
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: TempVoiceFailed,
+                },
+            )
+            .await?;
+            return Ok(None);
+        }
+    };
+    let call = match join_voice_channel(ctx, locale, guild_id, channel_id).await {
+        Ok(call) => call,
+        Err(err) => {
+            error!("Failed to join voice channel {channel_id}: {err}");
+            return Ok(None);
+        }
+    };
+    // only the ones in a voice channel can be moved, the others join by themselves
+    if let VoiceChannelState::OnlySecond(_) = vc_stat {
+        let moved = guild_id.move_member(ctx, ctx.author().id, channel_id).await;
+        if let Err(err) = moved {
+            warn!(
+                "Failed to move {} to {}: {}",
+                ctx.author().id,
+                channel_id,
+                err
+            );
+        }
+    }
+    Ok(Some(call))
+}
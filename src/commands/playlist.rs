@@ -1,123 +1,728 @@
 use crate::{
     messages::{
         TurtoMessage,
-        TurtoMessageKind::{EmptyPlaylist, InvalidPlaylistPage},
+        TurtoMessageKind::{
+            EmptyPlaylist, EmptySavedPlaylists, InvalidName, InvalidPlaylistPage, InvalidUrl,
+            Queue, SavedPlaylistAdded, SavedPlaylistDeleted, SavedPlaylistEditor,
+            SavedPlaylistExists, SavedPlaylistLimit, SavedPlaylistNoPermission,
+            SavedPlaylistNotFound, SavedPlaylistPublic, SavedPlaylistSaved, SavedPlaylistShared,
+            SavedPlaylistShuffled,
+        },
     },
     models::{
         alias::{Context, Error},
+        error::TurtoError,
+        guild::saved_playlist::{
+            is_valid_name, SavedPlaylist, SAVED_PLAYLIST_LIMIT, SAVED_PLAYLIST_NAME_MAX_LEN,
+        },
+        invocation::Phase::{Discord, Ytdl},
         playlist::Playlist,
+        queue_item::{QueueItem, QueueItemKind},
+        youtube_playlist::YouTubePlaylist,
+    },
+    utils::{
+        get_locale, is_admin,
+        misc::{format_duration, ToEmoji},
+        queue::{add_to_playlist, author_priority, QueueType},
+        resolve_query, say,
+        timing::timed,
     },
-    utils::misc::ToEmoji,
 };
-use poise::CreateReply;
+use poise::{execute_modal_on_component_interaction, CreateReply};
+use rand::{seq::SliceRandom, thread_rng};
 use serenity::{
-    all::{ComponentInteractionDataKind, EditMessage},
+    all::{ButtonStyle, ComponentInteractionDataKind, ResolvedValue, Unresolved, UserId},
     builder::{
-        CreateActionRow, CreateInteractionResponse, CreateSelectMenu, CreateSelectMenuKind,
-        CreateSelectMenuOption,
+        CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage,
+        CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
     },
     collector::ComponentInteractionCollector,
 };
 use std::time::Duration;
 
+/// The components of the pages are removed if they aren't used for this long
+const PAGE_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands(
+        "show", "save", "add", "append", "load", "merge", "shuffle", "list", "delete", "editor",
+        "public", "share"
+    )
+)]
+pub async fn playlist(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
 #[poise::command(slash_command, guild_only)]
-pub async fn playlist(ctx: Context<'_>, #[min = 1] page: Option<usize>) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
-    let guild_data = ctx.data().guilds.entry(guild_id).or_default();
-    let total_pages = guild_data.playlist.total_pages();
+pub async fn show(ctx: Context<'_>, #[min = 1] page: Option<usize>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    // the locale of the guild is read from the guild data, get it before locking the entry
+    let locale = get_locale(ctx);
+    let (is_empty, total_pages) = {
+        let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        (
+            guild_data.playlist.is_empty(),
+            guild_data.playlist.total_pages(),
+        )
+    };
 
-    if guild_data.playlist.is_empty() {
-        drop(guild_data);
+    if is_empty {
         ctx.say(TurtoMessage {
-            locale: ctx.locale(),
+            locale,
             kind: EmptyPlaylist,
         })
         .await?;
         return Ok(());
-    } 
+    }
 
-    if let Some(page) = page {
-        let response = match generate_playlist_str(&guild_data.playlist, page) {
-            Some(res) => res,
-            None => TurtoMessage {
-                locale: ctx.locale(),
+    let mut page = page.unwrap_or(1);
+    let Some(content) = playlist_page(ctx, page).await else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
                 kind: InvalidPlaylistPage { total_pages },
-            }
-            .to_string(),
-        };
-        drop(guild_data);
-        ctx.say(response).await?;
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+    if total_pages == 1 {
+        ctx.say(content).await?;
         return Ok(());
     }
 
-    if guild_data.playlist.len() <= 10 {
-        // directly display if the playlist has less than 10 items
-        let response = generate_playlist_str(&guild_data.playlist, 1);
-        drop(guild_data);
-
-        ctx.say(response.unwrap()).await?;
-        Ok(())
-    } else if guild_data.playlist.len() <= 250 {
-        // show the select menu if the playlist has more than 10 and less than 250 items
-        // since discord text message has a length limitation of 2000 unicode chars
-        // and select menu has a limitation of 25 options
-
-        let custom_id = ctx.id().to_string();
-        let select_menu = generate_page_select_menu(&guild_data.playlist, &custom_id);
-        drop(guild_data);
-
-        let select_response = CreateReply::default().components(vec![select_menu]);
-        let select_msg = ctx.send(select_response).await?;
-
-        let Some(mut mci) = ComponentInteractionCollector::new(ctx)
+    // the pages can be turned, jumped to with the modal or chosen in the select menu
+    let id_prefix = format!("{}_", ctx.id());
+    let reply = ctx
+        .send(
+            CreateReply::default()
+                .content(content)
+                .components(page_components(&id_prefix, page, total_pages)),
+        )
+        .await?;
+    loop {
+        let Some(mci) = ComponentInteractionCollector::new(ctx)
             .author_id(ctx.author().id)
             .channel_id(ctx.channel_id())
-            .timeout(Duration::from_secs(60))
-            .filter(move |mci| mci.data.custom_id == custom_id)
+            .timeout(PAGE_TIMEOUT)
+            .filter({
+                let id_prefix = id_prefix.clone();
+                move |mci| mci.data.custom_id.starts_with(&id_prefix)
+            })
             .await
         else {
-            // delete the select menu if not selected after timeout
-            select_msg.delete(ctx).await?;
+            // the pages stay as they are without the components after the timeout
+            reply
+                .edit(ctx, CreateReply::default().components(vec![]))
+                .await?;
             return Ok(());
         };
 
-        let page = match &mci.data.kind {
-            ComponentInteractionDataKind::StringSelect { values } => {
-                values[0].parse::<usize>().unwrap()
-            }
-            _ => unreachable!(),
+        let total_pages = {
+            let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+            // the playlist may be changed while the pages are shown
+            guild_data.playlist.total_pages().max(1)
         };
+        let Some(action) =
+            PageAction::parse(&mci.data.custom_id[id_prefix.len()..], &mci.data.kind)
+        else {
+            mci.defer(ctx).await?;
+            continue;
+        };
+        if action == PageAction::Goto {
+            let defaults = GotoPageModal {
+                page: page.to_string(),
+            };
+            let modal = execute_modal_on_component_interaction::<GotoPageModal>(
+                ctx,
+                mci,
+                Some(defaults),
+                Some(PAGE_TIMEOUT),
+            )
+            .await?;
+            let Some(page_to) = modal.and_then(|modal| modal.page.trim().parse::<usize>().ok())
+            else {
+                continue;
+            };
+            // the modal is already responded to, so the message is edited instead
+            page = page_to.clamp(1, total_pages);
+            let content = playlist_page(ctx, page).await.unwrap_or_default();
+            reply
+                .edit(
+                    ctx,
+                    CreateReply::default()
+                        .content(content)
+                        .components(page_components(&id_prefix, page, total_pages)),
+                )
+                .await?;
+            continue;
+        }
+        page = action.turn(page, total_pages);
+        let updated = playlist_page(ctx, page).await.unwrap_or_default();
+        mci.create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(updated)
+                    .components(page_components(&id_prefix, page, total_pages)),
+            ),
+        )
+        .await?;
+    }
+}
 
-        let guild_data = ctx.data().guilds.entry(guild_id).or_default();
-        let response_content = match generate_playlist_str(&guild_data.playlist, page) {
-            Some(res) => res,
-            // just in case the playlist in changed during the wait
-            None => TurtoMessage {
-                locale: ctx.locale(),
-                kind: InvalidPlaylistPage { total_pages },
+#[poise::command(slash_command, guild_only)]
+pub async fn save(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_saved_playlist"] name: String,
+    mine: Option<bool>,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    if !is_valid_name(&name) {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: InvalidName {
+                    max: SAVED_PLAYLIST_NAME_MAX_LEN,
+                },
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let author = ctx.author().id;
+    if mine.unwrap_or_default() {
+        let items = ctx
+            .data()
+            .guilds
+            .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+            .or_default()
+            .playlist
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut user_prefs = ctx.data().users.entry(author).or_default();
+        let saved_playlists = &mut user_prefs.saved_playlists;
+        let kind = if items.is_empty() {
+            EmptyPlaylist
+        } else if let Some(saved) = saved_playlists.get_mut(&name) {
+            saved.items.clear();
+            let count = saved.extend(items);
+            SavedPlaylistSaved { name: &name, count }
+        } else if saved_playlists.len() >= SAVED_PLAYLIST_LIMIT {
+            SavedPlaylistLimit {
+                limit: SAVED_PLAYLIST_LIMIT,
             }
-            .to_string(),
+        } else {
+            let saved = SavedPlaylist::new(author, items);
+            let count = saved.items.len();
+            saved_playlists.insert(name.clone(), saved);
+            SavedPlaylistSaved { name: &name, count }
         };
-        drop(guild_data);
+        drop(user_prefs);
+
+        ctx.say(TurtoMessage { locale, kind }).await?;
+        return Ok(());
+    }
 
-        mci.message
-            .edit(
+    let is_admin = is_admin(ctx).await;
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let mut entry = ctx.data().guilds.entry(guild_id).or_default();
+    let guild_data = &mut *entry;
+    let kind = if guild_data.playlist.is_empty() {
+        EmptyPlaylist
+    } else if let Some(saved) = guild_data.saved_playlists.get_mut(&name) {
+        // saving with the same name again overwrites the items, but not who can edit it
+        if saved.can_edit(author, is_admin) {
+            saved.items.clear();
+            let count = saved.extend(guild_data.playlist.iter().cloned());
+            SavedPlaylistSaved { name: &name, count }
+        } else {
+            SavedPlaylistNoPermission { name: &name }
+        }
+    } else if guild_data.saved_playlists.len() >= SAVED_PLAYLIST_LIMIT {
+        SavedPlaylistLimit {
+            limit: SAVED_PLAYLIST_LIMIT,
+        }
+    } else {
+        let saved = SavedPlaylist::new(author, guild_data.playlist.iter().cloned());
+        let count = saved.items.len();
+        guild_data.saved_playlists.insert(name.clone(), saved);
+        SavedPlaylistSaved { name: &name, count }
+    };
+    drop(entry);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn add(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_saved_playlist"] name: String,
+    url: String,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let author = ctx.author().id;
+    let is_admin = is_admin(ctx).await;
+    // check before resolving the url, which can take a while
+    let can_edit = ctx
+        .data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .saved_playlists
+        .get(&name)
+        .map(|saved| saved.can_edit(author, is_admin));
+    match can_edit {
+        Some(true) => (),
+        Some(false) => {
+            say(
                 ctx,
-                EditMessage::new()
-                    .components(vec![])
-                    .content(response_content),
+                TurtoMessage {
+                    locale,
+                    kind: SavedPlaylistNoPermission { name: &name },
+                },
             )
             .await?;
-        mci.create_response(ctx, CreateInteractionResponse::Acknowledge)
+            return Ok(());
+        }
+        None => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: SavedPlaylistNotFound { name: &name },
+                },
+            )
             .await?;
-        Ok(())
+            return Ok(());
+        }
+    }
+
+    timed(ctx, Discord, ctx.defer()).await?;
+    let queue_item = QueueItem::new(resolve_query(ctx, &url));
+    let Ok(queue_item_kind) = timed(ctx, Ytdl, queue_item.query()).await else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: InvalidUrl(Some(&url)),
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+    let items = match queue_item_kind {
+        QueueItemKind::Single(playlist_item) => vec![playlist_item],
+        QueueItemKind::Playlist(yt_playlist) => yt_playlist.into_iter().collect(),
+    };
+
+    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    // it may be deleted while resolving the url
+    let kind = match guild_data.saved_playlists.get_mut(&name) {
+        Some(saved) => {
+            let count = saved.extend(items);
+            SavedPlaylistAdded { name: &name, count }
+        }
+        None => SavedPlaylistNotFound { name: &name },
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn append(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_saved_playlist"] name: String,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let saved = ctx
+        .data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .saved_playlists
+        .get(&name)
+        .cloned();
+    match saved {
+        Some(saved) => queue_saved(ctx, &name, saved).await,
+        None => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: SavedPlaylistNotFound { name: &name },
+                },
+            )
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn load(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_user_playlist"] name: String,
+    user: Option<UserId>,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let author = ctx.author().id;
+    let owner = user.unwrap_or(author);
+    // the others' playlists can be loaded only if they're shared
+    let saved = ctx.data().users.get(&owner).and_then(|user_prefs| {
+        user_prefs
+            .saved_playlists
+            .get(&name)
+            .filter(|saved| owner == author || saved.shared)
+            .cloned()
+    });
+    match saved {
+        Some(saved) => queue_saved(ctx, &name, saved).await,
+        // the private ones are taken as not found, so their names aren't exposed
+        None => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: SavedPlaylistNotFound { name: &name },
+                },
+            )
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+async fn queue_saved(ctx: Context<'_>, name: &str, saved: SavedPlaylist) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let mut yt_playlist = YouTubePlaylist::default();
+    yt_playlist.title = Some(name.to_owned());
+    yt_playlist.extend(saved.items);
+    let priority = author_priority(ctx).await;
+    add_to_playlist(
+        &ctx.data().guilds,
+        ctx.guild_id().ok_or(TurtoError::NotInGuild)?,
+        QueueItemKind::Playlist(yt_playlist),
+        priority,
+        ctx.author().id,
+        QueueType::Back,
+    )?;
+    ctx.say(TurtoMessage {
+        locale,
+        kind: Queue { title: name },
+    })
+    .await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn merge(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_saved_playlist"] first: String,
+    #[autocomplete = "autocomplete_saved_playlist"] second: String,
+    new: String,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    if !is_valid_name(&new) {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: InvalidName {
+                    max: SAVED_PLAYLIST_NAME_MAX_LEN,
+                },
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let author = ctx.author().id;
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    let saved_playlists = &mut guild_data.saved_playlists;
+    let kind = match (saved_playlists.get(&first), saved_playlists.get(&second)) {
+        (None, _) => SavedPlaylistNotFound { name: &first },
+        (_, None) => SavedPlaylistNotFound { name: &second },
+        _ if saved_playlists.contains_key(&new) => SavedPlaylistExists { name: &new },
+        _ if saved_playlists.len() >= SAVED_PLAYLIST_LIMIT => SavedPlaylistLimit {
+            limit: SAVED_PLAYLIST_LIMIT,
+        },
+        (Some(first), Some(second)) => {
+            let merged = first.merge(second, author);
+            let count = merged.items.len();
+            saved_playlists.insert(new.clone(), merged);
+            SavedPlaylistSaved { name: &new, count }
+        }
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn shuffle(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_saved_playlist"] name: String,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let author = ctx.author().id;
+    let is_admin = is_admin(ctx).await;
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    let kind = match guild_data.saved_playlists.get_mut(&name) {
+        Some(saved) if saved.can_edit(author, is_admin) => {
+            saved.items.shuffle(&mut thread_rng());
+            SavedPlaylistShuffled { name: &name }
+        }
+        Some(_) => SavedPlaylistNoPermission { name: &name },
+        None => SavedPlaylistNotFound { name: &name },
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn list(ctx: Context<'_>, mine: Option<bool>) -> Result<(), Error> {
+    let lines = if mine.unwrap_or_default() {
+        ctx.data()
+            .users
+            .get(&ctx.author().id)
+            .map(|user_prefs| {
+                user_prefs
+                    .saved_playlists
+                    .iter()
+                    .map(|(name, saved)| {
+                        let mark = if saved.shared { "🔗 " } else { "" };
+                        format!("{}`{}` ({})", mark, name, saved.items.len())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
     } else {
-        // when the playlist has more than 250 items just display the total number of pages
-        let response = "📖".to_string() + &total_pages.to_emoji();
+        let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+        let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        guild_data
+            .saved_playlists
+            .iter()
+            .map(|(name, saved)| format!("`{}` ({})", name, saved.items.len()))
+            .collect::<Vec<_>>()
+    };
 
-        ctx.say(response).await?;
-        Ok(())
+    let response = if lines.is_empty() {
+        TurtoMessage {
+            locale: get_locale(ctx),
+            kind: EmptySavedPlaylists,
+        }
+        .to_string()
+    } else {
+        lines.join("\n")
+    };
+    ctx.say(response).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn delete(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_saved_playlist"] name: String,
+    mine: Option<bool>,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let author = ctx.author().id;
+    if mine.unwrap_or_default() {
+        let removed = ctx
+            .data()
+            .users
+            .get_mut(&author)
+            .and_then(|mut user_prefs| user_prefs.saved_playlists.remove(&name));
+        let kind = match removed {
+            Some(_) => SavedPlaylistDeleted { name: &name },
+            None => SavedPlaylistNotFound { name: &name },
+        };
+        ctx.say(TurtoMessage { locale, kind }).await?;
+        return Ok(());
     }
+
+    let is_admin = is_admin(ctx).await;
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    let saved_playlists = &mut guild_data.saved_playlists;
+    let kind = match saved_playlists.get(&name) {
+        Some(saved) if saved.can_manage(author, is_admin) => {
+            saved_playlists.remove(&name);
+            SavedPlaylistDeleted { name: &name }
+        }
+        Some(_) => SavedPlaylistNoPermission { name: &name },
+        None => SavedPlaylistNotFound { name: &name },
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn editor(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_saved_playlist"] name: String,
+    user: UserId,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let author = ctx.author().id;
+    let is_admin = is_admin(ctx).await;
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    let kind = match guild_data.saved_playlists.get_mut(&name) {
+        Some(saved) if saved.can_manage(author, is_admin) => {
+            // toggle the editor
+            let added = saved.editors.insert(user);
+            if !added {
+                saved.editors.remove(&user);
+            }
+            SavedPlaylistEditor {
+                name: &name,
+                user,
+                added,
+            }
+        }
+        Some(_) => SavedPlaylistNoPermission { name: &name },
+        None => SavedPlaylistNotFound { name: &name },
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn public(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_saved_playlist"] name: String,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let author = ctx.author().id;
+    let is_admin = is_admin(ctx).await;
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    let kind = match guild_data.saved_playlists.get_mut(&name) {
+        Some(saved) if saved.can_manage(author, is_admin) => {
+            saved.public = !saved.public;
+            SavedPlaylistPublic {
+                name: &name,
+                public: saved.public,
+            }
+        }
+        Some(_) => SavedPlaylistNoPermission { name: &name },
+        None => SavedPlaylistNotFound { name: &name },
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn share(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_user_playlist"] name: String,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let mut user_prefs = ctx.data().users.entry(ctx.author().id).or_default();
+    let kind = match user_prefs.saved_playlists.get_mut(&name) {
+        Some(saved) => {
+            saved.shared = !saved.shared;
+            SavedPlaylistShared {
+                name: &name,
+                shared: saved.shared,
+            }
+        }
+        None => SavedPlaylistNotFound { name: &name },
+    };
+    drop(user_prefs);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+pub(super) async fn autocomplete_saved_playlist<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let names = ctx
+        .guild_id()
+        .map(|guild_id| {
+            let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+            guild_data
+                .saved_playlists
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let partial = partial.to_lowercase();
+    // discord allows at most 25 choices
+    names
+        .into_iter()
+        .filter(move |name| name.to_lowercase().starts_with(&partial))
+        .take(25)
+}
+
+async fn autocomplete_user_playlist<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    // the shared ones of the user in the `user` option are suggested if it's filled first
+    let author = ctx.author().id;
+    let owner = user_option(ctx).unwrap_or(author);
+    let names = ctx
+        .data()
+        .users
+        .get(&owner)
+        .map(|user_prefs| {
+            user_prefs
+                .saved_playlists
+                .iter()
+                .filter(|(_, saved)| owner == author || saved.shared)
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let partial = partial.to_lowercase();
+    names
+        .into_iter()
+        .filter(move |name| name.to_lowercase().starts_with(&partial))
+        .take(25)
+}
+
+/// The user given in the `user` option of the command being autocompleted
+fn user_option(ctx: Context<'_>) -> Option<UserId> {
+    let Context::Application(ctx) = ctx else {
+        return None;
+    };
+    ctx.args
+        .iter()
+        .find(|option| option.name == "user")
+        .and_then(|option| match option.value {
+            ResolvedValue::User(user, _) => Some(user.id),
+            ResolvedValue::Unresolved(Unresolved::User(user_id)) => Some(user_id),
+            _ => None,
+        })
 }
 
 fn generate_playlist_str(playlist: &Playlist, page_index: usize) -> Option<String> {
@@ -128,6 +733,12 @@ fn generate_playlist_str(playlist: &Playlist, page_index: usize) -> Option<Strin
             let mut line = (index + 1).to_string() + ". ";
             line.push(' ');
             line.push_str(&playlist_item.title);
+            // live streams have no duration
+            if playlist_item.duration.is_zero() {
+                line.push_str(" 🔴");
+            } else {
+                line.push_str(&format!(" `{}`", format_duration(playlist_item.duration)));
+            }
             line
         })
         .fold(String::new(), |acc, title| acc + &title + "\n")
@@ -136,14 +747,146 @@ fn generate_playlist_str(playlist: &Playlist, page_index: usize) -> Option<Strin
     Some(res)
 }
 
-fn generate_page_select_menu(playlist: &Playlist, custom_id: impl Into<String>) -> CreateActionRow {
-    let options = (1..=playlist.total_pages())
-        .map(|index| {
-            CreateSelectMenuOption::new("📄".to_string() + &index.to_emoji(), index.to_string())
-        })
-        .collect::<Vec<_>>();
-    CreateActionRow::SelectMenu(
-        CreateSelectMenu::new(custom_id, CreateSelectMenuKind::String { options })
+/// How the pages of the playlist are turned, the custom ids of the components are the id of the
+/// command followed by the names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PageAction {
+    Previous,
+    Next,
+    /// Back to the first page, where the track playing is shown
+    Playing,
+    /// Ask for the page with a modal
+    Goto,
+    Select(usize),
+}
+
+impl PageAction {
+    const PREVIOUS: &'static str = "previous";
+    const NEXT: &'static str = "next";
+    const PLAYING: &'static str = "playing";
+    const GOTO: &'static str = "goto";
+    const SELECT: &'static str = "select";
+
+    fn parse(name: &str, kind: &ComponentInteractionDataKind) -> Option<Self> {
+        match name {
+            Self::PREVIOUS => Some(Self::Previous),
+            Self::NEXT => Some(Self::Next),
+            Self::PLAYING => Some(Self::Playing),
+            Self::GOTO => Some(Self::Goto),
+            Self::SELECT => match kind {
+                ComponentInteractionDataKind::StringSelect { values } => values
+                    .first()
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .map(Self::Select),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The page shown after the action, which is always one of the pages
+    fn turn(self, page: usize, total_pages: usize) -> usize {
+        let page = match self {
+            Self::Previous => page.saturating_sub(1),
+            Self::Next => page + 1,
+            Self::Playing => 1,
+            Self::Goto => page,
+            Self::Select(page) => page,
+        };
+        page.clamp(1, total_pages.max(1))
+    }
+}
+
+#[derive(Debug, poise::Modal)]
+#[name = "📖"]
+struct GotoPageModal {
+    #[name = "📄"]
+    #[min_length = 1]
+    #[max_length = 6]
+    page: String,
+}
+
+/// The page of the playlist with the page number, the first one starts with the track playing
+async fn playlist_page(ctx: Context<'_>, page: usize) -> Option<String> {
+    let guild_id = ctx.guild_id()?;
+    let playing = match page {
+        1 => ctx
+            .data()
+            .playing
+            .read()
+            .await
+            .get(&guild_id)
+            .and_then(|playing| playing.metadata.title.clone()),
+        _ => None,
+    };
+    let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    let mut content = String::new();
+    let total_pages = guild_data.playlist.total_pages();
+    if total_pages > 1 {
+        content.push_str(&format!("📖 {}/{}\n", page, total_pages));
+    }
+    if let Some(title) = playing {
+        content.push_str(&format!("▶️ {}\n", title));
+    }
+    content.push_str(&generate_playlist_str(&guild_data.playlist, page)?);
+    Some(content)
+}
+
+fn page_components(id_prefix: &str, page: usize, total_pages: usize) -> Vec<CreateActionRow> {
+    let button = |name: &str, emoji: char| {
+        CreateButton::new(format!("{}{}", id_prefix, name))
+            .emoji(emoji)
+            .style(ButtonStyle::Secondary)
+    };
+    let mut components = vec![CreateActionRow::Buttons(vec![
+        button(PageAction::PREVIOUS, '◀').disabled(page <= 1),
+        button(PageAction::NEXT, '▶').disabled(page >= total_pages),
+        button(PageAction::GOTO, '🔢'),
+        button(PageAction::PLAYING, '🎵'),
+    ])];
+    // discord allows at most 25 options
+    if total_pages <= 25 {
+        let options = (1..=total_pages)
+            .map(|index| {
+                CreateSelectMenuOption::new("📄".to_string() + &index.to_emoji(), index.to_string())
+                    .default_selection(index == page)
+            })
+            .collect::<Vec<_>>();
+        components.push(CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(
+                format!("{}{}", id_prefix, PageAction::SELECT),
+                CreateSelectMenuKind::String { options },
+            )
             .placeholder("📖❓"),
-    )
+        ));
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PageAction;
+    use serenity::all::ComponentInteractionDataKind;
+
+    #[test]
+    fn test_page_action() {
+        let button = ComponentInteractionDataKind::Button;
+        assert_eq!(PageAction::parse("next", &button), Some(PageAction::Next));
+        assert_eq!(PageAction::parse("select", &button), None);
+        let select = ComponentInteractionDataKind::StringSelect {
+            values: vec!["7".to_owned()],
+        };
+        assert_eq!(
+            PageAction::parse("select", &select),
+            Some(PageAction::Select(7))
+        );
+
+        assert_eq!(PageAction::Previous.turn(1, 60), 1);
+        assert_eq!(PageAction::Next.turn(59, 60), 60);
+        assert_eq!(PageAction::Next.turn(60, 60), 60);
+        assert_eq!(PageAction::Playing.turn(42, 60), 1);
+        // the playlist got shorter while the pages were shown
+        assert_eq!(PageAction::Select(25).turn(3, 10), 10);
+        assert_eq!(PageAction::Next.turn(3, 0), 1);
+    }
 }
@@ -1,26 +1,34 @@
 use crate::{
+    handlers::reaction::ReactionControl,
     messages::{
         TurtoMessage,
-        TurtoMessageKind::{NotPlaying, Pause, Play},
+        TurtoMessageKind::{Live, NotPlaying, Pause, Play},
     },
-    models::alias::{Context, Error},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{bookmark::BOOKMARK_BUTTON_ID, cleanup::track_reply, get_locale, play::is_live, say},
 };
 use poise::CreateReply;
-use serenity::builder::CreateEmbed;
+use serenity::builder::{CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter};
 use songbird::tracks::PlayMode;
-use tracing::error;
+use tracing::{error, warn};
 
 #[poise::command(slash_command, guild_only)]
 pub async fn playwhat(ctx: Context<'_>) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
-    let locale = ctx.locale();
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let locale = get_locale(ctx);
 
     let playing_map = ctx.data().playing.read().await;
     let Some(playing) = playing_map.get(&guild_id) else {
-        ctx.say(TurtoMessage {
-            locale,
-            kind: NotPlaying,
-        })
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: NotPlaying,
+            },
+        )
         .await?;
         return Ok(());
     };
@@ -37,20 +45,26 @@ pub async fn playwhat(ctx: Context<'_>) -> Result<(), Error> {
                 kind: Pause { title: &title },
             },
             _ => {
-                ctx.say(TurtoMessage {
-                    locale,
-                    kind: NotPlaying,
-                })
+                say(
+                    ctx,
+                    TurtoMessage {
+                        locale,
+                        kind: NotPlaying,
+                    },
+                )
                 .await?;
                 return Ok(());
             }
         },
         Err(err) => {
             error!("Error getting track: {err}");
-            ctx.say(TurtoMessage {
-                locale,
-                kind: NotPlaying,
-            })
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: NotPlaying,
+                },
+            )
             .await?;
             return Ok(());
         }
@@ -60,16 +74,50 @@ pub async fn playwhat(ctx: Context<'_>) -> Result<(), Error> {
     if let Some(url) = &playing.metadata.source_url {
         embed = embed.url(url);
     }
-    if let Some(channel) = playing.metadata.artist.as_deref().or(playing.metadata.channel.as_deref()) {
+    if let Some(channel) = playing
+        .metadata
+        .artist
+        .as_deref()
+        .or(playing.metadata.channel.as_deref())
+    {
         embed = embed.description(channel);
     }
     if let Some(thumbnail) = &playing.metadata.thumbnail {
         embed = embed.image(thumbnail);
     }
+    if is_live(&playing.metadata) {
+        embed = embed.footer(CreateEmbedFooter::new(
+            TurtoMessage { locale, kind: Live }.to_string(),
+        ));
+    }
     drop(playing_map);
 
-    let response = CreateReply::default().embed(embed);
-    ctx.send(response).await?;
+    // anyone can bookmark the track with the button, it's handled by the event handler
+    let bookmark_button = CreateButton::new(BOOKMARK_BUTTON_ID).emoji('🔖');
+    let response = CreateReply::default()
+        .embed(embed)
+        .components(vec![CreateActionRow::Buttons(vec![bookmark_button])]);
+    let reply = ctx.send(response).await?;
+    track_reply(ctx, &reply).await;
+
+    let reaction_controls = ctx
+        .data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .config
+        .reaction_controls;
+    if reaction_controls {
+        // only the latest now playing message takes the reactions
+        let message = reply.message().await?;
+        ctx.data().control_messages.insert(guild_id, message.id);
+        for control in ReactionControl::ALL {
+            if let Err(err) = message.react(ctx, control.reaction_type()).await {
+                warn!("Failed to add the reaction controls: {}", err);
+                break;
+            }
+        }
+    }
 
     Ok(())
 }
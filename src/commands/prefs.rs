@@ -0,0 +1,86 @@
+use crate::{
+    config::message_template::get_locales,
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            InvalidLocale, PrefsLocale, PrefsNotifyTrackStart, PrefsSearchSource, PrefsTitle,
+            SettingsNotSet,
+        },
+    },
+    models::{
+        alias::{Context, Error},
+        toggle::ToggleOption,
+        user_prefs::SearchSource,
+    },
+    utils::{get_locale, say},
+};
+use poise::{ChoiceParameter, CreateReply};
+use serenity::builder::CreateEmbed;
+
+/// The value of `locale` which unsets the preferred locale
+const DEFAULT_LOCALE: &str = "default";
+
+#[poise::command(slash_command)]
+pub async fn prefs(
+    ctx: Context<'_>,
+    notify: Option<ToggleOption>,
+    search: Option<SearchSource>,
+    #[autocomplete = "autocomplete_locale"] locale: Option<String>,
+) -> Result<(), Error> {
+    if let Some(locale) = &locale {
+        let is_available = locale == DEFAULT_LOCALE
+            || get_locales().any(|supported| supported.eq_ignore_ascii_case(locale));
+        if !is_available {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale: get_locale(ctx),
+                    kind: InvalidLocale { locale },
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+    }
+
+    let mut prefs = ctx.data().users.entry(ctx.author().id).or_default();
+    if let Some(notify) = notify {
+        prefs.notify_track_start = matches!(notify, ToggleOption::On);
+    }
+    if let Some(search) = search {
+        prefs.search_source = search;
+    }
+    if let Some(locale) = locale {
+        prefs.locale = (locale != DEFAULT_LOCALE).then_some(locale);
+    }
+    let notify_track_start = prefs.notify_track_start;
+    let search_source = prefs.search_source;
+    let preferred_locale = prefs.locale.clone();
+    // the locale is read from the preferences, which can't be locked at the same time
+    drop(prefs);
+
+    let locale = get_locale(ctx);
+    let message = |kind| TurtoMessage { locale, kind }.to_string();
+    let notify_str = if notify_track_start { "✅" } else { "❎" };
+    let embed = CreateEmbed::new()
+        .title(message(PrefsTitle))
+        .field(message(PrefsNotifyTrackStart), notify_str, true)
+        .field(message(PrefsSearchSource), search_source.name(), true)
+        .field(
+            message(PrefsLocale),
+            preferred_locale.unwrap_or_else(|| message(SettingsNotSet)),
+            true,
+        );
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+async fn autocomplete_locale<'a>(
+    _ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = &'static str> + 'a {
+    std::iter::once(DEFAULT_LOCALE)
+        .chain(get_locales())
+        .filter(move |locale| locale.starts_with(&partial.to_ascii_lowercase()))
+}
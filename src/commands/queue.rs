@@ -1,9 +1,596 @@
 use crate::{
-    models::alias::{Context, Error},
-    utils::queue::{enqueue, QueueType},
+    commands::playlist::autocomplete_saved_playlist,
+    config::get_config,
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            InvalidName, JobWaiting, QueueBulkCanceled, QueueBulkDone, QueueBulkInvalidFile,
+            QueueBulkProgress, QueueSnapshotLimit, QueueSnapshotNotFound, QueueSnapshotRestored,
+            QueueSnapshotSaved, SavedPlaylistLimit, SavedPlaylistNoPermission, SavedPlaylistSaved,
+        },
+    },
+    models::{
+        alias::{ApplicationContext, Context, Error},
+        error::TurtoError,
+        guild::{
+            saved_playlist::{
+                is_valid_name, SavedPlaylist, SAVED_PLAYLIST_LIMIT, SAVED_PLAYLIST_NAME_MAX_LEN,
+            },
+            snapshot::{QueueSnapshot, SnapshotTrack, SNAPSHOT_LIMIT},
+        },
+        invocation::Phase::{Discord, Ytdl},
+        playlist::Playlist,
+        queue_item::{QueueItem, QueueItemKind},
+    },
+    utils::{
+        explicit::{filter_explicit, should_filter},
+        get_locale,
+        guild::{GuildUtil, VoiceChannelState},
+        is_admin,
+        jobs::new_job,
+        misc::unix_now,
+        play::play_url,
+        queue::{add_to_playlist, author_priority, enqueue, QueueType},
+        resolve_query, say,
+        suggest::autocomplete_search,
+        timing::timed,
+        url::parse_web_url,
+    },
 };
+use poise::{execute_modal, CreateReply};
+use serenity::{all::Attachment, builder::CreateAttachment};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// A list of URLs is small even with hundreds of lines
+const MAX_BULK_SIZE: u32 = 256 * 1024;
+const MAX_BULK_URLS: usize = 500;
+/// Editing the message for every URL easily hits the rate limit
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(3);
+const PASTE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("add", "bulk", "paste", "snapshot", "restore")
+)]
+pub async fn queue(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn add(
+    ctx: Context<'_>,
+    #[rename = "url"]
+    #[autocomplete = "autocomplete_search"]
+    query: String,
+    items: Option<String>,
+) -> Result<(), Error> {
+    enqueue(ctx, query, items, QueueType::Back).await
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn bulk(ctx: Context<'_>, file: Attachment) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let is_list = [".txt", ".csv"]
+        .iter()
+        .any(|extension| file.filename.to_lowercase().ends_with(extension));
+    let content = if is_list && file.size <= MAX_BULK_SIZE {
+        timed(ctx, Discord, ctx.defer()).await?;
+        file.download()
+            .await
+            .ok()
+            .and_then(|content| String::from_utf8(content).ok())
+    } else {
+        None
+    };
+    let Some(content) = content else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: QueueBulkInvalidFile {
+                    size: MAX_BULK_SIZE / 1024,
+                },
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let (urls, failed) = parse_bulk(&content);
+    queue_lines(ctx, urls, failed, None).await
+}
+
+#[derive(Debug, poise::Modal)]
+#[name = "Queue"]
+struct PasteModal {
+    #[name = "Links or keywords, one on each line"]
+    #[paragraph]
+    #[max_length = 4000]
+    content: String,
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn paste(
+    ctx: ApplicationContext<'_>,
+    #[autocomplete = "autocomplete_saved_playlist"] save_as: Option<String>,
+) -> Result<(), Error> {
+    let Some(modal) = execute_modal::<_, _, PasteModal>(ctx, None, Some(PASTE_TIMEOUT)).await?
+    else {
+        return Ok(());
+    };
+    let ctx = Context::from(ctx);
+    let locale = get_locale(ctx);
+
+    // check before resolving the lines, which can take a while
+    if let Some(name) = &save_as {
+        let author = ctx.author().id;
+        let is_admin = is_admin(ctx).await;
+        let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+        let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        let kind = if !is_valid_name(name) {
+            Some(InvalidName {
+                max: SAVED_PLAYLIST_NAME_MAX_LEN,
+            })
+        } else {
+            match guild_data.saved_playlists.get(name) {
+                Some(saved) if !saved.can_edit(author, is_admin) => {
+                    Some(SavedPlaylistNoPermission { name })
+                }
+                Some(_) => None,
+                None if guild_data.saved_playlists.len() >= SAVED_PLAYLIST_LIMIT => {
+                    Some(SavedPlaylistLimit {
+                        limit: SAVED_PLAYLIST_LIMIT,
+                    })
+                }
+                None => None,
+            }
+        };
+        drop(guild_data);
+        if let Some(kind) = kind {
+            ctx.say(TurtoMessage { locale, kind }).await?;
+            return Ok(());
+        }
+    }
+
+    let (lines, failed) = parse_paste(&modal.content);
+    queue_lines(ctx, lines, failed, save_as.as_deref()).await
+}
+
+/// Queue the lines one by one with the progress shown, or save them as a saved playlist
+/// `save_as` instead, the lines that failed are listed in the end. It's run as a job of the guild,
+/// so it waits for the other jobs and it can be canceled with `/jobs cancel`
+pub(super) async fn queue_lines(
+    ctx: Context<'_>,
+    urls: Vec<Line>,
+    mut failed: Vec<Line>,
+    save_as: Option<&str>,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let total = urls.len();
+    let mut handle = new_job(guild_id, ctx.author().id, total);
+    let job = handle.job.clone();
+    let progress = |done| {
+        CreateReply::default().content(TurtoMessage {
+            locale,
+            kind: QueueBulkProgress {
+                done,
+                total,
+                job: job.id,
+            },
+        })
+    };
+    let limit = get_config().guild_jobs;
+    let (reply, mut last_update) = if handle.try_start(limit) {
+        (ctx.send(progress(0)).await?, Some(Instant::now()))
+    } else {
+        let waiting = CreateReply::default().content(TurtoMessage {
+            locale,
+            kind: JobWaiting { job: job.id },
+        });
+        // the progress is shown as soon as it starts
+        (ctx.send(waiting).await?, None)
+    };
+    let started = handle.wait_turn(limit).await;
+
+    let priority = author_priority(ctx).await;
+    let filter = should_filter(ctx).await;
+    let mut queued = 0;
+    let mut skipped = 0;
+    let mut full = None;
+    let mut saved_items = save_as.map(|_| Vec::new());
+    for (done, (line, url)) in urls.into_iter().enumerate() {
+        if !started || job.is_canceled() {
+            skipped = total - done;
+            break;
+        }
+        job.set_done(done);
+        let outdated = match last_update {
+            Some(last_update) => last_update.elapsed() >= PROGRESS_INTERVAL,
+            None => true,
+        };
+        if outdated {
+            reply.edit(ctx, progress(done)).await?;
+            last_update = Some(Instant::now());
+        }
+        let Ok(queue_item_kind) =
+            timed(ctx, Ytdl, QueueItem::new(resolve_query(ctx, &url)).query()).await
+        else {
+            failed.push((line, url));
+            continue;
+        };
+        let queue_item_kind = if filter {
+            match timed(ctx, Ytdl, filter_explicit(queue_item_kind)).await {
+                (Some(queue_item_kind), _) => queue_item_kind,
+                (None, _) => {
+                    failed.push((line, url));
+                    continue;
+                }
+            }
+        } else {
+            queue_item_kind
+        };
+        if let Some(saved_items) = &mut saved_items {
+            match queue_item_kind {
+                QueueItemKind::Single(playlist_item) => saved_items.push(playlist_item),
+                QueueItemKind::Playlist(yt_playlist) => saved_items.extend(yt_playlist),
+            }
+            queued += 1;
+            continue;
+        }
+        match add_to_playlist(
+            &ctx.data().guilds,
+            guild_id,
+            queue_item_kind,
+            priority,
+            ctx.author().id,
+            QueueType::Back,
+        ) {
+            Ok(_) => queued += 1,
+            Err(
+                err @ (TurtoError::QueueFull { .. }
+                | TurtoError::QueueDurationFull { .. }
+                | TurtoError::UserDurationFull { .. }
+                | TurtoError::QueueLocked),
+            ) => {
+                full = Some(err);
+                break;
+            }
+            // never played again in the guild
+            Err(_) => failed.push((line, url)),
+        }
+    }
+    failed.sort_unstable_by_key(|(line, _)| *line);
+
+    // the permission and the limit are checked before resolving the lines
+    let saved = match (save_as, saved_items) {
+        (Some(name), Some(saved_items)) if !saved_items.is_empty() => {
+            let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+            let count = match guild_data.saved_playlists.get_mut(name) {
+                Some(saved) => {
+                    saved.items.clear();
+                    saved.extend(saved_items)
+                }
+                None => {
+                    let saved = SavedPlaylist::new(ctx.author().id, saved_items);
+                    let count = saved.items.len();
+                    guild_data.saved_playlists.insert(name.to_owned(), saved);
+                    count
+                }
+            };
+            Some((name, count))
+        }
+        _ => None,
+    };
+
+    let mut summary = TurtoMessage {
+        locale,
+        kind: QueueBulkDone {
+            queued,
+            failed: failed.len(),
+        },
+    }
+    .to_string();
+    if let Some((name, count)) = saved {
+        summary.push('\n');
+        summary.push_str(
+            &TurtoMessage {
+                locale,
+                kind: SavedPlaylistSaved { name, count },
+            }
+            .to_string(),
+        );
+    }
+    if skipped > 0 {
+        summary.push('\n');
+        summary.push_str(
+            &TurtoMessage {
+                locale,
+                kind: QueueBulkCanceled { skipped },
+            }
+            .to_string(),
+        );
+    }
+    if let Some(message) = full.as_ref().and_then(|err| err.message(locale)) {
+        summary.push('\n');
+        summary.push_str(&message.to_string());
+    }
+    let failures = failed
+        .iter()
+        .map(|(line, url)| format!("{}: {}", line, url))
+        .collect::<Vec<_>>()
+        .join("\n");
+    // discord text message has a length limitation of 2000 unicode chars
+    let response = if failures.is_empty() {
+        CreateReply::default().content(summary)
+    } else if summary.chars().count() + failures.chars().count() + 10 <= 2000 {
+        CreateReply::default().content(format!("{}\n```\n{}\n```", summary, failures))
+    } else {
+        CreateReply::default()
+            .content(summary)
+            .attachment(CreateAttachment::bytes(failures, "failed.txt"))
+    };
+    reply.edit(ctx, response).await?;
+    Ok(())
+}
 
 #[poise::command(slash_command, guild_only)]
-pub async fn queue(ctx: Context<'_>, #[rename = "url"] query: String) -> Result<(), Error> {
-    enqueue(ctx, query, QueueType::Back).await
+pub async fn snapshot(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_snapshot"] name: String,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    if !is_valid_name(&name) {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: InvalidName {
+                    max: SAVED_PLAYLIST_NAME_MAX_LEN,
+                },
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let current = match ctx.data().playing.read().await.get(&guild_id) {
+        Some(playing) => Some(SnapshotTrack::from_playing(playing).await),
+        None => None,
+    };
+
+    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    let kind = if guild_data.snapshots.len() >= SNAPSHOT_LIMIT
+        && !guild_data.snapshots.contains_key(&name)
+    {
+        QueueSnapshotLimit {
+            limit: SNAPSHOT_LIMIT,
+        }
+    } else {
+        let snapshot = QueueSnapshot {
+            playlist: guild_data.playlist.iter().cloned().collect(),
+            current,
+            repeat: guild_data.config.repeat,
+            volume: guild_data.config.volume,
+            timestamp: unix_now(),
+        };
+        let count = snapshot.items().len();
+        // taking a snapshot with the same name again overwrites it
+        guild_data.snapshots.insert(name.clone(), snapshot);
+        QueueSnapshotSaved { name: &name, count }
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn restore(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_snapshot"] name: String,
+) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let data = ctx.data();
+    let Some(snapshot) = data
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .snapshots
+        .get(&name)
+        .cloned()
+    else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: QueueSnapshotNotFound { name: &name },
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    {
+        let mut guild_data = data.guilds.entry(guild_id).or_default();
+        guild_data.playlist = Playlist::from(snapshot.items());
+        guild_data.config.repeat = snapshot.repeat;
+        guild_data.config.volume = snapshot.volume;
+    }
+    if let Some(playing) = data.playing.read().await.get(&guild_id) {
+        let volume = data
+            .guilds
+            .entry(guild_id)
+            .or_default()
+            .track_volume(&playing.url);
+        let _ = playing.track_handle.set_volume(volume);
+    }
+
+    // resume the current item right away if the bot is playing for the author, otherwise it's
+    // played from the start by the next `/play`
+    let bot_id = ctx.cache().current_user().id;
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &ctx.author().id);
+    let call = songbird::get(ctx.serenity_context())
+        .await
+        .ok_or(TurtoError::VoiceUnavailable)?
+        .get(guild_id);
+    if let (Some(current), VoiceChannelState::Same(_), Some(call)) =
+        (snapshot.current, vc_stat, call)
+    {
+        timed(ctx, Discord, ctx.defer()).await?;
+        let item = data
+            .guilds
+            .entry(guild_id)
+            .or_default()
+            .playlist
+            .pop_front();
+        if let Some(item) = item {
+            timed(
+                ctx,
+                Ytdl,
+                play_url(
+                    call,
+                    data.guilds.clone(),
+                    data.playing.clone(),
+                    guild_id,
+                    item.url,
+                    item.requester,
+                ),
+            )
+            .await?;
+            if current.position > 0 {
+                if let Some(playing) = data.playing.read().await.get(&guild_id) {
+                    // the ones which can't be seeked, like live streams, start over
+                    let position = Duration::from_secs(current.position);
+                    if let Err(err) = playing.track_handle.seek_async(position).await {
+                        warn!(
+                            "Failed to resume the restored track at {:?}: {}",
+                            position, err
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    ctx.say(TurtoMessage {
+        locale,
+        kind: QueueSnapshotRestored { name: &name },
+    })
+    .await?;
+    Ok(())
+}
+
+async fn autocomplete_snapshot<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let names = ctx
+        .guild_id()
+        .map(|guild_id| {
+            let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+            guild_data.snapshots.keys().cloned().collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let partial = partial.to_lowercase();
+    // discord allows at most 25 choices
+    names
+        .into_iter()
+        .filter(move |name| name.to_lowercase().starts_with(&partial))
+        .take(25)
+}
+
+/// A line of the file with its line number
+pub(super) type Line = (usize, String);
+
+/// Parse the pasted lines into the queries to queue and the ones over the limit, unlike a file,
+/// the keywords to search are allowed and the lines are taken as a whole
+fn parse_paste(content: &str) -> (Vec<Line>, Vec<Line>) {
+    let mut queries = Vec::new();
+    let mut over = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if queries.len() < MAX_BULK_URLS {
+            queries.push((index + 1, line.to_owned()));
+        } else {
+            over.push((index + 1, line.to_owned()));
+        }
+    }
+    (queries, over)
+}
+
+/// Parse the lines of the file into the URLs to queue and the invalid ones, with their line numbers,
+/// the first column is taken for a csv file, empty lines and lines starting with `#` are skipped
+fn parse_bulk(content: &str) -> (Vec<Line>, Vec<Line>) {
+    let mut urls = Vec::new();
+    let mut invalid = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let field = line
+            .split(',')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .trim_matches('"');
+        if parse_web_url(field).is_some() && urls.len() < MAX_BULK_URLS {
+            urls.push((index + 1, field.to_owned()));
+        } else {
+            invalid.push((index + 1, field.to_owned()));
+        }
+    }
+    (urls, invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_bulk, parse_paste};
+
+    #[test]
+    fn test_parse_paste() {
+        let content =
+            "https://youtu.be/dQw4w9WgXcQ\n\n  never gonna give you up, rick astley  \n# comment\n";
+        let (queries, over) = parse_paste(content);
+        assert_eq!(
+            queries,
+            vec![
+                (1, "https://youtu.be/dQw4w9WgXcQ".to_owned()),
+                (3, "never gonna give you up, rick astley".to_owned()),
+            ]
+        );
+        assert!(over.is_empty());
+    }
+
+    #[test]
+    fn test_parse_bulk() {
+        let content = "# exported playlist\nhttps://youtu.be/dQw4w9WgXcQ\n\n\"https://soundcloud.com/a/b\",Title\nnot a url\nftp://example.com/a.mp3\n";
+        let (urls, invalid) = parse_bulk(content);
+        assert_eq!(
+            urls,
+            vec![
+                (2, "https://youtu.be/dQw4w9WgXcQ".to_owned()),
+                (4, "https://soundcloud.com/a/b".to_owned()),
+            ]
+        );
+        assert_eq!(
+            invalid,
+            vec![
+                (5, "not a url".to_owned()),
+                (6, "ftp://example.com/a.mp3".to_owned()),
+            ]
+        );
+    }
 }
@@ -0,0 +1,82 @@
+use crate::{
+    commands::queue::{queue_lines, Line},
+    messages::{TurtoMessage, TurtoMessageKind::NoUrlsInMessage},
+    models::alias::{Context, Error},
+    utils::{get_locale, say},
+};
+use regex::Regex;
+use serenity::all::Message;
+use std::{collections::HashSet, sync::OnceLock};
+
+#[poise::command(context_menu_command = "Queue this", guild_only)]
+pub async fn queue_this(ctx: Context<'_>, message: Message) -> Result<(), Error> {
+    let mut urls = extract_urls(&message.content);
+    // the audio and video files are played from their urls
+    urls.extend(
+        message
+            .attachments
+            .iter()
+            .filter(|attachment| {
+                attachment
+                    .content_type
+                    .as_deref()
+                    .is_some_and(|content_type| {
+                        content_type.starts_with("audio/") || content_type.starts_with("video/")
+                    })
+            })
+            .map(|attachment| attachment.url.clone()),
+    );
+    if urls.is_empty() {
+        say(
+            ctx,
+            TurtoMessage {
+                locale: get_locale(ctx),
+                kind: NoUrlsInMessage,
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let lines = urls
+        .into_iter()
+        .enumerate()
+        .map(|(index, url)| (index + 1, url))
+        .collect::<Vec<Line>>();
+    queue_lines(ctx, lines, Vec::new(), None).await
+}
+
+/// The distinct http urls in the text, in the order they appear
+fn extract_urls(text: &str) -> Vec<String> {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    // discord suppresses the embed of the urls in `<>`, and markdown links end with `)`
+    let url = URL.get_or_init(|| Regex::new(r"https?://[^\s<>()\[\]`|]+").unwrap());
+    let mut seen = HashSet::new();
+    url.find_iter(text)
+        .map(|url| {
+            url.as_str()
+                .trim_end_matches(['.', ',', '!', '?', '*', '_', '~'])
+        })
+        .filter(|url| seen.insert(*url))
+        .map(str::to_owned)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_urls;
+
+    #[test]
+    fn test_extract_urls() {
+        let text = "check this out https://youtu.be/dQw4w9WgXcQ, and <https://soundcloud.com/a/b>\n[link](https://example.com/c?d=e) https://youtu.be/dQw4w9WgXcQ!";
+        assert_eq!(
+            extract_urls(text),
+            [
+                "https://youtu.be/dQw4w9WgXcQ",
+                "https://soundcloud.com/a/b",
+                "https://example.com/c?d=e",
+            ]
+        );
+        assert!(extract_urls("no links").is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::QueueLengthLimits},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{check_admin, get_locale},
+};
+
+/// The limits are capped at a week
+const MAX_LIMIT_MINUTES: u64 = 7 * 24 * 60;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn queuelength(
+    ctx: Context<'_>,
+    #[min = 0]
+    #[max = 10080]
+    total_minutes: Option<u64>,
+    #[min = 0]
+    #[max = 10080]
+    member_minutes: Option<u64>,
+) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let mut guild_data = ctx
+        .data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default();
+    let config = &mut guild_data.config;
+    // 0 removes the limit
+    if let Some(total_minutes) = total_minutes {
+        config.queue_duration_limit =
+            Some(total_minutes.min(MAX_LIMIT_MINUTES)).filter(|&minutes| minutes > 0);
+    }
+    if let Some(member_minutes) = member_minutes {
+        config.user_duration_limit =
+            Some(member_minutes.min(MAX_LIMIT_MINUTES)).filter(|&minutes| minutes > 0);
+    }
+    let kind = QueueLengthLimits {
+        total: config.queue_duration_limit,
+        member: config.user_duration_limit,
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind,
+    })
+    .await?;
+    Ok(())
+}
@@ -1,9 +1,13 @@
 use crate::{
     messages::{
         TurtoMessage,
-        TurtoMessageKind::{InvalidRemove, InvalidRangeRemove, Remove, RemoveMany},
+        TurtoMessageKind::{InvalidRangeRemove, InvalidRemove, Remove, RemoveMany},
     },
-    models::alias::{Context, Error},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{get_locale, say},
 };
 
 enum RemoveType {
@@ -25,9 +29,9 @@ pub async fn remove(
         None => RemoveType::Index(which - 1),
     };
 
-    let guild_id = ctx.guild_id().unwrap();
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
     let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
-    let locale = ctx.locale();
+    let locale = get_locale(ctx);
     let length = guild_data.playlist.len();
 
     match remove_item {
@@ -35,10 +39,13 @@ pub async fn remove(
             // Check if the index is out of bounds
             if index >= length {
                 drop(guild_data);
-                ctx.say(TurtoMessage {
-                    locale,
-                    kind: InvalidRemove { length },
-                })
+                say(
+                    ctx,
+                    TurtoMessage {
+                        locale,
+                        kind: InvalidRemove { length },
+                    },
+                )
                 .await?;
                 return Ok(());
             }
@@ -54,19 +61,21 @@ pub async fn remove(
             .await?;
         }
         RemoveType::Range { from, to } => {
-            // Check if the range is invalid
-            if from > to || length <= from || length <= to {
+            // `to` is inclusive and starts from 1, the range is checked by the playlist
+            let Some(drained) = guild_data.playlist.remove_range(from..=to - 1) else {
                 drop(guild_data);
-                ctx.say(TurtoMessage {
-                    locale,
-                    kind: InvalidRangeRemove { from, to, length },
-                })
+                say(
+                    ctx,
+                    TurtoMessage {
+                        locale,
+                        kind: InvalidRangeRemove { from, to, length },
+                    },
+                )
                 .await?;
                 return Ok(());
-            }
-            let drained = guild_data
-                .playlist
-                .drain(from..to)
+            };
+            let drained = drained
+                .iter()
                 .map(|drained_item| {
                     TurtoMessage {
                         locale,
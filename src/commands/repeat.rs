@@ -2,8 +2,10 @@ use crate::{
     messages::{TurtoMessage, TurtoMessageKind::SetRepeat},
     models::{
         alias::{Context, Error},
+        error::TurtoError,
         toggle::ToggleOption,
     },
+    utils::get_locale,
 };
 
 #[poise::command(slash_command, guild_only)]
@@ -16,13 +18,13 @@ pub async fn repeat(ctx: Context<'_>, toggle: ToggleOption) -> Result<(), Error>
     let mut guild_data = ctx
         .data()
         .guilds
-        .entry(ctx.guild_id().unwrap())
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
         .or_default();
     guild_data.config.repeat = toggle;
     drop(guild_data);
 
     ctx.say(TurtoMessage {
-        locale: ctx.locale(),
+        locale: get_locale(ctx),
         kind: SetRepeat(toggle),
     })
     .await?;
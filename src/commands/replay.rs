@@ -0,0 +1,122 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{BotNotInVoiceChannel, DifferentVoiceChannel, NotPlaying, Replay},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        invocation::Phase::{Discord, Ytdl},
+    },
+    utils::{
+        cleanup::track_reply,
+        get_locale,
+        guild::{GuildUtil, VoiceChannelState},
+        play::play_url,
+        say,
+        timing::timed,
+        vote::vote_move,
+    },
+};
+use std::time::Duration;
+use tracing::warn;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn replay(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let bot_id = ctx.cache().current_user().id;
+    let user_id = ctx.author().id;
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &user_id);
+    let locale = get_locale(ctx);
+
+    match vc_stat {
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlyFirst(bot_vc) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: DifferentVoiceChannel { bot: bot_vc },
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlySecond(_) | VoiceChannelState::None => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: BotNotInVoiceChannel,
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+        VoiceChannelState::Same(_) => (),
+    }
+
+    let data = ctx.data();
+    let playing = data.playing.read().await.get(&guild_id).map(|playing| {
+        (
+            playing.track_handle.clone(),
+            playing.url.clone(),
+            playing.requester,
+            playing.metadata.title.clone().unwrap_or_default(),
+        )
+    });
+    let (Some((track_handle, url, requester, title)), Some(call)) = (
+        playing,
+        songbird::get(ctx.serenity_context())
+            .await
+            .ok_or(TurtoError::VoiceUnavailable)?
+            .get(guild_id),
+    ) else {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: NotPlaying,
+            },
+        )
+        .await?;
+        return Ok(());
+    };
+
+    timed(ctx, Discord, ctx.defer()).await?;
+    // the filtered tracks and the live streams can't be seeked, they're played from the source again
+    if let Err(err) = track_handle.seek_async(Duration::ZERO).await {
+        warn!(
+            "Failed to seek {} back to the start, playing it again: {}",
+            url, err
+        );
+        timed(
+            ctx,
+            Ytdl,
+            play_url(
+                call,
+                data.guilds.clone(),
+                data.playing.clone(),
+                guild_id,
+                url,
+                requester,
+            ),
+        )
+        .await?;
+    }
+
+    let reply = ctx
+        .say(TurtoMessage {
+            locale,
+            kind: Replay { title: &title },
+        })
+        .await?;
+    track_reply(ctx, &reply).await;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::{
+    config::get_config,
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{RequestChannelOff, RequestChannelsDisabled, SetRequestChannel},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{check_admin, get_locale},
+};
+use serenity::all::GuildChannel;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn requestchannel(ctx: Context<'_>, channel: Option<GuildChannel>) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    if !get_config().request_channels {
+        ctx.say(TurtoMessage {
+            locale,
+            kind: RequestChannelsDisabled,
+        })
+        .await?;
+        return Ok(());
+    }
+
+    let channel = channel.map(|channel| channel.id);
+    {
+        let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+        let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        guild_data.config.request_channel = channel;
+        // the player of the previous channel is left as it is, the new one is posted after the
+        // command
+        guild_data.player_message = None;
+    }
+
+    let kind = match channel {
+        Some(channel_id) => SetRequestChannel(channel_id),
+        None => RequestChannelOff,
+    };
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
@@ -0,0 +1,9 @@
+use crate::{
+    models::alias::{Context, Error},
+    utils::seek::{seek_track, SeekTo},
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn rewind(ctx: Context<'_>, #[min = 1] secs: u64) -> Result<(), Error> {
+    seek_track(ctx, SeekTo::Backward(secs)).await
+}
@@ -0,0 +1,119 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            EmptyScripts, InvalidScript, InvalidScriptIndex, ScriptAdded, ScriptLimitReached,
+            ScriptRemoved,
+        },
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{
+        check_admin, get_locale,
+        script::{cache_rule, forget_rules, Rule, SCRIPT_LIMIT},
+    },
+};
+
+#[poise::command(slash_command, guild_only, subcommands("add", "remove", "list"))]
+pub async fn script(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn add(ctx: Context<'_>, #[max_length = 500] rule: String) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    let parsed = match rule.parse::<Rule>() {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            ctx.say(TurtoMessage {
+                locale,
+                kind: InvalidScript { error: &error },
+            })
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    let scripts = &mut guild_data.config.scripts;
+    let kind = if scripts.len() >= SCRIPT_LIMIT {
+        ScriptLimitReached {
+            limit: SCRIPT_LIMIT,
+        }
+    } else {
+        scripts.push(rule.trim().to_owned());
+        // so the rule isn't parsed again when it's run
+        cache_rule(guild_id, parsed);
+        ScriptAdded
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn remove(ctx: Context<'_>, #[min = 1] index: usize) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    let scripts = &mut guild_data.config.scripts;
+    let kind = if (1..=scripts.len()).contains(&index) {
+        scripts.remove(index - 1);
+        forget_rules(guild_id);
+        ScriptRemoved
+    } else {
+        InvalidScriptIndex {
+            length: scripts.len(),
+        }
+    };
+    drop(guild_data);
+
+    ctx.say(TurtoMessage { locale, kind }).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let guild_data = ctx
+        .data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default();
+    let lines = guild_data
+        .config
+        .scripts
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| format!("`{}.` `{}`", index + 1, rule))
+        .collect::<Vec<_>>();
+    drop(guild_data);
+
+    let response = if lines.is_empty() {
+        TurtoMessage {
+            locale: get_locale(ctx),
+            kind: EmptyScripts,
+        }
+        .to_string()
+    } else {
+        lines.join("\n")
+    };
+    ctx.say(response).await?;
+    Ok(())
+}
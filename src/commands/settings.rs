@@ -0,0 +1,542 @@
+use crate::{
+    config::{get_config, message_template::get_locales},
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            SettingsAutoLeave, SettingsDjRole, SettingsImportCancelled, SettingsImportConfirm,
+            SettingsImportInvalid, SettingsImported, SettingsLocale, SettingsNotSet,
+            SettingsQueueLimit, SettingsReactionControls, SettingsRepeat, SettingsTitle,
+            SettingsTrimSilence, SettingsUnlimited, SettingsVolume,
+        },
+    },
+    models::{
+        alias::{Context, Error},
+        autoleave::AutoleaveType,
+        error::TurtoError,
+        file_format::FileFormat,
+        guild::{config::GuildConfig, volume::GuildVolume},
+    },
+    utils::{check_admin, get_locale, say},
+};
+use poise::{ChoiceParameter, CreateReply};
+use serenity::{
+    all::{Attachment, ComponentInteractionDataKind, GuildId, RoleId},
+    builder::{
+        CreateActionRow, CreateAttachment, CreateButton, CreateEmbed, CreateInteractionResponse,
+        CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind,
+        CreateSelectMenuOption,
+    },
+    collector::ComponentInteractionCollector,
+    prelude::Mentionable,
+};
+use std::time::Duration;
+use tracing::error;
+
+/// The choices of the limit of the playlist, it's unlimited if none is chosen
+const QUEUE_LIMITS: [usize; 5] = [50, 100, 200, 500, 1000];
+/// The value of the options which unset the locale or the limit of the playlist
+const UNSET: &str = "unset";
+const VOLUME_STEP: usize = 10;
+/// The settings file is tiny, anything larger is unlikely to be one
+const MAX_IMPORT_SIZE: u32 = 64 * 1024;
+
+/// A snapshot of the config of the guild, so the guild data isn't locked while rendering
+struct Settings {
+    auto_leave: AutoleaveType,
+    volume: GuildVolume,
+    repeat: bool,
+    locale: Option<String>,
+    dj_role: Option<RoleId>,
+    queue_limit: Option<usize>,
+    trim_silence: bool,
+    reaction_controls: bool,
+}
+
+impl Settings {
+    fn read(ctx: Context<'_>, guild_id: GuildId) -> Self {
+        let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        Settings::from(&guild_data.config)
+    }
+}
+
+impl From<&GuildConfig> for Settings {
+    fn from(config: &GuildConfig) -> Self {
+        Settings {
+            auto_leave: config.auto_leave,
+            volume: config.volume,
+            repeat: config.repeat,
+            locale: config.locale.clone(),
+            dj_role: config.dj_role,
+            queue_limit: config.queue_limit,
+            trim_silence: config.trim_silence,
+            reaction_controls: config.reaction_controls,
+        }
+    }
+}
+
+#[poise::command(slash_command, guild_only, subcommands("show", "export", "import"))]
+pub async fn settings(_ctx: Context<'_>) -> Result<(), Error> {
+    // slash command with subcommands can't be invoked directly
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn show(ctx: Context<'_>) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let id_prefix = format!("{}_", ctx.id());
+    let (embed, components) = settings_message(ctx, guild_id, &id_prefix);
+    let settings_msg = ctx
+        .send(
+            CreateReply::default()
+                .embed(embed)
+                .components(components)
+                .ephemeral(get_config().ephemeral_replies),
+        )
+        .await?;
+
+    while let Some(mci) = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .timeout(Duration::from_secs(120))
+        .filter({
+            let id_prefix = id_prefix.clone();
+            move |mci| mci.data.custom_id.starts_with(&id_prefix)
+        })
+        .await
+    {
+        let setting = &mci.data.custom_id[id_prefix.len()..];
+        update_setting(ctx, guild_id, setting, &mci.data.kind).await;
+
+        let (embed, components) = settings_message(ctx, guild_id, &id_prefix);
+        mci.create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .components(components),
+            ),
+        )
+        .await?;
+    }
+
+    // remove the menus and buttons after timeout
+    settings_msg
+        .edit(ctx, CreateReply::default().components(vec![]))
+        .await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn export(ctx: Context<'_>, format: Option<FileFormat>) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let format = format.unwrap_or(FileFormat::Toml);
+    let guild_data = ctx
+        .data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default();
+    let content = match format {
+        FileFormat::Toml => toml::to_string_pretty(&guild_data.config)?,
+        FileFormat::Json => serde_json::to_string_pretty(&guild_data.config)?,
+    };
+    drop(guild_data);
+
+    let filename = format!("settings.{}", format.name());
+    ctx.send(CreateReply::default().attachment(CreateAttachment::bytes(content, filename)))
+        .await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn import(ctx: Context<'_>, file: Attachment) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let locale = get_locale(ctx);
+    let imported = match read_settings_file(&file).await {
+        Ok(config) => config,
+        Err(reason) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: SettingsImportInvalid { reason: &reason },
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    // the ids of the roles and the channels are different in every guild, they only carry over
+    // within the same guild
+    let mut config = GuildConfig::default();
+    match ctx.guild() {
+        Some(guild) => config.import(
+            imported,
+            |role_id| guild.roles.contains_key(&role_id),
+            |channel_id| guild.channels.contains_key(&channel_id),
+        ),
+        None => config.import(imported, |_| false, |_| false),
+    }
+
+    let confirm_id = format!("{}_confirm", ctx.id());
+    let cancel_id = format!("{}_cancel", ctx.id());
+    let confirm_msg = ctx
+        .send(
+            CreateReply::default()
+                .content(TurtoMessage {
+                    locale,
+                    kind: SettingsImportConfirm,
+                })
+                .embed(settings_embed(locale, &Settings::from(&config)))
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new(&confirm_id).emoji('✅'),
+                    CreateButton::new(&cancel_id).emoji('❎'),
+                ])]),
+        )
+        .await?;
+
+    let ctx_id = ctx.id().to_string();
+    let mci = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .timeout(Duration::from_secs(60))
+        .filter(move |mci| mci.data.custom_id.starts_with(&ctx_id))
+        .await;
+
+    // not confirming before timeout cancels the import
+    let confirmed = mci
+        .as_ref()
+        .is_some_and(|mci| mci.data.custom_id == confirm_id);
+    if confirmed {
+        // the roles and the channels are checked already
+        ctx.data()
+            .guilds
+            .entry(guild_id)
+            .or_default()
+            .config
+            .import(config, |_| true, |_| true);
+        set_playing_volume(ctx, guild_id).await;
+    }
+
+    let result = TurtoMessage {
+        locale,
+        kind: if confirmed {
+            SettingsImported
+        } else {
+            SettingsImportCancelled
+        },
+    }
+    .to_string();
+    match mci {
+        Some(mci) => {
+            mci.create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content(result)
+                        .components(vec![]),
+                ),
+            )
+            .await?
+        }
+        None => {
+            confirm_msg
+                .edit(
+                    ctx,
+                    CreateReply::default().content(result).components(vec![]),
+                )
+                .await?
+        }
+    }
+    Ok(())
+}
+
+/// Download and validate the settings file, the error is the reason shown to the user
+async fn read_settings_file(file: &Attachment) -> Result<GuildConfig, String> {
+    if file.size > MAX_IMPORT_SIZE {
+        return Err(format!(
+            "the file is larger than {} KiB",
+            MAX_IMPORT_SIZE / 1024
+        ));
+    }
+    let format = if file.filename.ends_with(".json") {
+        FileFormat::Json
+    } else {
+        FileFormat::Toml
+    };
+    let content = file.download().await.map_err(|err| err.to_string())?;
+    let content =
+        String::from_utf8(content).map_err(|_| "the file isn't valid UTF-8".to_owned())?;
+    let config = parse_settings(&content, format)?;
+    if let Some(locale) = &config.locale {
+        if !get_locales().any(|supported| supported.eq_ignore_ascii_case(locale)) {
+            return Err(format!("locale `{}` isn't available", locale));
+        }
+    }
+    Ok(config)
+}
+
+fn parse_settings(content: &str, format: FileFormat) -> Result<GuildConfig, String> {
+    let config = match format {
+        FileFormat::Toml => {
+            toml::from_str::<GuildConfig>(content).map_err(|err| err.message().to_owned())?
+        }
+        FileFormat::Json => {
+            serde_json::from_str::<GuildConfig>(content).map_err(|err| err.to_string())?
+        }
+    };
+    // the volume is deserialized as is, it's not checked by serde
+    if GuildVolume::try_from(*config.volume).is_err() {
+        return Err("`volume` must be between 0.0 and 1.0".to_owned());
+    }
+    if config.queue_limit == Some(0) {
+        return Err("`queue_limit` must be greater than 0".to_owned());
+    }
+    if config.queue_duration_limit == Some(0) || config.user_duration_limit == Some(0) {
+        return Err(
+            "`queue_duration_limit` and `user_duration_limit` must be greater than 0".to_owned(),
+        );
+    }
+    Ok(config)
+}
+
+async fn update_setting(
+    ctx: Context<'_>,
+    guild_id: GuildId,
+    setting: &str,
+    kind: &ComponentInteractionDataKind,
+) {
+    let value = match kind {
+        ComponentInteractionDataKind::StringSelect { values } => values.first().cloned(),
+        ComponentInteractionDataKind::RoleSelect { values } => {
+            values.first().map(|role_id| role_id.to_string())
+        }
+        _ => None,
+    };
+
+    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+    let config = &mut guild_data.config;
+    match setting {
+        "auto_leave" => {
+            let auto_leave = value
+                .and_then(|index| index.parse::<usize>().ok())
+                .and_then(AutoleaveType::from_index);
+            if let Some(auto_leave) = auto_leave {
+                config.auto_leave = auto_leave;
+            }
+        }
+        "volume_down" | "volume_up" => {
+            let volume = usize::from(config.volume);
+            let volume = if setting == "volume_down" {
+                volume.saturating_sub(VOLUME_STEP)
+            } else {
+                (volume + VOLUME_STEP).min(100)
+            };
+            config.volume = GuildVolume::try_from(volume).unwrap();
+        }
+        "repeat" => config.repeat = !config.repeat,
+        "trim_silence" => config.trim_silence = !config.trim_silence,
+        "reaction_controls" => config.reaction_controls = !config.reaction_controls,
+        "locale" => config.locale = value.filter(|locale| locale != UNSET),
+        // deselecting the role unsets it
+        "dj_role" => config.dj_role = value.and_then(|role_id| role_id.parse().ok()),
+        "queue_limit" => config.queue_limit = value.and_then(|limit| limit.parse().ok()),
+        _ => {}
+    }
+    drop(guild_data);
+
+    if setting.starts_with("volume") {
+        set_playing_volume(ctx, guild_id).await;
+    }
+}
+
+/// Update the volume of the currently playing track, so the new volume applies right away
+async fn set_playing_volume(ctx: Context<'_>, guild_id: GuildId) {
+    let playing_map = ctx.data().playing.read().await;
+    if let Some(playing) = playing_map.get(&guild_id) {
+        let volume = ctx
+            .data()
+            .guilds
+            .entry(guild_id)
+            .or_default()
+            .track_volume(&playing.url);
+        if let Err(why) = playing.track_handle.set_volume(volume) {
+            let uuid = playing.track_handle.uuid();
+            error!("Failed to set volume for track {uuid}: {why}");
+        }
+    }
+}
+
+fn settings_message(
+    ctx: Context<'_>,
+    guild_id: GuildId,
+    id_prefix: &str,
+) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let settings = Settings::read(ctx, guild_id);
+    // the locale may have just been changed
+    let locale = get_locale(ctx);
+    (
+        settings_embed(locale, &settings),
+        settings_components(locale, &settings, id_prefix),
+    )
+}
+
+fn settings_embed(locale: Option<&str>, settings: &Settings) -> CreateEmbed {
+    let message = |kind| TurtoMessage { locale, kind }.to_string();
+    let repeat = if settings.repeat { "✅" } else { "❎" };
+    let trim_silence = if settings.trim_silence { "✅" } else { "❎" };
+    let reaction_controls = if settings.reaction_controls {
+        "✅"
+    } else {
+        "❎"
+    };
+    let locale_str = settings
+        .locale
+        .clone()
+        .unwrap_or_else(|| message(SettingsNotSet));
+    let dj_role = settings
+        .dj_role
+        .map(|role_id| role_id.mention().to_string())
+        .unwrap_or_else(|| message(SettingsNotSet));
+    let queue_limit = settings
+        .queue_limit
+        .map(|limit| limit.to_string())
+        .unwrap_or_else(|| message(SettingsUnlimited));
+    CreateEmbed::new()
+        .title(message(SettingsTitle))
+        .field(message(SettingsAutoLeave), settings.auto_leave.name(), true)
+        .field(
+            message(SettingsVolume),
+            usize::from(settings.volume).to_string(),
+            true,
+        )
+        .field(message(SettingsRepeat), repeat, true)
+        .field(message(SettingsLocale), locale_str, true)
+        .field(message(SettingsDjRole), dj_role, true)
+        .field(message(SettingsQueueLimit), queue_limit, true)
+        .field(message(SettingsTrimSilence), trim_silence, true)
+        .field(message(SettingsReactionControls), reaction_controls, true)
+}
+
+fn settings_components(
+    locale: Option<&str>,
+    settings: &Settings,
+    id_prefix: &str,
+) -> Vec<CreateActionRow> {
+    let message = |kind| TurtoMessage { locale, kind }.to_string();
+    let auto_leave_options = AutoleaveType::list()
+        .into_iter()
+        .enumerate()
+        .map(|(index, choice)| {
+            let is_current = AutoleaveType::from_index(index) == Some(settings.auto_leave);
+            CreateSelectMenuOption::new(choice.name, index.to_string())
+                .default_selection(is_current)
+        })
+        .collect();
+
+    let unset_locale = CreateSelectMenuOption::new(message(SettingsNotSet), UNSET)
+        .default_selection(settings.locale.is_none());
+    // discord allows at most 25 options
+    let locale_options = std::iter::once(unset_locale)
+        .chain(get_locales().take(24).map(|supported| {
+            let is_current = settings
+                .locale
+                .as_deref()
+                .is_some_and(|locale| locale.eq_ignore_ascii_case(supported));
+            CreateSelectMenuOption::new(supported, supported).default_selection(is_current)
+        }))
+        .collect();
+
+    let unlimited = CreateSelectMenuOption::new(message(SettingsUnlimited), UNSET)
+        .default_selection(settings.queue_limit.is_none());
+    let queue_limit_options = std::iter::once(unlimited)
+        .chain(QUEUE_LIMITS.into_iter().map(|limit| {
+            CreateSelectMenuOption::new(limit.to_string(), limit.to_string())
+                .default_selection(settings.queue_limit == Some(limit))
+        }))
+        .collect();
+
+    let id = |setting: &str| format!("{}{}", id_prefix, setting);
+    vec![
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(
+                id("auto_leave"),
+                CreateSelectMenuKind::String {
+                    options: auto_leave_options,
+                },
+            )
+            .placeholder(message(SettingsAutoLeave)),
+        ),
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(
+                id("locale"),
+                CreateSelectMenuKind::String {
+                    options: locale_options,
+                },
+            )
+            .placeholder(message(SettingsLocale)),
+        ),
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(
+                id("dj_role"),
+                CreateSelectMenuKind::Role {
+                    default_roles: settings.dj_role.map(|role_id| vec![role_id]),
+                },
+            )
+            .placeholder(message(SettingsDjRole))
+            .min_values(0)
+            .max_values(1),
+        ),
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(
+                id("queue_limit"),
+                CreateSelectMenuKind::String {
+                    options: queue_limit_options,
+                },
+            )
+            .placeholder(message(SettingsQueueLimit)),
+        ),
+        CreateActionRow::Buttons(vec![
+            CreateButton::new(id("volume_down"))
+                .emoji('🔉')
+                .disabled(usize::from(settings.volume) == 0),
+            CreateButton::new(id("volume_up"))
+                .emoji('🔊')
+                .disabled(usize::from(settings.volume) >= 100),
+            CreateButton::new(id("repeat")).emoji('🔂'),
+            CreateButton::new(id("trim_silence")).emoji('✂'),
+            CreateButton::new(id("reaction_controls")).emoji('⏯'),
+        ]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_settings;
+    use crate::models::{file_format::FileFormat, guild::config::GuildConfig};
+
+    #[test]
+    fn test_parse_settings() {
+        let exported = toml::to_string_pretty(&GuildConfig::default()).unwrap();
+        assert!(parse_settings(&exported, FileFormat::Toml).is_ok());
+        let exported = serde_json::to_string_pretty(&GuildConfig::default()).unwrap();
+        assert!(parse_settings(&exported, FileFormat::Json).is_ok());
+
+        let loud = exported.replace("\"volume\": 1.0", "\"volume\": 2.0");
+        assert_eq!(
+            parse_settings(&loud, FileFormat::Json).unwrap_err(),
+            "`volume` must be between 0.0 and 1.0"
+        );
+        assert!(parse_settings("volume = 1.0", FileFormat::Toml).is_err());
+    }
+}
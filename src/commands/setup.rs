@@ -0,0 +1,274 @@
+use crate::{
+    config::{get_config, message_template::get_locales},
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            SettingsAnnouncementChannel, SettingsAutoLeave, SettingsDjRole, SettingsLocale,
+            SetupDone, SetupSkip, SetupStep, SetupTimeout,
+        },
+    },
+    models::{
+        alias::{Context, Error},
+        autoleave::AutoleaveType,
+        error::TurtoError,
+        guild::config::GuildConfig,
+    },
+    utils::{check_admin, get_locale},
+};
+use poise::{ChoiceParameter, CreateReply};
+use serenity::{
+    all::{ChannelType, ComponentInteractionDataKind},
+    builder::{
+        CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage,
+        CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
+    },
+    collector::ComponentInteractionCollector,
+};
+use std::time::Duration;
+
+const STEP_TIMEOUT: Duration = Duration::from_secs(120);
+const SKIP: &str = "skip";
+
+/// The settings chosen one by one, prefixes aren't among them since there are only slash commands
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+    AnnouncementChannel,
+    DjRole,
+    Locale,
+    AutoLeave,
+}
+
+impl Step {
+    const ALL: [Step; 4] = [
+        Step::AnnouncementChannel,
+        Step::DjRole,
+        Step::Locale,
+        Step::AutoLeave,
+    ];
+
+    /// Write the choice into the config, it's left as it is if nothing valid is chosen
+    fn apply(self, config: &mut GuildConfig, kind: &ComponentInteractionDataKind) {
+        match (self, kind) {
+            (Step::AnnouncementChannel, ComponentInteractionDataKind::ChannelSelect { values }) => {
+                if let Some(channel_id) = values.first() {
+                    config.announcement_channel = Some(*channel_id);
+                }
+            }
+            (Step::DjRole, ComponentInteractionDataKind::RoleSelect { values }) => {
+                if let Some(role_id) = values.first() {
+                    config.dj_role = Some(*role_id);
+                }
+            }
+            (Step::Locale, ComponentInteractionDataKind::StringSelect { values }) => {
+                if let Some(locale) = values.first() {
+                    config.locale = Some(locale.clone());
+                }
+            }
+            (Step::AutoLeave, ComponentInteractionDataKind::StringSelect { values }) => {
+                let auto_leave = values
+                    .first()
+                    .and_then(|index| index.parse::<usize>().ok())
+                    .and_then(AutoleaveType::from_index);
+                if let Some(auto_leave) = auto_leave {
+                    config.auto_leave = auto_leave;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn menu(self, id: String, config: &GuildConfig) -> CreateSelectMenu {
+        let kind = match self {
+            Step::AnnouncementChannel => CreateSelectMenuKind::Channel {
+                channel_types: Some(vec![ChannelType::Text, ChannelType::News]),
+                default_channels: config.announcement_channel.map(|channel| vec![channel]),
+            },
+            Step::DjRole => CreateSelectMenuKind::Role {
+                default_roles: config.dj_role.map(|role_id| vec![role_id]),
+            },
+            // discord allows at most 25 options
+            Step::Locale => CreateSelectMenuKind::String {
+                options: get_locales()
+                    .take(25)
+                    .map(|supported| {
+                        let is_current = config
+                            .locale
+                            .as_deref()
+                            .is_some_and(|locale| locale.eq_ignore_ascii_case(supported));
+                        CreateSelectMenuOption::new(supported, supported)
+                            .default_selection(is_current)
+                    })
+                    .collect(),
+            },
+            Step::AutoLeave => CreateSelectMenuKind::String {
+                options: AutoleaveType::list()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, choice)| {
+                        let is_current =
+                            AutoleaveType::from_index(index) == Some(config.auto_leave);
+                        CreateSelectMenuOption::new(choice.name, index.to_string())
+                            .default_selection(is_current)
+                    })
+                    .collect(),
+            },
+        };
+        CreateSelectMenu::new(id, kind)
+    }
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn setup(ctx: Context<'_>) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let id_prefix = format!("{}_", ctx.id());
+    let step_reply = |index: usize| {
+        // the locale may have just been chosen
+        let locale = get_locale(ctx);
+        let message = |kind| TurtoMessage { locale, kind }.to_string();
+        let step = Step::ALL[index];
+        let setting = message(match step {
+            Step::AnnouncementChannel => SettingsAnnouncementChannel,
+            Step::DjRole => SettingsDjRole,
+            Step::Locale => SettingsLocale,
+            Step::AutoLeave => SettingsAutoLeave,
+        });
+        let menu = {
+            let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+            step.menu(format!("{}{}", id_prefix, index), &guild_data.config)
+                .placeholder(&setting)
+        };
+        let content = message(SetupStep {
+            step: index + 1,
+            total: Step::ALL.len(),
+            setting: &setting,
+        });
+        let components = vec![
+            CreateActionRow::SelectMenu(menu),
+            CreateActionRow::Buttons(vec![CreateButton::new(format!(
+                "{}{}{}",
+                id_prefix, SKIP, index
+            ))
+            .label(message(SetupSkip))]),
+        ];
+        (content, components)
+    };
+
+    let (content, components) = step_reply(0);
+    let setup_msg = ctx
+        .send(
+            CreateReply::default()
+                .content(content)
+                .components(components)
+                .ephemeral(get_config().ephemeral_replies),
+        )
+        .await?;
+
+    let mut index = 0;
+    while index < Step::ALL.len() {
+        let Some(mci) = ComponentInteractionCollector::new(ctx)
+            .author_id(ctx.author().id)
+            .channel_id(ctx.channel_id())
+            .timeout(STEP_TIMEOUT)
+            .filter({
+                let id_prefix = id_prefix.clone();
+                move |mci| mci.data.custom_id.starts_with(&id_prefix)
+            })
+            .await
+        else {
+            setup_msg
+                .edit(
+                    ctx,
+                    CreateReply::default()
+                        .content(TurtoMessage {
+                            locale: get_locale(ctx),
+                            kind: SetupTimeout,
+                        })
+                        .components(vec![]),
+                )
+                .await?;
+            return Ok(());
+        };
+
+        let choice = &mci.data.custom_id[id_prefix.len()..];
+        // the components of the previous steps may still be used before the message is updated
+        if choice == index.to_string() {
+            let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+            Step::ALL[index].apply(&mut guild_data.config, &mci.data.kind);
+        } else if choice != format!("{}{}", SKIP, index) {
+            mci.defer(ctx).await?;
+            continue;
+        }
+        index += 1;
+
+        let response = if index < Step::ALL.len() {
+            let (content, components) = step_reply(index);
+            CreateInteractionResponseMessage::new()
+                .content(content)
+                .components(components)
+        } else {
+            CreateInteractionResponseMessage::new()
+                .content(TurtoMessage {
+                    locale: get_locale(ctx),
+                    kind: SetupDone,
+                })
+                .components(vec![])
+        };
+        mci.create_response(ctx, CreateInteractionResponse::UpdateMessage(response))
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Step;
+    use crate::models::{autoleave::AutoleaveType, guild::config::GuildConfig};
+    use serenity::all::{ChannelId, ComponentInteractionDataKind, RoleId};
+
+    #[test]
+    fn test_setup_steps() {
+        let mut config = GuildConfig::default();
+        Step::AnnouncementChannel.apply(
+            &mut config,
+            &ComponentInteractionDataKind::ChannelSelect {
+                values: vec![ChannelId::new(1)],
+            },
+        );
+        Step::DjRole.apply(
+            &mut config,
+            &ComponentInteractionDataKind::RoleSelect {
+                values: vec![RoleId::new(2)],
+            },
+        );
+        Step::Locale.apply(
+            &mut config,
+            &ComponentInteractionDataKind::StringSelect {
+                values: vec!["zh-TW".to_owned()],
+            },
+        );
+        Step::AutoLeave.apply(
+            &mut config,
+            &ComponentInteractionDataKind::StringSelect {
+                values: vec!["3".to_owned()],
+            },
+        );
+        assert_eq!(config.announcement_channel, Some(ChannelId::new(1)));
+        assert_eq!(config.dj_role, Some(RoleId::new(2)));
+        assert_eq!(config.locale.as_deref(), Some("zh-TW"));
+        assert_eq!(config.auto_leave, AutoleaveType::Off);
+
+        // a choice from the menu of another step is ignored
+        Step::AutoLeave.apply(
+            &mut config,
+            &ComponentInteractionDataKind::RoleSelect {
+                values: vec![RoleId::new(3)],
+            },
+        );
+        assert_eq!(config.auto_leave, AutoleaveType::Off);
+        assert_eq!(config.dj_role, Some(RoleId::new(2)));
+    }
+}
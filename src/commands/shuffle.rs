@@ -3,15 +3,19 @@ use crate::{
         TurtoMessage,
         TurtoMessageKind::{EmptyPlaylist, Shuffle},
     },
-    models::alias::{Context, Error},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::get_locale,
 };
-use rand::{seq::SliceRandom, thread_rng};
+use rand::thread_rng;
 
 #[poise::command(slash_command, guild_only)]
 pub async fn shuffle(ctx: Context<'_>) -> Result<(), Error> {
-    let guild = ctx.guild_id().unwrap();
+    let guild = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
     let mut guild_data = ctx.data().guilds.entry(guild).or_default();
-    let locale = ctx.locale();
+    let locale = get_locale(ctx);
     if guild_data.playlist.is_empty() {
         drop(guild_data);
         ctx.say(TurtoMessage {
@@ -21,8 +25,7 @@ pub async fn shuffle(ctx: Context<'_>) -> Result<(), Error> {
         .await?;
         return Ok(());
     }
-    let playlist = guild_data.playlist.make_contiguous();
-    playlist.shuffle(&mut thread_rng());
+    guild_data.playlist.shuffle(&mut thread_rng());
     drop(guild_data);
 
     ctx.say(TurtoMessage {
@@ -5,36 +5,58 @@ use crate::{
     },
     models::{
         alias::{Context, Error},
-        autoleave::AutoleaveType,
+        error::TurtoError,
+        invocation::Phase::{Discord, Ytdl},
+        premium::Feature,
     },
     utils::{
+        fade::fade_out_playing,
+        get_locale,
         guild::{GuildUtil, VoiceChannelState},
         play::play_next,
+        premium::has_feature,
+        say,
+        timing::timed,
+        vote::vote_move,
     },
 };
+use std::time::Duration;
 
 #[poise::command(slash_command, guild_only)]
 pub async fn skip(ctx: Context<'_>) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
     let bot_id = ctx.cache().current_user().id;
     let user_id = ctx.author().id;
-    let vc_stat = ctx.guild().unwrap().cmp_voice_channel(&bot_id, &user_id);
-    let locale = ctx.locale();
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &user_id);
+    let locale = get_locale(ctx);
 
     match vc_stat {
-        VoiceChannelState::Different(bot_vc, _) | VoiceChannelState::OnlyFirst(bot_vc) => {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: DifferentVoiceChannel { bot: bot_vc },
-            })
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlyFirst(bot_vc) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: DifferentVoiceChannel { bot: bot_vc },
+                },
+            )
             .await?;
             return Ok(());
         }
         VoiceChannelState::OnlySecond(_) | VoiceChannelState::None => {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: BotNotInVoiceChannel,
-            })
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: BotNotInVoiceChannel,
+                },
+            )
             .await?;
             return Ok(());
         }
@@ -43,36 +65,50 @@ pub async fn skip(ctx: Context<'_>) -> Result<(), Error> {
 
     let Some(call) = songbird::get(ctx.serenity_context())
         .await
-        .unwrap()
+        .ok_or(TurtoError::VoiceUnavailable)?
         .get(guild_id)
     else {
-        ctx.say(TurtoMessage {
-            locale,
-            kind: NotPlaying,
-        })
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: NotPlaying,
+            },
+        )
         .await?;
         return Ok(());
     };
+    fade_out_playing(&ctx.data().playing, guild_id).await;
     {
         let mut call = call.lock().await;
         call.stop();
     }
 
     let data = ctx.data();
-    ctx.defer().await?;
-    let meta = play_next(
-        call.clone(),
-        data.guilds.clone(),
-        data.playing.clone(),
-        guild_id,
+    timed(ctx, Discord, ctx.defer()).await?;
+    let meta = timed(
+        ctx,
+        Ytdl,
+        play_next(
+            call.clone(),
+            data.guilds.clone(),
+            data.playing.clone(),
+            guild_id,
+        ),
     )
     .await
     .and_then(Result::ok);
 
-    // Leave when there is no next track and autoleave is on or in silent mode
-    let auto_leave = data.guilds.entry(guild_id).or_default().config.auto_leave;
-    let should_leave =
-        meta.is_none() && (auto_leave == AutoleaveType::On || auto_leave == AutoleaveType::Silent);
+    // Leave when there is no next track and autoleave is on or in silent mode, the idle tracker
+    // leaves later if there's a timeout
+    let always_on = has_feature(guild_id, Feature::AlwaysOn).await;
+    let leave_when_idle = data
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .config
+        .leave_when_idle(always_on);
+    let should_leave = meta.is_none() && leave_when_idle == Some(Duration::ZERO);
 
     let title = meta.as_ref().and_then(|meta| meta.title.as_deref());
     ctx.say(TurtoMessage {
@@ -3,33 +3,55 @@ use crate::{
         TurtoMessage,
         TurtoMessageKind::{BotNotInVoiceChannel, DifferentVoiceChannel, NotPlaying, Stop},
     },
-    models::alias::{Context, Error},
-    utils::guild::{GuildUtil, VoiceChannelState},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{
+        fade::fade_stop,
+        get_locale,
+        guild::{GuildUtil, VoiceChannelState},
+        say,
+        vote::vote_move,
+    },
 };
 use tracing::error;
 
 #[poise::command(slash_command, guild_only)]
 pub async fn stop(ctx: Context<'_>) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
     let bot_id = ctx.cache().current_user().id;
     let user_id = ctx.author().id;
-    let vc_stat = ctx.guild().unwrap().cmp_voice_channel(&bot_id, &user_id);
-    let locale = ctx.locale();
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &user_id);
+    let locale = get_locale(ctx);
 
     match vc_stat {
         VoiceChannelState::None | VoiceChannelState::OnlySecond(_) => {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: BotNotInVoiceChannel,
-            })
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: BotNotInVoiceChannel,
+                },
+            )
             .await?;
             return Ok(());
         }
-        VoiceChannelState::Different(bot_vc, _) | VoiceChannelState::OnlyFirst(bot_vc) => {
-            ctx.say(TurtoMessage {
-                locale,
-                kind: DifferentVoiceChannel { bot: bot_vc },
-            })
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlyFirst(bot_vc) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: DifferentVoiceChannel { bot: bot_vc },
+                },
+            )
             .await?;
             return Ok(());
         }
@@ -38,21 +60,24 @@ pub async fn stop(ctx: Context<'_>) -> Result<(), Error> {
 
     let mut playing_map = ctx.data().playing.write().await;
     let Some(playing) = playing_map.remove(&guild_id) else {
-        ctx.say(TurtoMessage {
-            locale,
-            kind: NotPlaying,
-        })
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: NotPlaying,
+            },
+        )
         .await?;
         return Ok(());
     };
     drop(playing_map);
 
-    if let Err(why) = playing.track_handle.stop() {
+    if let Err(why) = fade_stop(&playing.track_handle).await {
         let uuid = playing.track_handle.uuid();
         error!("Failed to stop track {uuid}: {why}");
     }
 
-    let title = playing.metadata.title.clone().unwrap();
+    let title = playing.metadata.title.clone().unwrap_or_default();
     ctx.say(TurtoMessage {
         locale,
         kind: Stop { title: &title },
@@ -0,0 +1,72 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{AlreadyInVoiceChannel, MovedVoiceChannel, UserNotInVoiceChannel},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{
+        get_locale,
+        guild::{GuildUtil, VoiceChannelState},
+        join_voice_channel, say,
+        vote::{listeners, move_to, vote_move},
+    },
+};
+use tracing::error;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn summon(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let bot_id = ctx.cache().current_user().id;
+    let user_id = ctx.author().id;
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &user_id);
+    let locale = get_locale(ctx);
+
+    match vc_stat {
+        VoiceChannelState::None | VoiceChannelState::OnlyFirst(_) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: UserNotInVoiceChannel,
+                },
+            )
+            .await?;
+        }
+        VoiceChannelState::OnlySecond(user_vc) => {
+            if let Err(err) = join_voice_channel(ctx, locale, guild_id, user_vc).await {
+                error!("Failed to join voice channel {user_vc}: {err}");
+            }
+        }
+        VoiceChannelState::Same(bot_vc) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: AlreadyInVoiceChannel(bot_vc),
+                },
+            )
+            .await?;
+        }
+        // nobody is listening in the other channel, so there's no one to ask
+        VoiceChannelState::Different(bot_vc, user_vc) if listeners(ctx, bot_vc).is_empty() => {
+            ctx.defer().await?;
+            if move_to(ctx, user_vc).await? {
+                ctx.say(TurtoMessage {
+                    locale,
+                    kind: MovedVoiceChannel(user_vc),
+                })
+                .await?;
+            }
+        }
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
+        }
+    }
+    Ok(())
+}
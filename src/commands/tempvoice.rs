@@ -0,0 +1,41 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{SetTempVoice, TempVoiceOff},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{check_admin, get_locale},
+};
+use serenity::all::GuildChannel;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn tempvoice(
+    ctx: Context<'_>,
+    #[channel_types("Category")] category: Option<GuildChannel>,
+) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let category = category.map(|category| category.id);
+    {
+        let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+        let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        // the channel created before is still deleted once the session ends
+        guild_data.config.temp_voice_category = category;
+    }
+
+    let kind = match category {
+        Some(category) => SetTempVoice(category),
+        None => TempVoiceOff,
+    };
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind,
+    })
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::UnlockQueue},
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{check_admin, get_locale, is_guild_dj},
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn unlockqueue(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let dj_role = ctx
+        .data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .config
+        .dj_role;
+    // the djs can approve the queue locked by the raid protection as well
+    if !is_guild_dj(ctx, dj_role).await && !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    ctx.data().guilds.entry(guild_id).or_default().queue_lock = None;
+
+    ctx.say(TurtoMessage {
+        locale: get_locale(ctx),
+        kind: UnlockQueue,
+    })
+    .await?;
+    Ok(())
+}
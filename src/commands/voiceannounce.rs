@@ -0,0 +1,45 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{SetVoiceAnnouncements, VoiceAnnouncementsDisabled},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        toggle::ToggleOption,
+    },
+    utils::{check_admin, get_locale, tts::tts_enabled},
+};
+
+#[poise::command(slash_command, guild_only)]
+pub async fn voiceannounce(ctx: Context<'_>, toggle: ToggleOption) -> Result<(), Error> {
+    if !check_admin(ctx).await? {
+        return Ok(());
+    }
+
+    let locale = get_locale(ctx);
+    if !tts_enabled() {
+        ctx.say(TurtoMessage {
+            locale,
+            kind: VoiceAnnouncementsDisabled,
+        })
+        .await?;
+        return Ok(());
+    }
+    let toggle = match toggle {
+        ToggleOption::On => true,
+        ToggleOption::Off => false,
+    };
+    ctx.data()
+        .guilds
+        .entry(ctx.guild_id().ok_or(TurtoError::NotInGuild)?)
+        .or_default()
+        .config
+        .voice_announcements = toggle;
+    ctx.say(TurtoMessage {
+        locale,
+        kind: SetVoiceAnnouncements(toggle),
+    })
+    .await?;
+    Ok(())
+}
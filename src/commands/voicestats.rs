@@ -0,0 +1,72 @@
+use crate::{
+    config::get_config,
+    handlers::voice_stats::session_stats,
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{NoVoiceStats, VoiceStats, VoiceStatsDisabled},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{get_locale, misc::format_duration},
+};
+use poise::CreateReply;
+use serenity::builder::CreateAllowedMentions;
+
+/// The speakers shown, the message would be too long with everyone in a big voice channel
+const VOICE_STATS_LIMIT: usize = 20;
+
+#[poise::command(slash_command, guild_only)]
+pub async fn voicestats(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    if !get_config().voice_stats {
+        ctx.say(TurtoMessage {
+            locale,
+            kind: VoiceStatsDisabled,
+        })
+        .await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let Some((session, ranking)) =
+        session_stats(guild_id).filter(|(_, ranking)| !ranking.is_empty())
+    else {
+        ctx.say(TurtoMessage {
+            locale,
+            kind: NoVoiceStats,
+        })
+        .await?;
+        return Ok(());
+    };
+    let stats = ranking
+        .into_iter()
+        .take(VOICE_STATS_LIMIT)
+        .enumerate()
+        .map(|(index, (user_id, duration))| {
+            format!(
+                "{}. <@{}> `{}`",
+                index + 1,
+                user_id,
+                format_duration(duration)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let message = TurtoMessage {
+        locale,
+        kind: VoiceStats {
+            session: &format_duration(session),
+            stats: &stats,
+        },
+    };
+    // the speakers are mentioned to show their names, not to notify them
+    ctx.send(
+        CreateReply::default()
+            .content(message.to_string())
+            .allowed_mentions(CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
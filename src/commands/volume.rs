@@ -1,9 +1,14 @@
 use crate::{
-    messages::{TurtoMessage, TurtoMessageKind::SetVolume},
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{NotPlaying, SetTrackGain, SetVolume},
+    },
     models::{
         alias::{Context, Error},
+        error::TurtoError,
         guild::volume::GuildVolume,
     },
+    utils::{get_locale, say},
 };
 use tracing::error;
 
@@ -13,41 +18,83 @@ pub async fn volume(
     #[min = 0]
     #[max = 100]
     value: Option<usize>,
+    #[min = -20.0]
+    #[max = 20.0]
+    track: Option<f32>,
 ) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
-    let locale = ctx.locale();
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let locale = get_locale(ctx);
 
     if let Some(vol) = value {
-        // Update the volume if there is a currently playing TrackHandle
+        // Update the volume setting of guild
         let new_vol = GuildVolume::try_from(vol).unwrap();
+        let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        guild_data.config.volume = new_vol;
+        drop(guild_data);
+    }
+
+    let title = if let Some(gain) = track {
+        // the gain is stored under the url the playing track is played with
+        let playing_map = ctx.data().playing.read().await;
+        let Some(playing) = playing_map.get(&guild_id) else {
+            drop(playing_map);
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: NotPlaying,
+                },
+            )
+            .await?;
+            return Ok(());
+        };
+        let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        if gain == 0.0 {
+            guild_data.track_gains.remove(&*playing.url);
+        } else {
+            guild_data.track_gains.insert(playing.url.to_string(), gain);
+        }
+        playing.metadata.title.clone()
+    } else {
+        None
+    };
+
+    if value.is_some() || track.is_some() {
+        // Update the volume if there is a currently playing TrackHandle
         let playing_map = ctx.data().playing.read().await;
         if let Some(playing) = playing_map.get(&guild_id) {
-            if let Err(why) = playing.track_handle.set_volume(*new_vol) {
+            let volume = ctx
+                .data()
+                .guilds
+                .entry(guild_id)
+                .or_default()
+                .track_volume(&playing.url);
+            if let Err(why) = playing.track_handle.set_volume(volume) {
                 let uuid = playing.track_handle.uuid();
                 error!("Failed to set volume for track {uuid}: {why}");
             }
         }
-        drop(playing_map);
-
-        // Update the volume setting of guild
-        let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
-        guild_data.config.volume = new_vol;
-        drop(guild_data);
-
-        ctx.say(TurtoMessage {
-            locale,
-            kind: SetVolume(new_vol),
-        })
-        .await?;
+    }
 
-        Ok(())
-    } else {
-        let curr_vol = ctx.data().guilds.entry(guild_id).or_default().config.volume;
-        ctx.say(TurtoMessage {
-            locale,
-            kind: SetVolume(curr_vol),
-        })
-        .await?;
-        Ok(())
+    let curr_vol = ctx.data().guilds.entry(guild_id).or_default().config.volume;
+    let mut response = TurtoMessage {
+        locale,
+        kind: SetVolume(curr_vol),
+    }
+    .to_string();
+    if let Some(gain) = track {
+        response.push('\n');
+        response.push_str(
+            &TurtoMessage {
+                locale,
+                kind: SetTrackGain {
+                    title: title.as_deref().unwrap_or_default(),
+                    gain,
+                },
+            }
+            .to_string(),
+        );
     }
+    ctx.say(response).await?;
+    Ok(())
 }
@@ -0,0 +1,355 @@
+use super::{help::COMMAND_LIST, message_template::TEMPLATES_LIST, read_config};
+use crate::{
+    models::config::TurtoConfig,
+    models::help::Help,
+    utils::{deprecation::is_active, template::Template},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs,
+    path::Path,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// turto won't start, or will fail at runtime
+    Error,
+    /// turto works, but probably not in the way it's expected
+    Warning,
+}
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: String,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {}: {}", severity, self.file, self.message)
+    }
+}
+
+struct Diagnostics {
+    file: String,
+    list: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    fn new(file: &Path) -> Self {
+        Self {
+            file: file.display().to_string(),
+            list: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, message: impl Into<String>) {
+        self.push(Severity::Error, message.into());
+    }
+
+    fn warning(&mut self, message: impl Into<String>) {
+        self.push(Severity::Warning, message.into());
+    }
+
+    fn push(&mut self, severity: Severity, message: String) {
+        self.list.push(Diagnostic {
+            severity,
+            file: self.file.clone(),
+            message,
+        });
+    }
+}
+
+/// Check the config, help info and message templates files, the files are read again so the
+/// changes made after turto started are checked as well
+pub fn check_files(
+    config_path: impl AsRef<Path>,
+    help_path: impl AsRef<Path>,
+    templates_path: impl AsRef<Path>,
+) -> Vec<Diagnostic> {
+    let mut res = check_config_file(config_path.as_ref());
+    res.extend(check_help_file(help_path.as_ref()));
+    res.extend(check_templates_file(templates_path.as_ref()));
+    res
+}
+
+/// Check the values of the config which are valid toml but don't make sense
+pub fn lint_config(config: &TurtoConfig) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics::new(Path::new(super::CONFIG_PATH));
+    lint_config_values(config, &mut diagnostics);
+    diagnostics.list
+}
+
+fn check_config_file(path: &Path) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics::new(path);
+    let (config, file_table) = match read_config(path) {
+        Ok(res) => res,
+        Err(err) => {
+            diagnostics.error(format!("{:#}", err));
+            return diagnostics.list;
+        }
+    };
+
+    match file_table {
+        Some(file_table) => {
+            // every key of the config is present in the json, including the ones with None value
+            let known_keys = match serde_json::to_value(&config) {
+                Ok(serde_json::Value::Object(map)) => map.into_iter().map(|(key, _)| key).collect(),
+                _ => HashSet::new(),
+            };
+            for key in file_table.keys() {
+                if !known_keys.contains(key) {
+                    diagnostics.warning(format!("Unknown key `{}`, it's ignored", key));
+                }
+            }
+        }
+        None => {
+            diagnostics.warning("The file is not found, only the environment variables are used")
+        }
+    }
+
+    lint_config_values(&config, &mut diagnostics);
+    diagnostics.list
+}
+
+fn lint_config_values(config: &TurtoConfig, diagnostics: &mut Diagnostics) {
+    if config.owner.is_none() {
+        diagnostics.warning("The owner of this bot hasn't been set");
+    }
+    if config.auto_save && config.auto_save_interval == 0 {
+        diagnostics.error("`auto_save_interval` must be greater than 0 when `auto_save` is on");
+    }
+    if config.allow_seek && config.seek_limit == 0 {
+        diagnostics.warning(
+            "`seek_limit` is 0, seeking is effectively disabled, set `allow_seek = false` instead",
+        );
+    }
+    if !config.premium_features.is_empty()
+        && config.premium_guilds.is_empty()
+        && config.entitlement_url.is_none()
+    {
+        diagnostics.warning(
+            "`premium_features` are unavailable in every guild, since neither `premium_guilds` nor `entitlement_url` is set",
+        );
+    }
+    if config.http_server && config.http_port == 0 {
+        diagnostics.error("`http_port` must be between 1 and 65535");
+    }
+    if let Some(url) = &config.overlay_url {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            diagnostics.error("`overlay_url` must start with `http://` or `https://`");
+        }
+        if !config.http_server {
+            diagnostics.warning(
+                "`overlay_url` is set but `http_server` is off, the overlays are unavailable",
+            );
+        }
+    }
+    if !config.tts_command.is_empty()
+        && !config.tts_command.iter().any(|arg| arg.contains("{text}"))
+    {
+        diagnostics
+            .warning("`tts_command` has no `{text}`, every announcement says the same thing");
+    }
+    if config.guild_jobs == 0 {
+        diagnostics.warning("`guild_jobs` is 0, the guilds run 1 job at a time anyway");
+    }
+    for deprecation in &config.deprecated_commands {
+        if COMMAND_LIST.contains(&deprecation.old.as_str()) {
+            diagnostics.error(format!(
+                "`{}` in `deprecated_commands` is still a command, it can't be redirected",
+                deprecation.old
+            ));
+        }
+        if !COMMAND_LIST.contains(&deprecation.new.as_str()) {
+            diagnostics.warning(format!(
+                "`{}` in `deprecated_commands` is not a command of turto",
+                deprecation.new
+            ));
+        }
+        if !is_active(env!("CARGO_PKG_VERSION"), deprecation.until.as_deref()) {
+            diagnostics.warning(format!(
+                "`{}` in `deprecated_commands` has been removed in {}, the entry can be removed",
+                deprecation.old,
+                deprecation.until.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+}
+
+fn check_help_file(path: &Path) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics::new(path);
+    let help = match fs::read_to_string(path) {
+        Ok(help_str) => match toml::from_str::<Help>(&help_str) {
+            Ok(help) => help,
+            Err(err) => {
+                diagnostics.error(format!("Failed to parse help info: {}", err));
+                return diagnostics.list;
+            }
+        },
+        Err(err) => {
+            diagnostics.error(format!("Failed to read the file: {}", err));
+            return diagnostics.list;
+        }
+    };
+
+    match help.get("default") {
+        Some(default) => {
+            for command_name in COMMAND_LIST {
+                if !default.contains_key(command_name) {
+                    diagnostics.error(format!(
+                        "Missing the help info of command `{}` in [default]",
+                        command_name
+                    ));
+                }
+            }
+        }
+        None => diagnostics.error("Missing the [default] section"),
+    }
+
+    // the help info of the commands of the plugins is optional
+    #[cfg(feature = "plugins")]
+    let plugin_commands = crate::plugin::plugins()
+        .iter()
+        .flat_map(|plugin| plugin.commands())
+        .map(|command| command.name)
+        .collect::<Vec<_>>();
+    #[cfg(not(feature = "plugins"))]
+    let plugin_commands = Vec::<String>::new();
+    let mut locales = help.keys().collect::<Vec<_>>();
+    locales.sort_unstable();
+    for locale in locales {
+        let mut command_names = help[locale].keys().collect::<Vec<_>>();
+        command_names.sort_unstable();
+        for command_name in command_names {
+            if !COMMAND_LIST.contains(&command_name.as_str())
+                && !plugin_commands.contains(command_name)
+            {
+                diagnostics.warning(format!(
+                    "Unknown command `{}` in [{}], it's never shown",
+                    command_name, locale
+                ));
+            }
+        }
+    }
+    diagnostics.list
+}
+
+fn check_templates_file(path: &Path) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics::new(path);
+    let templates = match fs::read_to_string(path) {
+        Ok(templates_str) => {
+            match toml::from_str::<HashMap<String, HashMap<String, String>>>(&templates_str) {
+                Ok(templates) => templates,
+                Err(err) => {
+                    diagnostics.error(format!("Failed to parse message templates: {}", err));
+                    return diagnostics.list;
+                }
+            }
+        }
+        Err(err) => {
+            diagnostics.error(format!("Failed to read the file: {}", err));
+            return diagnostics.list;
+        }
+    };
+    check_templates(&templates, &mut diagnostics);
+    diagnostics.list
+}
+
+fn check_templates(
+    templates: &HashMap<String, HashMap<String, String>>,
+    diagnostics: &mut Diagnostics,
+) {
+    let Some(default) = templates.get("default") else {
+        diagnostics.error("Missing the [default] section");
+        return;
+    };
+
+    let mut locales = templates.keys().collect::<Vec<_>>();
+    locales.sort_unstable();
+    for locale in locales {
+        let locale_templates = &templates[locale];
+        for template_name in TEMPLATES_LIST {
+            if locale_templates.contains_key(template_name) {
+                continue;
+            }
+            // a locale falls back to default only when the whole locale is missing
+            diagnostics.error(format!(
+                "Missing template `{}` in [{}]",
+                template_name, locale
+            ));
+        }
+
+        let mut template_names = locale_templates.keys().collect::<Vec<_>>();
+        template_names.sort_unstable();
+        for template_name in template_names {
+            if !TEMPLATES_LIST.contains(&template_name.as_str()) {
+                diagnostics.warning(format!(
+                    "Unknown template `{}` in [{}], it's never used",
+                    template_name, locale
+                ));
+                continue;
+            }
+            if locale == "default" {
+                continue;
+            }
+            // the placeholders of the default templates are taken as the available ones
+            let Some(default_template) = default.get(template_name) else {
+                continue;
+            };
+            let default_template = Template::parse(default_template);
+            let available = default_template.args().collect::<HashSet<_>>();
+            let template = Template::parse(&locale_templates[template_name]);
+            for arg in template.args() {
+                if !available.contains(arg) {
+                    diagnostics.warning(format!(
+                        "Unknown placeholder `{{{}}}` in template `{}` in [{}], it will be rendered as empty",
+                        arg, template_name, locale
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_templates, Diagnostics, Severity};
+    use crate::config::message_template::TEMPLATES_LIST;
+    use std::{collections::HashMap, path::Path};
+
+    #[test]
+    fn test_check_templates() {
+        let default = TEMPLATES_LIST
+            .iter()
+            .map(|name| (name.to_string(), "{title}".to_owned()))
+            .collect::<HashMap<_, _>>();
+        let mut zh = default.clone();
+        zh.remove("not_playing");
+        zh.insert("play".to_owned(), "{titel}".to_owned());
+        zh.insert("unknown".to_owned(), String::new());
+        let templates = HashMap::from([("default".to_owned(), default), ("zh-TW".to_owned(), zh)]);
+
+        let mut diagnostics = Diagnostics::new(Path::new("templates.toml"));
+        check_templates(&templates, &mut diagnostics);
+        let messages = diagnostics
+            .list
+            .iter()
+            .map(|diagnostic| (diagnostic.severity, diagnostic.message.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                (Severity::Error, "Missing template `not_playing` in [zh-TW]"),
+                (Severity::Warning, "Unknown placeholder `{titel}` in template `play` in [zh-TW], it will be rendered as empty"),
+                (Severity::Warning, "Unknown template `unknown` in [zh-TW], it's never used"),
+            ]
+        );
+    }
+}
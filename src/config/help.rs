@@ -1,34 +1,123 @@
 use crate::models::help::{CommandHelp, Help};
 use anyhow::{anyhow, Context, Result};
-use std::{collections::HashMap, path::Path, sync::OnceLock};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    sync::OnceLock,
+};
 
 static HELP: OnceLock<Help> = OnceLock::new();
-static COMMAND_LIST: [&str; 20] = [
+pub(super) static COMMAND_LIST: [&str; 100] = [
     "about",
+    "announce",
+    "announcements",
+    "approval",
+    "audit",
     "autoleave",
+    "autoplay",
     "ban",
+    "bookmark",
+    "cleanup",
+    "clear",
+    "clip",
+    "configcheck",
+    "debug",
+    "debug guild",
+    "ducking",
+    "event",
+    "event create",
+    "explicit",
+    "filter",
+    "filter 8d",
+    "filter karaoke",
+    "filter off",
+    "filter vocals",
+    "forward",
+    "greeting",
+    "greeting add",
+    "greeting list",
+    "greeting remove",
+    "guilds",
+    "guilds blacklist",
+    "guilds leave",
+    "guilds list",
+    "guilds unblacklist",
     "help",
     "insert",
+    "jobs",
+    "jobs cancel",
+    "jobs list",
     "join",
+    "jump",
     "leave",
+    "listeninglog",
+    "lockqueue",
+    "mix",
+    "neveragain",
+    "overlay",
+    "party",
+    "party start",
+    "party sync",
     "pause",
     "play",
     "playlist",
+    "playlist add",
+    "playlist append",
+    "playlist delete",
+    "playlist editor",
+    "playlist list",
+    "playlist load",
+    "playlist merge",
+    "playlist public",
+    "playlist save",
+    "playlist share",
+    "playlist show",
+    "playlist shuffle",
     "playwhat",
+    "prefs",
     "queue",
+    "queue add",
+    "queue bulk",
+    "queue paste",
+    "queue restore",
+    "queue snapshot",
+    "queue_this",
+    "queuelength",
     "remove",
     "repeat",
+    "replay",
+    "requestchannel",
+    "rewind",
+    "script",
+    "script add",
+    "script list",
+    "script remove",
     "seek",
+    "settings",
+    "settings export",
+    "settings import",
+    "settings show",
+    "setup",
     "shuffle",
     "skip",
     "stop",
+    "summon",
+    "tempvoice",
     "unban",
+    "unlockqueue",
+    "voiceannounce",
+    "voicestats",
     "volume",
 ];
 
-pub fn get_locale_help(locale: Option<&str>) -> &HashMap<String, CommandHelp> {
+pub fn get_locale_help(locale: Option<&str>) -> &'static HashMap<String, CommandHelp> {
     let help = get_help();
-    if let Some(res) = locale.and_then(|locale| help.get(locale)) {
+    // case insensitive for locale ID, the locale of a guild is stored in lowercase
+    let res = locale.and_then(|locale| {
+        help.iter()
+            .find(|(help_locale, _)| help_locale.eq_ignore_ascii_case(locale))
+    });
+    if let Some((_, res)) = res {
         res
     } else {
         // fallback to default if the locale is not available
@@ -36,6 +125,50 @@ pub fn get_locale_help(locale: Option<&str>) -> &HashMap<String, CommandHelp> {
     }
 }
 
+/// Get the help info of a command in the specified locale, fallback to default if not available
+pub fn get_command_help(locale: Option<&str>, command_name: &str) -> Option<&'static CommandHelp> {
+    get_locale_help(locale)
+        .get(command_name)
+        .or_else(|| get_locale_help(None).get(command_name))
+}
+
+/// Group the help info of every command by category, both categories and commands are sorted by name,
+/// commands without category fallback to the category they have in default language
+pub fn get_help_categories(
+    locale: Option<&str>,
+) -> Vec<(&'static str, Vec<(&'static str, &'static CommandHelp)>)> {
+    let default = get_locale_help(None);
+    let helps = get_locale_help(locale);
+    group_by_category(default.keys().map(|command_name| {
+        let command_help = helps.get(command_name).unwrap_or(&default[command_name]);
+        let category = command_help
+            .category
+            .as_deref()
+            .or(default[command_name].category.as_deref())
+            .unwrap_or_default();
+        (category, command_name.as_str(), command_help)
+    }))
+}
+
+fn group_by_category<'a>(
+    commands: impl Iterator<Item = (&'a str, &'a str, &'a CommandHelp)>,
+) -> Vec<(&'a str, Vec<(&'a str, &'a CommandHelp)>)> {
+    let mut categories = BTreeMap::<&str, Vec<(&str, &CommandHelp)>>::new();
+    for (category, command_name, command_help) in commands {
+        categories
+            .entry(category)
+            .or_default()
+            .push((command_name, command_help));
+    }
+    categories
+        .into_iter()
+        .map(|(category, mut commands)| {
+            commands.sort_unstable_by_key(|(command_name, _)| *command_name);
+            (category, commands)
+        })
+        .collect()
+}
+
 pub fn get_help() -> &'static Help {
     HELP.get().unwrap()
 }
@@ -75,3 +208,50 @@ pub fn load_help(help_path: impl AsRef<Path>) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::group_by_category;
+    use crate::models::help::CommandHelp;
+
+    fn command_help() -> CommandHelp {
+        CommandHelp {
+            short_description: String::new(),
+            description: String::new(),
+            parameters: None,
+            category: None,
+            usage: None,
+            examples: Vec::new(),
+            aliases: Vec::new(),
+            permissions: None,
+        }
+    }
+
+    #[test]
+    fn test_group_by_category() {
+        let help = command_help();
+        let commands = vec![
+            ("Playlist", "queue", &help),
+            ("Playback", "skip", &help),
+            ("Playlist", "clear", &help),
+            ("Playback", "play", &help),
+        ];
+        let grouped = group_by_category(commands.into_iter())
+            .into_iter()
+            .map(|(category, commands)| {
+                let names = commands
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>();
+                (category, names)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            grouped,
+            vec![
+                ("Playback", vec!["play", "skip"]),
+                ("Playlist", vec!["clear", "queue"])
+            ]
+        );
+    }
+}
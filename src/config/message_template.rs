@@ -5,7 +5,7 @@ use std::{collections::HashMap, fs, path::Path, sync::OnceLock};
 type Templates = HashMap<String, HashMap<String, Template>>;
 
 static TEMPLATES: OnceLock<Templates> = OnceLock::new();
-static TEMPLATES_LIST: [&str; 34] = [
+pub(super) static TEMPLATES_LIST: [&str; 217] = [
     "not_playing",
     "user_not_in_voice_channel",
     "bot_not_in_voice_channel",
@@ -13,6 +13,7 @@ static TEMPLATES_LIST: [&str; 34] = [
     "play",
     "pause",
     "skip",
+    "skip_success",
     "stop",
     "join",
     "leave",
@@ -40,14 +41,211 @@ static TEMPLATES_LIST: [&str; 34] = [
     "toggle_repeat",
     "invalid_playlist_page",
     "remove_many",
+    "command_not_found",
+    "help_usage",
+    "help_parameters",
+    "help_examples",
+    "help_aliases",
+    "help_permissions",
+    "help_page",
+    "owner_only",
+    "invalid_guild_id",
+    "guild_left",
+    "guild_leave_failed",
+    "guild_blacklisted",
+    "guild_already_blacklisted",
+    "guild_unblacklisted",
+    "guild_not_blacklisted",
+    "no_guilds",
+    "toggle_audit",
+    "empty_audit_log",
+    "config_check_passed",
+    "settings_title",
+    "settings_auto_leave",
+    "settings_volume",
+    "settings_repeat",
+    "settings_locale",
+    "settings_dj_role",
+    "settings_queue_limit",
+    "settings_not_set",
+    "settings_unlimited",
+    "dj_only",
+    "queue_full",
+    "settings_import_invalid",
+    "settings_import_confirm",
+    "settings_imported",
+    "settings_import_cancelled",
+    "premium_required",
+    "prefs_title",
+    "prefs_notify_track_start",
+    "prefs_search_source",
+    "prefs_locale",
+    "invalid_locale",
+    "track_start_notify",
+    "bookmark_dm",
+    "bookmark_sent",
+    "bookmark_dm_failed",
+    "greeting_added",
+    "greeting_removed",
+    "greeting_target_required",
+    "greeting_limit_reached",
+    "invalid_greeting_index",
+    "empty_greetings",
+    "queue_bulk_invalid_file",
+    "queue_bulk_progress",
+    "queue_bulk_done",
+    "live",
+    "toggle_explicit_filter",
+    "explicit_rejected",
+    "explicit_filtered",
+    "set_filter",
+    "filter_off",
+    "settings_trim_silence",
+    "rewind_success",
+    "replay",
+    "invalid_jump_index",
+    "toggle_autoplay",
+    "saved_playlist_saved",
+    "saved_playlist_shuffled",
+    "saved_playlist_deleted",
+    "saved_playlist_not_found",
+    "saved_playlist_exists",
+    "saved_playlist_limit",
+    "invalid_name",
+    "empty_saved_playlists",
+    "saved_playlist_added",
+    "saved_playlist_no_permission",
+    "saved_playlist_editor_added",
+    "saved_playlist_editor_removed",
+    "toggle_saved_playlist_public",
+    "saved_playlist_shared",
+    "saved_playlist_unshared",
+    "queue_snapshot_saved",
+    "queue_snapshot_restored",
+    "queue_snapshot_not_found",
+    "queue_snapshot_limit",
+    "no_urls_in_message",
+    "settings_reaction_controls",
+    "busy_in_voice_channel",
+    "vote_move_button",
+    "vote_not_listener",
+    "moved_voice_channel",
+    "vote_move_expired",
+    "already_in_voice_channel",
+    "track_stuck",
+    "autoleave_timeouts",
+    "set_message_cleanup",
+    "track_finished",
+    "welcome",
+    "set_announcements",
+    "announce_done",
+    "whats_new",
+    "settings_announcement_channel",
+    "setup_step",
+    "setup_skip",
+    "setup_done",
+    "setup_timeout",
+    "set_request_channel",
+    "request_channel_off",
+    "request_channels_disabled",
+    "player_idle",
+    "player_up_next",
+    "player_more",
+    "set_track_gain",
+    "never_again_added",
+    "never_again_removed",
+    "never_again_not_found",
+    "never_again_rejected",
+    "party_countdown",
+    "party_started",
+    "party_synced",
+    "toggle_ducking",
+    "voice_stats_disabled",
+    "no_voice_stats",
+    "voice_stats",
+    "clip_ready",
+    "clip_failed",
+    "invalid_playlist_range",
+    "mixed_interleave",
+    "mixed_alphabetical",
+    "voice_announcement",
+    "voice_announcement_autoplay",
+    "voice_announcements_disabled",
+    "toggle_voice_announcements",
+    "listening_log_entry",
+    "listening_log_entry_autoplay",
+    "listening_log_thread",
+    "set_listening_log",
+    "listening_log_off",
+    "invalid_event_start",
+    "event_create_failed",
+    "event_created",
+    "temp_voice_channel_name",
+    "temp_voice_disabled",
+    "temp_voice_failed",
+    "set_temp_voice",
+    "temp_voice_off",
+    "job_waiting",
+    "queue_bulk_canceled",
+    "no_jobs",
+    "job_entry",
+    "job_not_found",
+    "job_no_permission",
+    "job_canceled",
+    "resolving",
+    "cancel_button",
+    "resolving_canceled",
+    "queue_batch",
+    "queue_batch_more",
+    "guild_only",
+    "no_thread_commands",
+    "script_added",
+    "script_removed",
+    "invalid_script",
+    "script_limit_reached",
+    "invalid_script_index",
+    "empty_scripts",
+    "command_renamed",
+    "queue_duration_full",
+    "user_duration_full",
+    "queue_length_limits",
+    "queue_locked",
+    "lock_queue",
+    "lock_queue_timed",
+    "unlock_queue",
+    "too_many_pending_requests",
+    "request_pending",
+    "approval_request",
+    "request_approved",
+    "request_rejected",
+    "approval_required",
+    "toggle_approval",
+    "overlay_disabled",
+    "overlay_link",
+    "overlay_off",
 ];
 
 pub fn get_template(template_name: &str, locale: Option<&str>) -> &'static Template {
     let langs = TEMPLATES.get().unwrap();
-    match langs.get(&locale.unwrap_or("default").to_ascii_lowercase()) { // case insensitive for locale ID
+    match langs.get(&locale.unwrap_or("default").to_ascii_lowercase()) {
+        // case insensitive for locale ID
         Some(templates) => templates.get(template_name),
         None => langs.get("default").unwrap().get(template_name),
-    }.unwrap()
+    }
+    .unwrap()
+}
+
+/// The locales which have message templates, in lowercase
+pub fn get_locales() -> impl Iterator<Item = &'static str> {
+    let mut locales = TEMPLATES
+        .get()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .filter(|locale| *locale != "default")
+        .collect::<Vec<_>>();
+    locales.sort_unstable();
+    locales.into_iter()
 }
 
 pub fn load_templates(path: impl AsRef<Path>) -> Result<()> {
@@ -93,31 +291,42 @@ pub fn load_templates(path: impl AsRef<Path>) -> Result<()> {
     Ok(())
 }
 
-
 #[cfg(test)]
 mod tests {
-    use super::{get_template, load_templates};
+    use super::get_template;
+    use crate::test::setup_templates;
 
     #[test]
     fn test_get_unsupported_lang() {
-        load_templates("templates.toml.template").unwrap();
-        let cn = get_template("not_playing", Some("zh-CN")).renderer().render();
-        let ja = get_template("empty_playlist", Some("ja")).renderer().render();
-        let none = get_template("user_not_in_voice_channel", None).renderer().render();
+        setup_templates();
+        let cn = get_template("not_playing", Some("zh-CN"))
+            .renderer()
+            .render();
+        let ja = get_template("empty_playlist", Some("ja"))
+            .renderer()
+            .render();
+        let none = get_template("user_not_in_voice_channel", None)
+            .renderer()
+            .render();
         assert_eq!(cn.as_str(), "Not playing now.");
         assert_eq!(ja.as_str(), "The playlist is empty.");
         assert_eq!(none.as_str(), "You are not in a voice channel.");
     }
-    
+
     #[test]
     fn test_get_supported_lang() {
-        load_templates("templates.toml.template").unwrap();
-        let upper = get_template("not_playing", Some("ZH-TW")).renderer().render();
-        let lower = get_template("not_playing", Some("zh-tw")).renderer().render();
-        let mixed = get_template("not_playing", Some("zh-TW")).renderer().render();
+        setup_templates();
+        let upper = get_template("not_playing", Some("ZH-TW"))
+            .renderer()
+            .render();
+        let lower = get_template("not_playing", Some("zh-tw"))
+            .renderer()
+            .render();
+        let mixed = get_template("not_playing", Some("zh-TW"))
+            .renderer()
+            .render();
         assert_eq!(upper.as_str(), "現在沒有在播放任何東西。");
         assert_eq!(lower.as_str(), "現在沒有在播放任何東西。");
         assert_eq!(mixed.as_str(), "現在沒有在播放任何東西。");
-    
     }
 }
@@ -1,31 +1,163 @@
+pub mod check;
 pub mod help;
 pub mod message_template;
+pub mod secret;
 
 use crate::models::config::TurtoConfig;
-use anyhow::{Context, Result};
-use std::{fs, path::Path, sync::OnceLock};
-use tracing::warn;
+use anyhow::{bail, Context, Result};
+use check::{lint_config, Severity};
+use std::{env, fs, io, path::Path, sync::RwLock};
+use toml::{Table, Value};
 
-static CONFIG: OnceLock<TurtoConfig> = OnceLock::new();
+// the configs are leaked, the old one is still borrowed by what's running when it's reloaded
+static CONFIG: RwLock<Option<&'static TurtoConfig>> = RwLock::new(None);
 
 pub fn get_config() -> &'static TurtoConfig {
-    CONFIG.get().unwrap()
+    CONFIG.read().unwrap().unwrap()
 }
 
+pub const CONFIG_PATH: &str = "config.toml";
+pub const HELP_PATH: &str = "help.toml";
+pub const TEMPLATES_PATH: &str = "templates.toml";
+
+/// Load the config from the file, any key can be overridden by the `TURTO_<KEY>` environment
+/// variable, e.g. `TURTO_SEEK_LIMIT=300` overrides `seek_limit`. It can be loaded again while
+/// turto is running, the config stays the same if the new one is invalid
 pub fn load_config(config_path: impl AsRef<Path>) -> Result<()> {
-    let config = fs::read_to_string(config_path.as_ref())
-        .context(format!(
-            "Failed to load config from {}",
-            config_path.as_ref().display()
-        ))
-        .and_then(|config_toml| {
-            toml::from_str::<TurtoConfig>(&config_toml).context("Failed to parse config")
-        })?;
-    if config.owner.is_none() {
-        warn!("The owner of this bot hasn't been set");
+    let (config, _) = read_config(config_path.as_ref())?;
+    if let Some(err) = lint_config(&config)
+        .into_iter()
+        .find(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        bail!("Invalid config: {}", err.message);
     }
 
-    CONFIG.set(config).unwrap();
+    *CONFIG.write().unwrap() = Some(Box::leak(Box::new(config)));
 
     Ok(())
 }
+
+/// Read the config with the environment variable overrides applied, the table of the keys in the
+/// file is also returned if the file exists
+fn read_config(config_path: &Path) -> Result<(TurtoConfig, Option<Table>)> {
+    let file_table = match fs::read_to_string(config_path) {
+        Ok(config_toml) => Some(
+            config_toml
+                .parse::<Table>()
+                .context("Failed to parse config")?,
+        ),
+        // the whole config can be provided with environment variables, e.g. in containers
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => {
+            return Err(err).context(format!(
+                "Failed to load config from {}",
+                config_path.display()
+            ))
+        }
+    };
+    let mut table = file_table.clone().unwrap_or_default();
+    apply_env_overrides(&mut table, env::vars());
+    let config = table.try_into::<TurtoConfig>().with_context(|| {
+        if file_table.is_some() {
+            "Failed to parse config".to_owned()
+        } else {
+            format!(
+                "Failed to load config, {} is not found and the TURTO_* environment variables are incomplete",
+                config_path.display()
+            )
+        }
+    })?;
+    Ok((config, file_table))
+}
+
+fn apply_env_overrides(table: &mut Table, vars: impl Iterator<Item = (String, String)>) {
+    for (key, raw) in vars {
+        let Some(key) = env_config_key(&key) else {
+            continue;
+        };
+        // the value is parsed as a toml value, e.g. `true` or `600`, and is taken as a string if
+        // it's not valid toml, so quotes can be omitted for strings
+        let value = format!("value = {}", raw)
+            .parse::<Table>()
+            .ok()
+            .and_then(|mut value| value.remove("value"))
+            .unwrap_or(Value::String(raw));
+        // `__` separates the keys of the tables, e.g. `TURTO_ANTI_RAID__WINDOW` is `window` of
+        // `anti_raid`, the tables are made if they're not in the file
+        let mut path = key.split("__").collect::<Vec<_>>();
+        let Some(last) = path.pop() else {
+            continue;
+        };
+        let mut parent = Some(&mut *table);
+        for key in path {
+            parent = parent.and_then(|parent| {
+                parent
+                    .entry(key)
+                    .or_insert_with(|| Value::Table(Table::new()))
+                    .as_table_mut()
+            });
+        }
+        // it's ignored if one of the keys on the way isn't a table
+        if let Some(parent) = parent {
+            parent.insert(last.to_owned(), value);
+        }
+    }
+}
+
+// `TURTO_LOG` is the log filter, it becomes `log` which isn't a key and is simply ignored
+fn env_config_key(env_key: &str) -> Option<String> {
+    env_key
+        .strip_prefix("TURTO_")
+        .filter(|key| !key.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_env_overrides;
+    use toml::{Table, Value};
+
+    #[test]
+    fn test_env_overrides() {
+        let mut table = "seek_limit = 600\nallow_seek = true"
+            .parse::<Table>()
+            .unwrap();
+        let vars = [
+            ("TURTO_SEEK_LIMIT", "300"),
+            ("TURTO_ALLOW_SEEK", "false"),
+            ("TURTO_HTTP_ADDRESS", "0.0.0.0"),
+            ("TURTO_ERROR_REPORT_DSN", "\"https://key@sentry.io/1\""),
+            ("DISCORD_TOKEN", "token"),
+        ]
+        .map(|(key, value)| (key.to_owned(), value.to_owned()));
+        apply_env_overrides(&mut table, vars.into_iter());
+
+        assert_eq!(table["seek_limit"], Value::Integer(300));
+        assert_eq!(table["allow_seek"], Value::Boolean(false));
+        assert_eq!(table["http_address"], Value::from("0.0.0.0"));
+        assert_eq!(
+            table["error_report_dsn"],
+            Value::from("https://key@sentry.io/1")
+        );
+        assert!(!table.contains_key("discord_token"));
+    }
+
+    #[test]
+    fn test_nested_env_overrides() {
+        let mut table = "seek_limit = 600\n[anti_raid]\nwindow = 60"
+            .parse::<Table>()
+            .unwrap();
+        let vars = [
+            ("TURTO_ANTI_RAID__WINDOW", "30"),
+            ("TURTO_PLUGINS__LYRICS__ENABLED", "true"),
+            // `seek_limit` isn't a table
+            ("TURTO_SEEK_LIMIT__MAX", "1"),
+        ]
+        .map(|(key, value)| (key.to_owned(), value.to_owned()));
+        apply_env_overrides(&mut table, vars.into_iter());
+
+        assert_eq!(table["anti_raid"]["window"], Value::Integer(30));
+        assert_eq!(table["plugins"]["lyrics"]["enabled"], Value::Boolean(true));
+        assert_eq!(table["seek_limit"], Value::Integer(600));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{models::config::TurtoConfig, utils::get_http_client};
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use serenity::async_trait;
+use std::{env, fs};
+
+/// A source of secrets like the Discord token
+#[async_trait]
+pub trait SecretProvider: Send + Sync {
+    /// Get the secret by its name, `None` if this provider doesn't have it
+    async fn get(&self, name: &str) -> Result<Option<String>>;
+}
+
+/// Read the secret from the file specified by the `<NAME>_FILE` environment variable, e.g. a
+/// Docker or Kubernetes secret mounted as a file
+pub struct FileProvider;
+
+#[async_trait]
+impl SecretProvider for FileProvider {
+    async fn get(&self, name: &str) -> Result<Option<String>> {
+        let Ok(path) = env::var(format!("{}_FILE", name)) else {
+            return Ok(None);
+        };
+        let secret =
+            fs::read_to_string(&path).context(format!("Failed to read {} from {}", name, path))?;
+        Ok(Some(secret.trim().to_owned()))
+    }
+}
+
+/// Read the secret from the `<NAME>` environment variable
+pub struct EnvProvider;
+
+#[async_trait]
+impl SecretProvider for EnvProvider {
+    async fn get(&self, name: &str) -> Result<Option<String>> {
+        Ok(env::var(name).ok())
+    }
+}
+
+/// Read the secret from a HashiCorp Vault compatible KV secrets engine, the secret is the field
+/// named after the lowercased name, e.g. `discord_token`, the token is read from `VAULT_TOKEN`
+pub struct VaultProvider {
+    address: String,
+    path: String,
+}
+
+#[async_trait]
+impl SecretProvider for VaultProvider {
+    async fn get(&self, name: &str) -> Result<Option<String>> {
+        let token = match FileProvider.get("VAULT_TOKEN").await? {
+            Some(token) => token,
+            None => env::var("VAULT_TOKEN").context("VAULT_TOKEN is not set")?,
+        };
+        let url = format!(
+            "{}/v1/{}",
+            self.address.trim_end_matches('/'),
+            self.path.trim_start_matches('/')
+        );
+        let body = get_http_client()
+            .get(&url)
+            .header("X-Vault-Token", token)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context(format!("Failed to request the secret from {}", url))?
+            .text()
+            .await?;
+        let response = serde_json::from_str::<Value>(&body)
+            .map_err(|err| anyhow!("Invalid response from Vault: {}", err))?;
+        Ok(vault_secret(&response, &name.to_lowercase()))
+    }
+}
+
+// KV version 2 nests the secret in `data.data` while version 1 has it in `data`
+fn vault_secret(response: &Value, key: &str) -> Option<String> {
+    let data = &response["data"];
+    data["data"][key]
+        .as_str()
+        .or_else(|| data[key].as_str())
+        .map(str::to_owned)
+}
+
+/// The providers in the order they are looked up, the Vault provider is only used if configured
+pub fn providers(config: &TurtoConfig) -> Vec<Box<dyn SecretProvider>> {
+    let mut providers: Vec<Box<dyn SecretProvider>> =
+        vec![Box::new(FileProvider), Box::new(EnvProvider)];
+    if let (Some(address), Some(path)) = (&config.vault_address, &config.vault_path) {
+        providers.push(Box::new(VaultProvider {
+            address: address.clone(),
+            path: path.clone(),
+        }));
+    }
+    providers
+}
+
+/// Get the secret from the first provider that has a non-empty value of it
+pub async fn get_secret(
+    providers: &[Box<dyn SecretProvider>],
+    name: &str,
+) -> Result<Option<String>> {
+    for provider in providers {
+        if let Some(secret) = provider.get(name).await? {
+            if !secret.is_empty() {
+                return Ok(Some(secret));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::vault_secret;
+    use serde_json::json;
+
+    #[test]
+    fn test_vault_secret() {
+        let kv1 = json!({ "data": { "discord_token": "v1" } });
+        let kv2 = json!({ "data": { "data": { "discord_token": "v2" }, "metadata": {} } });
+        assert_eq!(vault_secret(&kv1, "discord_token").as_deref(), Some("v1"));
+        assert_eq!(vault_secret(&kv2, "discord_token").as_deref(), Some("v2"));
+        assert_eq!(vault_secret(&kv2, "other"), None);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{
+    models::{alias::Context, invocation::CommandSpan},
+    utils::{
+        deprecation::{canonical_name, notify_deprecation},
+        player::refresh_player,
+    },
+};
+use std::{future::Future, pin::Pin};
+
+// commands that mutate the queue or the playback, which are recorded in the audit log
+const AUDITED_COMMANDS: [&str; 7] = [
+    "clear", "mix", "remove", "shuffle", "skip", "stop", "volume",
+];
+
+pub fn after(ctx: Context<'_>) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+    Box::pin(async move {
+        if let Some(command_span) = ctx.invocation_data::<CommandSpan>().await {
+            command_span.finish(true);
+        }
+        notify_deprecation(ctx).await;
+
+        let Some(guild_id) = ctx.guild_id() else {
+            return;
+        };
+        // any command may change what the player in the request channel shows
+        refresh_player(guild_id);
+        if !AUDITED_COMMANDS.contains(&canonical_name(&ctx.command().qualified_name).as_ref()) {
+            return;
+        }
+        let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        if guild_data.config.audit {
+            guild_data
+                .audit_log
+                .record(ctx.author().id, ctx.invocation_string());
+        }
+    })
+}
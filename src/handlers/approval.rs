@@ -0,0 +1,117 @@
+use super::SerenityEventHandler;
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{DjOnly, RequestApproved, RequestRejected},
+    },
+    utils::{is_member_dj, player::refresh_player, queue::add_to_playlist, resolve_locale},
+};
+use serenity::{
+    all::{ComponentInteraction, CreateInteractionResponse, CreateInteractionResponseMessage},
+    prelude::Context,
+};
+use tracing::warn;
+
+impl SerenityEventHandler {
+    /// Queue or drop the request of the approval message, only the DJs and the administrators can
+    /// decide
+    pub(super) async fn approval_pressed(
+        &self,
+        ctx: &Context,
+        mci: &ComponentInteraction,
+        approved: bool,
+    ) {
+        let Some(guild_id) = mci.guild_id else {
+            return;
+        };
+        let dj_role = self.guild_data.entry(guild_id).or_default().config.dj_role;
+        if !mci
+            .member
+            .as_ref()
+            .is_some_and(|member| is_member_dj(member, None, dj_role))
+        {
+            let locale = resolve_locale(
+                &self.users,
+                &self.guild_data,
+                mci.user.id,
+                Some(guild_id),
+                Some(&mci.locale),
+            );
+            let response = CreateInteractionResponseMessage::new()
+                .content(TurtoMessage {
+                    locale,
+                    kind: DjOnly,
+                })
+                .ephemeral(true);
+            if let Err(err) = mci
+                .create_response(&ctx.http, CreateInteractionResponse::Message(response))
+                .await
+            {
+                warn!("Failed to respond to the approval button: {}", err);
+            }
+            return;
+        }
+
+        let (request, locale) = {
+            let mut guild_data = self.guild_data.entry(guild_id).or_default();
+            let request = guild_data.pending_requests.remove(&mci.message.id);
+            (request, guild_data.config.locale.clone())
+        };
+        let locale = locale.as_deref();
+        // another DJ has decided already if it's gone, only the buttons are removed then
+        let content = request.map(|request| {
+            let (requester, priority, dj) = (request.requester, request.priority, mci.user.id);
+            let title = request.title.clone();
+            if !approved {
+                return TurtoMessage {
+                    locale,
+                    kind: RequestRejected {
+                        requester,
+                        title: &title,
+                        dj,
+                    },
+                }
+                .to_string();
+            }
+            let (queue_item_kind, queue_type) = request.into_queue_item();
+            match add_to_playlist(
+                &self.guild_data,
+                guild_id,
+                queue_item_kind,
+                priority,
+                requester,
+                queue_type,
+            ) {
+                Ok(_) => {
+                    refresh_player(guild_id);
+                    TurtoMessage {
+                        locale,
+                        kind: RequestApproved {
+                            requester,
+                            title: &title,
+                            dj,
+                        },
+                    }
+                    .to_string()
+                }
+                Err(err) => err
+                    .message(locale)
+                    .map(|message| message.to_string())
+                    .unwrap_or_else(|| err.to_string()),
+            }
+        });
+        let mut response = CreateInteractionResponseMessage::new().components(vec![]);
+        if let Some(content) = content {
+            response = response.content(content);
+        }
+        if let Err(err) = mci
+            .create_response(
+                &ctx.http,
+                CreateInteractionResponse::UpdateMessage(response),
+            )
+            .await
+        {
+            warn!("Failed to update the approval message: {}", err);
+        }
+    }
+}
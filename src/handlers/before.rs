@@ -1,32 +1,271 @@
 use crate::{
-    messages::{TurtoMessage, TurtoMessageKind::BannedUserResponse},
-    models::alias::{Context, Error},
+    config::get_config,
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            ApprovalRequired, BannedUserResponse, DjOnly, NoThreadCommands, QueueLocked,
+        },
+    },
+    models::alias::{Command, Context, Error},
+    utils::{
+        cluster::owns,
+        deprecation::canonical_name,
+        get_locale, is_guild_dj, say,
+        script::{reply, run_rules, Action, ScriptEvent},
+    },
+};
+use poise::CreateReply;
+use serenity::{
+    all::CreateAllowedMentions,
+    model::prelude::{ChannelId, ChannelType},
 };
 use std::{future::Future, pin::Pin};
 
+/// The commands controlling the playback by their qualified names, only the members with the DJ
+/// role can use them when the role is set
+const DJ_COMMANDS: [&str; 16] = [
+    "clear",
+    "forward",
+    "jump",
+    "mix",
+    "neveragain",
+    "pause",
+    "queue restore",
+    "remove",
+    "repeat",
+    "replay",
+    "rewind",
+    "seek",
+    "shuffle",
+    "skip",
+    "stop",
+    "volume",
+];
+
+/// The commands queueing the tracks, only the DJs and the administrators can use them while the
+/// playlist is locked
+const QUEUE_COMMANDS: [&str; 9] = [
+    "insert",
+    "play",
+    "playlist append",
+    "playlist load",
+    "queue add",
+    "queue bulk",
+    "queue paste",
+    "queue restore",
+    "queue_this",
+];
+
+/// The commands whose requests can wait for approval, the other commands queueing the tracks are
+/// only for the DJs when the requests need approval
+const APPROVAL_COMMANDS: [&str; 2] = ["insert", "queue add"];
+
+/// The commands about the playback, they can't be used in threads if `thread_commands` is off
+const PLAYBACK_COMMANDS: [&str; 26] = [
+    "bookmark",
+    "clear",
+    "clip",
+    "forward",
+    "insert",
+    "join",
+    "jump",
+    "leave",
+    "mix",
+    "neveragain",
+    "party",
+    "pause",
+    "play",
+    "playlist",
+    "playwhat",
+    "queue",
+    "queue_this",
+    "remove",
+    "repeat",
+    "replay",
+    "rewind",
+    "seek",
+    "shuffle",
+    "skip",
+    "stop",
+    "summon",
+];
+
 pub fn before(ctx: Context<'_>) -> Pin<Box<dyn Future<Output = Result<bool, Error>> + Send + '_>> {
     Box::pin(async move {
         if let Some(guild_id) = ctx.guild_id() {
+            // another instance of the cluster handles the guild
+            if !owns(guild_id).await {
+                return Ok(false);
+            }
+            // ignore commands from blacklisted guilds, the bot is going to leave anyway
+            if ctx.data().blacklist.contains(&guild_id) {
+                return Ok(false);
+            }
             let user_id = ctx.author().id;
-            let is_banned = ctx
-                .data()
-                .guilds
-                .entry(guild_id)
-                .or_default()
-                .config
-                .banned
-                .contains(&user_id);
+            let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+            let is_banned = guild_data.config.banned.contains(&user_id);
+            let dj_role = guild_data.config.dj_role;
+            let queue_locked = guild_data.is_queue_locked();
+            let request_approval = guild_data.config.request_approval;
+            drop(guild_data);
 
             if is_banned {
-                ctx.say(TurtoMessage {
-                    locale: ctx.locale(),
-                    kind: BannedUserResponse,
-                })
+                say(
+                    ctx,
+                    TurtoMessage {
+                        locale: get_locale(ctx),
+                        kind: BannedUserResponse,
+                    },
+                )
                 .await?;
+                return Ok(false);
+            }
+
+            if let Some(thread_parent) = thread_parent(ctx) {
+                let root = ctx
+                    .parent_commands()
+                    .first()
+                    .copied()
+                    .unwrap_or(ctx.command());
+                if !get_config().thread_commands
+                    && PLAYBACK_COMMANDS.contains(&canonical_name(&root.name).as_ref())
+                {
+                    say(
+                        ctx,
+                        TurtoMessage {
+                            locale: get_locale(ctx),
+                            kind: NoThreadCommands(thread_parent),
+                        },
+                    )
+                    .await?;
+                    return Ok(false);
+                }
+            }
+
+            if let Some(dj_role) = dj_role {
+                if is_dj_command(ctx.command()) && !is_guild_dj(ctx, Some(dj_role)).await {
+                    say(
+                        ctx,
+                        TurtoMessage {
+                            locale: get_locale(ctx),
+                            kind: DjOnly,
+                        },
+                    )
+                    .await?;
+                    return Ok(false);
+                }
+            }
+
+            let command_name = canonical_name(&ctx.command().qualified_name);
+            let command_name = command_name.as_ref();
+            if QUEUE_COMMANDS.contains(&command_name) {
+                let kind = if queue_locked {
+                    Some(QueueLocked)
+                } else if request_approval && !APPROVAL_COMMANDS.contains(&command_name) {
+                    Some(ApprovalRequired)
+                } else {
+                    None
+                };
+                if let Some(kind) = kind {
+                    if !is_guild_dj(ctx, dj_role).await {
+                        say(
+                            ctx,
+                            TurtoMessage {
+                                locale: get_locale(ctx),
+                                kind,
+                            },
+                        )
+                        .await?;
+                        return Ok(false);
+                    }
+                }
             }
 
-            return Ok(!is_banned);
+            let root = ctx
+                .parent_commands()
+                .first()
+                .copied()
+                .unwrap_or(ctx.command());
+            // so a rule can't keep the admins from removing it
+            if canonical_name(&root.name) == "script" {
+                return Ok(true);
+            }
+            let fields = [
+                ("command", canonical_name(&root.name).into_owned()),
+                ("user", user_id.to_string()),
+                ("channel", ctx.channel_id().to_string()),
+            ];
+            for action in run_rules(&ctx.data().guilds, guild_id, ScriptEvent::Command, &fields) {
+                match action {
+                    // the reply of the command goes to the interaction
+                    Action::Reply(text) => reply(ctx.channel_id(), text),
+                    Action::Veto(text) => {
+                        let veto = CreateReply::default()
+                            .content(text)
+                            .allowed_mentions(CreateAllowedMentions::new());
+                        ctx.send(veto).await?;
+                        return Ok(false);
+                    }
+                    Action::Skip | Action::Clear => (),
+                }
+            }
         }
         Ok(true)
     })
 }
+
+/// Whether only the DJs can use the command when the DJ role is set. The subcommands are named
+/// with their parents, so `/playlist shuffle` isn't `/shuffle`
+fn is_dj_command(command: &Command) -> bool {
+    DJ_COMMANDS.contains(&canonical_name(&command.qualified_name).as_ref())
+}
+
+/// The channel the thread is in if the command is invoked in a thread
+fn thread_parent(ctx: Context<'_>) -> Option<ChannelId> {
+    let poise::Context::Application(ctx) = ctx else {
+        return None;
+    };
+    let channel = ctx.interaction.channel.as_ref()?;
+    let is_thread = matches!(
+        channel.kind,
+        ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread
+    );
+    if is_thread {
+        channel.parent_id
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_dj_command;
+    use crate::{
+        commands::{greeting::greeting, playlist::playlist, queue::queue, shuffle::shuffle},
+        models::alias::Command,
+    };
+    use poise::framework::set_qualified_names;
+
+    fn subcommand<'a>(command: &'a Command, name: &str) -> &'a Command {
+        command
+            .subcommands
+            .iter()
+            .find(|subcommand| subcommand.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_is_dj_command() {
+        crate::test::setup_config();
+        let mut commands = [greeting(), playlist(), queue(), shuffle()];
+        set_qualified_names(&mut commands);
+        let [greeting, playlist, queue, shuffle] = &commands;
+
+        assert!(is_dj_command(shuffle));
+        assert!(is_dj_command(subcommand(queue, "restore")));
+        // the subcommands named like the DJ commands
+        assert!(!is_dj_command(subcommand(playlist, "shuffle")));
+        assert!(!is_dj_command(subcommand(greeting, "remove")));
+        assert!(!is_dj_command(subcommand(queue, "add")));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::models::guild::data::GuildData;
+use dashmap::DashMap;
+use serenity::{all::GuildId, async_trait};
+use songbird::{
+    events::{CoreEvent, Event, EventContext, EventHandler},
+    tracks::TrackHandle,
+    Call,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+/// The music is this loud while someone is speaking
+const DUCKED_VOLUME: f32 = 0.3;
+/// The volume is restored after nobody has spoken for this long
+const DUCK_RELEASE: Duration = Duration::from_millis(1500);
+
+/// The track each guild is ducking, the handlers of the previous tracks cancel themselves
+static DUCKING_TRACKS: LazyLock<DashMap<GuildId, Instant>> = LazyLock::new(DashMap::new);
+
+/// Lower the volume of the track while the users in the voice channel are speaking, it's added to
+/// the call for every track when `ducking` of the guild is on
+pub struct DuckingHandler {
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    guild_id: GuildId,
+    track_handle: TrackHandle,
+    /// When the handler is added, the one added last in the guild is the handler of the track
+    since: Instant,
+    /// What's passed to yt-dlp to play the track, the gain of the track is kept when restored
+    url: Arc<str>,
+    ducked: AtomicBool,
+    /// When someone was speaking last time, `None` if nobody has spoken during the track
+    last_speech: Mutex<Option<Instant>>,
+}
+
+impl DuckingHandler {
+    pub fn new(
+        guild_data: Arc<DashMap<GuildId, GuildData>>,
+        guild_id: GuildId,
+        track_handle: TrackHandle,
+        url: Arc<str>,
+    ) -> Self {
+        let since = Instant::now();
+        DUCKING_TRACKS.insert(guild_id, since);
+        Self {
+            guild_data,
+            guild_id,
+            track_handle,
+            since,
+            url,
+            ducked: AtomicBool::new(false),
+            last_speech: Mutex::new(None),
+        }
+    }
+
+    /// The volume of the track as it's set by the guild, `None` if ducking is turned off
+    fn full_volume(&self) -> Option<f32> {
+        let guild_data = self.guild_data.get(&self.guild_id)?;
+        guild_data
+            .config
+            .ducking
+            .then(|| guild_data.track_volume(&self.url))
+    }
+}
+
+#[async_trait]
+impl EventHandler for DuckingHandler {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        let EventContext::VoiceTick(tick) = ctx else {
+            return None;
+        };
+        let current = DUCKING_TRACKS
+            .get(&self.guild_id)
+            .is_some_and(|since| *since == self.since);
+        let Some(full_volume) = self.full_volume().filter(|_| current) else {
+            // turned off while it's ducked
+            if self.ducked.swap(false, Ordering::Relaxed) {
+                if let Some(volume) = self
+                    .guild_data
+                    .get(&self.guild_id)
+                    .map(|guild_data| guild_data.track_volume(&self.url))
+                {
+                    let _ = self.track_handle.set_volume(volume);
+                }
+            }
+            return Some(Event::Cancel);
+        };
+
+        let now = Instant::now();
+        let speaking = !tick.speaking.is_empty();
+        let volume = {
+            let mut last_speech = self.last_speech.lock().unwrap();
+            if speaking {
+                *last_speech = Some(now);
+            }
+            let silence = last_speech.map(|last_speech| now.duration_since(last_speech));
+            duck_volume(full_volume, speaking, silence)
+        };
+        let ducked = volume < full_volume;
+        if ducked == self.ducked.swap(ducked, Ordering::Relaxed) {
+            return None;
+        }
+        // the track has ended if it fails
+        if let Err(err) = self.track_handle.set_volume(volume) {
+            warn!(
+                "Failed to duck the track in guild {}: {}",
+                self.guild_id, err
+            );
+            return Some(Event::Cancel);
+        }
+        None
+    }
+}
+
+/// The volume while someone is speaking or spoke within `DUCK_RELEASE`
+fn duck_volume(full_volume: f32, speaking: bool, silence: Option<Duration>) -> f32 {
+    if speaking || silence.is_some_and(|silence| silence < DUCK_RELEASE) {
+        full_volume * DUCKED_VOLUME
+    } else {
+        full_volume
+    }
+}
+
+/// Duck the track while it's playing in the call, the handler of the previous track is replaced
+pub fn start_ducking(
+    call: &mut Call,
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    guild_id: GuildId,
+    track_handle: TrackHandle,
+    url: Arc<str>,
+) {
+    let handler = DuckingHandler::new(guild_data, guild_id, track_handle, url);
+    call.add_global_event(Event::Core(CoreEvent::VoiceTick), handler);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{duck_volume, DUCKED_VOLUME, DUCK_RELEASE};
+    use std::time::Duration;
+
+    #[test]
+    fn test_duck_volume() {
+        assert_eq!(duck_volume(0.8, true, None), 0.8 * DUCKED_VOLUME);
+        assert_eq!(
+            duck_volume(0.8, false, Some(Duration::from_millis(500))),
+            0.8 * DUCKED_VOLUME
+        );
+        assert_eq!(duck_volume(0.8, false, Some(DUCK_RELEASE)), 0.8);
+        assert_eq!(duck_volume(0.8, false, None), 0.8);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::GuildOnly},
+    models::{alias::Error, data::Data, error::TurtoError, invocation::CommandSpan},
+    utils::{
+        get_locale,
+        report::{report_error, ErrorReport},
+        say,
+    },
+};
+use poise::FrameworkError;
+use std::{future::Future, pin::Pin};
+use tracing::{error, info};
+
+pub fn on_error(
+    error: FrameworkError<'_, Data, Error>,
+) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+    Box::pin(async move {
+        if let FrameworkError::Command { error, ctx, .. } = &error {
+            // the expected errors are replied like the results of the command
+            let expected = error.downcast_ref::<TurtoError>().and_then(|turto_error| {
+                Some((turto_error, turto_error.message(get_locale(*ctx))?))
+            });
+            if let Some(command_span) = ctx.invocation_data::<CommandSpan>().await {
+                command_span.finish(expected.is_some());
+            }
+            if let Some((turto_error, message)) = expected {
+                info!(
+                    "Command {} of {} ended: {}",
+                    ctx.command().qualified_name,
+                    ctx.author().id,
+                    turto_error
+                );
+                if let Err(err) = say(*ctx, message).await {
+                    error!("Failed to reply the error: {}", err);
+                }
+                return;
+            }
+            report_error(
+                ErrorReport {
+                    title: "Command error",
+                    guild_id: ctx.guild_id(),
+                    command: Some(&ctx.command().qualified_name),
+                    user_id: Some(ctx.author().id),
+                    ..Default::default()
+                },
+                error.as_ref(),
+            )
+            .await;
+        }
+        // the commands hidden in DMs may still be invoked before the command list is refreshed
+        if let FrameworkError::GuildOnly { ctx, .. } = &error {
+            let message = TurtoMessage {
+                locale: get_locale(*ctx),
+                kind: GuildOnly,
+            };
+            if let Err(err) = say(*ctx, message).await {
+                error!("Failed to reply the error: {}", err);
+            }
+            return;
+        }
+        // the checks reply the users themselves, or they're left to another instance of the cluster
+        if let FrameworkError::CommandCheckFailed { error: None, .. } = &error {
+            return;
+        }
+        // let poise log the error and respond to the user
+        if let Err(err) = poise::builtins::on_error(error).await {
+            error!("Error while handling error: {}", err);
+        }
+    })
+}
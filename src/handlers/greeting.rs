@@ -0,0 +1,75 @@
+use super::SerenityEventHandler;
+use crate::{
+    config::get_config,
+    models::guild::greeting::{find_greeting, GreetingEvent},
+    utils::get_http_client,
+};
+use serenity::{model::voice::VoiceState, prelude::Context};
+use songbird::{input::YoutubeDl, tracks::Track};
+use std::time::{Duration, Instant};
+
+impl SerenityEventHandler {
+    /// Play the join/leave sound of the user if the user joins or leaves the voice channel turto
+    /// is in, the sound is mixed with the playing track instead of interrupting it
+    pub(super) async fn greet(&self, ctx: &Context, old: Option<&VoiceState>, new: &VoiceState) {
+        let Some(guild_id) = new.guild_id else {
+            return;
+        };
+        let Some(call) = songbird::get(ctx)
+            .await
+            .and_then(|manager| manager.get(guild_id))
+        else {
+            return;
+        };
+        let Some(bot_channel) = call.lock().await.current_channel() else {
+            return;
+        };
+        let is_bot_channel = |voice_state: Option<&VoiceState>| {
+            voice_state
+                .and_then(|voice_state| voice_state.channel_id)
+                .is_some_and(|channel_id| channel_id.get() == bot_channel.0.get())
+        };
+        let event = match (is_bot_channel(old), is_bot_channel(Some(new))) {
+            (false, true) => GreetingEvent::Join,
+            (true, false) => GreetingEvent::Leave,
+            _ => return,
+        };
+
+        let user_id = new.user_id;
+        let roles = new
+            .member
+            .as_ref()
+            .map(|member| member.roles.as_slice())
+            .unwrap_or_default();
+        let guild_data = self.guild_data.entry(guild_id).or_default();
+        let Some(greeting) = find_greeting(&guild_data.config.greetings, event, user_id, roles)
+        else {
+            return;
+        };
+        let url = greeting.url.clone();
+        let volume = guild_data.config.volume;
+        drop(guild_data);
+
+        let config = get_config();
+        let cooldown = Duration::from_secs(config.greeting_cooldown);
+        let now = Instant::now();
+        let mut last_played = self
+            .greeting_cooldowns
+            .entry((guild_id, user_id))
+            .or_insert(now);
+        if *last_played != now && now.duration_since(*last_played) < cooldown {
+            return;
+        }
+        *last_played = now;
+        drop(last_played);
+
+        let source = YoutubeDl::new(get_http_client(), url);
+        let track_handle = call.lock().await.play(Track::from(source).volume(*volume));
+        let max_duration = Duration::from_secs(config.greeting_max_duration);
+        tokio::spawn(async move {
+            tokio::time::sleep(max_duration).await;
+            // the sound may have ended already
+            let _ = track_handle.stop();
+        });
+    }
+}
@@ -0,0 +1,351 @@
+use super::{voice_stats::clear_voice_session, SerenityEventHandler};
+use crate::{
+    config::get_config,
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{Welcome, WhatsNew},
+    },
+    models::{
+        alias::Error,
+        data::Data,
+        guild::{data::GuildData, snapshot::SnapshotTrack, was_playing::WasPlaying},
+        playing::Playing,
+    },
+    utils::{
+        announce::RELEASE_NOTES_URL, cluster::owns, misc::unix_now, play::play_url,
+        presence::initial_activity, resolve_locale,
+    },
+};
+use dashmap::DashMap;
+use serenity::{
+    all::{ChannelId, ChannelType, Guild, GuildId, Ready, ResumedEvent, UnavailableGuild, UserId},
+    prelude::Context,
+};
+use songbird::Songbird;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+impl SerenityEventHandler {
+    /// Set the presence and resume what the guilds of the shard were playing before shutting down,
+    /// it's run every time a shard connects with a new session
+    pub(super) async fn shard_ready(&self, ctx: &Context, ready: &Ready) {
+        let shard = ready.shard.map_or(0, |shard| shard.id.0);
+        info!(
+            "{} is connected with user id {}, session id {}, shard {} is ready with {} guilds",
+            ready.user.name,
+            ready.user.id,
+            ready.session_id,
+            shard,
+            ready.guilds.len()
+        );
+        if let Some(activity) = initial_activity(&ctx.cache) {
+            ctx.set_activity(Some(activity));
+        }
+        let guilds = ready
+            .guilds
+            .iter()
+            .map(|guild| guild.id)
+            .collect::<Vec<_>>();
+        self.resume_playing(ctx, &guilds).await;
+    }
+
+    pub(super) async fn shard_resumed(&self, ctx: &Context, _: &ResumedEvent) {
+        // the session is kept, so is the presence and the calls
+        info!("Shard {} resumed its session", ctx.shard_id);
+    }
+
+    /// Rejoin the voice channels and play the tracks recorded by `record_playing`, the tracks
+    /// which can't be played are put back to the front of the playlists instead
+    async fn resume_playing(&self, ctx: &Context, guilds: &[GuildId]) {
+        let mut was_playing = Vec::new();
+        for guild_id in guilds {
+            // the guilds of the other instances of the cluster are resumed by them
+            let recorded = self
+                .guild_data
+                .get(guild_id)
+                .is_some_and(|guild_data| guild_data.was_playing.is_some());
+            if !recorded || !owns(*guild_id).await {
+                continue;
+            }
+            let track = self
+                .guild_data
+                .get_mut(guild_id)
+                .and_then(|mut guild_data| guild_data.was_playing.take());
+            if let Some(track) = track {
+                was_playing.push((*guild_id, track));
+            }
+        }
+        if was_playing.is_empty() {
+            return;
+        }
+        let Some(manager) = songbird::get(ctx).await else {
+            return;
+        };
+        for (guild_id, WasPlaying { channel, track }) in was_playing {
+            let call = match manager.join(guild_id, channel).await {
+                Ok(call) => call,
+                Err(err) => {
+                    warn!(
+                        "Failed to rejoin {} in guild {}: {}",
+                        channel, guild_id, err
+                    );
+                    self.requeue(guild_id, track);
+                    continue;
+                }
+            };
+            let played = play_url(
+                call,
+                self.guild_data.clone(),
+                self.playing.clone(),
+                guild_id,
+                &track.item.url,
+                track.item.requester,
+            )
+            .await;
+            if let Err(err) = played {
+                error!(
+                    "Failed to resume {} in guild {}: {}",
+                    track.item.url, guild_id, err
+                );
+                self.requeue(guild_id, track);
+                continue;
+            }
+            if track.position > 0 {
+                if let Some(playing) = self.playing.read().await.get(&guild_id) {
+                    // the ones which can't be seeked, like live streams, start over
+                    let position = Duration::from_secs(track.position);
+                    if let Err(err) = playing.track_handle.seek_async(position).await {
+                        warn!("Failed to resume the track at {:?}: {}", position, err);
+                    }
+                }
+            }
+            info!("Resumed playing {} in guild {}", track.item.title, guild_id);
+        }
+    }
+
+    fn requeue(&self, guild_id: GuildId, track: SnapshotTrack) {
+        self.guild_data
+            .entry(guild_id)
+            .or_default()
+            .playlist
+            .push_front(track.item);
+    }
+
+    /// Set the guild up when turto is invited to it, or invited back before its data is deleted
+    pub(super) async fn guild_joined(&self, ctx: &Context, guild: &Guild) {
+        let returned = {
+            let mut guild_data = self.guild_data.entry(guild.id).or_default();
+            // only the versions after joining are worth a notice
+            guild_data.announced_version = Some(env!("CARGO_PKG_VERSION").to_owned());
+            guild_data.left_at.take().is_some()
+        };
+        info!(
+            "Joined guild {} ({}), {}",
+            guild.name,
+            guild.id,
+            if returned {
+                "its data is kept"
+            } else {
+                "with no data"
+            }
+        );
+        if !get_config().welcome_message || !owns(guild.id).await {
+            return;
+        }
+        let Some(channel_id) = welcome_channel(ctx, guild) else {
+            return;
+        };
+        // nobody has invoked anything yet, so there is no user locale
+        let locale = resolve_locale(
+            &self.users,
+            &self.guild_data,
+            UserId::default(),
+            Some(guild.id),
+            Some(&guild.preferred_locale),
+        );
+        let message = TurtoMessage {
+            locale,
+            kind: Welcome,
+        };
+        if let Err(err) = channel_id.say(&ctx.http, message.to_string()).await {
+            warn!(
+                "Failed to send the welcome message to {}: {}",
+                guild.id, err
+            );
+        }
+    }
+
+    /// Post the notice about the new version once turto is updated, see `announce_updates` in the
+    /// config
+    pub(super) async fn announce_update(&self, ctx: &Context, guild: &Guild) {
+        if !get_config().announce_updates || !owns(guild.id).await {
+            return;
+        }
+        let version = env!("CARGO_PKG_VERSION");
+        let channel_id = {
+            let mut guild_data = self.guild_data.entry(guild.id).or_default();
+            if guild_data.announced_version.as_deref() == Some(version) {
+                return;
+            }
+            guild_data.announced_version = Some(version.to_owned());
+            guild_data
+                .config
+                .announcement_channel(guild.system_channel_id)
+        };
+        let Some(channel_id) = channel_id else {
+            return;
+        };
+        let locale = resolve_locale(
+            &self.users,
+            &self.guild_data,
+            UserId::default(),
+            Some(guild.id),
+            Some(&guild.preferred_locale),
+        );
+        let message = TurtoMessage {
+            locale,
+            kind: WhatsNew {
+                version,
+                url: RELEASE_NOTES_URL,
+            },
+        };
+        if let Err(err) = channel_id.say(&ctx.http, message.to_string()).await {
+            warn!(
+                "Failed to announce version {} to {}: {}",
+                version, guild.id, err
+            );
+        }
+    }
+
+    /// Stop playing in the guild turto is removed from, its data is deleted later by
+    /// `guild_data_cleaner`
+    pub(super) async fn guild_left(&self, ctx: &Context, guild: &UnavailableGuild) {
+        // the guild is only unavailable during an outage, turto is still in it
+        if guild.unavailable {
+            return;
+        }
+        if let Some(mut guild_data) = self.guild_data.get_mut(&guild.id) {
+            guild_data.left_at = Some(unix_now());
+        }
+        self.playing.write().await.remove(&guild.id);
+        self.control_messages.remove(&guild.id);
+        clear_voice_session(guild.id);
+        if let Some(manager) = songbird::get(ctx).await {
+            // the call is already disconnected along with the guild
+            let _ = manager.remove(guild.id).await;
+        }
+        info!("Left guild {}", guild.id);
+    }
+}
+
+/// Register the commands in `test_guild` if it's set, otherwise globally
+pub async fn register_commands(
+    ctx: &Context,
+    commands: &[poise::Command<Data, Error>],
+) -> Result<(), serenity::Error> {
+    match get_config().test_guild {
+        Some(guild_id) => {
+            poise::builtins::register_in_guild(ctx, commands, guild_id).await?;
+            info!(
+                "Registered {} commands in guild {}",
+                commands.len(),
+                guild_id
+            );
+        }
+        None => {
+            poise::builtins::register_globally(ctx, commands).await?;
+            info!("Registered {} commands globally", commands.len());
+        }
+    }
+    Ok(())
+}
+
+/// Record what the guilds are playing and where before shutting down, so they're resumed by the
+/// ready handler after restarting
+pub async fn record_playing(
+    manager: &Songbird,
+    guild_data: &DashMap<GuildId, GuildData>,
+    guild_playing: &RwLock<HashMap<GuildId, Playing>>,
+) {
+    for (guild_id, playing) in guild_playing.read().await.iter() {
+        if let Some(was_playing) = WasPlaying::record(manager, *guild_id, playing).await {
+            guild_data.entry(*guild_id).or_default().was_playing = Some(was_playing);
+        }
+    }
+}
+
+/// The system channel of the guild, otherwise the first text channel turto can send messages to
+fn welcome_channel(ctx: &Context, guild: &Guild) -> Option<ChannelId> {
+    if guild.system_channel_id.is_some() {
+        return guild.system_channel_id;
+    }
+    let member = guild.members.get(&ctx.cache.current_user().id)?;
+    let mut channels = guild
+        .channels
+        .values()
+        .filter(|channel| channel.kind == ChannelType::Text)
+        .collect::<Vec<_>>();
+    channels.sort_unstable_by_key(|channel| channel.position);
+    channels
+        .into_iter()
+        .find(|channel| guild.user_permissions_in(channel, member).send_messages())
+        .map(|channel| channel.id)
+}
+
+/// Remove the data of the guilds turto left longer than the retention ago, their ids are returned
+fn purge_left_guilds(
+    guild_data: &DashMap<GuildId, GuildData>,
+    retention: Duration,
+    now: u64,
+) -> Vec<GuildId> {
+    let mut purged = Vec::new();
+    guild_data.retain(|guild_id, guild_data| match guild_data.left_at {
+        Some(left_at) if now.saturating_sub(left_at) >= retention.as_secs() => {
+            purged.push(*guild_id);
+            false
+        }
+        _ => true,
+    });
+    purged
+}
+
+/// Delete the data of the guilds turto has been removed from for `guild_data_retention` days
+pub async fn guild_data_cleaner(guild_data: Arc<DashMap<GuildId, GuildData>>) {
+    let retention = Duration::from_secs(get_config().guild_data_retention * 24 * 60 * 60);
+    let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+    loop {
+        interval.tick().await;
+        for guild_id in purge_left_guilds(&guild_data, retention, unix_now()) {
+            info!("Deleted the data of guild {}, which turto left", guild_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::purge_left_guilds;
+    use crate::models::guild::data::GuildData;
+    use dashmap::DashMap;
+    use serenity::all::GuildId;
+    use std::time::Duration;
+
+    #[test]
+    fn test_purge_left_guilds() {
+        let guild_data = DashMap::<GuildId, GuildData>::new();
+        guild_data.entry(GuildId::new(1)).or_default();
+        guild_data.entry(GuildId::new(2)).or_default().left_at = Some(100);
+        guild_data.entry(GuildId::new(3)).or_default().left_at = Some(1000);
+
+        let retention = Duration::from_secs(500);
+        assert_eq!(
+            purge_left_guilds(&guild_data, retention, 700),
+            [GuildId::new(2)]
+        );
+        assert!(purge_left_guilds(&guild_data, retention, 1200).is_empty());
+        assert!(guild_data.contains_key(&GuildId::new(1)));
+        assert!(guild_data.contains_key(&GuildId::new(3)));
+    }
+}
@@ -1,6 +1,10 @@
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use serenity::{
-    all::{ChannelId, GuildId},
+    all::{
+        ChannelId, CreateInteractionResponse, CreateInteractionResponseMessage, Guild, GuildId,
+        Interaction, Message, MessageId, Reaction, ResumedEvent, UnavailableGuild, UserId,
+        VoiceServerUpdateEvent,
+    },
     async_trait,
     model::{prelude::Ready, voice::VoiceState},
     prelude::{Context, EventHandler},
@@ -11,32 +15,145 @@ use std::{
         atomic::{AtomicUsize, Ordering},
         Arc,
     },
+    time::{Duration, Instant},
 };
 use tokio::sync::RwLock;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-use crate::models::{autoleave::AutoleaveType, guild::data::GuildData, playing::Playing};
+use crate::{
+    messages::TurtoMessage,
+    models::{guild::data::GuildData, playing::Playing, premium::Feature, user_prefs::UserPrefs},
+    utils::{
+        approval::{APPROVE_BUTTON_ID, REJECT_BUTTON_ID},
+        bookmark::{bookmark_playing, BOOKMARK_BUTTON_ID},
+        cluster::owns,
+        listening_log::start_log_session,
+        player::player_control,
+        premium::{has_feature, voice_bitrate},
+        resolve_locale,
+    },
+};
+use voice_migration::Migration;
 
+pub mod after;
+pub mod approval;
 pub mod before;
+pub mod ducking;
+pub mod error;
+pub mod greeting;
+pub mod lifecycle;
+pub mod pre;
+pub mod reaction;
+pub mod request_channel;
 pub mod track_end;
+pub mod track_hooks;
+pub mod voice_migration;
+pub mod voice_stats;
 
 #[derive(Default)]
 pub struct SerenityEventHandler {
     pub playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
     pub guild_data: Arc<DashMap<GuildId, GuildData>>,
+    pub blacklist: Arc<DashSet<GuildId>>,
+    pub users: Arc<DashMap<UserId, UserPrefs>>,
     pub voice_channel_counts: DashMap<ChannelId, AtomicUsize>,
+    pub greeting_cooldowns: DashMap<(GuildId, UserId), Instant>,
+    /// The latest now playing message of each guild with the reaction controls
+    pub control_messages: Arc<DashMap<GuildId, MessageId>>,
+    /// The voice server changes in progress
+    pub voice_migrations: Arc<DashMap<GuildId, Migration>>,
 }
 
 #[async_trait]
 impl EventHandler for SerenityEventHandler {
-    async fn ready(&self, _: Context, ready: Ready) {
-        let name = &ready.user.name;
-        let user_id = &ready.user.id;
-        let session = &ready.session_id;
-        info!(
-            "{} is connected with user id {}, session id {}",
-            name, user_id, session
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        self.shard_ready(&ctx, &ready).await;
+    }
+
+    async fn resume(&self, ctx: Context, resumed: ResumedEvent) {
+        self.shard_resumed(&ctx, &resumed).await;
+    }
+
+    async fn guild_create(&self, ctx: Context, guild: Guild, is_new: Option<bool>) {
+        // leave the blacklisted guilds whenever they become available
+        if self.blacklist.contains(&guild.id) {
+            match guild.id.leave(&ctx.http).await {
+                Ok(_) => info!("Left blacklisted guild {} ({})", guild.name, guild.id),
+                Err(err) => warn!("Failed to leave blacklisted guild {}: {}", guild.id, err),
+            }
+            return;
+        }
+        // the guilds turto is already in are created as well when it connects
+        if is_new == Some(true) {
+            self.guild_joined(&ctx, &guild).await;
+        } else {
+            self.announce_update(&ctx, &guild).await;
+        }
+    }
+
+    async fn guild_delete(&self, ctx: Context, incomplete: UnavailableGuild, _full: Option<Guild>) {
+        self.guild_left(&ctx, &incomplete).await;
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        // the commands and the components with collectors are handled by poise
+        let Interaction::Component(mci) = interaction else {
+            return;
+        };
+        if let Some(guild_id) = mci.guild_id {
+            if !owns(guild_id).await {
+                return;
+            }
+        }
+        if let Some(control) = player_control(&mci.data.custom_id) {
+            self.player_pressed(&ctx, &mci, control).await;
+            return;
+        }
+        if [APPROVE_BUTTON_ID, REJECT_BUTTON_ID].contains(&mci.data.custom_id.as_str()) {
+            let approved = mci.data.custom_id == APPROVE_BUTTON_ID;
+            self.approval_pressed(&ctx, &mci, approved).await;
+            return;
+        }
+        if mci.data.custom_id != BOOKMARK_BUTTON_ID {
+            return;
+        }
+        let Some(guild_id) = mci.guild_id else {
+            return;
+        };
+        let user_id = mci.user.id;
+        let locale = resolve_locale(
+            &self.users,
+            &self.guild_data,
+            user_id,
+            Some(guild_id),
+            Some(&mci.locale),
         );
+        let kind = bookmark_playing(
+            &ctx.http,
+            &self.playing,
+            &self.users,
+            guild_id,
+            user_id,
+            locale,
+        )
+        .await;
+        let response = CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(TurtoMessage { locale, kind }.to_string())
+                .ephemeral(true),
+        );
+        if let Err(err) = mci.create_response(&ctx.http, response).await {
+            warn!("Failed to respond to the bookmark button: {}", err);
+        }
+    }
+
+    async fn message(&self, ctx: Context, msg: Message) {
+        // the messages in DMs are ignored, `/help`, `/about` and `/prefs` can be used there
+        self.request_track(&ctx, &msg).await;
+    }
+
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        self.react_control(&ctx, &reaction).await;
     }
 
     async fn cache_ready(&self, ctx: Context, guilds: Vec<GuildId>) {
@@ -58,32 +175,58 @@ impl EventHandler for SerenityEventHandler {
         }
     }
 
+    async fn voice_server_update(&self, ctx: Context, event: VoiceServerUpdateEvent) {
+        self.voice_server_migrated(&ctx, &event).await;
+    }
+
     async fn voice_state_update(&self, ctx: Context, old: Option<VoiceState>, new: VoiceState) {
         if new.user_id == ctx.cache.current_user().id {
-            // if the bot is manually disconnected by the user instead using command,
-            // then remove the current track handle (if there is one)
-            self.playing.write().await.remove(&new.guild_id.unwrap());
+            if let Some(guild_id) = new.guild_id {
+                forget_disconnected(&mut *self.playing.write().await, guild_id, new.channel_id);
+                let old_channel = old.as_ref().and_then(|old| old.channel_id);
+                if new.channel_id.is_some() && new.channel_id != old_channel {
+                    if let Some(mut guild_data) = self.guild_data.get_mut(&guild_id) {
+                        start_log_session(&mut guild_data);
+                    }
+                    self.start_voice_session(&ctx, guild_id).await;
+                    if let Some(channel_id) = new.channel_id {
+                        let bitrate = voice_bitrate(&ctx.cache, guild_id, channel_id).await;
+                        if let Some(call) = songbird::get(&ctx)
+                            .await
+                            .and_then(|manager| manager.get(guild_id))
+                        {
+                            call.lock().await.set_bitrate(bitrate);
+                        }
+                    }
+                }
+            }
         } else {
             // update the user count in both old and new voice channels
             if let Some(new_channel) = new.channel_id {
                 self.voice_channel_add(new_channel);
             }
-            if let Some(old_channel) = old.and_then(|old| old.channel_id) {
+            if let Some(old_channel) = old.as_ref().and_then(|old| old.channel_id) {
                 self.voice_channel_sub(old_channel);
             }
+            self.greet(&ctx, old.as_ref(), &new).await;
             // check if there are other users in the channel that the bot currently in,
             // and leave if autoleave if enabled
             let Some(guild_id) = new.guild_id else {
                 return;
             };
-            let autoleave = self
+            let always_on = has_feature(guild_id, Feature::AlwaysOn).await;
+            let leave_when_alone = self
                 .guild_data
                 .entry(guild_id)
                 .or_default()
                 .config
-                .auto_leave;
-            if autoleave == AutoleaveType::Empty || autoleave == AutoleaveType::On {
-                let Some(call) = songbird::get(&ctx).await.unwrap().get(guild_id) else {
+                .leave_when_alone(always_on);
+            // the idle tracker leaves later if there's a timeout
+            if leave_when_alone == Some(Duration::ZERO) {
+                let Some(call) = songbird::get(&ctx)
+                    .await
+                    .and_then(|manager| manager.get(guild_id))
+                else {
                     return;
                 };
                 let mut call = call.lock().await;
@@ -124,3 +267,51 @@ impl SerenityEventHandler {
             .fetch_sub(1, Ordering::Relaxed);
     }
 }
+
+/// If the bot is manually disconnected by the user instead using command, then remove the current
+/// track handle (if there is one). The track keeps playing when the bot is moved to another
+/// channel, or rejoins after the voice server migrated, so it's kept then
+fn forget_disconnected(
+    playing: &mut HashMap<GuildId, Playing>,
+    guild_id: GuildId,
+    channel_id: Option<ChannelId>,
+) {
+    if channel_id.is_none() {
+        playing.remove(&guild_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::forget_disconnected;
+    use crate::models::playing::Playing;
+    use serenity::all::{ChannelId, GuildId};
+    use songbird::{
+        input::{AuxMetadata, Input, RawAdapter},
+        Config, Driver,
+    };
+    use std::{collections::HashMap, io::Cursor, sync::Arc};
+
+    #[tokio::test]
+    async fn test_forget_disconnected() {
+        let mut driver = Driver::new(Config::default());
+        let silence = Input::from(RawAdapter::new(Cursor::new(vec![0; 8]), 48000, 2));
+        let track_handle = driver.play_only_input(silence);
+        let guild_id = GuildId::new(1);
+        let mut playing = HashMap::from([(
+            guild_id,
+            Playing {
+                track_handle,
+                metadata: Arc::new(AuxMetadata::default()),
+                url: "url".into(),
+                requester: None,
+            },
+        )]);
+
+        // moved by `/summon` or a vote
+        forget_disconnected(&mut playing, guild_id, Some(ChannelId::new(2)));
+        assert!(playing.contains_key(&guild_id));
+        forget_disconnected(&mut playing, guild_id, None);
+        assert!(playing.is_empty());
+    }
+}
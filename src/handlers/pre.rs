@@ -0,0 +1,151 @@
+use crate::models::{
+    alias::{ApplicationContext, Command, Context, Error},
+    data::Data,
+    invocation::CommandSpan,
+};
+use poise::{BoxFuture, ContextMenuCommandAction, FrameworkError, PrefixContext};
+use serenity::model::{channel::Message, user::User, Timestamp};
+use std::{future::Future, pin::Pin};
+use tracing::{field::Empty, info_span, Instrument, Span};
+
+type ActionResult<'a> = BoxFuture<'a, Result<(), FrameworkError<'a, Data, Error>>>;
+
+/// The actions of a command, they're moved to `custom_data` and run in the span of the invocation
+/// by the actions which replace them, see `instrument`
+struct Actions {
+    slash: Option<for<'a> fn(ApplicationContext<'a>) -> ActionResult<'a>>,
+    prefix: Option<for<'a> fn(PrefixContext<'a, Data, Error>) -> ActionResult<'a>>,
+    context_menu: Option<ContextMenuCommandAction<Data, Error>>,
+}
+
+pub fn pre(ctx: Context<'_>) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+    Box::pin(async move {
+        // the time between the interaction is created and the command starts running, including
+        // the gateway latency, argument parsing and the checks
+        let resolution = (*Timestamp::now() - *ctx.created_at())
+            .num_milliseconds()
+            .max(0) as u64;
+        let span = info_span!(
+            "command",
+            guild_id = ctx.guild_id().map(|guild_id| guild_id.get()),
+            user_id = ctx.author().id.get(),
+            command = ctx.command().qualified_name.as_str(),
+            resolution,
+            ytdl = Empty,
+            discord = Empty,
+            duration = Empty,
+        );
+        ctx.set_invocation_data(CommandSpan::new(span)).await;
+    })
+}
+
+/// Run the actions of the command and its subcommands in the span created by `pre`, so the events
+/// logged while they run are in it
+pub fn instrument(command: &mut Command) {
+    let actions = Actions {
+        slash: command.slash_action,
+        prefix: command.prefix_action,
+        context_menu: command.context_menu_action,
+    };
+    command.slash_action = actions.slash.and(Some(slash_in_span));
+    command.prefix_action = actions.prefix.and(Some(prefix_in_span));
+    command.context_menu_action = match actions.context_menu {
+        Some(ContextMenuCommandAction::User(_)) => {
+            Some(ContextMenuCommandAction::User(user_in_span))
+        }
+        Some(ContextMenuCommandAction::Message(_)) => {
+            Some(ContextMenuCommandAction::Message(message_in_span))
+        }
+        _ => None,
+    };
+    command.custom_data = Box::new(actions);
+    for subcommand in command.subcommands.iter_mut() {
+        instrument(subcommand);
+    }
+}
+
+fn actions(command: &Command) -> &Actions {
+    command
+        .custom_data
+        .downcast_ref()
+        .expect("the actions of an instrumented command are in its custom data")
+}
+
+async fn command_span(ctx: Context<'_>) -> Span {
+    ctx.invocation_data::<CommandSpan>()
+        .await
+        .map_or_else(Span::none, |command_span| command_span.span.clone())
+}
+
+fn slash_in_span(ctx: ApplicationContext<'_>) -> ActionResult<'_> {
+    Box::pin(async move {
+        let Some(action) = actions(ctx.command).slash else {
+            return Ok(());
+        };
+        let span = command_span(ctx.into()).await;
+        action(ctx).instrument(span).await
+    })
+}
+
+fn prefix_in_span(ctx: PrefixContext<'_, Data, Error>) -> ActionResult<'_> {
+    Box::pin(async move {
+        let Some(action) = actions(ctx.command).prefix else {
+            return Ok(());
+        };
+        let span = command_span(ctx.into()).await;
+        action(ctx).instrument(span).await
+    })
+}
+
+fn user_in_span(ctx: ApplicationContext<'_>, user: User) -> ActionResult<'_> {
+    Box::pin(async move {
+        let Some(ContextMenuCommandAction::User(action)) = actions(ctx.command).context_menu else {
+            return Ok(());
+        };
+        let span = command_span(ctx.into()).await;
+        action(ctx, user).instrument(span).await
+    })
+}
+
+fn message_in_span(ctx: ApplicationContext<'_>, message: Message) -> ActionResult<'_> {
+    Box::pin(async move {
+        let Some(ContextMenuCommandAction::Message(action)) = actions(ctx.command).context_menu
+        else {
+            return Ok(());
+        };
+        let span = command_span(ctx.into()).await;
+        action(ctx, message).instrument(span).await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{actions, instrument};
+    use crate::commands::{clear::clear, queue::queue, queue_this::queue_this};
+    use poise::ContextMenuCommandAction;
+
+    #[test]
+    fn test_instrument() {
+        let mut command = clear();
+        instrument(&mut command);
+        assert!(command.slash_action.is_some());
+        assert!(command.prefix_action.is_some());
+        assert!(actions(&command).slash.is_some());
+
+        let mut command = queue_this();
+        instrument(&mut command);
+        assert!(command.slash_action.is_none());
+        assert!(matches!(
+            command.context_menu_action,
+            Some(ContextMenuCommandAction::Message(_))
+        ));
+
+        let mut command = queue();
+        instrument(&mut command);
+        // the subcommands are run in the span as well
+        assert!(command
+            .subcommands
+            .iter()
+            .all(|subcommand| actions(subcommand).slash.is_some()));
+    }
+}
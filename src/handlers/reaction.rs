@@ -0,0 +1,249 @@
+use super::SerenityEventHandler;
+use crate::{
+    models::premium::Feature,
+    utils::{
+        fade::{fade_out_playing, fade_pause, fade_play},
+        guild::GuildUtil,
+        is_member_dj,
+        play::play_next,
+        player::refresh_player,
+        premium::has_feature,
+    },
+};
+use rand::{seq::SliceRandom, thread_rng};
+use serenity::{
+    all::{ChannelId, GuildId, Member, Reaction, ReactionType, UserId},
+    prelude::Context,
+};
+use songbird::tracks::PlayMode;
+use std::time::Duration;
+use tracing::{error, warn};
+
+/// The reactions added to the now playing message when the reaction controls are on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactionControl {
+    PlayPause,
+    Skip,
+    Repeat,
+    Shuffle,
+}
+
+impl ReactionControl {
+    pub const ALL: [ReactionControl; 4] = [
+        ReactionControl::PlayPause,
+        ReactionControl::Skip,
+        ReactionControl::Repeat,
+        ReactionControl::Shuffle,
+    ];
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            ReactionControl::PlayPause => "⏯️",
+            ReactionControl::Skip => "⏭️",
+            ReactionControl::Repeat => "🔁",
+            ReactionControl::Shuffle => "🔀",
+        }
+    }
+
+    pub fn reaction_type(&self) -> ReactionType {
+        ReactionType::Unicode(self.emoji().to_owned())
+    }
+
+    fn from_reaction(reaction_type: &ReactionType) -> Option<Self> {
+        let ReactionType::Unicode(emoji) = reaction_type else {
+            return None;
+        };
+        // the variation selector may be dropped by the clients
+        let emoji = emoji.trim_end_matches('\u{fe0f}');
+        Self::ALL
+            .into_iter()
+            .find(|control| control.emoji().trim_end_matches('\u{fe0f}') == emoji)
+    }
+}
+
+impl SerenityEventHandler {
+    /// Control the playback with the reactions on the latest now playing message, the same rules
+    /// as the commands apply, the user must be in the voice channel of turto and be a DJ
+    pub(super) async fn react_control(&self, ctx: &Context, reaction: &Reaction) {
+        let (Some(guild_id), Some(user_id)) = (reaction.guild_id, reaction.user_id) else {
+            return;
+        };
+        if user_id == ctx.cache.current_user().id {
+            return;
+        }
+        let is_control_message = self
+            .control_messages
+            .get(&guild_id)
+            .is_some_and(|message_id| *message_id == reaction.message_id);
+        if !is_control_message {
+            return;
+        }
+        let Some(control) = ReactionControl::from_reaction(&reaction.emoji) else {
+            return;
+        };
+
+        let allowed = {
+            let guild_data = self.guild_data.entry(guild_id).or_default();
+            guild_data.config.reaction_controls
+        } && self.can_control(
+            ctx,
+            guild_id,
+            reaction.channel_id,
+            user_id,
+            reaction.member.as_ref(),
+        );
+        // remove the reaction so it can be used again, it fails without the Manage Messages
+        // permission and the reaction simply stays
+        if let Err(err) = reaction.delete(&ctx.http).await {
+            warn!("Failed to remove the reaction of {}: {}", user_id, err);
+        }
+        if allowed {
+            self.control_playback(ctx, guild_id, control).await;
+        }
+    }
+
+    /// What the reactions on the now playing message and the buttons of the player do
+    pub(super) async fn control_playback(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        control: ReactionControl,
+    ) {
+        match control {
+            ReactionControl::PlayPause => {
+                let playing_map = self.playing.read().await;
+                let Some(playing) = playing_map.get(&guild_id) else {
+                    return;
+                };
+                let result = match playing.track_handle.get_info().await {
+                    Ok(track_state) if track_state.playing == PlayMode::Play => {
+                        fade_pause(&playing.track_handle).await
+                    }
+                    Ok(track_state) if track_state.playing == PlayMode::Pause => {
+                        fade_play(&playing.track_handle).await
+                    }
+                    _ => Ok(()),
+                };
+                if let Err(err) = result {
+                    let uuid = playing.track_handle.uuid();
+                    error!("Failed to toggle pause of track {uuid}: {err}");
+                }
+            }
+            ReactionControl::Skip => {
+                let Some(call) = songbird::get(ctx)
+                    .await
+                    .and_then(|manager| manager.get(guild_id))
+                else {
+                    return;
+                };
+                fade_out_playing(&self.playing, guild_id).await;
+                call.lock().await.stop();
+                let meta = play_next(
+                    call.clone(),
+                    self.guild_data.clone(),
+                    self.playing.clone(),
+                    guild_id,
+                )
+                .await
+                .and_then(Result::ok);
+                let always_on = has_feature(guild_id, Feature::AlwaysOn).await;
+                let leave_now = self
+                    .guild_data
+                    .entry(guild_id)
+                    .or_default()
+                    .config
+                    .leave_when_idle(always_on)
+                    == Some(Duration::ZERO);
+                if meta.is_none() && leave_now {
+                    if let Err(err) = call.lock().await.leave().await {
+                        error!(
+                            "Failed to leave the voice channel of guild {}: {}",
+                            guild_id, err
+                        );
+                    }
+                }
+            }
+            ReactionControl::Repeat => {
+                let mut guild_data = self.guild_data.entry(guild_id).or_default();
+                guild_data.config.repeat = !guild_data.config.repeat;
+            }
+            ReactionControl::Shuffle => {
+                let mut guild_data = self.guild_data.entry(guild_id).or_default();
+                guild_data
+                    .playlist
+                    .make_contiguous()
+                    .shuffle(&mut thread_rng());
+            }
+        }
+        refresh_player(guild_id);
+    }
+
+    /// The user must be in the voice channel of turto and be a DJ, the banned users can't control
+    /// anything, `channel_id` is where the control is used
+    pub(super) fn can_control(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        user_id: UserId,
+        member: Option<&Member>,
+    ) -> bool {
+        let dj_role = {
+            let guild_data = self.guild_data.entry(guild_id).or_default();
+            if guild_data.config.banned.contains(&user_id) {
+                return false;
+            }
+            guild_data.config.dj_role
+        };
+        let Some(guild) = guild_id.to_guild_cached(ctx) else {
+            return false;
+        };
+        let bot_id = ctx.cache.current_user().id;
+        let channel_of = |user_id| {
+            guild
+                .voice_states
+                .get(&user_id)
+                .and_then(|voice_state| voice_state.channel_id)
+        };
+        let bot_channel = channel_of(bot_id);
+        if bot_channel.is_none() || bot_channel != channel_of(user_id) {
+            return false;
+        }
+
+        let Some(dj_role) = dj_role else {
+            return true;
+        };
+        let Some(member) = member else {
+            return false;
+        };
+        is_member_dj(
+            member,
+            guild.permissions_in(channel_id, member),
+            Some(dj_role),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReactionControl;
+    use serenity::all::ReactionType;
+
+    #[test]
+    fn test_from_reaction() {
+        let unicode = |emoji: &str| ReactionType::Unicode(emoji.to_owned());
+        assert_eq!(
+            ReactionControl::from_reaction(&unicode("⏯️")),
+            Some(ReactionControl::PlayPause)
+        );
+        assert_eq!(
+            ReactionControl::from_reaction(&unicode("\u{23ed}")),
+            Some(ReactionControl::Skip)
+        );
+        assert_eq!(
+            ReactionControl::from_reaction(&unicode("🔀")),
+            Some(ReactionControl::Shuffle)
+        );
+        assert_eq!(ReactionControl::from_reaction(&unicode("👍")), None);
+    }
+}
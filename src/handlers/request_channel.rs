@@ -0,0 +1,241 @@
+use super::{reaction::ReactionControl, SerenityEventHandler};
+use crate::{
+    config::get_config,
+    models::{guild::pending_request::PendingRequest, queue_item::QueueItem},
+    utils::{
+        approval::request_approval,
+        cluster::owns,
+        explicit::filter_explicit,
+        guild::GuildUtil,
+        is_member_dj,
+        play::play_next,
+        player::refresh_player,
+        queue::{add_to_playlist, roles_priority, QueueType},
+        resolve_user_query,
+    },
+};
+use serenity::{
+    all::{
+        ComponentInteraction, CreateInteractionResponse, GuildId, Message, ReactionType, RoleId,
+        UserId,
+    },
+    prelude::Context,
+};
+use tracing::warn;
+
+const QUEUED: char = '✅';
+const REJECTED: char = '❌';
+const PENDING: char = '⏳';
+
+impl SerenityEventHandler {
+    /// Queue the link or the keywords posted in the request channel of the guild like `/queue`,
+    /// and start playing in the voice channel of the author if nothing is playing
+    pub(super) async fn request_track(&self, ctx: &Context, msg: &Message) {
+        let Some(guild_id) = msg.guild_id else {
+            return;
+        };
+        if !get_config().request_channels || msg.author.bot || msg.content.trim().is_empty() {
+            return;
+        }
+        let (banned, locked, explicit_filter, bypass_role, request_approval, dj_role) = {
+            let Some(guild_data) = self.guild_data.get(&guild_id) else {
+                return;
+            };
+            let config = &guild_data.config;
+            if config.request_channel != Some(msg.channel_id) {
+                return;
+            }
+            (
+                config.banned.contains(&msg.author.id),
+                guild_data.is_queue_locked(),
+                config.explicit_filter,
+                config.explicit_bypass_role,
+                config.request_approval,
+                config.dj_role,
+            )
+        };
+        if !owns(guild_id).await {
+            return;
+        }
+        // only the dj can queue the tracks while the playlist is locked
+        let is_dj = (locked || request_approval) && self.is_author_dj(ctx, msg, dj_role).await;
+        let banned = banned || (locked && !is_dj);
+        let needs_approval = request_approval && !is_dj;
+        let reaction = if banned {
+            REJECTED
+        } else {
+            self.queue_request(
+                ctx,
+                guild_id,
+                msg,
+                explicit_filter,
+                bypass_role,
+                needs_approval,
+            )
+            .await
+        };
+        let reaction = ReactionType::Unicode(reaction.to_string());
+        if let Err(err) = msg.react(&ctx.http, reaction).await {
+            warn!(
+                "Failed to react to the request of {}: {}",
+                msg.author.id, err
+            );
+        }
+    }
+
+    /// The members of the messages don't come with their permissions, so they're taken from the
+    /// cache
+    async fn is_author_dj(&self, ctx: &Context, msg: &Message, dj_role: Option<RoleId>) -> bool {
+        let member = match msg.member(ctx).await {
+            Ok(member) => member,
+            Err(err) => {
+                warn!("Failed to get the member {}: {}", msg.author.id, err);
+                return false;
+            }
+        };
+        let permissions = msg
+            .guild(&ctx.cache)
+            .and_then(|guild| guild.permissions_in(msg.channel_id, &member));
+        is_member_dj(&member, permissions, dj_role)
+    }
+
+    async fn queue_request(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        msg: &Message,
+        explicit_filter: bool,
+        bypass_role: Option<RoleId>,
+        needs_approval: bool,
+    ) -> char {
+        let roles = msg
+            .member
+            .as_ref()
+            .map(|member| member.roles.as_slice())
+            .unwrap_or_default();
+        let query = resolve_user_query(&self.users, msg.author.id, msg.content.trim());
+        let Ok(queue_item_kind) = QueueItem::new(query).query().await else {
+            return REJECTED;
+        };
+        let bypassed = bypass_role.is_some_and(|role_id| roles.contains(&role_id));
+        let queue_item_kind = if explicit_filter && !bypassed {
+            match filter_explicit(queue_item_kind).await {
+                (Some(queue_item_kind), _) => queue_item_kind,
+                (None, _) => return REJECTED,
+            }
+        } else {
+            queue_item_kind
+        };
+        if needs_approval {
+            let request = PendingRequest::new(
+                queue_item_kind,
+                msg.author.id,
+                roles_priority(roles),
+                QueueType::Back,
+            );
+            return match request_approval(
+                &ctx.http,
+                &self.guild_data,
+                guild_id,
+                msg.channel_id,
+                request,
+            )
+            .await
+            {
+                Ok(()) => PENDING,
+                Err(err) => {
+                    warn!(
+                        "Failed to request the approval of {}: {}",
+                        msg.author.id, err
+                    );
+                    REJECTED
+                }
+            };
+        }
+        let added = add_to_playlist(
+            &self.guild_data,
+            guild_id,
+            queue_item_kind,
+            roles_priority(roles),
+            msg.author.id,
+            QueueType::Back,
+        );
+        if added.is_err() {
+            return REJECTED;
+        }
+        refresh_player(guild_id);
+        if !self.playing.read().await.contains_key(&guild_id) {
+            self.start_requested(ctx, guild_id, msg.author.id).await;
+        }
+        QUEUED
+    }
+
+    /// Control the playback with the buttons on the player, the same rules as the reaction
+    /// controls apply
+    pub(super) async fn player_pressed(
+        &self,
+        ctx: &Context,
+        mci: &ComponentInteraction,
+        control: ReactionControl,
+    ) {
+        let Some(guild_id) = mci.guild_id else {
+            return;
+        };
+        // there's no reply, the change is shown on the player
+        if let Err(err) = mci
+            .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+            .await
+        {
+            warn!("Failed to respond to the player button: {}", err);
+        }
+        if self.can_control(
+            ctx,
+            guild_id,
+            mci.channel_id,
+            mci.user.id,
+            mci.member.as_ref(),
+        ) {
+            self.control_playback(ctx, guild_id, control).await;
+        }
+    }
+
+    /// Join the voice channel of the author unless turto is already in one, then play the next
+    /// track of the playlist
+    async fn start_requested(&self, ctx: &Context, guild_id: GuildId, author: UserId) {
+        let Some(manager) = songbird::get(ctx).await else {
+            return;
+        };
+        let call = match manager.get(guild_id) {
+            Some(call) if call.lock().await.current_channel().is_some() => call,
+            _ => {
+                let channel_id = ctx.cache.guild(guild_id).and_then(|guild| {
+                    guild
+                        .voice_states
+                        .get(&author)
+                        .and_then(|voice_state| voice_state.channel_id)
+                });
+                // the track stays in the playlist for `/play`
+                let Some(channel_id) = channel_id else {
+                    return;
+                };
+                match manager.join(guild_id, channel_id).await {
+                    Ok(call) => call,
+                    Err(err) => {
+                        warn!("Failed to join {} for a request: {}", channel_id, err);
+                        return;
+                    }
+                }
+            }
+        };
+        let played = play_next(
+            call,
+            self.guild_data.clone(),
+            self.playing.clone(),
+            guild_id,
+        )
+        .await;
+        if let Some(Err(err)) = played {
+            warn!("Failed to play the request in guild {}: {}", guild_id, err);
+        }
+    }
+}
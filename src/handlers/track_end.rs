@@ -1,6 +1,11 @@
+use super::track_hooks::{track_hooks, TrackEndReason, TrackInfo};
 use crate::{
-    models::{autoleave::AutoleaveType, guild::data::GuildData, playing::Playing},
-    utils::play::{play_next, play_url},
+    models::{guild::data::GuildData, playing::Playing, premium::Feature},
+    utils::{
+        play::{is_live, play_next, play_url},
+        premium::has_feature,
+        report::{report_error, ErrorReport},
+    },
 };
 use dashmap::DashMap;
 use serenity::{async_trait, model::prelude::GuildId};
@@ -9,64 +14,276 @@ use songbird::{
     tracks::PlayMode,
     Call,
 };
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::sync::{Mutex, RwLock};
-use tracing::error;
+use tracing::{error, info};
 
 pub struct TrackEndHandler {
     pub guild_data: Arc<DashMap<GuildId, GuildData>>,
     pub guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
     pub call: Arc<Mutex<Call>>,
-    pub url: Arc<str>,
-    pub guild_id: GuildId,
+    pub track: Arc<TrackInfo>,
 }
 
 #[async_trait]
 impl EventHandler for TrackEndHandler {
     async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
-        let data = self.guild_data.entry(self.guild_id).or_default();
+        let always_on = has_feature(self.track.guild_id, Feature::AlwaysOn).await;
+        let data = self.guild_data.entry(self.track.guild_id).or_default();
         let repeat = data.config.repeat;
-        let auto_leave = data.config.auto_leave;
+        // the idle tracker leaves later if there's a timeout
+        let leave_now = data.config.leave_when_idle(always_on) == Some(Duration::ZERO);
         drop(data);
 
-        let EventContext::Track(ctx) = ctx else {
+        let EventContext::Track([(state, _handle), ..]) = ctx else {
             return None;
         };
-        let (state, _handle) = ctx[0];
+        // a live stream ends or errors when the stream goes offline
+        let live = is_live(&self.track.metadata);
+
+        let reason = match &state.playing {
+            PlayMode::End => TrackEndReason::Finished,
+            PlayMode::Errored(_) => TrackEndReason::Errored,
+            PlayMode::Stop => TrackEndReason::Stopped,
+            _ => return None,
+        };
+        if let Some(hooks) = track_hooks() {
+            hooks.track_end(&self.track, reason).await;
+        }
 
         match &state.playing {
             PlayMode::End => {
-                if repeat {
-                    let _meta = play_url(
+                // an ended live stream is offline, repeating it just fails
+                if repeat && !live {
+                    let meta = play_url(
                         self.call.clone(),
                         self.guild_data.clone(),
                         self.guild_playing.clone(),
-                        self.guild_id,
-                        self.url.clone(),
+                        self.track.guild_id,
+                        self.track.url.clone(),
+                        self.track.requester,
                     )
                     .await;
+                    if let Err(err) = meta {
+                        self.report_playback_error(&err).await;
+                    }
                     None
                 } else {
-                    if play_next(
-                        self.call.clone(),
-                        self.guild_data.clone(),
-                        self.guild_playing.clone(),
-                        self.guild_id,
-                    )
-                    .await
-                    .is_none()
-                        && (auto_leave == AutoleaveType::Silent || auto_leave == AutoleaveType::On)
-                    {
-                        let mut call = self.call.lock().await;
-                        if let Err(err) = call.leave().await {
-                            error!("Failed to leave voice channel: {}", err);
-                        }
-                    }
+                    self.play_next(leave_now).await;
                     None
                 }
             }
+            // the stream is cut off when it goes offline, it's not an error of turto
+            PlayMode::Errored(err) if live => {
+                info!("Live stream {} went offline: {}", self.track.url, err);
+                self.play_next(leave_now).await;
+                None
+            }
+            PlayMode::Errored(err) => {
+                self.report_playback_error(err).await;
+                None
+            }
             PlayMode::Stop => return None,
             _ => return None,
         }
     }
 }
+
+impl TrackEndHandler {
+    async fn play_next(&self, leave_now: bool) {
+        match play_next(
+            self.call.clone(),
+            self.guild_data.clone(),
+            self.guild_playing.clone(),
+            self.track.guild_id,
+        )
+        .await
+        {
+            Some(Ok(_)) => (),
+            Some(Err(err)) => self.report_playback_error(&err).await,
+            None => {
+                if leave_now {
+                    let mut call = self.call.lock().await;
+                    if let Err(err) = call.leave().await {
+                        error!("Failed to leave voice channel: {}", err);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn report_playback_error(&self, err: &(dyn std::error::Error + Send + Sync + '_)) {
+        error!("Playback of {} failed: {}", self.track.url, err);
+        report_error(
+            ErrorReport {
+                title: "Playback error",
+                guild_id: Some(self.track.guild_id),
+                ..Default::default()
+            },
+            err,
+        )
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrackEndHandler;
+    use crate::{
+        handlers::track_hooks::TrackInfo,
+        models::{guild::data::GuildData, playing::Playing, playlist_item::PlaylistItem},
+        test::setup_config,
+        utils::play::play_next,
+    };
+    use dashmap::DashMap;
+    use serenity::model::prelude::{GuildId, UserId};
+    use songbird::{
+        events::{EventContext, EventHandler},
+        input::{AudioStreamError, AuxMetadata},
+        tracks::{PlayError, PlayMode, TrackState},
+        Call,
+    };
+    use std::{collections::HashMap, sync::Arc};
+    use tokio::sync::{Mutex, RwLock};
+
+    struct Player {
+        call: Arc<Mutex<Call>>,
+        guild_data: Arc<DashMap<GuildId, GuildData>>,
+        playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+        guild_id: GuildId,
+    }
+
+    impl Player {
+        fn new(urls: &[&str]) -> Self {
+            setup_config();
+            let guild_id = GuildId::new(1);
+            let guild_data = Arc::new(DashMap::<GuildId, GuildData>::new());
+            guild_data
+                .entry(guild_id)
+                .or_default()
+                .playlist
+                .extend(urls.iter().map(|url| {
+                    PlaylistItem::from(AuxMetadata {
+                        source_url: Some(url.to_string()),
+                        ..Default::default()
+                    })
+                }));
+            Self {
+                call: Arc::new(Mutex::new(Call::standalone(guild_id, UserId::new(1)))),
+                guild_data,
+                playing: Default::default(),
+                guild_id,
+            }
+        }
+
+        async fn current(&self) -> Option<String> {
+            let playing = self.playing.read().await;
+            playing
+                .get(&self.guild_id)
+                .map(|playing| playing.url.to_string())
+        }
+
+        fn playlist_len(&self) -> usize {
+            self.guild_data.get(&self.guild_id).unwrap().playlist.len()
+        }
+
+        /// What the driver does when the current track ends
+        async fn end_current(&self, mode: PlayMode) {
+            let (handle, url, metadata) = {
+                let playing = self.playing.read().await;
+                let playing = &playing[&self.guild_id];
+                (
+                    playing.track_handle.clone(),
+                    playing.url.clone(),
+                    playing.metadata.clone(),
+                )
+            };
+            let handler = TrackEndHandler {
+                guild_data: self.guild_data.clone(),
+                guild_playing: self.playing.clone(),
+                call: self.call.clone(),
+                track: Arc::new(TrackInfo {
+                    guild_id: self.guild_id,
+                    url,
+                    requester: None,
+                    metadata,
+                    channel_id: None,
+                    queued: true,
+                }),
+            };
+            let state = TrackState {
+                playing: mode,
+                ..Default::default()
+            };
+            handler
+                .act(&EventContext::Track(&[(&state, &handle)]))
+                .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_track_end() {
+        let player = Player::new(&[
+            "fake:sine?title=a",
+            "fake:silence?title=b",
+            "fake:fail",
+            "fake:sine?title=c",
+        ]);
+        let meta = play_next(
+            player.call.clone(),
+            player.guild_data.clone(),
+            player.playing.clone(),
+            player.guild_id,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(meta.title.as_deref(), Some("a"));
+
+        player.end_current(PlayMode::End).await;
+        assert_eq!(
+            player.current().await.as_deref(),
+            Some("fake:silence?title=b")
+        );
+        assert_eq!(player.playlist_len(), 2);
+
+        // the track is played again in the repeat mode
+        player
+            .guild_data
+            .get_mut(&player.guild_id)
+            .unwrap()
+            .config
+            .repeat = true;
+        player.end_current(PlayMode::End).await;
+        assert_eq!(
+            player.current().await.as_deref(),
+            Some("fake:silence?title=b")
+        );
+        assert_eq!(player.playlist_len(), 2);
+
+        // the failed track is dropped and the current one stays
+        player
+            .guild_data
+            .get_mut(&player.guild_id)
+            .unwrap()
+            .config
+            .repeat = false;
+        player.end_current(PlayMode::End).await;
+        assert_eq!(
+            player.current().await.as_deref(),
+            Some("fake:silence?title=b")
+        );
+        assert_eq!(player.playlist_len(), 1);
+
+        // a stopped track is handled by the command stopping it, and an errored one is only reported
+        player.end_current(PlayMode::Stop).await;
+        assert_eq!(player.playlist_len(), 1);
+
+        player
+            .end_current(PlayMode::Errored(PlayError::Create(Arc::new(
+                AudioStreamError::Unsupported,
+            ))))
+            .await;
+        assert_eq!(player.playlist_len(), 1);
+    }
+}
@@ -0,0 +1,362 @@
+use crate::{
+    http::overlay::refresh_overlay,
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{ListeningLogEntry, ListeningLogThread, TrackFinished},
+    },
+    models::{guild::data::GuildData, playing::Playing, user_prefs::UserPrefs},
+    utils::{
+        cleanup::clean_track_messages,
+        listening_log::log_track,
+        metrics::{Metrics, METRICS},
+        notify::notify_track_start,
+        player::refresh_player,
+        resolve_locale,
+        script::{reply, run_rules, skip, Action, ScriptEvent},
+    },
+};
+use dashmap::DashMap;
+use serenity::{
+    all::{ChannelId, GuildId, UserId},
+    async_trait,
+};
+use songbird::input::AuxMetadata;
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+use tokio::sync::RwLock;
+
+static TRACK_HOOKS: OnceLock<TrackHooks> = OnceLock::new();
+
+/// Set up the hooks of the track events, can only be called once
+pub fn init_track_hooks(hooks: TrackHooks) {
+    let _ = TRACK_HOOKS.set(hooks);
+}
+
+/// The hooks subscribed to the track events, there's none before `init_track_hooks` is called
+pub fn track_hooks() -> Option<&'static TrackHooks> {
+    TRACK_HOOKS.get()
+}
+
+/// The track that started or ended
+pub struct TrackInfo {
+    pub guild_id: GuildId,
+    /// What's passed to yt-dlp to play the track
+    pub url: Arc<str>,
+    pub requester: Option<UserId>,
+    pub metadata: Arc<AuxMetadata>,
+    /// The voice channel where the track is played
+    pub channel_id: Option<ChannelId>,
+    /// Whether the track is taken from the playlist, it's started by a command otherwise
+    pub queued: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackEndReason {
+    /// The track is played to the end
+    Finished,
+    /// The playback failed, or the live stream went offline
+    Errored,
+    /// The track is stopped or replaced, e.g. skipped
+    Stopped,
+}
+
+/// Something to be done when a track starts or ends, both do nothing by default. The hooks are
+/// awaited one by one in the playback, so the slow work should be spawned
+#[async_trait]
+pub trait TrackHook: Send + Sync {
+    async fn track_start(&self, _track: &TrackInfo) {}
+
+    async fn track_end(&self, _track: &TrackInfo, _reason: TrackEndReason) {}
+}
+
+#[derive(Default)]
+pub struct TrackHooks {
+    hooks: Vec<Box<dyn TrackHook>>,
+}
+
+impl TrackHooks {
+    /// The hooks that turto comes with
+    pub fn with_builtin(
+        guild_data: Arc<DashMap<GuildId, GuildData>>,
+        users: Arc<DashMap<UserId, UserPrefs>>,
+        playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+    ) -> Self {
+        Self::default()
+            .register(HistoryHook {
+                guild_data: guild_data.clone(),
+            })
+            .register(AnnounceHook)
+            .register(MetricsHook)
+            .register(CleanupHook {
+                guild_data: guild_data.clone(),
+                users: users.clone(),
+            })
+            .register(PlayerHook)
+            .register(OverlayHook)
+            .register(ListeningLogHook {
+                guild_data: guild_data.clone(),
+                users,
+            })
+            .register(ScriptHook {
+                guild_data,
+                playing,
+            })
+    }
+
+    /// Subscribe a hook to the track events, the hooks are run in the order they're registered
+    pub fn register(mut self, hook: impl TrackHook + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Subscribe the hooks which are already boxed, like the ones of the plugins
+    pub fn extend(mut self, hooks: impl IntoIterator<Item = Box<dyn TrackHook>>) -> Self {
+        self.hooks.extend(hooks);
+        self
+    }
+
+    pub async fn track_start(&self, track: &TrackInfo) {
+        for hook in &self.hooks {
+            hook.track_start(track).await;
+        }
+    }
+
+    pub async fn track_end(&self, track: &TrackInfo, reason: TrackEndReason) {
+        for hook in &self.hooks {
+            hook.track_end(track, reason).await;
+        }
+    }
+}
+
+/// Record the started tracks in the history of the guild, autoplay avoids repeating them
+struct HistoryHook {
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+}
+
+#[async_trait]
+impl TrackHook for HistoryHook {
+    async fn track_start(&self, track: &TrackInfo) {
+        let meta = &track.metadata;
+        self.guild_data
+            .entry(track.guild_id)
+            .or_default()
+            .history
+            .record(
+                meta.source_url
+                    .clone()
+                    .unwrap_or_else(|| track.url.to_string()),
+                meta.title.clone().unwrap_or_default(),
+                meta.channel.clone().unwrap_or_default(),
+            );
+    }
+}
+
+/// Notify the requester when their track starts
+struct AnnounceHook;
+
+#[async_trait]
+impl TrackHook for AnnounceHook {
+    async fn track_start(&self, track: &TrackInfo) {
+        // the requester is only notified when the track is from the playlist, it's started by the
+        // requester themselves otherwise
+        if let (true, Some(requester), Some(channel_id)) =
+            (track.queued, track.requester, track.channel_id)
+        {
+            let title = track.metadata.title.clone().unwrap_or_default();
+            tokio::spawn(notify_track_start(requester, title, channel_id));
+        }
+    }
+}
+
+/// Run the rules of the scripts on the started tracks
+struct ScriptHook {
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+}
+
+#[async_trait]
+impl TrackHook for ScriptHook {
+    async fn track_start(&self, track: &TrackInfo) {
+        let meta = &track.metadata;
+        let fields = [
+            ("title", meta.title.clone().unwrap_or_default()),
+            ("url", track.url.to_string()),
+            (
+                "requester",
+                track
+                    .requester
+                    .map(|user_id| user_id.to_string())
+                    .unwrap_or_default(),
+            ),
+            (
+                "duration",
+                meta.duration
+                    .map(|duration| duration.as_secs().to_string())
+                    .unwrap_or_default(),
+            ),
+            (
+                "channel",
+                track
+                    .channel_id
+                    .map(|channel_id| channel_id.to_string())
+                    .unwrap_or_default(),
+            ),
+        ];
+        for action in run_rules(
+            &self.guild_data,
+            track.guild_id,
+            ScriptEvent::TrackStart,
+            &fields,
+        ) {
+            match action {
+                // the voice channels have their text chats
+                Action::Reply(text) => {
+                    if let Some(channel_id) = track.channel_id {
+                        reply(channel_id, text);
+                    }
+                }
+                Action::Skip => skip(
+                    track.guild_id,
+                    self.guild_data.clone(),
+                    self.playing.clone(),
+                ),
+                Action::Clear => {
+                    if let Some(mut guild_data) = self.guild_data.get_mut(&track.guild_id) {
+                        guild_data.playlist.clear();
+                    }
+                }
+                Action::Veto(_) => (),
+            }
+        }
+    }
+}
+
+/// Count the started and the failed tracks
+struct MetricsHook;
+
+#[async_trait]
+impl TrackHook for MetricsHook {
+    async fn track_start(&self, _track: &TrackInfo) {
+        Metrics::increment(&METRICS.tracks_started);
+    }
+
+    async fn track_end(&self, _track: &TrackInfo, reason: TrackEndReason) {
+        if reason == TrackEndReason::Errored {
+            Metrics::increment(&METRICS.tracks_failed);
+        }
+    }
+}
+
+/// Clean the replies about the ended track as the guild chooses
+struct CleanupHook {
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    users: Arc<DashMap<UserId, UserPrefs>>,
+}
+
+#[async_trait]
+impl TrackHook for CleanupHook {
+    async fn track_end(&self, track: &TrackInfo, _reason: TrackEndReason) {
+        let policy = self
+            .guild_data
+            .entry(track.guild_id)
+            .or_default()
+            .config
+            .message_cleanup;
+        clean_track_messages(track.guild_id, &track.metadata, policy, || {
+            // the default id is nobody, so only the locale of the guild is taken without a requester
+            let locale = resolve_locale(
+                &self.users,
+                &self.guild_data,
+                track.requester.unwrap_or_default(),
+                Some(track.guild_id),
+                None,
+            );
+            TurtoMessage {
+                locale,
+                kind: TrackFinished {
+                    title: track.metadata.title.as_deref().unwrap_or_default(),
+                },
+            }
+            .to_string()
+        });
+    }
+}
+
+/// Show the track on the player in the request channel
+struct PlayerHook;
+
+#[async_trait]
+impl TrackHook for PlayerHook {
+    async fn track_start(&self, track: &TrackInfo) {
+        refresh_player(track.guild_id);
+    }
+
+    async fn track_end(&self, track: &TrackInfo, _reason: TrackEndReason) {
+        refresh_player(track.guild_id);
+    }
+}
+
+/// Show the track on the overlays of the guild, see `/overlay`
+struct OverlayHook;
+
+#[async_trait]
+impl TrackHook for OverlayHook {
+    async fn track_start(&self, track: &TrackInfo) {
+        refresh_overlay(track.guild_id);
+    }
+
+    async fn track_end(&self, track: &TrackInfo, _reason: TrackEndReason) {
+        refresh_overlay(track.guild_id);
+    }
+}
+
+/// Post the started tracks to the listening log of the guild
+struct ListeningLogHook {
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    users: Arc<DashMap<UserId, UserPrefs>>,
+}
+
+#[async_trait]
+impl TrackHook for ListeningLogHook {
+    async fn track_start(&self, track: &TrackInfo) {
+        let enabled = self
+            .guild_data
+            .get(&track.guild_id)
+            .is_some_and(|guild_data| guild_data.config.listening_log.is_some());
+        if !enabled {
+            return;
+        }
+        // it's read by everyone in the guild, so it's in the locale of the guild
+        let locale = resolve_locale(
+            &self.users,
+            &self.guild_data,
+            UserId::default(),
+            Some(track.guild_id),
+            None,
+        );
+        let meta = &track.metadata;
+        let entry = TurtoMessage {
+            locale,
+            kind: ListeningLogEntry {
+                title: meta.title.as_deref().unwrap_or_default(),
+                url: meta.source_url.as_deref().unwrap_or(&track.url),
+                requester: track.requester,
+            },
+        }
+        .to_string();
+        tokio::spawn(log_track(
+            self.guild_data.clone(),
+            track.guild_id,
+            entry,
+            move |date| {
+                TurtoMessage {
+                    locale,
+                    kind: ListeningLogThread { date: &date },
+                }
+                .to_string()
+            },
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+use super::SerenityEventHandler;
+use crate::utils::metrics::{Metrics, METRICS};
+use dashmap::DashMap;
+use serenity::{
+    all::{GuildId, VoiceServerUpdateEvent},
+    async_trait,
+    prelude::Context,
+};
+use songbird::{
+    events::{CoreEvent, Event, EventContext, EventHandler},
+    tracks::{PlayMode, TrackHandle},
+    Songbird,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// The playback lost while reconnecting up to this long isn't worth seeking back
+const MIGRATION_TOLERANCE: Duration = Duration::from_secs(1);
+/// How many times turto rejoins if it fails to connect to the new voice server
+const MIGRATION_RETRIES: usize = 2;
+
+/// A voice server change of a guild in progress
+pub struct Migration {
+    /// The track playing when the voice server changed, it's kept since the rejoins update the
+    /// voice state of turto as well. The migration is ignored if it's skipped
+    track_handle: TrackHandle,
+    position: Duration,
+    since: Instant,
+    retries: usize,
+}
+
+impl SerenityEventHandler {
+    /// The voice server of the guild is changed by discord, e.g. when the region of the voice
+    /// channel is changed. songbird connects to the new server by itself, then the track is put
+    /// back where it was by `MigrationHandler`
+    pub(super) async fn voice_server_migrated(
+        &self,
+        ctx: &Context,
+        event: &VoiceServerUpdateEvent,
+    ) {
+        let Some(guild_id) = event.guild_id else {
+            return;
+        };
+        // the voice server is set when joining as well, it's only moving when playing
+        let track_handle = match self.playing.read().await.get(&guild_id) {
+            Some(playing) => playing.track_handle.clone(),
+            None => return,
+        };
+        let Some(manager) = songbird::get(ctx).await else {
+            return;
+        };
+        let Some(call) = manager.get(guild_id) else {
+            return;
+        };
+        // the rejoins of a failing migration are updated with new servers as well
+        if self.voice_migrations.contains_key(&guild_id) {
+            return;
+        }
+        let position = match track_handle.get_info().await {
+            Ok(state) => state.position,
+            Err(_) => return,
+        };
+        Metrics::increment(&METRICS.voice_migrations);
+        info!(
+            "Voice server of guild {} is moving to {}",
+            guild_id,
+            event.endpoint.as_deref().unwrap_or("nowhere")
+        );
+        let since = Instant::now();
+        self.voice_migrations.insert(
+            guild_id,
+            Migration {
+                track_handle,
+                position,
+                since,
+                retries: 0,
+            },
+        );
+        let handler = MigrationHandler {
+            guild_id,
+            since,
+            migrations: self.voice_migrations.clone(),
+            manager,
+        };
+        let mut call = call.lock().await;
+        call.add_global_event(Event::Core(CoreEvent::DriverConnect), handler.clone());
+        call.add_global_event(Event::Core(CoreEvent::DriverDisconnect), handler);
+    }
+}
+
+/// Wait for the driver to connect to the new voice server, it's removed once the migration is done
+#[derive(Clone)]
+pub struct MigrationHandler {
+    guild_id: GuildId,
+    /// The start of the migration it's waiting for
+    since: Instant,
+    migrations: Arc<DashMap<GuildId, Migration>>,
+    manager: Arc<Songbird>,
+}
+
+#[async_trait]
+impl EventHandler for MigrationHandler {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        // the other handler of the migration has finished it
+        let current = self
+            .migrations
+            .get(&self.guild_id)
+            .is_some_and(|migration| migration.since == self.since);
+        if !current {
+            return Some(Event::Cancel);
+        }
+        match ctx {
+            EventContext::DriverConnect(_) => {
+                let (_, migration) = self.migrations.remove(&self.guild_id)?;
+                self.resume(migration).await;
+                Some(Event::Cancel)
+            }
+            EventContext::DriverDisconnect(disconnect) => {
+                // leaving or moving by a command isn't a failure
+                if disconnect.reason.is_none() {
+                    self.migrations.remove(&self.guild_id);
+                    return Some(Event::Cancel);
+                }
+                let channel_id = disconnect.channel_id?;
+                let retry = {
+                    let mut migration = self.migrations.get_mut(&self.guild_id)?;
+                    migration.retries += 1;
+                    migration.retries <= MIGRATION_RETRIES
+                };
+                if !retry {
+                    self.migrations.remove(&self.guild_id);
+                    Metrics::increment(&METRICS.voice_migrations_interrupted);
+                    warn!(
+                        "Failed to connect to the new voice server of guild {}",
+                        self.guild_id
+                    );
+                    return Some(Event::Cancel);
+                }
+                warn!(
+                    "Failed to connect to the new voice server of guild {}, rejoining",
+                    self.guild_id
+                );
+                // the handlers are kept for the connection of the rejoin, which is waited for
+                // elsewhere so the events of the call aren't held up
+                let (manager, guild_id) = (self.manager.clone(), self.guild_id);
+                tokio::spawn(async move {
+                    if let Err(err) = manager.join(guild_id, channel_id).await {
+                        warn!("Failed to rejoin {}: {}", channel_id, err);
+                    }
+                });
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+impl MigrationHandler {
+    async fn resume(&self, migration: Migration) {
+        let elapsed = migration.since.elapsed();
+        let track_handle = &migration.track_handle;
+        // the track is stopped once it's skipped
+        let Ok(state) = track_handle.get_info().await else {
+            return;
+        };
+        if !matches!(state.playing, PlayMode::Play | PlayMode::Pause) {
+            return;
+        }
+        let Some(position) = resume_position(migration.position, state.position) else {
+            info!(
+                "Voice server of guild {} moved in {:?} without interrupting",
+                self.guild_id, elapsed
+            );
+            return;
+        };
+        Metrics::increment(&METRICS.voice_migrations_interrupted);
+        info!(
+            "Voice server of guild {} moved in {:?}, seeking back to {:?}",
+            self.guild_id, elapsed, position
+        );
+        if let Err(err) = track_handle.seek_async(position).await {
+            warn!("Failed to resume the track at {:?}: {}", position, err);
+        }
+    }
+}
+
+/// Where to seek the track back to, the track kept playing while there was no connection to send
+/// it with if it has advanced from the position when the voice server changed
+fn resume_position(migrated_at: Duration, current: Duration) -> Option<Duration> {
+    (current.saturating_sub(migrated_at) > MIGRATION_TOLERANCE).then_some(migrated_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resume_position;
+    use std::time::Duration;
+
+    #[test]
+    fn test_resume_position() {
+        let migrated_at = Duration::from_secs(30);
+        assert_eq!(resume_position(migrated_at, migrated_at), None);
+        assert_eq!(
+            resume_position(migrated_at, Duration::from_millis(30500)),
+            None
+        );
+        assert_eq!(
+            resume_position(migrated_at, Duration::from_secs(35)),
+            Some(migrated_at)
+        );
+        // seeked by a command in the meantime
+        assert_eq!(resume_position(migrated_at, Duration::from_secs(10)), None);
+    }
+}
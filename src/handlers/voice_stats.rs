@@ -0,0 +1,143 @@
+use super::SerenityEventHandler;
+use crate::config::get_config;
+use dashmap::DashMap;
+use serenity::{
+    all::{GuildId, UserId},
+    async_trait,
+    prelude::Context,
+};
+use songbird::events::{CoreEvent, Event, EventContext, EventHandler};
+use std::{
+    collections::HashMap,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// The audio received in a voice tick
+const TICK: Duration = Duration::from_millis(20);
+
+/// The speaking time in the voice channel turto joined last time in each guild, it's kept after
+/// leaving until turto joins again
+static VOICE_SESSIONS: LazyLock<DashMap<GuildId, VoiceSession>> = LazyLock::new(DashMap::new);
+
+pub struct VoiceSession {
+    since: Instant,
+    /// The users sending the audio streams, they're told by discord once they start speaking
+    ssrcs: HashMap<u32, UserId>,
+    speaking: HashMap<UserId, Duration>,
+}
+
+impl VoiceSession {
+    fn new(since: Instant) -> Self {
+        Self {
+            since,
+            ssrcs: HashMap::new(),
+            speaking: HashMap::new(),
+        }
+    }
+
+    /// Count a tick for each user speaking in it
+    fn speak(&mut self, ssrcs: impl IntoIterator<Item = u32>) {
+        for ssrc in ssrcs {
+            if let Some(user_id) = self.ssrcs.get(&ssrc) {
+                *self.speaking.entry(*user_id).or_default() += TICK;
+            }
+        }
+    }
+
+    /// The users who have spoken, the ones speaking for the longest first
+    fn ranking(&self) -> Vec<(UserId, Duration)> {
+        let mut ranking = self
+            .speaking
+            .iter()
+            .map(|(user_id, duration)| (*user_id, *duration))
+            .collect::<Vec<_>>();
+        ranking.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranking
+    }
+}
+
+/// How long the session of the guild has lasted and the speaking time of the users in it
+pub fn session_stats(guild_id: GuildId) -> Option<(Duration, Vec<(UserId, Duration)>)> {
+    let session = VOICE_SESSIONS.get(&guild_id)?;
+    Some((session.since.elapsed(), session.ranking()))
+}
+
+/// Forget the session of the guild turto is removed from
+pub(super) fn clear_voice_session(guild_id: GuildId) {
+    VOICE_SESSIONS.remove(&guild_id);
+}
+
+impl SerenityEventHandler {
+    /// Start recording the speaking time when turto joins or is moved to a voice channel, see
+    /// `voice_stats` in the config
+    pub(super) async fn start_voice_session(&self, ctx: &Context, guild_id: GuildId) {
+        if !get_config().voice_stats {
+            return;
+        }
+        let Some(call) = songbird::get(ctx)
+            .await
+            .and_then(|manager| manager.get(guild_id))
+        else {
+            return;
+        };
+        let since = Instant::now();
+        VOICE_SESSIONS.insert(guild_id, VoiceSession::new(since));
+        let handler = VoiceStatsHandler { guild_id, since };
+        let mut call = call.lock().await;
+        call.add_global_event(Event::Core(CoreEvent::SpeakingStateUpdate), handler.clone());
+        call.add_global_event(Event::Core(CoreEvent::VoiceTick), handler);
+        info!("Recording the speaking time in guild {}", guild_id);
+    }
+}
+
+/// Record the speaking time of a session, the handlers of the previous sessions cancel themselves
+#[derive(Clone)]
+pub struct VoiceStatsHandler {
+    guild_id: GuildId,
+    /// The start of the session it's recording
+    since: Instant,
+}
+
+#[async_trait]
+impl EventHandler for VoiceStatsHandler {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        let mut session = match VOICE_SESSIONS.get_mut(&self.guild_id) {
+            Some(session) if session.since == self.since => session,
+            _ => return Some(Event::Cancel),
+        };
+        match ctx {
+            EventContext::SpeakingStateUpdate(speaking) => {
+                if let Some(user_id) = speaking.user_id {
+                    session.ssrcs.insert(speaking.ssrc, UserId::new(user_id.0));
+                }
+            }
+            EventContext::VoiceTick(tick) => session.speak(tick.speaking.keys().copied()),
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VoiceSession, TICK};
+    use serenity::all::UserId;
+    use std::time::Instant;
+
+    #[test]
+    fn test_voice_session() {
+        let mut session = VoiceSession::new(Instant::now());
+        session.ssrcs.insert(1, UserId::new(10));
+        session.ssrcs.insert(2, UserId::new(20));
+        session.speak([1, 2]);
+        session.speak([2]);
+        // the stream of someone who hasn't been told yet
+        session.speak([3]);
+        assert_eq!(
+            session.ranking(),
+            [(UserId::new(20), TICK * 2), (UserId::new(10), TICK)]
+        );
+    }
+}
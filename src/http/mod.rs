@@ -0,0 +1,332 @@
+use crate::{
+    models::{guild::data::GuildData, playing::Playing},
+    utils::{guild::parse_guild_id, metrics::METRICS},
+};
+use dashmap::DashMap;
+use overlay::{events_guild, overlay, stream_overlay};
+use player::player_state;
+use serenity::{
+    all::{Cache, GuildId, ShardManager},
+    gateway::ConnectionStage,
+};
+use songbird::Songbird;
+use std::{collections::HashMap, io, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::RwLock,
+    time::timeout,
+};
+use tracing::{debug, warn};
+
+pub mod overlay;
+pub mod player;
+
+// requests larger than this are rejected, there is no endpoint that takes a body
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A minimal HTTP server for the health check and other operator endpoints, and the overlays of
+/// the guilds
+pub struct HttpServer {
+    listener: TcpListener,
+    state: Arc<HttpState>,
+}
+
+/// What the endpoints about the guilds read, it's shared with the bot
+pub struct HttpState {
+    pub cache: Arc<Cache>,
+    pub shard_manager: Arc<ShardManager>,
+    pub guild_data: Arc<DashMap<GuildId, GuildData>>,
+    pub playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+    pub voice_manager: Arc<Songbird>,
+    /// The bearer token the endpoints about the guilds need, they're disabled without it
+    pub token: Option<String>,
+}
+
+impl HttpServer {
+    pub async fn bind(addr: SocketAddr, state: HttpState) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        Ok(Self {
+            listener,
+            state: Arc::new(state),
+        })
+    }
+
+    pub async fn run(self) {
+        loop {
+            match self.listener.accept().await {
+                Ok((stream, peer)) => {
+                    let state = self.state.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = handle_connection(stream, &state).await {
+                            debug!("Failed to handle HTTP request from {}: {}", peer, err);
+                        }
+                    });
+                }
+                Err(err) => warn!("Failed to accept HTTP connection: {}", err),
+            }
+        }
+    }
+}
+
+pub struct Request<'a> {
+    pub method: &'a str,
+    pub path: &'a str,
+    pub authorization: Option<&'a str>,
+}
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: body.to_string(),
+        }
+    }
+
+    pub fn html(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "text/html; charset=utf-8",
+            body: body.into(),
+        }
+    }
+
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.into(),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            503 => "Service Unavailable",
+            _ => "",
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, state: &HttpState) -> io::Result<()> {
+    let response = match timeout(REQUEST_TIMEOUT, read_head(&mut stream)).await {
+        Ok(Ok(Some(head))) => match parse_request(&head) {
+            // the overlays keep the connection open for the changes of the track
+            Some(request) => match events_guild(&request, state) {
+                Some((guild_id, token)) => {
+                    return stream_overlay(stream, state, guild_id, &token).await
+                }
+                None => route(&request, state).await,
+            },
+            None => Response::text(400, "bad request"),
+        },
+        Ok(Ok(None)) => Response::text(413, "request too large"),
+        Ok(Err(err)) => return Err(err),
+        Err(_) => return Ok(()),
+    };
+
+    write_response(&mut stream, &response).await?;
+    stream.shutdown().await
+}
+
+async fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.reason(),
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await
+}
+
+// read until the end of the headers, `None` if the request is too large
+async fn read_head(stream: &mut TcpStream) -> io::Result<Option<String>> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0; 1024];
+    while !buf.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..read]);
+        if buf.len() > MAX_REQUEST_SIZE {
+            return Ok(None);
+        }
+    }
+    Ok(Some(String::from_utf8_lossy(&buf).into_owned()))
+}
+
+fn parse_request(head: &str) -> Option<Request<'_>> {
+    let mut lines = head.lines();
+    let mut parts = lines.next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    parts.next()?.starts_with("HTTP/").then_some(())?;
+    // the query string is not used by any endpoint
+    let path = target.split('?').next().unwrap_or(target);
+    let authorization = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .map(|(_, value)| value.trim());
+    Some(Request {
+        method,
+        path,
+        authorization,
+    })
+}
+
+/// Whether the request has the bearer token, there's no way in if no token is set
+fn authorized(token: Option<&str>, authorization: Option<&str>) -> bool {
+    match (
+        token,
+        authorization.and_then(|value| value.strip_prefix("Bearer ")),
+    ) {
+        (Some(token), Some(given)) => constant_time_eq(token.as_bytes(), given.as_bytes()),
+        _ => false,
+    }
+}
+
+/// Compare the bytes without stopping at the first difference, so the time taken doesn't tell
+/// how much of the token is guessed right. Only the length can be told
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+async fn route(request: &Request<'_>, state: &HttpState) -> Response {
+    if let Some(guild_id) = request
+        .path
+        .strip_prefix("/guilds/")
+        .and_then(|path| path.strip_suffix("/player"))
+    {
+        return guild_player(request, state, guild_id).await;
+    }
+    if let Some(path) = request.path.strip_prefix("/overlay/") {
+        return overlay(request, state, path).await;
+    }
+    match (request.method, request.path) {
+        ("GET", "/health") => health(state).await,
+        (_, "/health") => Response::text(405, "method not allowed"),
+        ("GET", "/live") => Response::json(200, serde_json::json!({ "status": "ok" })),
+        (_, "/live") => Response::text(405, "method not allowed"),
+        ("GET", "/metrics") => Response::text(200, METRICS.render()),
+        (_, "/metrics") => Response::text(405, "method not allowed"),
+        _ => Response::text(404, "not found"),
+    }
+}
+
+/// Ready once every shard is connected to the gateway, the process being up is told by `/live`
+async fn health(state: &HttpState) -> Response {
+    let stages = state
+        .shard_manager
+        .runners
+        .lock()
+        .await
+        .values()
+        .map(|runner| runner.stage)
+        .collect::<Vec<_>>();
+    let shard_count = state.cache.shard_count();
+    let connected = stages
+        .iter()
+        .filter(|stage| **stage == ConnectionStage::Connected)
+        .count();
+    let body = |status| {
+        serde_json::json!({
+            "status": status,
+            "shards": shard_count,
+            "connected": connected,
+        })
+    };
+    if is_ready(&stages, shard_count) {
+        Response::json(200, body("ok"))
+    } else {
+        Response::json(503, body("unavailable"))
+    }
+}
+
+/// Whether every shard has started and is connected, there are none before the bot starts
+fn is_ready(stages: &[ConnectionStage], shard_count: u32) -> bool {
+    !stages.is_empty()
+        && stages.len() >= shard_count as usize
+        && stages
+            .iter()
+            .all(|stage| *stage == ConnectionStage::Connected)
+}
+
+async fn guild_player(request: &Request<'_>, state: &HttpState, guild_id: &str) -> Response {
+    if request.method != "GET" {
+        return Response::text(405, "method not allowed");
+    }
+    if !authorized(state.token.as_deref(), request.authorization) {
+        return Response::text(401, "unauthorized");
+    }
+    let Some(guild_id) = parse_guild_id(guild_id) else {
+        return Response::text(400, "bad guild id");
+    };
+    match player_state(state, guild_id).await {
+        Some(player) => Response::json(200, player),
+        None => Response::text(404, "guild not found"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{authorized, is_ready, parse_request};
+    use serenity::gateway::ConnectionStage;
+
+    #[test]
+    fn test_parse_request() {
+        let request =
+            parse_request("GET /health?verbose=1 HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/health");
+        assert!(request.authorization.is_none());
+
+        assert!(parse_request("GET /health\r\n\r\n").is_none());
+        assert!(parse_request("").is_none());
+
+        let request =
+            parse_request("GET /guilds/1/player HTTP/1.1\r\nauthorization:  Bearer abc \r\n\r\n")
+                .unwrap();
+        assert_eq!(request.authorization, Some("Bearer abc"));
+    }
+
+    #[test]
+    fn test_is_ready() {
+        use ConnectionStage::{Connected, Resuming};
+        assert!(is_ready(&[Connected, Connected], 2));
+        assert!(!is_ready(&[Connected, Resuming], 2));
+        // the other shard hasn't started yet
+        assert!(!is_ready(&[Connected], 2));
+        assert!(!is_ready(&[], 1));
+    }
+
+    #[test]
+    fn test_authorized() {
+        assert!(authorized(Some("abc"), Some("Bearer abc")));
+        assert!(!authorized(Some("abc"), Some("Bearer abd")));
+        assert!(!authorized(Some("abc"), Some("Bearer ab")));
+        assert!(!authorized(Some("abc"), Some("Bearer abcd")));
+        assert!(!authorized(Some("abc"), Some("abc")));
+        assert!(!authorized(Some("abc"), None));
+        assert!(!authorized(None, Some("Bearer ")));
+    }
+}
@@ -0,0 +1,155 @@
+use super::{write_response, HttpState, Request, Response};
+use crate::config::get_config;
+use serde_json::{json, Value};
+use serenity::all::GuildId;
+use songbird::tracks::PlayMode;
+use std::{io, net::SocketAddr, sync::OnceLock, time::Duration};
+use tokio::{
+    io::AsyncWriteExt,
+    net::TcpStream,
+    sync::broadcast::{self, error::RecvError},
+    time::timeout,
+};
+
+/// The page for the browser sources of the streaming software, like OBS
+const OVERLAY_PAGE: &str = include_str!("overlay.html");
+// the overlays kept open at once, each one is a connection to the server
+const MAX_OVERLAY_STREAMS: usize = 100;
+// the track is sent again this often, the pauses and the seeks aren't told otherwise
+const RESEND_INTERVAL: Duration = Duration::from_secs(10);
+// the stream has no length, it goes on until the connection is closed
+const EVENTS_HEAD: &str = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+    Cache-Control: no-cache\r\nConnection: close\r\n\r\n";
+
+static OVERLAY_EVENTS: OnceLock<broadcast::Sender<GuildId>> = OnceLock::new();
+
+fn overlay_events() -> &'static broadcast::Sender<GuildId> {
+    OVERLAY_EVENTS.get_or_init(|| broadcast::channel(64).0)
+}
+
+/// Send the track of the guild to its overlays again, see the overlay hook of the track events
+pub fn refresh_overlay(guild_id: GuildId) {
+    // it fails when no overlay is open
+    let _ = overlay_events().send(guild_id);
+}
+
+/// The link to the overlay with the token, the public address is made of `http_address` and
+/// `http_port` if `overlay_url` isn't set
+pub fn overlay_link(token: &str) -> String {
+    let config = get_config();
+    let base = match &config.overlay_url {
+        Some(url) => url.trim_end_matches('/').to_owned(),
+        None => format!(
+            "http://{}",
+            SocketAddr::new(config.http_address, config.http_port)
+        ),
+    };
+    format!("{}/overlay/{}", base, token)
+}
+
+/// The guild whose overlay has the token, see `/overlay`
+fn overlay_guild(state: &HttpState, token: &str) -> Option<GuildId> {
+    if token.is_empty() {
+        return None;
+    }
+    state
+        .guild_data
+        .iter()
+        .find(|guild_data| guild_data.overlay_token.as_deref() == Some(token))
+        .map(|guild_data| *guild_data.key())
+}
+
+/// The track playing for the overlay, it's only what's shown on the stream
+async fn overlay_state(state: &HttpState, guild_id: GuildId) -> Value {
+    let current = match state.playing.read().await.get(&guild_id) {
+        // the handle of the ended track stays until the next one starts
+        Some(playing) => match playing.track_handle.get_info().await {
+            Ok(info) => json!({
+                "title": playing.metadata.title,
+                "url": playing.metadata.source_url,
+                "position": info.position.as_secs(),
+                "duration": playing.metadata.duration.map(|duration| duration.as_secs()),
+                "paused": info.playing == PlayMode::Pause,
+            }),
+            Err(_) => Value::Null,
+        },
+        None => Value::Null,
+    };
+    json!({ "current": current })
+}
+
+/// `/overlay/{token}` is the page for the browser sources, and `/overlay/{token}/json` is the
+/// track playing. The token is the only thing needed, so anyone with the link can see them
+pub async fn overlay(request: &Request<'_>, state: &HttpState, path: &str) -> Response {
+    if request.method != "GET" {
+        return Response::text(405, "method not allowed");
+    }
+    let (token, endpoint) = path.split_once('/').unwrap_or((path, ""));
+    let Some(guild_id) = overlay_guild(state, token) else {
+        return Response::text(404, "not found");
+    };
+    match endpoint {
+        "" => Response::html(200, OVERLAY_PAGE),
+        "json" => Response::json(200, overlay_state(state, guild_id).await),
+        _ => Response::text(404, "not found"),
+    }
+}
+
+/// The guild of the request for the stream of the overlay, `/overlay/{token}/events`
+pub fn events_guild(request: &Request<'_>, state: &HttpState) -> Option<(GuildId, String)> {
+    if request.method != "GET" {
+        return None;
+    }
+    let token = request
+        .path
+        .strip_prefix("/overlay/")?
+        .strip_suffix("/events")?;
+    overlay_guild(state, token).map(|guild_id| (guild_id, token.to_owned()))
+}
+
+/// Send the track playing as server-sent events until the overlay is closed or its token is
+/// changed
+pub async fn stream_overlay(
+    mut stream: TcpStream,
+    state: &HttpState,
+    guild_id: GuildId,
+    token: &str,
+) -> io::Result<()> {
+    let events = overlay_events();
+    if events.receiver_count() >= MAX_OVERLAY_STREAMS {
+        write_response(&mut stream, &Response::text(503, "too many overlays")).await?;
+        return stream.shutdown().await;
+    }
+    let mut receiver = events.subscribe();
+    stream.write_all(EVENTS_HEAD.as_bytes()).await?;
+    while overlay_guild(state, token) == Some(guild_id) {
+        let data = overlay_state(state, guild_id).await;
+        stream
+            .write_all(format!("data: {}\n\n", data).as_bytes())
+            .await?;
+        let changed = async {
+            loop {
+                match receiver.recv().await {
+                    Ok(changed) if changed != guild_id => continue,
+                    // the missed changes are caught up by sending the track again
+                    Ok(_) | Err(RecvError::Lagged(_)) | Err(RecvError::Closed) => break,
+                }
+            }
+        };
+        let _ = timeout(RESEND_INTERVAL, changed).await;
+    }
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::overlay_link;
+    use crate::test::setup_config;
+
+    #[test]
+    fn test_overlay_link() {
+        setup_config();
+        // the template doesn't set `overlay_url`
+        assert_eq!(overlay_link("abc"), "http://127.0.0.1:8080/overlay/abc");
+    }
+}
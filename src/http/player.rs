@@ -0,0 +1,49 @@
+use super::HttpState;
+use crate::models::guild::volume::GuildVolume;
+use serde_json::{json, Value};
+use serenity::all::GuildId;
+use songbird::tracks::PlayMode;
+
+/// The player of the guild for `/guilds/{id}/player`, `None` if turto knows nothing about the
+/// guild
+pub async fn player_state(state: &HttpState, guild_id: GuildId) -> Option<Value> {
+    let call = state.voice_manager.get(guild_id);
+    let (queue_length, repeat, volume) = match state.guild_data.get(&guild_id) {
+        Some(guild_data) => (
+            guild_data.playlist.len(),
+            guild_data.config.repeat,
+            *guild_data.config.volume,
+        ),
+        None if call.is_none() => return None,
+        None => (0, false, *GuildVolume::default()),
+    };
+    let voice_channel = match call {
+        Some(call) => call.lock().await.current_channel(),
+        None => None,
+    };
+
+    let current = match state.playing.read().await.get(&guild_id) {
+        Some(playing) => {
+            let info = playing.track_handle.get_info().await.ok();
+            json!({
+                "title": playing.metadata.title,
+                "url": playing.metadata.source_url,
+                "requester": playing.requester.map(|requester| requester.to_string()),
+                "position": info.as_ref().map(|info| info.position.as_secs()),
+                "duration": playing.metadata.duration.map(|duration| duration.as_secs()),
+                "paused": info.is_some_and(|info| info.playing == PlayMode::Pause),
+            })
+        }
+        None => Value::Null,
+    };
+
+    // the ids are strings since they don't fit in the numbers of javascript
+    Some(json!({
+        "guild_id": guild_id.to_string(),
+        "voice_channel": voice_channel.map(|channel| channel.0.to_string()),
+        "current": current,
+        "queue_length": queue_length,
+        "repeat": repeat,
+        "volume": (volume * 100.0).round() as u32,
+    }))
+}
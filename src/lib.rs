@@ -2,10 +2,21 @@ pub mod bot;
 pub mod commands;
 pub mod config;
 pub mod handlers;
+pub mod http;
+#[cfg(feature = "load-test")]
+pub mod load_test;
+pub mod log;
 pub mod messages;
 pub mod models;
+#[cfg(unix)]
+pub mod operator;
+#[cfg(feature = "plugins")]
+pub mod plugin;
 pub mod signal;
 pub mod utils;
 
+#[cfg(feature = "plugins")]
+pub use plugin::{init_plugins, TurtoPlugin};
+
 #[cfg(test)]
 pub mod test;
@@ -0,0 +1,303 @@
+//! A harness simulating many guilds playing at the same time, with silent tracks in unconnected
+//! calls and a stream of commands reproducing the locking of the real ones. It measures how long
+//! the commands wait for the locks and how late the tasks are woken, so the regressions of the
+//! shared state are caught before a release
+use crate::models::{guild::data::GuildData, playing::Playing, playlist_item::PlaylistItem};
+use dashmap::DashMap;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serenity::all::{GuildId, UserId};
+use songbird::{
+    input::{AuxMetadata, Input, RawAdapter},
+    tracks::Track,
+    Call,
+};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::Cursor,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::{Mutex, RwLock};
+
+/// The sample rate of the silent tracks
+const SAMPLE_RATE: u32 = 48000;
+/// How often the event loop probe wakes up
+const PROBE_INTERVAL: Duration = Duration::from_millis(10);
+
+pub struct LoadTestConfig {
+    pub guilds: u64,
+    pub duration: Duration,
+    /// The commands issued in each guild per second
+    pub commands_per_second: u32,
+    pub seed: u64,
+}
+
+impl Default for LoadTestConfig {
+    fn default() -> Self {
+        Self {
+            guilds: 100,
+            duration: Duration::from_secs(10),
+            commands_per_second: 5,
+            seed: 0,
+        }
+    }
+}
+
+/// The commands simulated, each takes the same locks as the real one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimulatedCommand {
+    Queue,
+    Playlist,
+    Playwhat,
+    Volume,
+    Skip,
+}
+
+impl SimulatedCommand {
+    const ALL: [SimulatedCommand; 5] = [
+        SimulatedCommand::Queue,
+        SimulatedCommand::Playlist,
+        SimulatedCommand::Playwhat,
+        SimulatedCommand::Volume,
+        SimulatedCommand::Skip,
+    ];
+}
+
+/// The latencies of a kind of event, the percentiles are taken from the sorted samples
+#[derive(Debug, Default)]
+pub struct Latencies(Vec<Duration>);
+
+impl Latencies {
+    pub fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The latency which `percent` of the samples are under, `None` if there's no sample
+    pub fn percentile(&self, percent: f64) -> Option<Duration> {
+        let index = ((self.0.len() as f64 * percent / 100.0).ceil() as usize).checked_sub(1)?;
+        self.0.get(index.min(self.0.len() - 1)).copied()
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.0.last().copied()
+    }
+
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+        Self(samples)
+    }
+}
+
+impl Display for Latencies {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format = |latency: Option<Duration>| {
+            latency.map_or("-".to_owned(), |latency| format!("{:.2?}", latency))
+        };
+        write!(
+            f,
+            "n={} p50={} p99={} max={}",
+            self.count(),
+            format(self.percentile(50.0)),
+            format(self.percentile(99.0)),
+            format(self.max())
+        )
+    }
+}
+
+pub struct LoadTestReport {
+    pub commands: HashMap<SimulatedCommand, Latencies>,
+    /// How late the probe task is woken, it's high when the tasks block the runtime
+    pub event_loop_lag: Latencies,
+}
+
+impl Display for LoadTestReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for command in SimulatedCommand::ALL {
+            if let Some(latencies) = self.commands.get(&command) {
+                writeln!(f, "{:?}: {}", command, latencies)?;
+            }
+        }
+        write!(f, "event loop lag: {}", self.event_loop_lag)
+    }
+}
+
+/// The shared state, the same as what the bot has
+struct State {
+    guilds: Arc<DashMap<GuildId, GuildData>>,
+    playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+    calls: HashMap<GuildId, Arc<Mutex<Call>>>,
+}
+
+/// A track of silence that never ends
+fn silent_track() -> Track {
+    // a second of stereo f32 silence, looped
+    let silence = vec![0; SAMPLE_RATE as usize * 2 * 4];
+    let input = Input::from(RawAdapter::new(Cursor::new(silence), SAMPLE_RATE, 2));
+    Track::from(input).loops(songbird::tracks::LoopState::Infinite)
+}
+
+async fn play_silence(state: &State, guild_id: GuildId) {
+    let call = &state.calls[&guild_id];
+    let track_handle = {
+        let mut call = call.lock().await;
+        call.stop();
+        call.play_only(silent_track())
+    };
+    let playing = Playing {
+        track_handle,
+        metadata: Arc::new(AuxMetadata::default()),
+        url: "silence".into(),
+        requester: None,
+    };
+    state.playing.write().await.insert(guild_id, playing);
+}
+
+async fn simulate(state: &State, guild_id: GuildId, command: SimulatedCommand) {
+    match command {
+        SimulatedCommand::Queue => {
+            let item = PlaylistItem::from(AuxMetadata::default());
+            let mut guild_data = state.guilds.entry(guild_id).or_default();
+            guild_data.playlist.push_back(item);
+        }
+        SimulatedCommand::Playlist => {
+            let guild_data = state.guilds.entry(guild_id).or_default();
+            let _ = guild_data.playlist.page_with_indices(1);
+        }
+        SimulatedCommand::Playwhat => {
+            // the calls aren't connected, so the tracks are never mixed and their state can't be
+            // queried, only the metadata is read like the embed of the real one
+            let playing_map = state.playing.read().await;
+            if let Some(playing) = playing_map.get(&guild_id) {
+                let _ = playing.metadata.title.clone();
+            }
+        }
+        SimulatedCommand::Volume => {
+            let playing_map = state.playing.read().await;
+            if let Some(playing) = playing_map.get(&guild_id) {
+                let _ = playing.track_handle.set_volume(0.5);
+            }
+            drop(playing_map);
+            let _ = state.guilds.entry(guild_id).or_default().config.volume;
+        }
+        SimulatedCommand::Skip => {
+            let next = state
+                .guilds
+                .entry(guild_id)
+                .or_default()
+                .playlist
+                .pop_front();
+            if next.is_some() {
+                play_silence(state, guild_id).await;
+            }
+        }
+    }
+}
+
+/// Run the simulation and collect the latencies
+pub async fn run_load_test(config: LoadTestConfig) -> LoadTestReport {
+    let guild_ids = (1..=config.guilds).map(GuildId::new).collect::<Vec<_>>();
+    let state = Arc::new(State {
+        guilds: Default::default(),
+        playing: Default::default(),
+        calls: guild_ids
+            .iter()
+            .map(|guild_id| {
+                let call = Call::standalone(*guild_id, UserId::new(1));
+                (*guild_id, Arc::new(Mutex::new(call)))
+            })
+            .collect(),
+    });
+    for guild_id in &guild_ids {
+        play_silence(&state, *guild_id).await;
+    }
+
+    let deadline = Instant::now() + config.duration;
+    let probe = tokio::spawn(async move {
+        let mut lags = Vec::new();
+        while Instant::now() < deadline {
+            let start = Instant::now();
+            tokio::time::sleep(PROBE_INTERVAL).await;
+            lags.push(start.elapsed().saturating_sub(PROBE_INTERVAL));
+        }
+        lags
+    });
+
+    let interval = Duration::from_secs(1) / config.commands_per_second.max(1);
+    let tasks = guild_ids
+        .into_iter()
+        .map(|guild_id| {
+            let state = state.clone();
+            let mut rng = StdRng::seed_from_u64(config.seed ^ guild_id.get());
+            tokio::spawn(async move {
+                let mut samples = Vec::new();
+                // the guilds start at different times, like the real ones
+                tokio::time::sleep(interval.mul_f64(rng.gen())).await;
+                while Instant::now() < deadline {
+                    let command =
+                        SimulatedCommand::ALL[rng.gen_range(0..SimulatedCommand::ALL.len())];
+                    let start = Instant::now();
+                    simulate(&state, guild_id, command).await;
+                    samples.push((command, start.elapsed()));
+                    tokio::time::sleep(interval).await;
+                }
+                samples
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut samples = HashMap::<SimulatedCommand, Vec<Duration>>::new();
+    for task in tasks {
+        for (command, latency) in task.await.unwrap_or_default() {
+            samples.entry(command).or_default().push(latency);
+        }
+    }
+    let event_loop_lag = Latencies::from_samples(probe.await.unwrap_or_default());
+
+    for call in state.calls.values() {
+        call.lock().await.stop();
+    }
+
+    LoadTestReport {
+        commands: samples
+            .into_iter()
+            .map(|(command, samples)| (command, Latencies::from_samples(samples)))
+            .collect(),
+        event_loop_lag,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_load_test, Latencies, LoadTestConfig};
+    use std::time::Duration;
+
+    #[test]
+    fn test_percentile() {
+        let latencies =
+            Latencies::from_samples((1..=100).rev().map(Duration::from_millis).collect());
+        assert_eq!(latencies.percentile(50.0), Some(Duration::from_millis(50)));
+        assert_eq!(latencies.percentile(99.0), Some(Duration::from_millis(99)));
+        assert_eq!(latencies.max(), Some(Duration::from_millis(100)));
+        assert_eq!(Latencies::default().percentile(50.0), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_load() {
+        let report = run_load_test(LoadTestConfig {
+            guilds: 10,
+            duration: Duration::from_secs(1),
+            ..Default::default()
+        })
+        .await;
+        assert!(
+            report
+                .commands
+                .values()
+                .map(Latencies::count)
+                .sum::<usize>()
+                > 0
+        );
+        assert!(report.event_loop_lag.count() > 0);
+    }
+}
@@ -0,0 +1,54 @@
+pub mod json;
+pub mod rolling;
+
+use crate::models::config::{LogFormat, TurtoConfig};
+use anyhow::{Context, Result};
+use json::{JsonFields, JsonFormat};
+use rolling::RollingFile;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{fmt::layer, layer::SubscriberExt, EnvFilter, Layer, Registry};
+
+pub fn setup_log(config: &TurtoConfig) -> Result<WorkerGuard> {
+    let file_appender = RollingFile::new(
+        &config.log_dir,
+        config.log_rotation,
+        config.log_max_size * 1024 * 1024,
+        config.log_retention_days,
+    )
+    .context(format!(
+        "Failed to create log file in {}",
+        config.log_dir.display()
+    ))?;
+    let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+
+    let layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = match config.log_format {
+        LogFormat::Text => vec![
+            layer().with_writer(non_blocking).with_ansi(false).boxed(),
+            layer().with_writer(std::io::stdout).boxed(),
+        ],
+        LogFormat::Json => vec![
+            layer()
+                .with_writer(non_blocking)
+                .with_ansi(false)
+                .fmt_fields(JsonFields)
+                .event_format(JsonFormat)
+                .boxed(),
+            layer()
+                .with_writer(std::io::stdout)
+                .with_ansi(false)
+                .fmt_fields(JsonFields)
+                .event_format(JsonFormat)
+                .boxed(),
+        ],
+    };
+    let subscriber = tracing_subscriber::registry().with(layers).with(
+        EnvFilter::builder()
+            .with_default_directive(LevelFilter::INFO.into())
+            .with_env_var("TURTO_LOG")
+            .from_env_lossy(),
+    );
+
+    tracing::subscriber::set_global_default(subscriber)?;
+    Ok(guard)
+}
@@ -0,0 +1,191 @@
+use crate::models::config::LogRotation;
+use chrono::{DateTime, Local, NaiveDateTime};
+use std::{
+    ffi::OsStr,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+const FILENAME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+// the length of a timestamp formatted with FILENAME_FORMAT
+const FILENAME_TIMESTAMP_LENGTH: usize = 19;
+
+/// A log file writer that starts a new file once the rotation period is over or the current file
+/// exceeds the size limitation, rotated files older than the retention period are removed
+pub struct RollingFile {
+    dir: PathBuf,
+    rotation: LogRotation,
+    max_size: u64,
+    retention: Option<Duration>,
+    path: PathBuf,
+    file: File,
+    size: u64,
+    period: Option<String>,
+}
+
+impl RollingFile {
+    /// `max_size` is denoted by bytes and `retention_days` by days, `0` disables them
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        rotation: LogRotation,
+        max_size: u64,
+        retention_days: u64,
+    ) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        let now = Local::now();
+        let (path, file) = create_log_file(&dir, now)?;
+        let rolling = Self {
+            dir,
+            rotation,
+            max_size,
+            retention: (retention_days > 0)
+                .then(|| Duration::from_secs(retention_days * 24 * 60 * 60)),
+            path,
+            file,
+            size: 0,
+            period: period(rotation, now),
+        };
+        rolling.prune();
+        Ok(rolling)
+    }
+
+    fn rotate(&mut self, now: DateTime<Local>) -> io::Result<()> {
+        self.file.flush()?;
+        let (path, file) = create_log_file(&self.dir, now)?;
+        self.path = path;
+        self.file = file;
+        self.size = 0;
+        self.period = period(self.rotation, now);
+        self.prune();
+        Ok(())
+    }
+
+    fn prune(&self) {
+        let Some(retention) = self.retention else {
+            return;
+        };
+        let Some(cutoff) = SystemTime::now().checked_sub(retention) else {
+            return;
+        };
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path == self.path || !is_log_file(&path) {
+                continue;
+            }
+            let expired = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified < cutoff);
+            if expired {
+                // there is no logger to report to here, the failures are simply ignored
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let now = Local::now();
+        let period_over = period(self.rotation, now) != self.period;
+        let oversized =
+            self.max_size > 0 && self.size > 0 && self.size + buf.len() as u64 > self.max_size;
+        if period_over || oversized {
+            self.rotate(now)?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn period(rotation: LogRotation, time: DateTime<Local>) -> Option<String> {
+    match rotation {
+        LogRotation::Hourly => Some(time.format("%Y-%m-%d_%H").to_string()),
+        LogRotation::Daily => Some(time.format("%Y-%m-%d").to_string()),
+        LogRotation::Never => None,
+    }
+}
+
+fn create_log_file(dir: &Path, time: DateTime<Local>) -> io::Result<(PathBuf, File)> {
+    let timestamp = time.format(FILENAME_FORMAT).to_string();
+    // files can be rotated more than once in a second when the size limitation is small
+    for index in 0.. {
+        let filename = match index {
+            0 => format!("{}.log", timestamp),
+            _ => format!("{}_{}.log", timestamp, index),
+        };
+        let path = dir.join(filename);
+        match OpenOptions::new().append(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    unreachable!()
+}
+
+// only the files created by RollingFile are considered, other files in the directory are kept
+fn is_log_file(path: &Path) -> bool {
+    if path.extension() != Some(OsStr::new("log")) {
+        return false;
+    }
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.get(..FILENAME_TIMESTAMP_LENGTH))
+        .is_some_and(|timestamp| NaiveDateTime::parse_from_str(timestamp, FILENAME_FORMAT).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RollingFile;
+    use crate::models::config::LogRotation;
+    use std::{
+        env,
+        fs::{self, File},
+        io::Write,
+        process,
+        time::{Duration, SystemTime},
+    };
+
+    #[test]
+    fn test_rolling_file() {
+        let dir = env::temp_dir().join(format!("turto_log_test_{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let expired = dir.join("2000-01-01_00-00-00.log");
+        File::create(&expired)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3 * 24 * 60 * 60))
+            .unwrap();
+        let unrelated = dir.join("notes.log");
+        File::create(&unrelated).unwrap();
+
+        let mut rolling = RollingFile::new(&dir, LogRotation::Never, 10, 1).unwrap();
+        assert!(!expired.exists());
+        assert!(unrelated.exists());
+
+        rolling.write_all(b"12345678").unwrap();
+        rolling.write_all(b"12345678").unwrap();
+        rolling.flush().unwrap();
+        let log_files = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.path() != unrelated)
+            .count();
+        assert_eq!(log_files, 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
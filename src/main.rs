@@ -1,19 +1,40 @@
 use anyhow::{Context, Result};
-use chrono::Local;
-use std::env;
-use tracing::{error, level_filters::LevelFilter, warn};
-use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{fmt::layer, layer::SubscriberExt, EnvFilter};
+use std::{env, net::SocketAddr, path::PathBuf, process};
+use tracing::{error, info, warn};
 use turto::{
     bot::Turto,
-    config::{help::load_help, load_config, message_template::load_templates},
+    config::{
+        check::{check_files, Severity},
+        get_config,
+        help::load_help,
+        load_config,
+        message_template::load_templates,
+        secret::{get_secret, providers},
+        CONFIG_PATH, HELP_PATH, TEMPLATES_PATH,
+    },
+    http::HttpServer,
+    log::setup_log,
+    models::config::TurtoConfig,
     signal::wait_shutdown_signal,
 };
 use which::which_global;
 
+#[cfg(unix)]
+use turto::operator::OperatorSocket;
+
 #[tokio::main]
 async fn main() {
-    let _log_guard = match setup_log() {
+    // the config is loaded first since the logging setup depends on it, and the .env file goes
+    // before it since the environment variables may override the config
+    let dotenv = dotenv::dotenv();
+    if env::args().any(|arg| arg == "--check-config") {
+        return check_config();
+    }
+    if let Err(err) = load_config(CONFIG_PATH) {
+        return println!("{:#}", err);
+    }
+
+    let _log_guard = match setup_log(get_config()) {
         Ok(guard) => guard,
         Err(err) => {
             println!("{:#}", err);
@@ -21,18 +42,27 @@ async fn main() {
         }
     };
 
+    if let Err(err) = dotenv {
+        warn!("Failed to load .env file: {}", err);
+    }
+
     if let Err(err) = setup_env() {
         return error!("{:#}", err);
     }
-
-    let token = match env::var("DISCORD_TOKEN") {
-        Ok(token) => {
-            if token.is_empty() {
-                return error!("DISCORD_TOKEN is not set in the environment");
-            }
-            token
+    for diagnostic in check_files(CONFIG_PATH, HELP_PATH, TEMPLATES_PATH) {
+        match diagnostic.severity {
+            Severity::Error => error!("{}", diagnostic),
+            Severity::Warning => warn!("{}", diagnostic),
         }
-        Err(err) => return error!("Failed to load DISCORD_TOKEN from the environment: {}", err),
+    }
+
+    // DISCORD_TOKEN_FILE goes first, then DISCORD_TOKEN, then Vault if it's configured
+    let token = match get_secret(&providers(get_config()), "DISCORD_TOKEN").await {
+        Ok(Some(token)) => token,
+        Ok(None) => return error!(
+            "DISCORD_TOKEN is not set, provide it with DISCORD_TOKEN, DISCORD_TOKEN_FILE or Vault"
+        ),
+        Err(err) => return error!("Failed to load DISCORD_TOKEN: {:#}", err),
     };
 
     let data_path = "guilds.json".to_string();
@@ -40,47 +70,104 @@ async fn main() {
         Ok(bot) => bot,
         Err(err) => return error!("Turto client initialization failed: {}", err),
     };
+    // the state exported by the previous instance replaces the data files
+    if let Some(path) = arg_path("--import-state") {
+        if let Err(err) = bot.import_state(&path) {
+            return error!("{:#}", err);
+        }
+    }
 
-    bot_process(bot).await;
+    // the bot runs without them if they can't be started
+    if let Err(err) = start_http_server(get_config(), &bot).await {
+        warn!("{:#}, the bot runs without it", err);
+    }
+    #[cfg(unix)]
+    if let Err(err) = start_operator_socket(get_config(), &bot) {
+        warn!("{:#}, the bot runs without it", err);
+    }
+
+    bot_process(bot, arg_path("--export-state")).await;
+}
+
+/// The path after the flag in the arguments, like `--import-state state.json`
+fn arg_path(flag: &str) -> Option<PathBuf> {
+    let mut args = env::args().skip_while(|arg| arg != flag);
+    args.next()?;
+    args.next().map(PathBuf::from)
 }
 
 fn setup_env() -> Result<()> {
-    if let Err(err) = dotenv::dotenv() {
-        warn!("Failed to load .env file: {}", err);
-    }
     which_global("yt-dlp").context("yt-dlp is not installed")?;
-    load_config("config.toml")?;
-    load_help("help.toml")?;
-    load_templates("templates.toml")?;
+    load_help(HELP_PATH)?;
+    load_templates(TEMPLATES_PATH)?;
     Ok(())
 }
 
-fn setup_log() -> Result<WorkerGuard> {
-    let time = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
-    let log_filename = format!("{}.log", time);
-
-    let file_appender = tracing_appender::rolling::never(env::current_dir()?, log_filename);
-    let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+// check the config files and print the diagnostics without starting the bot
+fn check_config() {
+    let diagnostics = check_files(CONFIG_PATH, HELP_PATH, TEMPLATES_PATH);
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+    }
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count();
+    println!(
+        "{} error(s), {} warning(s)",
+        errors,
+        diagnostics.len() - errors
+    );
+    if errors > 0 {
+        process::exit(1);
+    }
+}
 
-    let file_layer = layer().with_writer(non_blocking).with_ansi(false);
-    let console_layer = layer().with_writer(std::io::stdout);
-    let subscriber = tracing_subscriber::registry()
-        .with(file_layer)
-        .with(console_layer)
-        .with(
-            EnvFilter::builder()
-                .with_default_directive(LevelFilter::INFO.into())
-                .with_env_var("TURTO_LOG")
-                .from_env_lossy(),
-        );
+async fn start_http_server(config: &TurtoConfig, bot: &Turto) -> Result<()> {
+    if !config.http_server {
+        return Ok(());
+    }
+    // the endpoints about the guilds are disabled without the token
+    let token = match get_secret(&providers(config), "HTTP_TOKEN").await {
+        Ok(token) => token,
+        Err(err) => {
+            warn!("Failed to load HTTP_TOKEN: {:#}", err);
+            None
+        }
+    };
+    let addr = SocketAddr::new(config.http_address, config.http_port);
+    let server = HttpServer::bind(addr, bot.http_state(token))
+        .await
+        .context(format!("Failed to bind the HTTP server to {}", addr))?;
+    info!("HTTP server is listening on {}", addr);
+    tokio::spawn(server.run());
+    Ok(())
+}
 
-    tracing::subscriber::set_global_default(subscriber).unwrap();
-    Ok(guard)
+#[cfg(unix)]
+fn start_operator_socket(config: &TurtoConfig, bot: &Turto) -> Result<()> {
+    let Some(path) = &config.operator_socket else {
+        return Ok(());
+    };
+    let socket = OperatorSocket::bind(path, bot.operator_state()).context(format!(
+        "Failed to bind the operator socket to {}",
+        path.display()
+    ))?;
+    info!("Operator socket is listening on {}", path.display());
+    tokio::spawn(socket.run());
+    Ok(())
 }
 
-async fn bot_process(mut bot: Turto) {
+async fn bot_process(mut bot: Turto, export_path: Option<PathBuf>) {
     tokio::select! {
         _ = wait_shutdown_signal() => {
+            // before the calls are left, so the positions of the tracks are exported
+            if let Some(path) = export_path {
+                match bot.export_state(&path).await {
+                    Ok(bytes) => info!("State exported to {}, {} bytes", path.display(), bytes),
+                    Err(err) => error!("Failed to export the state: {:#}", err),
+                }
+            }
             bot.shutdown().await;
         }
         _ = bot.start() => ()
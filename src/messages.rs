@@ -1,13 +1,20 @@
 use crate::{
     config::message_template::get_template,
-    models::{autoleave::AutoleaveType, guild::volume::GuildVolume},
-    utils::misc::ToEmoji,
+    models::{
+        autoleave::AutoleaveType,
+        cleanup::MessageCleanup,
+        guild::{listening_log::LogPeriod, volume::GuildVolume},
+        mix::MixOrder,
+        premium::Feature,
+    },
+    utils::misc::{format_duration, ToEmoji},
 };
+use poise::ChoiceParameter;
 use serenity::{
-    model::prelude::{ChannelId, UserId},
+    model::prelude::{ChannelId, GuildId, RoleId, UserId},
     prelude::Mentionable,
 };
-use std::fmt::Display;
+use std::{fmt::Display, time::Duration};
 
 pub struct TurtoMessage<'a> {
     pub locale: Option<&'a str>,
@@ -19,34 +26,465 @@ pub enum TurtoMessageKind<'a> {
     NotPlaying,
     UserNotInVoiceChannel,
     BotNotInVoiceChannel,
-    DifferentVoiceChannel { bot: ChannelId },
-    Play { title: &'a str },
-    Pause { title: &'a str },
-    Skip { title: Option<&'a str> },
-    Stop { title: &'a str },
+    DifferentVoiceChannel {
+        bot: ChannelId,
+    },
+    Play {
+        title: &'a str,
+    },
+    Pause {
+        title: &'a str,
+    },
+    Skip {
+        title: Option<&'a str>,
+    },
+    Stop {
+        title: &'a str,
+    },
     Join(ChannelId),
     Leave(ChannelId),
-    Queue { title: &'a str },
-    Remove { title: &'a str },
+    Queue {
+        title: &'a str,
+    },
+    Remove {
+        title: &'a str,
+    },
     RemoveAll,
-    InvalidRemove { length: usize },
-    InvalidRangeRemove { from: usize, to: usize, length: usize },
+    InvalidRemove {
+        length: usize,
+    },
+    InvalidRangeRemove {
+        from: usize,
+        to: usize,
+        length: usize,
+    },
     InvalidUrl(Option<&'a str>),
     SetVolume(GuildVolume),
     SetAutoleave(AutoleaveType),
     SeekSuccess,
-    InvalidSeek { seek_limit: u64 },
-    SeekNotAllow { backward: bool },
-    SeekNotLongEnough { title: &'a str, length: u64 },
+    InvalidSeek {
+        seek_limit: u64,
+    },
+    SeekNotAllow {
+        backward: bool,
+    },
+    SeekNotLongEnough {
+        title: &'a str,
+        length: u64,
+    },
     AdministratorOnly,
-    Ban { success: bool, user: UserId },
-    Unban { success: bool, user: UserId },
+    Ban {
+        success: bool,
+        user: UserId,
+    },
+    Unban {
+        success: bool,
+        user: UserId,
+    },
     BannedUserResponse,
     Shuffle,
     SetRepeat(bool),
     EmptyPlaylist,
-    InvalidPlaylistPage { total_pages: usize },
-    RemoveMany { removed_number: usize },
+    InvalidPlaylistPage {
+        total_pages: usize,
+    },
+    RemoveMany {
+        removed_number: usize,
+    },
+    CommandNotFound {
+        command_name: &'a str,
+    },
+    HelpUsage,
+    HelpParameters,
+    HelpExamples,
+    HelpAliases,
+    HelpPermissions,
+    HelpPage {
+        current: usize,
+        total: usize,
+    },
+    OwnerOnly,
+    InvalidGuildId {
+        id: &'a str,
+    },
+    GuildLeave {
+        success: bool,
+        guild_id: GuildId,
+    },
+    GuildBlacklist {
+        success: bool,
+        guild_id: GuildId,
+    },
+    GuildUnblacklist {
+        success: bool,
+        guild_id: GuildId,
+    },
+    NoGuilds,
+    SetAudit(bool),
+    EmptyAuditLog,
+    ConfigCheckPassed,
+    SettingsTitle,
+    SettingsAutoLeave,
+    SettingsVolume,
+    SettingsRepeat,
+    SettingsLocale,
+    SettingsDjRole,
+    SettingsQueueLimit,
+    SettingsNotSet,
+    SettingsUnlimited,
+    DjOnly,
+    QueueFull {
+        limit: usize,
+    },
+    SettingsImportInvalid {
+        reason: &'a str,
+    },
+    SettingsImportConfirm,
+    SettingsImported,
+    SettingsImportCancelled,
+    PremiumRequired(Feature),
+    PrefsTitle,
+    PrefsNotifyTrackStart,
+    PrefsSearchSource,
+    PrefsLocale,
+    InvalidLocale {
+        locale: &'a str,
+    },
+    TrackStartNotify {
+        title: &'a str,
+        channel_id: ChannelId,
+    },
+    BookmarkDm {
+        title: &'a str,
+        url: &'a str,
+        position: &'a str,
+    },
+    BookmarkSent,
+    BookmarkDmFailed,
+    GreetingAdded,
+    GreetingRemoved,
+    GreetingTargetRequired,
+    GreetingLimitReached {
+        limit: usize,
+    },
+    InvalidGreetingIndex {
+        length: usize,
+    },
+    EmptyGreetings,
+    QueueBulkInvalidFile {
+        size: u32,
+    },
+    QueueBulkProgress {
+        done: usize,
+        total: usize,
+        job: u64,
+    },
+    QueueBulkDone {
+        queued: usize,
+        failed: usize,
+    },
+    Live,
+    SetExplicitFilter {
+        enabled: bool,
+        bypass_role: Option<RoleId>,
+    },
+    ExplicitRejected,
+    ExplicitFiltered {
+        count: usize,
+    },
+    SetFilter {
+        name: &'a str,
+    },
+    FilterOff,
+    SettingsTrimSilence,
+    SettingsReactionControls,
+    RewindSuccess,
+    Replay {
+        title: &'a str,
+    },
+    InvalidJumpIndex {
+        length: usize,
+    },
+    SetAutoplay(bool),
+    SavedPlaylistSaved {
+        name: &'a str,
+        count: usize,
+    },
+    SavedPlaylistShuffled {
+        name: &'a str,
+    },
+    SavedPlaylistDeleted {
+        name: &'a str,
+    },
+    SavedPlaylistNotFound {
+        name: &'a str,
+    },
+    SavedPlaylistExists {
+        name: &'a str,
+    },
+    SavedPlaylistLimit {
+        limit: usize,
+    },
+    InvalidName {
+        max: usize,
+    },
+    EmptySavedPlaylists,
+    SavedPlaylistAdded {
+        name: &'a str,
+        count: usize,
+    },
+    SavedPlaylistNoPermission {
+        name: &'a str,
+    },
+    SavedPlaylistEditor {
+        name: &'a str,
+        user: UserId,
+        added: bool,
+    },
+    SavedPlaylistPublic {
+        name: &'a str,
+        public: bool,
+    },
+    SavedPlaylistShared {
+        name: &'a str,
+        shared: bool,
+    },
+    QueueSnapshotSaved {
+        name: &'a str,
+        count: usize,
+    },
+    QueueSnapshotRestored {
+        name: &'a str,
+    },
+    QueueSnapshotNotFound {
+        name: &'a str,
+    },
+    QueueSnapshotLimit {
+        limit: usize,
+    },
+    NoUrlsInMessage,
+    BusyInVoiceChannel {
+        bot: ChannelId,
+        to: ChannelId,
+        votes: usize,
+        required: usize,
+    },
+    VoteMoveButton,
+    VoteNotListener {
+        bot: ChannelId,
+    },
+    MovedVoiceChannel(ChannelId),
+    VoteMoveExpired {
+        to: ChannelId,
+    },
+    AlreadyInVoiceChannel(ChannelId),
+    TrackStuck {
+        title: &'a str,
+    },
+    AutoleaveTimeouts {
+        idle: u64,
+        alone: u64,
+    },
+    SetMessageCleanup(MessageCleanup),
+    TrackFinished {
+        title: &'a str,
+    },
+    Welcome,
+    SetAnnouncements {
+        enabled: bool,
+        channel: Option<ChannelId>,
+    },
+    AnnounceDone {
+        sent: usize,
+        failed: usize,
+    },
+    WhatsNew {
+        version: &'a str,
+        url: &'a str,
+    },
+    SettingsAnnouncementChannel,
+    SetupStep {
+        step: usize,
+        total: usize,
+        setting: &'a str,
+    },
+    SetupSkip,
+    SetupDone,
+    SetupTimeout,
+    SetRequestChannel(ChannelId),
+    RequestChannelOff,
+    RequestChannelsDisabled,
+    PlayerIdle,
+    PlayerUpNext,
+    PlayerMore {
+        count: usize,
+    },
+    SetTrackGain {
+        title: &'a str,
+        gain: f32,
+    },
+    NeverAgainAdded {
+        title: &'a str,
+    },
+    NeverAgainRemoved,
+    NeverAgainNotFound,
+    NeverAgainRejected,
+    PartyCountdown {
+        seconds: u64,
+    },
+    PartyStarted {
+        title: &'a str,
+    },
+    PartySynced {
+        title: &'a str,
+    },
+    SetDucking(bool),
+    VoiceStatsDisabled,
+    NoVoiceStats,
+    VoiceStats {
+        session: &'a str,
+        stats: &'a str,
+    },
+    ClipReady {
+        title: &'a str,
+        from: &'a str,
+        to: &'a str,
+    },
+    ClipFailed,
+    InvalidPlaylistRange {
+        items: &'a str,
+    },
+    Mixed(MixOrder),
+    VoiceAnnouncement {
+        title: &'a str,
+        requester: Option<&'a str>,
+    },
+    VoiceAnnouncementsDisabled,
+    SetVoiceAnnouncements(bool),
+    ListeningLogEntry {
+        title: &'a str,
+        url: &'a str,
+        requester: Option<UserId>,
+    },
+    ListeningLogThread {
+        date: &'a str,
+    },
+    SetListeningLog {
+        channel: ChannelId,
+        period: LogPeriod,
+    },
+    ListeningLogOff,
+    InvalidEventStart {
+        start: &'a str,
+    },
+    EventCreateFailed,
+    EventCreated {
+        name: &'a str,
+        playlist: &'a str,
+        start: i64,
+    },
+    TempVoiceChannelName {
+        host: &'a str,
+    },
+    TempVoiceDisabled,
+    TempVoiceFailed,
+    SetTempVoice(ChannelId),
+    TempVoiceOff,
+    JobWaiting {
+        job: u64,
+    },
+    QueueBulkCanceled {
+        skipped: usize,
+    },
+    NoJobs,
+    JobEntry {
+        job: u64,
+        running: bool,
+        done: usize,
+        total: usize,
+        user: UserId,
+    },
+    JobNotFound {
+        job: u64,
+    },
+    JobNoPermission,
+    JobCanceled {
+        job: u64,
+    },
+    Resolving {
+        query: &'a str,
+    },
+    CancelButton,
+    ResolvingCanceled,
+    QueueBatch {
+        count: usize,
+        titles: &'a str,
+    },
+    QueueBatchMore {
+        count: usize,
+    },
+    GuildOnly,
+    NoThreadCommands(ChannelId),
+    ScriptAdded,
+    ScriptRemoved,
+    InvalidScript {
+        error: &'a str,
+    },
+    ScriptLimitReached {
+        limit: usize,
+    },
+    InvalidScriptIndex {
+        length: usize,
+    },
+    EmptyScripts,
+    CommandRenamed {
+        old: &'a str,
+        new: &'a str,
+    },
+    QueueDurationFull {
+        limit: Duration,
+        left: Duration,
+    },
+    UserDurationFull {
+        limit: Duration,
+        left: Duration,
+    },
+    QueueLengthLimits {
+        total: Option<u64>,
+        member: Option<u64>,
+    },
+    QueueLocked,
+    LockQueue {
+        minutes: Option<u64>,
+    },
+    UnlockQueue,
+    TooManyPendingRequests {
+        limit: usize,
+    },
+    RequestPending {
+        title: &'a str,
+    },
+    ApprovalRequest {
+        requester: UserId,
+        title: &'a str,
+    },
+    RequestApproved {
+        requester: UserId,
+        title: &'a str,
+        dj: UserId,
+    },
+    RequestRejected {
+        requester: UserId,
+        title: &'a str,
+        dj: UserId,
+    },
+    ApprovalRequired,
+    SetApproval(bool),
+    OverlayDisabled,
+    OverlayLink {
+        url: &'a str,
+    },
+    OverlayOff,
 }
 
 macro_rules! render {
@@ -59,6 +497,77 @@ macro_rules! render {
     }};
 }
 
+impl TurtoMessageKind<'_> {
+    /// The errors and the permission denials, they're only shown to the author when
+    /// `ephemeral_replies` is on
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            NotPlaying
+                | UserNotInVoiceChannel
+                | BotNotInVoiceChannel
+                | DifferentVoiceChannel { .. }
+                | InvalidRemove { .. }
+                | InvalidRangeRemove { .. }
+                | InvalidUrl(..)
+                | InvalidSeek { .. }
+                | SeekNotAllow { .. }
+                | SeekNotLongEnough { .. }
+                | AdministratorOnly
+                | BannedUserResponse
+                | InvalidPlaylistPage { .. }
+                | CommandNotFound { .. }
+                | OwnerOnly
+                | InvalidGuildId { .. }
+                | DjOnly
+                | QueueFull { .. }
+                | QueueDurationFull { .. }
+                | UserDurationFull { .. }
+                | QueueLocked
+                | TooManyPendingRequests { .. }
+                | ApprovalRequired
+                | OverlayDisabled
+                | SettingsImportInvalid { .. }
+                | PremiumRequired(..)
+                | InvalidLocale { .. }
+                | GreetingTargetRequired
+                | GreetingLimitReached { .. }
+                | InvalidGreetingIndex { .. }
+                | QueueBulkInvalidFile { .. }
+                | ExplicitRejected
+                | RequestChannelsDisabled
+                | VoiceStatsDisabled
+                | ClipFailed
+                | InvalidPlaylistRange { .. }
+                | VoiceAnnouncementsDisabled
+                | InvalidEventStart { .. }
+                | EventCreateFailed
+                | TempVoiceDisabled
+                | TempVoiceFailed
+                | JobNotFound { .. }
+                | JobNoPermission
+                | GuildOnly
+                | NoThreadCommands(..)
+                | InvalidScript { .. }
+                | ScriptLimitReached { .. }
+                | InvalidScriptIndex { .. }
+                | NeverAgainNotFound
+                | NeverAgainRejected
+                | InvalidJumpIndex { .. }
+                | SavedPlaylistNotFound { .. }
+                | SavedPlaylistExists { .. }
+                | SavedPlaylistLimit { .. }
+                | InvalidName { .. }
+                | SavedPlaylistNoPermission { .. }
+                | QueueSnapshotNotFound { .. }
+                | QueueSnapshotLimit { .. }
+                | NoUrlsInMessage
+                | AlreadyInVoiceChannel(..)
+                | VoteNotListener { .. }
+        )
+    }
+}
+
 impl Display for TurtoMessage<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let locale = self.locale;
@@ -168,6 +677,633 @@ impl Display for TurtoMessage<'_> {
                 locale,
                 ("removed_number", &removed_number.to_emoji())
             ),
+            CommandNotFound { command_name } => render!(
+                f,
+                "command_not_found",
+                locale,
+                ("command_name", command_name)
+            ),
+            HelpUsage => render!(f, "help_usage", locale),
+            HelpParameters => render!(f, "help_parameters", locale),
+            HelpExamples => render!(f, "help_examples", locale),
+            HelpAliases => render!(f, "help_aliases", locale),
+            HelpPermissions => render!(f, "help_permissions", locale),
+            HelpPage { current, total } => render!(
+                f,
+                "help_page",
+                locale,
+                ("current", current),
+                ("total", total)
+            ),
+            OwnerOnly => render!(f, "owner_only", locale),
+            InvalidGuildId { id } => render!(f, "invalid_guild_id", locale, ("guild_id", id)),
+            GuildLeave { success, guild_id } => match *success {
+                true => render!(f, "guild_left", locale, ("guild_id", guild_id)),
+                false => render!(f, "guild_leave_failed", locale, ("guild_id", guild_id)),
+            },
+            GuildBlacklist { success, guild_id } => match *success {
+                true => render!(f, "guild_blacklisted", locale, ("guild_id", guild_id)),
+                false => render!(
+                    f,
+                    "guild_already_blacklisted",
+                    locale,
+                    ("guild_id", guild_id)
+                ),
+            },
+            GuildUnblacklist { success, guild_id } => match *success {
+                true => render!(f, "guild_unblacklisted", locale, ("guild_id", guild_id)),
+                false => render!(f, "guild_not_blacklisted", locale, ("guild_id", guild_id)),
+            },
+            NoGuilds => render!(f, "no_guilds", locale),
+            SetAudit(audit) => match audit {
+                true => render!(f, "toggle_audit", locale, ("audit_status", &"✅")),
+                false => render!(f, "toggle_audit", locale, ("audit_status", &"❎")),
+            },
+            EmptyAuditLog => render!(f, "empty_audit_log", locale),
+            ConfigCheckPassed => render!(f, "config_check_passed", locale),
+            SettingsTitle => render!(f, "settings_title", locale),
+            SettingsAutoLeave => render!(f, "settings_auto_leave", locale),
+            SettingsVolume => render!(f, "settings_volume", locale),
+            SettingsRepeat => render!(f, "settings_repeat", locale),
+            SettingsLocale => render!(f, "settings_locale", locale),
+            SettingsDjRole => render!(f, "settings_dj_role", locale),
+            SettingsQueueLimit => render!(f, "settings_queue_limit", locale),
+            SettingsNotSet => render!(f, "settings_not_set", locale),
+            SettingsUnlimited => render!(f, "settings_unlimited", locale),
+            DjOnly => render!(f, "dj_only", locale),
+            QueueFull { limit } => render!(f, "queue_full", locale, ("limit", limit)),
+            SettingsImportInvalid { reason } => {
+                render!(f, "settings_import_invalid", locale, ("reason", reason))
+            }
+            SettingsImportConfirm => render!(f, "settings_import_confirm", locale),
+            SettingsImported => render!(f, "settings_imported", locale),
+            SettingsImportCancelled => render!(f, "settings_import_cancelled", locale),
+            PremiumRequired(feature) => {
+                render!(f, "premium_required", locale, ("feature", feature))
+            }
+            PrefsTitle => render!(f, "prefs_title", locale),
+            PrefsNotifyTrackStart => render!(f, "prefs_notify_track_start", locale),
+            PrefsSearchSource => render!(f, "prefs_search_source", locale),
+            PrefsLocale => render!(f, "prefs_locale", locale),
+            InvalidLocale { locale: invalid } => {
+                render!(f, "invalid_locale", locale, ("locale", invalid))
+            }
+            TrackStartNotify { title, channel_id } => render!(
+                f,
+                "track_start_notify",
+                locale,
+                ("title", title),
+                ("voice_channel", &channel_id.mention())
+            ),
+            BookmarkDm {
+                title,
+                url,
+                position,
+            } => render!(
+                f,
+                "bookmark_dm",
+                locale,
+                ("title", title),
+                ("url", url),
+                ("position", position)
+            ),
+            BookmarkSent => render!(f, "bookmark_sent", locale),
+            BookmarkDmFailed => render!(f, "bookmark_dm_failed", locale),
+            GreetingAdded => render!(f, "greeting_added", locale),
+            GreetingRemoved => render!(f, "greeting_removed", locale),
+            GreetingTargetRequired => render!(f, "greeting_target_required", locale),
+            GreetingLimitReached { limit } => {
+                render!(f, "greeting_limit_reached", locale, ("limit", limit))
+            }
+            InvalidGreetingIndex { length } => {
+                render!(
+                    f,
+                    "invalid_greeting_index",
+                    locale,
+                    ("greetings_length", length)
+                )
+            }
+            EmptyGreetings => render!(f, "empty_greetings", locale),
+            QueueBulkInvalidFile { size } => {
+                render!(f, "queue_bulk_invalid_file", locale, ("size", size))
+            }
+            QueueBulkProgress { done, total, job } => render!(
+                f,
+                "queue_bulk_progress",
+                locale,
+                ("done", done),
+                ("total", total),
+                ("job", job)
+            ),
+            QueueBulkDone { queued, failed } => render!(
+                f,
+                "queue_bulk_done",
+                locale,
+                ("queued", queued),
+                ("failed", failed)
+            ),
+            Live => render!(f, "live", locale),
+            SetExplicitFilter {
+                enabled,
+                bypass_role,
+            } => {
+                let status = if *enabled { "✅" } else { "❎" };
+                let bypass_role = bypass_role
+                    .map(|role_id| role_id.mention().to_string())
+                    .unwrap_or_default();
+                render!(
+                    f,
+                    "toggle_explicit_filter",
+                    locale,
+                    ("explicit_filter_status", &status),
+                    ("bypass_role", &bypass_role)
+                )
+            }
+            ExplicitRejected => render!(f, "explicit_rejected", locale),
+            ExplicitFiltered { count } => {
+                render!(f, "explicit_filtered", locale, ("count", count))
+            }
+            SetFilter { name } => render!(f, "set_filter", locale, ("filter", name)),
+            FilterOff => render!(f, "filter_off", locale),
+            SettingsTrimSilence => render!(f, "settings_trim_silence", locale),
+            SettingsReactionControls => render!(f, "settings_reaction_controls", locale),
+            RewindSuccess => render!(f, "rewind_success", locale),
+            Replay { title } => render!(f, "replay", locale, ("title", title)),
+            InvalidJumpIndex { length } => {
+                render!(f, "invalid_jump_index", locale, ("playlist_length", length))
+            }
+            SetAutoplay(autoplay) => match autoplay {
+                true => render!(f, "toggle_autoplay", locale, ("autoplay_status", &"✅")),
+                false => render!(f, "toggle_autoplay", locale, ("autoplay_status", &"❎")),
+            },
+            SavedPlaylistSaved { name, count } => render!(
+                f,
+                "saved_playlist_saved",
+                locale,
+                ("name", name),
+                ("count", count)
+            ),
+            SavedPlaylistShuffled { name } => {
+                render!(f, "saved_playlist_shuffled", locale, ("name", name))
+            }
+            SavedPlaylistDeleted { name } => {
+                render!(f, "saved_playlist_deleted", locale, ("name", name))
+            }
+            SavedPlaylistNotFound { name } => {
+                render!(f, "saved_playlist_not_found", locale, ("name", name))
+            }
+            SavedPlaylistExists { name } => {
+                render!(f, "saved_playlist_exists", locale, ("name", name))
+            }
+            SavedPlaylistLimit { limit } => {
+                render!(f, "saved_playlist_limit", locale, ("limit", limit))
+            }
+            InvalidName { max } => {
+                render!(f, "invalid_name", locale, ("max", max))
+            }
+            EmptySavedPlaylists => render!(f, "empty_saved_playlists", locale),
+            SavedPlaylistAdded { name, count } => render!(
+                f,
+                "saved_playlist_added",
+                locale,
+                ("name", name),
+                ("count", count)
+            ),
+            SavedPlaylistNoPermission { name } => {
+                render!(f, "saved_playlist_no_permission", locale, ("name", name))
+            }
+            SavedPlaylistEditor { name, user, added } => match added {
+                true => render!(
+                    f,
+                    "saved_playlist_editor_added",
+                    locale,
+                    ("name", name),
+                    ("user", &user.mention())
+                ),
+                false => render!(
+                    f,
+                    "saved_playlist_editor_removed",
+                    locale,
+                    ("name", name),
+                    ("user", &user.mention())
+                ),
+            },
+            SavedPlaylistPublic { name, public } => match public {
+                true => render!(
+                    f,
+                    "toggle_saved_playlist_public",
+                    locale,
+                    ("name", name),
+                    ("public_status", &"✅")
+                ),
+                false => render!(
+                    f,
+                    "toggle_saved_playlist_public",
+                    locale,
+                    ("name", name),
+                    ("public_status", &"❎")
+                ),
+            },
+            SavedPlaylistShared { name, shared } => match shared {
+                true => render!(f, "saved_playlist_shared", locale, ("name", name)),
+                false => render!(f, "saved_playlist_unshared", locale, ("name", name)),
+            },
+            QueueSnapshotSaved { name, count } => render!(
+                f,
+                "queue_snapshot_saved",
+                locale,
+                ("name", name),
+                ("count", count)
+            ),
+            QueueSnapshotRestored { name } => {
+                render!(f, "queue_snapshot_restored", locale, ("name", name))
+            }
+            QueueSnapshotNotFound { name } => {
+                render!(f, "queue_snapshot_not_found", locale, ("name", name))
+            }
+            QueueSnapshotLimit { limit } => {
+                render!(f, "queue_snapshot_limit", locale, ("limit", limit))
+            }
+            NoUrlsInMessage => render!(f, "no_urls_in_message", locale),
+            BusyInVoiceChannel {
+                bot,
+                to,
+                votes,
+                required,
+            } => render!(
+                f,
+                "busy_in_voice_channel",
+                locale,
+                ("bot_voice_channel", &bot.mention()),
+                ("voice_channel", &to.mention()),
+                ("votes", votes),
+                ("required", required)
+            ),
+            VoteMoveButton => render!(f, "vote_move_button", locale),
+            VoteNotListener { bot } => render!(
+                f,
+                "vote_not_listener",
+                locale,
+                ("bot_voice_channel", &bot.mention())
+            ),
+            MovedVoiceChannel(to) => {
+                render!(
+                    f,
+                    "moved_voice_channel",
+                    locale,
+                    ("voice_channel", &to.mention())
+                )
+            }
+            VoteMoveExpired { to } => {
+                render!(
+                    f,
+                    "vote_move_expired",
+                    locale,
+                    ("voice_channel", &to.mention())
+                )
+            }
+            AlreadyInVoiceChannel(channel) => render!(
+                f,
+                "already_in_voice_channel",
+                locale,
+                ("voice_channel", &channel.mention())
+            ),
+            TrackStuck { title } => render!(f, "track_stuck", locale, ("title", title)),
+            AutoleaveTimeouts { idle, alone } => render!(
+                f,
+                "autoleave_timeouts",
+                locale,
+                ("idle_minutes", idle),
+                ("alone_minutes", alone)
+            ),
+            SetMessageCleanup(policy) => render!(
+                f,
+                "set_message_cleanup",
+                locale,
+                ("cleanup", &policy.name())
+            ),
+            TrackFinished { title } => render!(f, "track_finished", locale, ("title", title)),
+            Welcome => render!(f, "welcome", locale),
+            SetAnnouncements { enabled, channel } => {
+                let status = if *enabled { "✅" } else { "❎" };
+                let channel = channel
+                    .map(|channel_id| channel_id.mention().to_string())
+                    .unwrap_or_default();
+                render!(
+                    f,
+                    "set_announcements",
+                    locale,
+                    ("announcements_status", &status),
+                    ("announcement_channel", &channel)
+                )
+            }
+            AnnounceDone { sent, failed } => render!(
+                f,
+                "announce_done",
+                locale,
+                ("sent", sent),
+                ("failed", failed)
+            ),
+            WhatsNew { version, url } => {
+                render!(f, "whats_new", locale, ("version", version), ("url", url))
+            }
+            SettingsAnnouncementChannel => render!(f, "settings_announcement_channel", locale),
+            SetupStep {
+                step,
+                total,
+                setting,
+            } => render!(
+                f,
+                "setup_step",
+                locale,
+                ("step", step),
+                ("total", total),
+                ("setting", setting)
+            ),
+            SetupSkip => render!(f, "setup_skip", locale),
+            SetupDone => render!(f, "setup_done", locale),
+            SetupTimeout => render!(f, "setup_timeout", locale),
+            SetRequestChannel(channel_id) => render!(
+                f,
+                "set_request_channel",
+                locale,
+                ("request_channel", &channel_id.mention())
+            ),
+            RequestChannelOff => render!(f, "request_channel_off", locale),
+            RequestChannelsDisabled => render!(f, "request_channels_disabled", locale),
+            PlayerIdle => render!(f, "player_idle", locale),
+            PlayerUpNext => render!(f, "player_up_next", locale),
+            PlayerMore { count } => render!(f, "player_more", locale, ("count", count)),
+            SetTrackGain { title, gain } => render!(
+                f,
+                "set_track_gain",
+                locale,
+                ("title", title),
+                ("gain", &format!("{:+.1}", gain))
+            ),
+            NeverAgainAdded { title } => render!(f, "never_again_added", locale, ("title", title)),
+            NeverAgainRemoved => render!(f, "never_again_removed", locale),
+            NeverAgainNotFound => render!(f, "never_again_not_found", locale),
+            NeverAgainRejected => render!(f, "never_again_rejected", locale),
+            PartyCountdown { seconds } => {
+                render!(f, "party_countdown", locale, ("seconds", seconds))
+            }
+            PartyStarted { title } => render!(f, "party_started", locale, ("title", title)),
+            PartySynced { title } => render!(f, "party_synced", locale, ("title", title)),
+            SetDucking(ducking) => match ducking {
+                true => render!(f, "toggle_ducking", locale, ("ducking_status", &"✅")),
+                false => render!(f, "toggle_ducking", locale, ("ducking_status", &"❎")),
+            },
+            VoiceStatsDisabled => render!(f, "voice_stats_disabled", locale),
+            NoVoiceStats => render!(f, "no_voice_stats", locale),
+            VoiceStats { session, stats } => render!(
+                f,
+                "voice_stats",
+                locale,
+                ("session", session),
+                ("stats", stats)
+            ),
+            ClipReady { title, from, to } => render!(
+                f,
+                "clip_ready",
+                locale,
+                ("title", title),
+                ("from", from),
+                ("to", to)
+            ),
+            ClipFailed => render!(f, "clip_failed", locale),
+            InvalidPlaylistRange { items } => {
+                render!(f, "invalid_playlist_range", locale, ("items", items))
+            }
+            Mixed(order) => match order {
+                MixOrder::Interleave => render!(f, "mixed_interleave", locale),
+                MixOrder::Alphabetical => render!(f, "mixed_alphabetical", locale),
+            },
+            VoiceAnnouncement { title, requester } => match requester {
+                Some(requester) => render!(
+                    f,
+                    "voice_announcement",
+                    locale,
+                    ("title", title),
+                    ("requester", requester)
+                ),
+                None => render!(f, "voice_announcement_autoplay", locale, ("title", title)),
+            },
+            VoiceAnnouncementsDisabled => render!(f, "voice_announcements_disabled", locale),
+            SetVoiceAnnouncements(enabled) => match enabled {
+                true => render!(
+                    f,
+                    "toggle_voice_announcements",
+                    locale,
+                    ("voice_announcements_status", &"✅")
+                ),
+                false => render!(
+                    f,
+                    "toggle_voice_announcements",
+                    locale,
+                    ("voice_announcements_status", &"❎")
+                ),
+            },
+            ListeningLogEntry {
+                title,
+                url,
+                requester,
+            } => match requester {
+                Some(requester) => render!(
+                    f,
+                    "listening_log_entry",
+                    locale,
+                    ("title", title),
+                    ("url", url),
+                    ("requester", &requester.mention())
+                ),
+                None => render!(
+                    f,
+                    "listening_log_entry_autoplay",
+                    locale,
+                    ("title", title),
+                    ("url", url)
+                ),
+            },
+            ListeningLogThread { date } => {
+                render!(f, "listening_log_thread", locale, ("date", date))
+            }
+            SetListeningLog { channel, period } => render!(
+                f,
+                "set_listening_log",
+                locale,
+                ("channel", &channel.mention()),
+                ("period", &period.name())
+            ),
+            ListeningLogOff => render!(f, "listening_log_off", locale),
+            InvalidEventStart { start } => {
+                render!(f, "invalid_event_start", locale, ("start", start))
+            }
+            EventCreateFailed => render!(f, "event_create_failed", locale),
+            EventCreated {
+                name,
+                playlist,
+                start,
+            } => render!(
+                f,
+                "event_created",
+                locale,
+                ("event_name", name),
+                ("name", playlist),
+                ("event_start", &format!("<t:{}:F>", start))
+            ),
+            TempVoiceChannelName { host } => {
+                render!(f, "temp_voice_channel_name", locale, ("host", host))
+            }
+            TempVoiceDisabled => render!(f, "temp_voice_disabled", locale),
+            TempVoiceFailed => render!(f, "temp_voice_failed", locale),
+            SetTempVoice(category) => render!(
+                f,
+                "set_temp_voice",
+                locale,
+                ("temp_voice_category", &category.mention())
+            ),
+            TempVoiceOff => render!(f, "temp_voice_off", locale),
+            JobWaiting { job } => render!(f, "job_waiting", locale, ("job", job)),
+            QueueBulkCanceled { skipped } => {
+                render!(f, "queue_bulk_canceled", locale, ("skipped", skipped))
+            }
+            NoJobs => render!(f, "no_jobs", locale),
+            JobEntry {
+                job,
+                running,
+                done,
+                total,
+                user,
+            } => render!(
+                f,
+                "job_entry",
+                locale,
+                ("job", job),
+                ("job_status", &if *running { "▶️" } else { "⏸️" }),
+                ("done", done),
+                ("total", total),
+                ("user", &user.mention())
+            ),
+            JobNotFound { job } => render!(f, "job_not_found", locale, ("job", job)),
+            JobNoPermission => render!(f, "job_no_permission", locale),
+            JobCanceled { job } => render!(f, "job_canceled", locale, ("job", job)),
+            Resolving { query } => render!(f, "resolving", locale, ("url", query)),
+            CancelButton => render!(f, "cancel_button", locale),
+            ResolvingCanceled => render!(f, "resolving_canceled", locale),
+            QueueBatch { count, titles } => render!(
+                f,
+                "queue_batch",
+                locale,
+                ("count", count),
+                ("titles", titles)
+            ),
+            QueueBatchMore { count } => render!(f, "queue_batch_more", locale, ("count", count)),
+            GuildOnly => render!(f, "guild_only", locale),
+            NoThreadCommands(channel) => render!(
+                f,
+                "no_thread_commands",
+                locale,
+                ("thread_parent", &channel.mention())
+            ),
+            ScriptAdded => render!(f, "script_added", locale),
+            ScriptRemoved => render!(f, "script_removed", locale),
+            InvalidScript { error } => render!(f, "invalid_script", locale, ("error", error)),
+            ScriptLimitReached { limit } => {
+                render!(f, "script_limit_reached", locale, ("limit", limit))
+            }
+            InvalidScriptIndex { length } => {
+                render!(
+                    f,
+                    "invalid_script_index",
+                    locale,
+                    ("scripts_length", length)
+                )
+            }
+            EmptyScripts => render!(f, "empty_scripts", locale),
+            CommandRenamed { old, new } => {
+                render!(f, "command_renamed", locale, ("old", old), ("new", new))
+            }
+            QueueDurationFull { limit, left } => render!(
+                f,
+                "queue_duration_full",
+                locale,
+                ("limit", &format_duration(*limit)),
+                ("left", &format_duration(*left)),
+            ),
+            UserDurationFull { limit, left } => render!(
+                f,
+                "user_duration_full",
+                locale,
+                ("limit", &format_duration(*limit)),
+                ("left", &format_duration(*left)),
+            ),
+            QueueLengthLimits { total, member } => {
+                let limit = |minutes: &Option<u64>| match minutes {
+                    Some(minutes) => format_duration(Duration::from_secs(minutes * 60)),
+                    None => TurtoMessage {
+                        locale,
+                        kind: SettingsUnlimited,
+                    }
+                    .to_string(),
+                };
+                render!(
+                    f,
+                    "queue_length_limits",
+                    locale,
+                    ("total", &limit(total)),
+                    ("member", &limit(member)),
+                )
+            }
+            QueueLocked => render!(f, "queue_locked", locale),
+            LockQueue { minutes: None } => render!(f, "lock_queue", locale),
+            LockQueue {
+                minutes: Some(minutes),
+            } => render!(f, "lock_queue_timed", locale, ("minutes", minutes)),
+            UnlockQueue => render!(f, "unlock_queue", locale),
+            TooManyPendingRequests { limit } => {
+                render!(f, "too_many_pending_requests", locale, ("limit", limit))
+            }
+            RequestPending { title } => render!(f, "request_pending", locale, ("title", title)),
+            ApprovalRequest { requester, title } => render!(
+                f,
+                "approval_request",
+                locale,
+                ("requester", &requester.mention()),
+                ("title", title),
+            ),
+            RequestApproved {
+                requester,
+                title,
+                dj,
+            } => render!(
+                f,
+                "request_approved",
+                locale,
+                ("requester", &requester.mention()),
+                ("title", title),
+                ("dj", &dj.mention()),
+            ),
+            RequestRejected {
+                requester,
+                title,
+                dj,
+            } => render!(
+                f,
+                "request_rejected",
+                locale,
+                ("requester", &requester.mention()),
+                ("title", title),
+                ("dj", &dj.mention()),
+            ),
+            ApprovalRequired => render!(f, "approval_required", locale),
+            SetApproval(approval) => match approval {
+                true => render!(f, "toggle_approval", locale, ("approval_status", &"✅")),
+                false => render!(f, "toggle_approval", locale, ("approval_status", &"❎")),
+            },
+            OverlayDisabled => render!(f, "overlay_disabled", locale),
+            OverlayLink { url } => render!(f, "overlay_link", locale, ("url", url)),
+            OverlayOff => render!(f, "overlay_off", locale),
         }
     }
 }
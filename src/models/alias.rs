@@ -2,4 +2,5 @@ use super::data::Data;
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Context<'a> = poise::Context<'a, Data, Error>;
-pub type Command = poise::Command<Data, Error>;
\ No newline at end of file
+pub type ApplicationContext<'a> = poise::ApplicationContext<'a, Data, Error>;
+pub type Command = poise::Command<Data, Error>;
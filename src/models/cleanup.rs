@@ -0,0 +1,16 @@
+use poise::ChoiceParameter;
+use serde::{Deserialize, Serialize};
+
+/// What happens to the replies about a track after it ends
+#[derive(Debug, Default, ChoiceParameter, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageCleanup {
+    #[default]
+    #[name = "keep"]
+    Keep,
+    /// Edit the replies to say the track is finished
+    #[name = "finished"]
+    Finished,
+    #[name = "delete"]
+    Delete,
+}
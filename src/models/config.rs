@@ -1,5 +1,11 @@
+use super::premium::Feature;
 use serde::{Deserialize, Serialize};
-use serenity::model::prelude::UserId;
+use serenity::model::prelude::{ChannelId, GuildId, RoleId, UserId};
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TurtoConfig {
@@ -9,14 +15,258 @@ pub struct TurtoConfig {
     pub command_delay: u64,
     pub owner: Option<UserId>,
     pub auto_save: bool,
-    pub auto_save_interval: u64
+    pub auto_save_interval: u64,
+    #[serde(default)]
+    pub error_report_channel: Option<ChannelId>,
+    #[serde(default)]
+    pub error_report_dsn: Option<String>,
+    #[serde(default)]
+    pub log_format: LogFormat,
+    #[serde(default = "default_log_dir")]
+    pub log_dir: PathBuf,
+    #[serde(default)]
+    pub log_rotation: LogRotation,
+    #[serde(default)]
+    pub log_max_size: u64,
+    #[serde(default)]
+    pub log_retention_days: u64,
+    #[serde(default = "default_slow_command_threshold")]
+    pub slow_command_threshold: u64,
+    #[serde(default)]
+    pub http_server: bool,
+    #[serde(default = "default_http_address")]
+    pub http_address: IpAddr,
+    #[serde(default = "default_http_port")]
+    pub http_port: u16,
+    /// The public address of the HTTP server in the links to the overlays, see `/overlay`
+    #[serde(default)]
+    pub overlay_url: Option<String>,
+    #[serde(default)]
+    pub vault_address: Option<String>,
+    #[serde(default)]
+    pub vault_path: Option<String>,
+    #[serde(default)]
+    pub role_priority: HashMap<RoleId, u32>,
+    #[serde(default)]
+    pub premium_features: HashSet<Feature>,
+    #[serde(default)]
+    pub premium_guilds: HashSet<GuildId>,
+    #[serde(default)]
+    pub entitlement_url: Option<String>,
+    #[serde(default = "default_greeting_cooldown")]
+    pub greeting_cooldown: u64,
+    #[serde(default = "default_greeting_max_duration")]
+    pub greeting_max_duration: u64,
+    #[serde(default = "default_jump_requeue")]
+    pub jump_requeue: bool,
+    #[serde(default = "default_autoplay_track_window")]
+    pub autoplay_track_window: usize,
+    #[serde(default = "default_autoplay_uploader_window")]
+    pub autoplay_uploader_window: usize,
+    #[serde(default = "default_watchdog_timeout")]
+    pub watchdog_timeout: u64,
+    #[serde(default = "default_fade_duration")]
+    pub fade_duration: u64,
+    #[serde(default = "default_ephemeral_replies")]
+    pub ephemeral_replies: bool,
+    #[serde(default = "default_welcome_message")]
+    pub welcome_message: bool,
+    #[serde(default = "default_guild_data_retention")]
+    pub guild_data_retention: u64,
+    /// The commands are only registered in this guild instead of globally, so they're updated
+    /// right away while testing
+    #[serde(default)]
+    pub test_guild: Option<GuildId>,
+    /// The statuses shown in turns while nothing is playing, see `utils::presence`
+    #[serde(default)]
+    pub activities: Vec<String>,
+    #[serde(default = "default_activity_interval")]
+    pub activity_interval: u64,
+    #[serde(default)]
+    pub announce_updates: bool,
+    /// Whether the guilds can set a request channel, it needs the message content intent
+    #[serde(default)]
+    pub request_channels: bool,
+    /// Whether how long everyone speaks in the voice channels with turto is recorded for
+    /// `/voicestats`, nothing they say is kept
+    #[serde(default)]
+    pub voice_stats: bool,
+    /// The program and its arguments to speak the announcements in voice with, `{text}` in the
+    /// arguments is replaced with what to say, and the audio is read from its stdout
+    #[serde(default)]
+    pub tts_command: Vec<String>,
+    /// How many long-running jobs, like `/queue bulk`, each guild runs at a time, the others wait
+    #[serde(default = "default_guild_jobs")]
+    pub guild_jobs: usize,
+    /// Whether the commands about the playback can be used in threads
+    #[serde(default = "default_thread_commands")]
+    pub thread_commands: bool,
+    /// The path of the unix socket for the operators to manage turto with, see `operator`
+    #[serde(default)]
+    pub operator_socket: Option<PathBuf>,
+    /// The etcd the instances with the same token share the guilds through, see `utils::cluster`
+    #[serde(default)]
+    pub etcd_address: Option<String>,
+    /// The name of this instance in the cluster, the host name is used if it's not set
+    #[serde(default)]
+    pub instance_id: Option<String>,
+    #[serde(default = "default_cluster_lease")]
+    pub cluster_lease: u64,
+    /// The Redis the data of the guilds is kept in between the saves, see `utils::redis`
+    #[serde(default)]
+    pub redis_address: Option<String>,
+    #[serde(default = "default_redis_sync_interval")]
+    pub redis_sync_interval: u64,
+    /// The rules of the operator, they're run on the events of every guild, see `utils::script`
+    #[serde(default)]
+    pub script_file: Option<PathBuf>,
+    /// The raid protection is on if it's set, see `utils::raid`
+    #[serde(default)]
+    pub anti_raid: Option<AntiRaid>,
+    /// The renamed commands whose old names still work for a while, see `utils::deprecation`
+    #[serde(default)]
+    pub deprecated_commands: Vec<DeprecatedCommand>,
+    /// The tables of the plugins by their names, see `plugin::TurtoPlugin`
+    #[serde(default)]
+    pub plugins: toml::Table,
+}
+
+/// When the tracks queued look like a raid, the playlist is locked for `lock_minutes`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AntiRaid {
+    /// The seconds the enqueues are counted in
+    pub window: u64,
+    pub new_account_days: u64,
+    /// The enqueues by the new accounts in the window to lock the playlist
+    pub new_account_enqueues: usize,
+    /// The enqueues of the same url in the window to lock the playlist
+    pub repeated_enqueues: usize,
+    pub long_track_hours: u64,
+    pub lock_minutes: u64,
+}
+
+impl Default for AntiRaid {
+    fn default() -> Self {
+        AntiRaid {
+            window: 60,
+            new_account_days: 7,
+            new_account_enqueues: 5,
+            repeated_enqueues: 3,
+            long_track_hours: 10,
+            lock_minutes: 15,
+        }
+    }
+}
+
+/// The old name of a renamed command, it's redirected to the new name up to the version `until`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprecatedCommand {
+    pub old: String,
+    pub new: String,
+    #[serde(default)]
+    pub until: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+// the log files used to be written to the working directory
+fn default_log_dir() -> PathBuf {
+    PathBuf::from(".")
+}
+
+fn default_slow_command_threshold() -> u64 {
+    3000
+}
+
+fn default_http_address() -> IpAddr {
+    IpAddr::V4(Ipv4Addr::LOCALHOST)
+}
+
+fn default_http_port() -> u16 {
+    8080
+}
+
+fn default_greeting_cooldown() -> u64 {
+    60
+}
+
+fn default_greeting_max_duration() -> u64 {
+    10
+}
+
+fn default_jump_requeue() -> bool {
+    true
+}
+
+fn default_autoplay_track_window() -> usize {
+    20
+}
+
+fn default_autoplay_uploader_window() -> usize {
+    3
+}
+
+fn default_watchdog_timeout() -> u64 {
+    30
+}
+
+fn default_fade_duration() -> u64 {
+    300
+}
+
+fn default_ephemeral_replies() -> bool {
+    true
+}
+
+fn default_welcome_message() -> bool {
+    true
+}
+
+fn default_guild_data_retention() -> u64 {
+    30
+}
+
+fn default_activity_interval() -> u64 {
+    60
+}
+
+fn default_guild_jobs() -> usize {
+    1
+}
+
+fn default_thread_commands() -> bool {
+    true
+}
+
+fn default_cluster_lease() -> u64 {
+    10
+}
+
+fn default_redis_sync_interval() -> u64 {
+    5
 }
 
 impl TurtoConfig {
     pub fn is_owner(&self, user: &UserId) -> bool {
         if let Some(owner) = &self.owner {
-            return owner == user
+            return owner == user;
         }
         false
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,15 @@
-use super::{guild::data::GuildData, playing::Playing};
-use dashmap::DashMap;
-use serenity::all::GuildId;
-use tokio::sync::RwLock;
+use super::{guild::data::GuildData, playing::Playing, user_prefs::UserPrefs};
+use dashmap::{DashMap, DashSet};
+use serenity::all::{GuildId, MessageId, UserId};
 use std::{collections::HashMap, sync::Arc};
+use tokio::sync::RwLock;
 
 #[derive(Default)]
 pub struct Data {
     pub guilds: Arc<DashMap<GuildId, GuildData>>,
     pub playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+    pub blacklist: Arc<DashSet<GuildId>>,
+    pub users: Arc<DashMap<UserId, UserPrefs>>,
+    /// The latest now playing message of each guild with the reaction controls
+    pub control_messages: Arc<DashMap<GuildId, MessageId>>,
 }
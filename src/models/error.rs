@@ -0,0 +1,137 @@
+use crate::messages::{
+    TurtoMessage,
+    TurtoMessageKind::{
+        AdministratorOnly, BotNotInVoiceChannel, DifferentVoiceChannel, ExplicitRejected,
+        InvalidPlaylistRange, InvalidUrl, NeverAgainRejected, NotPlaying, OwnerOnly,
+        QueueDurationFull, QueueFull, QueueLocked, TooManyPendingRequests, UserDurationFull,
+        UserNotInVoiceChannel,
+    },
+};
+use serenity::all::ChannelId;
+use std::{fmt::Display, time::Duration};
+
+/// The expected ways a command fails, they're replied with the templates by `on_error` and logged
+/// instead of being reported like the other errors
+#[derive(Debug)]
+pub enum TurtoError {
+    /// The command is invoked outside of a guild, or the guild isn't cached
+    NotInGuild,
+    UserNotInVoiceChannel,
+    BotNotInVoiceChannel,
+    /// The author is in a voice channel, but not the one of the bot
+    DifferentVoiceChannel(ChannelId),
+    NotPlaying,
+    /// yt-dlp can't extract the query
+    ExtractionFailed(String),
+    ExplicitRejected,
+    /// The track is in the never again list of the guild
+    NeverAgain,
+    QueueFull {
+        limit: usize,
+    },
+    /// The tracks would be longer than `queue_duration_limit` of the guild, `left` is the room left
+    QueueDurationFull {
+        limit: Duration,
+        left: Duration,
+    },
+    /// The tracks queued by the author would be longer than `user_duration_limit` of the guild
+    UserDurationFull {
+        limit: Duration,
+        left: Duration,
+    },
+    /// The playlist is locked by `/lockqueue` or the raid protection
+    QueueLocked,
+    TooManyPendingRequests {
+        limit: usize,
+    },
+    /// The items of the playlist to queue can't be parsed
+    InvalidPlaylistRange(String),
+    AdministratorOnly,
+    OwnerOnly,
+    /// The voice manager of songbird isn't registered to the client
+    VoiceUnavailable,
+}
+
+impl TurtoError {
+    /// The message replied to the author, `None` if there is no template for it, then it's reported
+    /// like the other errors
+    pub fn message<'a>(&'a self, locale: Option<&'a str>) -> Option<TurtoMessage<'a>> {
+        let kind = match self {
+            Self::NotInGuild | Self::VoiceUnavailable => return None,
+            Self::UserNotInVoiceChannel => UserNotInVoiceChannel,
+            Self::BotNotInVoiceChannel => BotNotInVoiceChannel,
+            Self::DifferentVoiceChannel(bot) => DifferentVoiceChannel { bot: *bot },
+            Self::NotPlaying => NotPlaying,
+            Self::ExtractionFailed(query) => InvalidUrl(Some(query)),
+            Self::ExplicitRejected => ExplicitRejected,
+            Self::NeverAgain => NeverAgainRejected,
+            Self::QueueFull { limit } => QueueFull { limit: *limit },
+            Self::QueueDurationFull { limit, left } => QueueDurationFull {
+                limit: *limit,
+                left: *left,
+            },
+            Self::UserDurationFull { limit, left } => UserDurationFull {
+                limit: *limit,
+                left: *left,
+            },
+            Self::QueueLocked => QueueLocked,
+            Self::TooManyPendingRequests { limit } => TooManyPendingRequests { limit: *limit },
+            Self::InvalidPlaylistRange(items) => InvalidPlaylistRange { items },
+            Self::AdministratorOnly => AdministratorOnly,
+            Self::OwnerOnly => OwnerOnly,
+        };
+        Some(TurtoMessage { locale, kind })
+    }
+}
+
+impl Display for TurtoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotInGuild => write!(f, "not in a guild"),
+            Self::UserNotInVoiceChannel => write!(f, "the author is not in a voice channel"),
+            Self::BotNotInVoiceChannel => write!(f, "not in a voice channel"),
+            Self::DifferentVoiceChannel(bot) => write!(f, "the author is not in {}", bot),
+            Self::NotPlaying => write!(f, "not playing"),
+            Self::ExtractionFailed(query) => write!(f, "failed to extract {}", query),
+            Self::ExplicitRejected => write!(f, "the track is explicit"),
+            Self::NeverAgain => write!(f, "the track is never played again"),
+            Self::QueueFull { limit } => write!(f, "the playlist is full with {} items", limit),
+            Self::QueueDurationFull { limit, .. } => {
+                let secs = limit.as_secs();
+                write!(f, "the playlist is full with {}s of tracks", secs)
+            }
+            Self::UserDurationFull { limit, .. } => {
+                write!(f, "the author has queued {}s of tracks", limit.as_secs())
+            }
+            Self::QueueLocked => write!(f, "the playlist is locked"),
+            Self::TooManyPendingRequests { limit } => {
+                write!(f, "{} requests are waiting for approval", limit)
+            }
+            Self::InvalidPlaylistRange(items) => write!(f, "invalid playlist items {}", items),
+            Self::AdministratorOnly => write!(f, "the author is not an administrator"),
+            Self::OwnerOnly => write!(f, "the author is not the owner"),
+            Self::VoiceUnavailable => write!(f, "the voice manager is not registered"),
+        }
+    }
+}
+
+impl std::error::Error for TurtoError {}
+
+#[cfg(test)]
+mod tests {
+    use super::TurtoError;
+    use crate::{models::alias::Error, test::setup_templates};
+
+    #[test]
+    fn test_error_message() {
+        setup_templates();
+        let error: Error = TurtoError::QueueFull { limit: 3 }.into();
+        let error = error.downcast_ref::<TurtoError>().unwrap();
+        assert_eq!(
+            error.message(None).unwrap().to_string(),
+            "The playlist is full, it can have at most 3 items."
+        );
+        assert_eq!(error.to_string(), "the playlist is full with 3 items");
+        assert!(TurtoError::NotInGuild.message(None).is_none());
+    }
+}
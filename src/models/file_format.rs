@@ -0,0 +1,9 @@
+use poise::ChoiceParameter;
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum FileFormat {
+    #[name = "toml"]
+    Toml,
+    #[name = "json"]
+    Json,
+}
@@ -0,0 +1,72 @@
+use crate::utils::misc::unix_now;
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::UserId;
+use std::collections::{vec_deque::Iter, VecDeque};
+
+/// The maximum number of entries kept for each guild, older ones are dropped first
+pub const AUDIT_LOG_LIMIT: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    pub user: UserId,
+    pub command: String,
+    pub timestamp: u64, // unix timestamp in seconds
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct AuditLog(VecDeque<AuditEntry>);
+
+impl AuditLog {
+    pub fn record(&mut self, user: UserId, command: impl Into<String>) {
+        let timestamp = unix_now();
+        self.push(AuditEntry {
+            user,
+            command: command.into(),
+            timestamp,
+        });
+    }
+
+    pub fn push(&mut self, entry: AuditEntry) {
+        while self.0.len() >= AUDIT_LOG_LIMIT {
+            self.0.pop_front();
+        }
+        self.0.push_back(entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate from the latest entry to the oldest one
+    pub fn latest(&self) -> std::iter::Rev<Iter<'_, AuditEntry>> {
+        self.0.iter().rev()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuditEntry, AuditLog, AUDIT_LOG_LIMIT};
+    use serenity::model::prelude::UserId;
+
+    #[test]
+    fn test_audit_log_limit() {
+        let mut log = AuditLog::default();
+        for i in 0..(AUDIT_LOG_LIMIT + 10) {
+            log.push(AuditEntry {
+                user: UserId::new(1),
+                command: i.to_string(),
+                timestamp: i as u64,
+            });
+        }
+        assert_eq!(log.len(), AUDIT_LOG_LIMIT);
+        assert_eq!(
+            log.latest().next().unwrap().command,
+            (AUDIT_LOG_LIMIT + 9).to_string()
+        );
+        assert_eq!(log.latest().next_back().unwrap().command, "10");
+    }
+}
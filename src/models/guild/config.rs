@@ -1,7 +1,16 @@
-use crate::models::{autoleave::AutoleaveType, guild::volume::GuildVolume};
+use crate::models::{
+    autoleave::AutoleaveType,
+    cleanup::MessageCleanup,
+    guild::{
+        filter::AudioFilter,
+        greeting::{Greeting, GreetingTarget},
+        listening_log::LogPeriod,
+        volume::GuildVolume,
+    },
+};
 use serde::{Deserialize, Serialize};
-use serenity::model::prelude::UserId;
-use std::collections::HashSet;
+use serenity::model::prelude::{ChannelId, RoleId, UserId};
+use std::{collections::HashSet, time::Duration};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GuildConfig {
@@ -9,6 +18,87 @@ pub struct GuildConfig {
     pub repeat: bool,
     pub volume: GuildVolume,
     pub banned: HashSet<UserId>,
+    #[serde(default)]
+    pub audit: bool,
+    /// Overrides the locale of the users for the replies in the guild
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Only the members with this role can control the playback when it's set
+    #[serde(default)]
+    pub dj_role: Option<RoleId>,
+    #[serde(default)]
+    pub queue_limit: Option<usize>,
+    /// The minutes of the tracks the playlist can have in total
+    #[serde(default)]
+    pub queue_duration_limit: Option<u64>,
+    /// The minutes of the tracks each member can have waiting in the playlist
+    #[serde(default)]
+    pub user_duration_limit: Option<u64>,
+    #[serde(default)]
+    pub greetings: Vec<Greeting>,
+    /// Reject the age-restricted items and the ones with explicit titles when they're queued
+    #[serde(default)]
+    pub explicit_filter: bool,
+    /// The members with this role can queue explicit items even if the filter is on
+    #[serde(default)]
+    pub explicit_bypass_role: Option<RoleId>,
+    /// The audio filter applied to the tracks from the next one
+    #[serde(default)]
+    pub filter: Option<AudioFilter>,
+    /// Trim the silence at the start and the end of the tracks
+    #[serde(default)]
+    pub trim_silence: bool,
+    /// Play the recommendations of the last played track when the playlist runs out
+    #[serde(default)]
+    pub autoplay: bool,
+    /// Add the reactions to control the playback to the now playing message
+    #[serde(default)]
+    pub reaction_controls: bool,
+    /// The minutes to wait before leaving when nothing is playing, turto leaves right away if it's 0
+    #[serde(default)]
+    pub idle_timeout: u64,
+    /// The minutes to wait before leaving when nobody else is in the voice channel
+    #[serde(default)]
+    pub alone_timeout: u64,
+    /// What happens to the replies about a track after it ends
+    #[serde(default)]
+    pub message_cleanup: MessageCleanup,
+    /// Where the announcements are posted, the system channel of the guild is used if it's unset
+    #[serde(default)]
+    pub announcement_channel: Option<ChannelId>,
+    /// Whether the announcements and the notices about the new versions are posted to the guild
+    #[serde(default = "default_announcements")]
+    pub announcements: bool,
+    /// Every message posted in this channel is queued as a track request, see `request_channels`
+    /// in the config
+    #[serde(default)]
+    pub request_channel: Option<ChannelId>,
+    /// Lower the volume of the music while the users in the voice channel are speaking
+    #[serde(default)]
+    pub ducking: bool,
+    /// Say which track is starting and who requested it in voice before playing it, see
+    /// `tts_command` in the config
+    #[serde(default)]
+    pub voice_announcements: bool,
+    /// Every played track is posted to a thread of this channel, it can be a forum
+    #[serde(default)]
+    pub listening_log: Option<ChannelId>,
+    /// How often a new thread of the listening log is started
+    #[serde(default)]
+    pub listening_log_period: LogPeriod,
+    /// `/play temp:True` hosts the session in a new voice channel in this category
+    #[serde(default)]
+    pub temp_voice_category: Option<ChannelId>,
+    /// The rules run on the events of the guild, see `utils::script`
+    #[serde(default)]
+    pub scripts: Vec<String>,
+    /// The requests of the members who aren't DJs wait for a DJ to approve them
+    #[serde(default)]
+    pub request_approval: bool,
+}
+
+fn default_announcements() -> bool {
+    true
 }
 
 impl Default for GuildConfig {
@@ -18,6 +108,225 @@ impl Default for GuildConfig {
             repeat: false,
             volume: GuildVolume::default(),
             banned: HashSet::default(),
+            audit: false,
+            locale: None,
+            dj_role: None,
+            queue_limit: None,
+            queue_duration_limit: None,
+            user_duration_limit: None,
+            greetings: Vec::new(),
+            explicit_filter: false,
+            explicit_bypass_role: None,
+            filter: None,
+            trim_silence: false,
+            autoplay: false,
+            reaction_controls: false,
+            idle_timeout: 0,
+            alone_timeout: 0,
+            message_cleanup: MessageCleanup::default(),
+            announcement_channel: None,
+            announcements: true,
+            request_channel: None,
+            ducking: false,
+            voice_announcements: false,
+            listening_log: None,
+            listening_log_period: LogPeriod::default(),
+            temp_voice_category: None,
+            scripts: Vec::new(),
+            request_approval: false,
         }
     }
 }
+
+impl GuildConfig {
+    /// The autoleave in effect, turning it off only keeps turto in the voice channel if the guild
+    /// has `always_on`
+    fn auto_leave(&self, always_on: bool) -> AutoleaveType {
+        match self.auto_leave {
+            AutoleaveType::Off if !always_on => AutoleaveType::On,
+            auto_leave => auto_leave,
+        }
+    }
+
+    /// How long to wait before leaving when nothing is playing, turto stays if it's `None`
+    pub fn leave_when_idle(&self, always_on: bool) -> Option<Duration> {
+        matches!(
+            self.auto_leave(always_on),
+            AutoleaveType::On | AutoleaveType::Silent
+        )
+        .then(|| Duration::from_secs(self.idle_timeout * 60))
+    }
+
+    /// How long to wait before leaving when nobody else is in the voice channel, turto stays if
+    /// it's `None`
+    pub fn leave_when_alone(&self, always_on: bool) -> Option<Duration> {
+        matches!(
+            self.auto_leave(always_on),
+            AutoleaveType::On | AutoleaveType::Empty
+        )
+        .then(|| Duration::from_secs(self.alone_timeout * 60))
+    }
+
+    /// The channel to post the announcements to, `None` if they're turned off
+    pub fn announcement_channel(&self, system_channel: Option<ChannelId>) -> Option<ChannelId> {
+        self.announcements
+            .then_some(self.announcement_channel.or(system_channel))
+            .flatten()
+    }
+
+    /// Take the settings of the imported config which carry over to other guilds. The roles and
+    /// the channels which aren't in this guild are unset, and the bans and the scripts are kept,
+    /// since they're managed by their own commands
+    pub fn import(
+        &mut self,
+        imported: GuildConfig,
+        has_role: impl Fn(RoleId) -> bool,
+        has_channel: impl Fn(ChannelId) -> bool,
+    ) {
+        // no `..`, so a new field can't be imported without deciding whether it should be
+        let GuildConfig {
+            auto_leave,
+            repeat,
+            volume,
+            banned: _,
+            audit,
+            locale,
+            dj_role,
+            queue_limit,
+            queue_duration_limit,
+            user_duration_limit,
+            greetings,
+            explicit_filter,
+            explicit_bypass_role,
+            filter,
+            trim_silence,
+            autoplay,
+            reaction_controls,
+            idle_timeout,
+            alone_timeout,
+            message_cleanup,
+            announcement_channel,
+            announcements,
+            request_channel,
+            ducking,
+            voice_announcements,
+            listening_log,
+            listening_log_period,
+            temp_voice_category,
+            scripts: _,
+            request_approval,
+        } = imported;
+        let role = |role_id: Option<RoleId>| role_id.filter(|role_id| has_role(*role_id));
+        let channel = |channel_id: Option<ChannelId>| {
+            channel_id.filter(|channel_id| has_channel(*channel_id))
+        };
+
+        self.auto_leave = auto_leave;
+        self.repeat = repeat;
+        self.volume = volume;
+        self.audit = audit;
+        self.locale = locale;
+        self.dj_role = role(dj_role);
+        self.queue_limit = queue_limit;
+        self.queue_duration_limit = queue_duration_limit;
+        self.user_duration_limit = user_duration_limit;
+        self.greetings = greetings
+            .into_iter()
+            .filter(|greeting| match greeting.target {
+                GreetingTarget::User(_) => true,
+                GreetingTarget::Role(role_id) => has_role(role_id),
+            })
+            .collect();
+        self.explicit_filter = explicit_filter;
+        self.explicit_bypass_role = role(explicit_bypass_role);
+        self.filter = filter;
+        self.trim_silence = trim_silence;
+        self.autoplay = autoplay;
+        self.reaction_controls = reaction_controls;
+        self.idle_timeout = idle_timeout;
+        self.alone_timeout = alone_timeout;
+        self.message_cleanup = message_cleanup;
+        self.announcement_channel = channel(announcement_channel);
+        self.announcements = announcements;
+        self.request_channel = channel(request_channel);
+        self.ducking = ducking;
+        self.voice_announcements = voice_announcements;
+        self.listening_log = channel(listening_log);
+        self.listening_log_period = listening_log_period;
+        self.temp_voice_category = channel(temp_voice_category);
+        self.request_approval = request_approval;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GuildConfig;
+    use crate::models::autoleave::AutoleaveType;
+    use serenity::all::{ChannelId, RoleId, UserId};
+    use std::time::Duration;
+
+    #[test]
+    fn test_announcement_channel() {
+        let system_channel = Some(ChannelId::new(1));
+        let mut config = GuildConfig::default();
+        assert_eq!(config.announcement_channel(system_channel), system_channel);
+        assert_eq!(config.announcement_channel(None), None);
+
+        config.announcement_channel = Some(ChannelId::new(2));
+        assert_eq!(
+            config.announcement_channel(system_channel),
+            Some(ChannelId::new(2))
+        );
+
+        config.announcements = false;
+        assert_eq!(config.announcement_channel(system_channel), None);
+    }
+
+    #[test]
+    fn test_leave_when_idle() {
+        let config = GuildConfig {
+            auto_leave: AutoleaveType::Off,
+            idle_timeout: 5,
+            ..Default::default()
+        };
+        assert_eq!(config.leave_when_idle(true), None);
+        assert_eq!(config.leave_when_alone(true), None);
+        // turto can't be kept in the voice channel without `always_on`
+        assert_eq!(
+            config.leave_when_idle(false),
+            Some(Duration::from_secs(5 * 60))
+        );
+        assert_eq!(config.leave_when_alone(false), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_import() {
+        let mut imported = GuildConfig {
+            repeat: true,
+            dj_role: Some(RoleId::new(1)),
+            explicit_bypass_role: Some(RoleId::new(2)),
+            request_channel: Some(ChannelId::new(3)),
+            listening_log: Some(ChannelId::new(4)),
+            ..Default::default()
+        };
+        imported.banned.insert(UserId::new(5));
+        imported.scripts.push("on track_start: skip".to_owned());
+
+        let mut config = GuildConfig::default();
+        config.banned.insert(UserId::new(6));
+        config.import(
+            imported,
+            |role_id| role_id == RoleId::new(1),
+            |channel_id| channel_id == ChannelId::new(3),
+        );
+        assert!(config.repeat);
+        assert_eq!(config.dj_role, Some(RoleId::new(1)));
+        assert_eq!(config.explicit_bypass_role, None);
+        assert_eq!(config.request_channel, Some(ChannelId::new(3)));
+        // the channels of the other guild aren't posted to
+        assert_eq!(config.listening_log, None);
+        assert_eq!(config.banned.len(), 1);
+        assert!(config.banned.contains(&UserId::new(6)));
+        assert!(config.scripts.is_empty());
+    }
+}
@@ -1,9 +1,79 @@
-use super::config::GuildConfig;
-use crate::models::playlist::Playlist;
+use super::{
+    audit::AuditLog, config::GuildConfig, history::History, listening_log::LogThread,
+    never_again::NeverAgain, pending_request::PendingRequest, queue_lock::QueueLock,
+    saved_playlist::SavedPlaylist, scheduled_session::ScheduledSession, snapshot::QueueSnapshot,
+    temp_voice::TempVoiceChannel, was_playing::WasPlaying,
+};
+use crate::{models::playlist::Playlist, utils::misc::unix_now};
 use serde::{Deserialize, Serialize};
+use serenity::all::{MessageId, ScheduledEventId};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct GuildData {
     pub config: GuildConfig,
     pub playlist: Playlist,
+    #[serde(default)]
+    pub audit_log: AuditLog,
+    /// The tracks played in the guild, autoplay avoids repeating them
+    #[serde(default)]
+    pub history: History,
+    /// The saved playlists by their names
+    #[serde(default)]
+    pub saved_playlists: BTreeMap<String, SavedPlaylist>,
+    /// The snapshots of the playback by their names
+    #[serde(default)]
+    pub snapshots: BTreeMap<String, QueueSnapshot>,
+    /// The unix time turto was removed from the guild, see `guild_data_retention` in the config
+    #[serde(default)]
+    pub left_at: Option<u64>,
+    /// The track playing when turto was shut down, it's taken when turto is ready again
+    #[serde(default)]
+    pub was_playing: Option<WasPlaying>,
+    /// The version of turto the guild is notified about, see `announce_updates` in the config
+    #[serde(default)]
+    pub announced_version: Option<String>,
+    /// The pinned player in the request channel, it's edited instead of posting new messages
+    #[serde(default)]
+    pub player_message: Option<MessageId>,
+    /// The gain offsets in dB of the tracks by their urls, they're applied whenever the tracks are
+    /// played
+    #[serde(default)]
+    pub track_gains: BTreeMap<String, f32>,
+    /// The tracks skipped by `/neveragain`
+    #[serde(default)]
+    pub never_again: NeverAgain,
+    /// The thread of the listening log the tracks are posted to for now
+    #[serde(default)]
+    pub listening_log_thread: Option<LogThread>,
+    /// The listening sessions by the scheduled events they're played in
+    #[serde(default)]
+    pub scheduled_sessions: BTreeMap<ScheduledEventId, ScheduledSession>,
+    /// The voice channel created for the session by `/play temp:True`
+    #[serde(default)]
+    pub temp_voice_channel: Option<TempVoiceChannel>,
+    /// Only the DJs and the administrators can queue the tracks while it's active
+    #[serde(default)]
+    pub queue_lock: Option<QueueLock>,
+    /// The requests waiting for a DJ to approve them, by their approval messages
+    #[serde(default)]
+    pub pending_requests: BTreeMap<MessageId, PendingRequest>,
+    /// The secret in the link to the overlay of the guild, see `/overlay`
+    #[serde(default)]
+    pub overlay_token: Option<String>,
+}
+
+impl GuildData {
+    /// The volume to play the track with, the volume of the guild with the gain of the track
+    pub fn track_volume(&self, url: &str) -> f32 {
+        let gain = self.track_gains.get(url).copied().unwrap_or_default();
+        self.config.volume.with_gain(gain)
+    }
+
+    pub fn is_queue_locked(&self) -> bool {
+        let now = unix_now();
+        self.queue_lock
+            .as_ref()
+            .is_some_and(|lock| lock.is_active(now))
+    }
 }
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+pub const KARAOKE_LEVEL_RANGE: RangeInclusive<f64> = 0.0..=1.0;
+pub const EIGHT_D_SPEED_RANGE: RangeInclusive<f64> = 0.05..=1.0;
+
+/// The preset of the ffmpeg audio filter applied to the tracks of the guild
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "preset", rename_all = "snake_case")]
+pub enum AudioFilter {
+    /// Remove the vocals in the center channel, `level` is how much of it is removed
+    Karaoke { level: f64 },
+    /// Keep the center channel in the frequency range of the vocals
+    Vocals,
+    /// Pan the sound around the listener, `speed` is the rotations per second
+    #[serde(rename = "8d")]
+    EightD { speed: f64 },
+}
+
+impl AudioFilter {
+    /// The filter in the syntax of the `-af` option of ffmpeg, the parameters are clamped since the
+    /// config can be imported from a file
+    pub fn to_ffmpeg(self) -> String {
+        match self {
+            Self::Karaoke { level } => {
+                let level = level.clamp(*KARAOKE_LEVEL_RANGE.start(), *KARAOKE_LEVEL_RANGE.end());
+                format!("pan=stereo|c0=c0-{level}*c1|c1=c1-{level}*c0")
+            }
+            Self::Vocals => {
+                "pan=stereo|c0=0.5*c0+0.5*c1|c1=0.5*c0+0.5*c1,highpass=f=200,lowpass=f=3500"
+                    .to_owned()
+            }
+            Self::EightD { speed } => {
+                let speed = speed.clamp(*EIGHT_D_SPEED_RANGE.start(), *EIGHT_D_SPEED_RANGE.end());
+                format!("apulsator=hz={speed}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AudioFilter;
+
+    #[test]
+    fn test_to_ffmpeg() {
+        assert_eq!(
+            AudioFilter::Karaoke { level: 0.5 }.to_ffmpeg(),
+            "pan=stereo|c0=c0-0.5*c1|c1=c1-0.5*c0"
+        );
+        assert_eq!(
+            AudioFilter::Karaoke { level: 3.0 }.to_ffmpeg(),
+            "pan=stereo|c0=c0-1*c1|c1=c1-1*c0"
+        );
+        assert_eq!(
+            AudioFilter::EightD { speed: 0.0 }.to_ffmpeg(),
+            "apulsator=hz=0.05"
+        );
+    }
+}
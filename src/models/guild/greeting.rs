@@ -0,0 +1,95 @@
+use poise::ChoiceParameter;
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::{RoleId, UserId};
+
+/// The maximum number of greetings of each guild
+pub const GREETING_LIMIT: usize = 25;
+
+/// A sound played when a user joins or leaves the voice channel turto is in
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Greeting {
+    pub target: GreetingTarget,
+    pub event: GreetingEvent,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum GreetingTarget {
+    User(UserId),
+    Role(RoleId),
+}
+
+#[derive(Debug, ChoiceParameter, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum GreetingEvent {
+    #[name = "join"]
+    Join,
+    #[name = "leave"]
+    Leave,
+}
+
+/// Find the greeting for the user, the greeting for the user goes first, then the greetings for
+/// the roles of the user in order
+pub fn find_greeting<'a>(
+    greetings: &'a [Greeting],
+    event: GreetingEvent,
+    user_id: UserId,
+    roles: &[RoleId],
+) -> Option<&'a Greeting> {
+    let mut greetings = greetings.iter().filter(|greeting| greeting.event == event);
+    greetings
+        .clone()
+        .find(|greeting| greeting.target == GreetingTarget::User(user_id))
+        .or_else(|| {
+            greetings.find(|greeting| match greeting.target {
+                GreetingTarget::Role(role_id) => roles.contains(&role_id),
+                GreetingTarget::User(_) => false,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_greeting, Greeting, GreetingEvent, GreetingTarget};
+    use serenity::model::prelude::{RoleId, UserId};
+
+    #[test]
+    fn test_find_greeting() {
+        let greeting = |target, event, url: &str| Greeting {
+            target,
+            event,
+            url: url.to_owned(),
+        };
+        let greetings = vec![
+            greeting(
+                GreetingTarget::Role(RoleId::new(10)),
+                GreetingEvent::Join,
+                "role",
+            ),
+            greeting(
+                GreetingTarget::User(UserId::new(1)),
+                GreetingEvent::Join,
+                "user",
+            ),
+            greeting(
+                GreetingTarget::User(UserId::new(1)),
+                GreetingEvent::Leave,
+                "leave",
+            ),
+        ];
+        let url = |user: u64, roles: &[RoleId], event| {
+            find_greeting(&greetings, event, UserId::new(user), roles)
+                .map(|greeting| greeting.url.as_str())
+        };
+        assert_eq!(
+            url(1, &[RoleId::new(10)], GreetingEvent::Join),
+            Some("user")
+        );
+        assert_eq!(
+            url(2, &[RoleId::new(10)], GreetingEvent::Join),
+            Some("role")
+        );
+        assert_eq!(url(2, &[], GreetingEvent::Join), None);
+        assert_eq!(url(1, &[], GreetingEvent::Leave), Some("leave"));
+        assert_eq!(url(2, &[RoleId::new(10)], GreetingEvent::Leave), None);
+    }
+}
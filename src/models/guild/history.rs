@@ -0,0 +1,49 @@
+use crate::utils::misc::unix_now;
+use serde::{Deserialize, Serialize};
+use std::collections::{vec_deque::Iter, VecDeque};
+
+/// The maximum number of played tracks kept for each guild, older ones are dropped first
+pub const HISTORY_LIMIT: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub url: String,
+    pub title: String,
+    pub channel: String,
+    pub timestamp: u64, // unix timestamp in seconds
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct History(VecDeque<HistoryEntry>);
+
+impl History {
+    pub fn record(&mut self, url: String, title: String, channel: String) {
+        let timestamp = unix_now();
+        self.push(HistoryEntry {
+            url,
+            title,
+            channel,
+            timestamp,
+        });
+    }
+
+    pub fn push(&mut self, entry: HistoryEntry) {
+        while self.0.len() >= HISTORY_LIMIT {
+            self.0.pop_front();
+        }
+        self.0.push_back(entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate from the latest entry to the oldest one
+    pub fn latest(&self) -> std::iter::Rev<Iter<'_, HistoryEntry>> {
+        self.0.iter().rev()
+    }
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Local};
+use poise::ChoiceParameter;
+use serde::{Deserialize, Serialize};
+use serenity::all::ChannelId;
+
+/// How long a thread of the listening log lasts before a new one is started
+#[derive(Debug, Default, ChoiceParameter, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum LogPeriod {
+    /// A thread for each day, in the time zone of the host of turto
+    #[default]
+    #[name = "day"]
+    Day,
+    /// A thread each time turto joins a voice channel
+    #[name = "session"]
+    Session,
+}
+
+/// The thread the played tracks are logged to
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LogThread {
+    /// Which day or session the thread is for, see `LogPeriod::key`
+    pub key: String,
+    pub id: ChannelId,
+}
+
+impl LogPeriod {
+    /// The day or the session the time belongs to, a new thread is started when it changes. The
+    /// session is always the same, the thread is dropped instead when a new session starts
+    pub fn key(self, now: DateTime<Local>) -> String {
+        match self {
+            LogPeriod::Day => now.format("%Y-%m-%d").to_string(),
+            LogPeriod::Session => "session".to_owned(),
+        }
+    }
+
+    /// The name of the thread started at the time
+    pub fn thread_name(self, now: DateTime<Local>) -> String {
+        match self {
+            LogPeriod::Day => now.format("%Y-%m-%d").to_string(),
+            LogPeriod::Session => now.format("%Y-%m-%d %H:%M").to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogPeriod;
+    use chrono::{Local, TimeZone};
+
+    #[test]
+    fn test_log_period() {
+        let morning = Local.with_ymd_and_hms(2024, 5, 1, 8, 30, 0).unwrap();
+        let night = Local.with_ymd_and_hms(2024, 5, 1, 23, 59, 0).unwrap();
+        let next_day = Local.with_ymd_and_hms(2024, 5, 2, 0, 1, 0).unwrap();
+        assert_eq!(LogPeriod::Day.key(morning), LogPeriod::Day.key(night));
+        assert_ne!(LogPeriod::Day.key(night), LogPeriod::Day.key(next_day));
+        assert_eq!(
+            LogPeriod::Session.key(night),
+            LogPeriod::Session.key(next_day)
+        );
+        assert_eq!(LogPeriod::Session.thread_name(morning), "2024-05-01 08:30");
+    }
+}
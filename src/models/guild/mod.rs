@@ -1,3 +1,16 @@
+pub mod audit;
 pub mod config;
 pub mod data;
+pub mod filter;
+pub mod greeting;
+pub mod history;
+pub mod listening_log;
+pub mod never_again;
+pub mod pending_request;
+pub mod queue_lock;
+pub mod saved_playlist;
+pub mod scheduled_session;
+pub mod snapshot;
+pub mod temp_voice;
 pub mod volume;
+pub mod was_playing;
@@ -0,0 +1,51 @@
+use crate::utils::url::UrlExt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use url::Url;
+
+/// The tracks the guild never wants to hear again, they can't be queued and autoplay never picks
+/// them. The YouTube videos are kept by their ids, so their other links are skipped as well
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct NeverAgain(BTreeSet<String>);
+
+impl NeverAgain {
+    fn key(url: &str) -> String {
+        Url::parse(url)
+            .ok()
+            .and_then(|url| url.yt_video_id())
+            .unwrap_or_else(|| url.to_owned())
+    }
+
+    /// Whether the track is newly added
+    pub fn insert(&mut self, url: &str) -> bool {
+        self.0.insert(Self::key(url))
+    }
+
+    /// Whether the track was in the list
+    pub fn remove(&mut self, url: &str) -> bool {
+        self.0.remove(&Self::key(url))
+    }
+
+    pub fn contains(&self, url: &str) -> bool {
+        self.0.contains(&Self::key(url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NeverAgain;
+
+    #[test]
+    fn test_never_again() {
+        let mut never_again = NeverAgain::default();
+        assert!(never_again.insert("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+        assert!(!never_again.insert("https://youtu.be/dQw4w9WgXcQ"));
+        assert!(never_again.contains("https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=RD"));
+        assert!(!never_again.contains("https://www.youtube.com/watch?v=9bZkp7q19f0"));
+
+        assert!(never_again.insert("https://soundcloud.com/artist/track"));
+        assert!(never_again.contains("https://soundcloud.com/artist/track"));
+        assert!(never_again.remove("https://youtu.be/dQw4w9WgXcQ"));
+        assert!(!never_again.contains("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{
+    models::{
+        playlist_item::PlaylistItem, queue_item::QueueItemKind, youtube_playlist::YouTubePlaylist,
+    },
+    utils::queue::QueueType,
+};
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+
+/// The maximum number of the requests waiting for approval in each guild
+pub const PENDING_REQUEST_LIMIT: usize = 50;
+
+/// A request waiting for a DJ to approve it, it's kept by the message with the buttons to approve
+/// or reject it, see `/approval`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PendingRequest {
+    pub requester: UserId,
+    pub priority: u32,
+    pub title: String,
+    /// The tracks of the request, there's more than one if it's a playlist
+    pub items: Vec<PlaylistItem>,
+    pub playlist: bool,
+    /// Whether it's queued to the front of the playlist, like `/insert`
+    pub front: bool,
+}
+
+impl PendingRequest {
+    pub fn new(
+        queue_item_kind: QueueItemKind,
+        requester: UserId,
+        priority: u32,
+        queue_type: QueueType,
+    ) -> Self {
+        let (title, items, playlist) = match queue_item_kind {
+            QueueItemKind::Single(playlist_item) => {
+                (playlist_item.title.clone(), vec![playlist_item], false)
+            }
+            QueueItemKind::Playlist(mut yt_playlist) => {
+                let title = yt_playlist.title.take().unwrap_or_default();
+                (title, yt_playlist.into_iter().collect(), true)
+            }
+        };
+        PendingRequest {
+            requester,
+            priority,
+            title,
+            items,
+            playlist,
+            front: matches!(queue_type, QueueType::Front),
+        }
+    }
+
+    /// What's queued when it's approved
+    pub fn into_queue_item(self) -> (QueueItemKind, QueueType) {
+        let queue_type = if self.front {
+            QueueType::Front
+        } else {
+            QueueType::Back
+        };
+        let queue_item_kind = match (self.playlist, self.items.len()) {
+            (false, 1) => QueueItemKind::Single(self.items.into_iter().next().unwrap()),
+            _ => {
+                let mut yt_playlist = YouTubePlaylist::default();
+                yt_playlist.title = Some(self.title);
+                yt_playlist.extend(self.items);
+                QueueItemKind::Playlist(yt_playlist)
+            }
+        };
+        (queue_item_kind, queue_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PendingRequest;
+    use crate::{
+        models::{queue_item::QueueItemKind, youtube_playlist::YouTubePlaylist},
+        test::item,
+        utils::queue::QueueType,
+    };
+    use serenity::all::UserId;
+
+    #[test]
+    fn test_into_queue_item() {
+        let requester = UserId::new(1);
+        let request = PendingRequest::new(
+            QueueItemKind::Single(item("1")),
+            requester,
+            0,
+            QueueType::Front,
+        );
+        assert_eq!(request.title, "1");
+        match request.into_queue_item() {
+            (QueueItemKind::Single(item), QueueType::Front) => assert_eq!(item.url, "1"),
+            _ => panic!("the single track should be queued to the front"),
+        }
+
+        let mut yt_playlist = YouTubePlaylist::default();
+        yt_playlist.title = Some("mix".to_owned());
+        yt_playlist.extend([item("1")]);
+        let request = PendingRequest::new(
+            QueueItemKind::Playlist(yt_playlist),
+            requester,
+            0,
+            QueueType::Back,
+        );
+        assert_eq!(request.title, "mix");
+        match request.into_queue_item() {
+            (QueueItemKind::Playlist(yt_playlist), QueueType::Back) => {
+                assert_eq!(yt_playlist.title.as_deref(), Some("mix"));
+                assert_eq!(yt_playlist.len(), 1);
+            }
+            _ => panic!("the playlist should stay a playlist"),
+        }
+    }
+}
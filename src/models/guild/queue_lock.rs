@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Only the DJs and the administrators can queue the tracks while the playlist is locked, by
+/// `/lockqueue` or by the raid protection
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueueLock {
+    pub reason: LockReason,
+    /// The unix time the lock is lifted, it's kept until `/unlockqueue` if it's unset
+    pub until: Option<u64>,
+}
+
+/// What looked like a raid, or `Manual` if it's locked by an administrator
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LockReason {
+    Manual,
+    /// Many tracks are queued by the new accounts in a short time
+    NewAccounts,
+    /// The same track is queued again and again in a short time
+    RepeatedUrl,
+    /// A track is absurdly long
+    LongTrack,
+}
+
+impl QueueLock {
+    pub fn is_active(&self, now: u64) -> bool {
+        self.until.map_or(true, |until| now < until)
+    }
+}
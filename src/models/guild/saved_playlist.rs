@@ -0,0 +1,130 @@
+use crate::models::playlist_item::PlaylistItem;
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+use std::collections::HashSet;
+
+/// The maximum number of saved playlists of each guild
+pub const SAVED_PLAYLIST_LIMIT: usize = 50;
+/// The maximum number of items of a saved playlist, the rest are dropped
+pub const SAVED_PLAYLIST_ITEM_LIMIT: usize = 1000;
+pub const SAVED_PLAYLIST_NAME_MAX_LEN: usize = 32;
+
+/// A playlist saved with a name, it's separated from the playlist being played. Everyone can
+/// play it, but only the owner, the editors and the administrators can change it unless it's public
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SavedPlaylist {
+    pub items: Vec<PlaylistItem>,
+    /// The ones saved before the owners were recorded have no owner, and everyone can change them
+    #[serde(default)]
+    pub owner: Option<UserId>,
+    #[serde(default)]
+    pub editors: HashSet<UserId>,
+    /// Everyone in the guild can change it
+    #[serde(default)]
+    pub public: bool,
+    /// The other users can load it, only for the ones saved by the users
+    #[serde(default)]
+    pub shared: bool,
+}
+
+impl SavedPlaylist {
+    pub fn new(owner: UserId, items: impl IntoIterator<Item = PlaylistItem>) -> Self {
+        let mut saved = Self {
+            owner: Some(owner),
+            ..Default::default()
+        };
+        saved.extend(items);
+        saved
+    }
+
+    /// Add the items to the end until the limit is reached, the number of the added ones is returned
+    pub fn extend(&mut self, items: impl IntoIterator<Item = PlaylistItem>) -> usize {
+        let room = SAVED_PLAYLIST_ITEM_LIMIT.saturating_sub(self.items.len());
+        let len = self.items.len();
+        self.items
+            .extend(items.into_iter().take(room).map(|mut item| {
+                // who queued the items and their priorities don't carry over
+                item.requester = None;
+                item.priority = 0;
+                item
+            }));
+        self.items.len() - len
+    }
+
+    pub fn can_edit(&self, user: UserId, is_admin: bool) -> bool {
+        is_admin
+            || self.public
+            || self.owner.map_or(true, |owner| owner == user)
+            || self.editors.contains(&user)
+    }
+
+    /// Only the owner and the administrators can change who can edit it
+    pub fn can_manage(&self, user: UserId, is_admin: bool) -> bool {
+        is_admin || self.owner.map_or(true, |owner| owner == user)
+    }
+
+    /// The items of `self` followed by the ones of `other` which aren't in `self`, owned by `owner`
+    pub fn merge(&self, other: &SavedPlaylist, owner: UserId) -> SavedPlaylist {
+        let mut urls = HashSet::new();
+        let items = self
+            .items
+            .iter()
+            .chain(other.items.iter())
+            .filter(|item| urls.insert(item.url.as_str()))
+            .cloned()
+            .collect::<Vec<_>>();
+        SavedPlaylist::new(owner, items)
+    }
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    let len = name.chars().count();
+    (1..=SAVED_PLAYLIST_NAME_MAX_LEN).contains(&len)
+        && !name.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_valid_name, SavedPlaylist};
+    use crate::test::item;
+    use serenity::all::UserId;
+
+    #[test]
+    fn test_merge() {
+        let owner = UserId::new(1);
+        let a = SavedPlaylist::new(owner, [item("1"), item("2")]);
+        let b = SavedPlaylist::new(owner, [item("2"), item("3")]);
+        let urls = a
+            .merge(&b, owner)
+            .items
+            .into_iter()
+            .map(|item| item.url)
+            .collect::<Vec<_>>();
+        assert_eq!(urls, ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_can_edit() {
+        let (owner, editor, other) = (UserId::new(1), UserId::new(2), UserId::new(3));
+        let mut saved = SavedPlaylist::new(owner, []);
+        saved.editors.insert(editor);
+        assert!(saved.can_edit(owner, false));
+        assert!(saved.can_edit(editor, false));
+        assert!(!saved.can_edit(other, false));
+        assert!(saved.can_edit(other, true));
+        assert!(!saved.can_manage(editor, false));
+
+        saved.public = true;
+        assert!(saved.can_edit(other, false));
+        assert!(!saved.can_manage(other, false));
+    }
+
+    #[test]
+    fn test_is_valid_name() {
+        assert!(is_valid_name("chill"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name(&"a".repeat(33)));
+        assert!(!is_valid_name("a b"));
+        assert!(!is_valid_name("a\nb"));
+    }
+}
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Duration, Local, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, UserId};
+
+/// A listening session planned with `/event create`, the saved playlist is played in the voice
+/// channel once its scheduled event begins
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScheduledSession {
+    /// The name of the saved playlist of the guild
+    pub playlist: String,
+    pub channel: ChannelId,
+    /// The unix time the event begins
+    pub start: i64,
+    /// Who created the event, the playlist is queued as requested by them
+    pub creator: UserId,
+}
+
+/// When the session starts, `HH:MM` is the next time it's that time, or `YYYY-MM-DD HH:MM`, both
+/// in the time zone of the host of turto. `None` if it can't be parsed or it has passed
+pub fn parse_start(input: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let input = input.trim();
+    let start = if let Ok(time) = NaiveTime::parse_from_str(input, "%H:%M") {
+        let today = now.date_naive().and_time(time);
+        let start = Local.from_local_datetime(&today).earliest()?;
+        if start > now {
+            start
+        } else {
+            Local
+                .from_local_datetime(&(today + Duration::days(1)))
+                .earliest()?
+        }
+    } else {
+        let start = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M").ok()?;
+        Local.from_local_datetime(&start).earliest()?
+    };
+    (start > now).then_some(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_start;
+    use chrono::{Local, TimeZone};
+
+    #[test]
+    fn test_parse_start() {
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 20, 0, 0).unwrap();
+        assert_eq!(
+            parse_start("21:30", now),
+            Local.with_ymd_and_hms(2024, 5, 1, 21, 30, 0).single()
+        );
+        // it has passed today
+        assert_eq!(
+            parse_start("08:00", now),
+            Local.with_ymd_and_hms(2024, 5, 2, 8, 0, 0).single()
+        );
+        assert_eq!(
+            parse_start("2024-05-03 19:00", now),
+            Local.with_ymd_and_hms(2024, 5, 3, 19, 0, 0).single()
+        );
+        assert_eq!(parse_start("2024-04-30 19:00", now), None);
+        assert_eq!(parse_start("tonight", now), None);
+    }
+}
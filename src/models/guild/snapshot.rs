@@ -0,0 +1,81 @@
+use super::volume::GuildVolume;
+use crate::models::{playing::Playing, playlist_item::PlaylistItem};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of snapshots of each guild
+pub const SNAPSHOT_LIMIT: usize = 10;
+
+/// The live state of the playback, unlike the saved playlists, the current item with its position
+/// and the settings of the playback are kept as well
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueueSnapshot {
+    pub playlist: Vec<PlaylistItem>,
+    pub current: Option<SnapshotTrack>,
+    pub repeat: bool,
+    pub volume: GuildVolume,
+    pub timestamp: u64, // unix timestamp in seconds
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotTrack {
+    pub item: PlaylistItem,
+    pub position: u64, // position in the track in seconds
+}
+
+impl SnapshotTrack {
+    /// The track playing now at its current position
+    pub async fn from_playing(playing: &Playing) -> Self {
+        let position = playing
+            .track_handle
+            .get_info()
+            .await
+            .map(|track_state| track_state.position.as_secs())
+            .unwrap_or_default();
+        let mut item = PlaylistItem::from((*playing.metadata).clone());
+        item.url = playing.url.to_string();
+        item.requester = playing.requester;
+        Self { item, position }
+    }
+}
+
+impl QueueSnapshot {
+    /// The items to restore the playlist with, the current item goes first so it's played next
+    pub fn items(&self) -> Vec<PlaylistItem> {
+        self.current
+            .iter()
+            .map(|current| current.item.clone())
+            .chain(self.playlist.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QueueSnapshot, SnapshotTrack};
+    use crate::{models::guild::volume::GuildVolume, test::item};
+
+    #[test]
+    fn test_items() {
+        let mut snapshot = QueueSnapshot {
+            playlist: vec![item("2"), item("3")],
+            current: Some(SnapshotTrack {
+                item: item("1"),
+                position: 42,
+            }),
+            repeat: false,
+            volume: GuildVolume::default(),
+            timestamp: 0,
+        };
+        let urls = |snapshot: &QueueSnapshot| {
+            snapshot
+                .items()
+                .into_iter()
+                .map(|item| item.url)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(urls(&snapshot), ["1", "2", "3"]);
+
+        snapshot.current = None;
+        assert_eq!(urls(&snapshot), ["2", "3"]);
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use serenity::all::ChannelId;
+
+/// How long a new temporary voice channel is kept before anything plays or anyone joins it
+pub const TEMP_VOICE_GRACE: u64 = 60;
+
+/// The voice channel created by `/play temp:True` to host the session in, it's deleted once
+/// nothing is playing and everyone has left
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct TempVoiceChannel {
+    pub id: ChannelId,
+    /// The unix time it was created
+    pub created_at: u64,
+}
+
+impl TempVoiceChannel {
+    pub fn is_finished(&self, playing: bool, alone: bool, now: u64) -> bool {
+        !playing && alone && now.saturating_sub(self.created_at) >= TEMP_VOICE_GRACE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TempVoiceChannel, TEMP_VOICE_GRACE};
+    use serenity::all::ChannelId;
+
+    #[test]
+    fn test_temp_voice_finished() {
+        let channel = TempVoiceChannel {
+            id: ChannelId::new(1),
+            created_at: 1000,
+        };
+        let later = 1000 + TEMP_VOICE_GRACE;
+        assert!(channel.is_finished(false, true, later));
+        assert!(!channel.is_finished(true, true, later));
+        assert!(!channel.is_finished(false, false, later));
+        // nobody may have joined it yet
+        assert!(!channel.is_finished(false, true, 1010));
+    }
+}
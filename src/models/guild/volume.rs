@@ -28,6 +28,13 @@ impl DerefMut for GuildVolume {
     }
 }
 
+impl GuildVolume {
+    /// The volume of a track with the gain offset in dB, it can be louder than 100
+    pub fn with_gain(self, gain_db: f32) -> f32 {
+        self.0 * 10_f32.powf(gain_db / 20.0)
+    }
+}
+
 impl TryFrom<f32> for GuildVolume {
     type Error = VolumeError;
     fn try_from(value: f32) -> Result<Self, Self::Error> {
@@ -76,3 +83,16 @@ impl Display for VolumeError {
 }
 
 impl Error for VolumeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::GuildVolume;
+
+    #[test]
+    fn test_with_gain() {
+        let volume = GuildVolume::try_from(50_usize).unwrap();
+        assert_eq!(volume.with_gain(0.0), 0.5);
+        assert!((volume.with_gain(20.0) - 5.0).abs() < 1e-4);
+        assert!((volume.with_gain(-6.0) - 0.2506).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,24 @@
+use super::snapshot::SnapshotTrack;
+use crate::models::playing::Playing;
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, GuildId};
+use songbird::Songbird;
+
+/// What the guild was playing when turto was shut down, it's resumed when turto is ready again
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WasPlaying {
+    pub channel: ChannelId,
+    pub track: SnapshotTrack,
+}
+
+impl WasPlaying {
+    /// The track the guild is playing now and the voice channel it's played in, `None` if turto
+    /// isn't in a voice channel of the guild
+    pub async fn record(manager: &Songbird, guild_id: GuildId, playing: &Playing) -> Option<Self> {
+        let channel = manager.get(guild_id)?.lock().await.current_channel()?;
+        Some(Self {
+            channel: ChannelId::new(channel.0.get()),
+            track: SnapshotTrack::from_playing(playing).await,
+        })
+    }
+}
@@ -6,7 +6,17 @@ pub struct CommandHelp {
     pub short_description: String,
     #[serde(default)]
     pub description: String,
-    pub parameters: Option<HashMap<String, String>>
+    pub parameters: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub usage: Option<String>,
+    #[serde(default)]
+    pub examples: Vec<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub permissions: Option<String>,
 }
 
-pub type Help = HashMap<String, HashMap<String, CommandHelp>>;
\ No newline at end of file
+pub type Help = HashMap<String, HashMap<String, CommandHelp>>;
@@ -0,0 +1,63 @@
+use crate::config::get_config;
+use std::time::{Duration, Instant};
+use tracing::{info, warn, Span};
+
+/// The tracing span of a command invocation, it's stored in the invocation data by the
+/// `pre_command` hook, entered while the command runs and finished in either `post_command` or
+/// `on_error`
+pub struct CommandSpan {
+    pub span: Span,
+    pub start: Instant,
+    pub timings: Timings,
+}
+
+/// The time spent on the slow parts of a command invocation
+#[derive(Debug, Default)]
+pub struct Timings {
+    pub ytdl: Duration,
+    pub discord: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Phase {
+    Ytdl,
+    Discord,
+}
+
+impl CommandSpan {
+    pub fn new(span: Span) -> Self {
+        Self {
+            span,
+            start: Instant::now(),
+            timings: Timings::default(),
+        }
+    }
+
+    pub fn add(&mut self, phase: Phase, elapsed: Duration) {
+        match phase {
+            Phase::Ytdl => self.timings.ytdl += elapsed,
+            Phase::Discord => self.timings.discord += elapsed,
+        }
+    }
+
+    pub fn finish(&self, success: bool) {
+        let duration = self.start.elapsed().as_millis() as u64;
+        let ytdl = self.timings.ytdl.as_millis() as u64;
+        let discord = self.timings.discord.as_millis() as u64;
+        self.span.record("duration", duration);
+        self.span.record("ytdl", ytdl);
+        self.span.record("discord", discord);
+
+        let threshold = get_config().slow_command_threshold;
+        if !success {
+            warn!(parent: &self.span, "Command failed in {}ms", duration);
+        } else if threshold > 0 && duration >= threshold {
+            warn!(
+                parent: &self.span,
+                "Slow command finished in {}ms (yt-dlp {}ms, discord {}ms)", duration, ytdl, discord
+            );
+        } else {
+            info!(parent: &self.span, "Command finished in {}ms", duration);
+        }
+    }
+}
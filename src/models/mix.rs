@@ -0,0 +1,12 @@
+use poise::ChoiceParameter;
+
+/// How `/mix` reorders the playlist
+#[derive(ChoiceParameter, Debug, Clone, Copy, PartialEq)]
+pub enum MixOrder {
+    /// The short and the long tracks take turns, keeping their order within each
+    #[name = "interleave"]
+    Interleave,
+    /// By the titles, ignoring the cases
+    #[name = "alphabetical"]
+    Alphabetical,
+}
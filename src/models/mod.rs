@@ -1,12 +1,20 @@
 pub mod alias;
 pub mod autoleave;
+pub mod cleanup;
 pub mod config;
 pub mod data;
+pub mod error;
+pub mod file_format;
 pub mod guild;
 pub mod help;
+pub mod invocation;
+pub mod mix;
 pub mod playing;
 pub mod playlist;
 pub mod playlist_item;
+pub mod playlist_range;
+pub mod premium;
 pub mod queue_item;
 pub mod toggle;
+pub mod user_prefs;
 pub mod youtube_playlist;
@@ -1,7 +1,11 @@
+use serenity::model::prelude::UserId;
 use songbird::{input::AuxMetadata, tracks::TrackHandle};
 use std::sync::Arc;
 
 pub struct Playing {
     pub track_handle: TrackHandle,
     pub metadata: Arc<AuxMetadata>, // Metadata here is only for read purpose and not write behavior is supposed to happen
+    /// What's passed to yt-dlp to play the track, it's played again from this
+    pub url: Arc<str>,
+    pub requester: Option<UserId>,
 }
@@ -1,8 +1,9 @@
-use super::playlist_item::PlaylistItem;
+use super::{mix::MixOrder, playlist_item::PlaylistItem};
+use rand::{seq::SliceRandom, Rng};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{vec_deque::IntoIter, VecDeque},
-    ops::{Deref, DerefMut},
+    ops::{Deref, DerefMut, RangeInclusive},
 };
 
 const PAGE_SIZE: usize = 10;
@@ -20,7 +21,8 @@ impl Playlist {
     }
 
     pub fn page_with_indices(&self, index: usize) -> Option<Vec<(usize, &PlaylistItem)>> {
-        if index > self.total_pages() {
+        // the pages start from 1
+        if index == 0 || index > self.total_pages() {
             return None;
         }
         let start = (index - 1) * PAGE_SIZE;
@@ -33,6 +35,75 @@ impl Playlist {
             .collect();
         Some(res)
     }
+
+    /// The index to queue an item with the priority at, which is after the items with the same or
+    /// higher priority, so the order within a priority is preserved
+    pub fn priority_index(&self, priority: u32) -> usize {
+        self.0
+            .iter()
+            .rposition(|item| item.priority >= priority)
+            .map_or(0, |index| index + 1)
+    }
+
+    // the operations below leave the playlist untouched if any index is out of bounds, the items
+    // are only reordered or taken out, so the requester and the priority of each item are kept
+
+    /// Move the item at `from` to `to`, the items in between are shifted by one
+    pub fn move_item(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.0.len() || to >= self.0.len() {
+            return false;
+        }
+        let item = self.0.remove(from).unwrap();
+        self.0.insert(to, item);
+        true
+    }
+
+    pub fn swap_items(&mut self, a: usize, b: usize) -> bool {
+        if a >= self.0.len() || b >= self.0.len() {
+            return false;
+        }
+        self.0.swap(a, b);
+        true
+    }
+
+    /// Remove the items in the range, `None` is returned if the range is empty or out of bounds
+    pub fn remove_range(&mut self, range: RangeInclusive<usize>) -> Option<Vec<PlaylistItem>> {
+        let (start, end) = range.into_inner();
+        if start > end || end >= self.0.len() {
+            return None;
+        }
+        Some(self.0.drain(start..=end).collect())
+    }
+
+    pub fn shuffle(&mut self, rng: &mut impl Rng) {
+        self.0.make_contiguous().shuffle(rng);
+    }
+
+    pub fn mix(&mut self, order: MixOrder) {
+        match order {
+            MixOrder::Interleave => {
+                // the tracks shorter than the median are the short ones
+                let mut durations = self.0.iter().map(|item| item.duration).collect::<Vec<_>>();
+                durations.sort_unstable();
+                let Some(&median) = durations.get(durations.len() / 2) else {
+                    return;
+                };
+                let (short, long): (Vec<_>, Vec<_>) =
+                    self.0.drain(..).partition(|item| item.duration < median);
+                let (mut short, mut long) = (short.into_iter(), long.into_iter());
+                loop {
+                    match (short.next(), long.next()) {
+                        (None, None) => break,
+                        (short, long) => self.0.extend(short.into_iter().chain(long)),
+                    }
+                }
+            }
+            MixOrder::Alphabetical => self
+                .0
+                .make_contiguous()
+                .sort_by_cached_key(|item| item.title.to_lowercase()),
+        }
+    }
 }
 
 impl Deref for Playlist {
@@ -67,4 +138,71 @@ impl From<Vec<PlaylistItem>> for Playlist {
     fn from(value: Vec<PlaylistItem>) -> Self {
         Self(VecDeque::from(value))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::Playlist;
+    use crate::models::{mix::MixOrder, playlist_item::PlaylistItem};
+    use std::time::Duration;
+
+    fn item(priority: u32) -> PlaylistItem {
+        track("", 0, priority)
+    }
+
+    fn track(title: &str, secs: u64, priority: u32) -> PlaylistItem {
+        PlaylistItem {
+            url: String::new(),
+            title: title.to_owned(),
+            channel: String::new(),
+            duration: Duration::from_secs(secs),
+            thumbnail: String::new(),
+            priority,
+            requester: None,
+        }
+    }
+
+    #[test]
+    fn test_priority_index() {
+        let playlist = Playlist::from(vec![item(2), item(2), item(1), item(0), item(0)]);
+        assert_eq!(playlist.priority_index(3), 0);
+        assert_eq!(playlist.priority_index(2), 2);
+        assert_eq!(playlist.priority_index(1), 3);
+        assert_eq!(playlist.priority_index(0), 5);
+        assert_eq!(Playlist::new().priority_index(1), 0);
+    }
+
+    #[test]
+    fn test_mix() {
+        let titles = |playlist: &Playlist| {
+            playlist
+                .iter()
+                .map(|item| item.title.clone())
+                .collect::<Vec<_>>()
+        };
+        let tracks = || {
+            vec![
+                track("b", 300, 0),
+                track("D", 400, 0),
+                track("a", 100, 0),
+                track("e", 500, 0),
+                track("c", 200, 0),
+            ]
+        };
+        let mut playlist = Playlist::from(tracks());
+        playlist.mix(MixOrder::Interleave);
+        assert_eq!(titles(&playlist), ["a", "b", "c", "D", "e"]);
+
+        let mut playlist = Playlist::from(tracks());
+        playlist.mix(MixOrder::Alphabetical);
+        assert_eq!(titles(&playlist), ["a", "b", "c", "D", "e"]);
+
+        let mut playlist = Playlist::from(vec![
+            track("long1", 600, 0),
+            track("long2", 500, 0),
+            track("short1", 60, 0),
+            track("short2", 90, 0),
+        ]);
+        playlist.mix(MixOrder::Interleave);
+        assert_eq!(titles(&playlist), ["short1", "long1", "short2", "long2"]);
+    }
+}
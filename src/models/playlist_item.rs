@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serenity::model::prelude::UserId;
 use songbird::input::AuxMetadata;
 use std::time::Duration;
 
@@ -9,6 +10,12 @@ pub struct PlaylistItem {
     pub channel: String,
     pub duration: Duration,
     pub thumbnail: String,
+    /// The priority of the user who queued this item, see `role_priority` in the config
+    #[serde(default)]
+    pub priority: u32,
+    /// The user who queued this item
+    #[serde(default)]
+    pub requester: Option<UserId>,
 }
 
 impl From<AuxMetadata> for PlaylistItem {
@@ -19,6 +26,8 @@ impl From<AuxMetadata> for PlaylistItem {
             channel: value.channel.unwrap_or_default(),
             duration: value.duration.unwrap_or_default(),
             thumbnail: value.thumbnail.unwrap_or_default(),
+            priority: 0,
+            requester: None,
         }
     }
-}
\ No newline at end of file
+}
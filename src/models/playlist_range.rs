@@ -0,0 +1,76 @@
+use super::error::TurtoError;
+use std::{fmt, ops::RangeInclusive, str::FromStr};
+
+/// The items of a playlist to queue, like `5-20` or `1,3,7-9`, they're counted from 1 as
+/// `--playlist-items` of yt-dlp
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistRange(Vec<RangeInclusive<usize>>);
+
+impl PlaylistRange {
+    /// Whether the item at the 1-based `index` is selected
+    pub fn contains(&self, index: usize) -> bool {
+        self.0.iter().any(|range| range.contains(&index))
+    }
+}
+
+impl FromStr for PlaylistRange {
+    type Err = TurtoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TurtoError::InvalidPlaylistRange(s.to_owned());
+        let parse = |index: &str| {
+            index
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|&index| index > 0)
+        };
+        let ranges = s
+            .split(',')
+            .map(|part| {
+                let (start, end) = part.split_once('-').unwrap_or((part, part));
+                match (parse(start), parse(end)) {
+                    (Some(start), Some(end)) if start <= end => Some(start..=end),
+                    _ => None,
+                }
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        Ok(Self(ranges))
+    }
+}
+
+impl fmt::Display for PlaylistRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges = self
+            .0
+            .iter()
+            .map(|range| match range.start() == range.end() {
+                true => range.start().to_string(),
+                false => format!("{}-{}", range.start(), range.end()),
+            })
+            .collect::<Vec<_>>();
+        f.write_str(&ranges.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PlaylistRange;
+
+    #[test]
+    fn test_playlist_range() {
+        let range = "5-20".parse::<PlaylistRange>().unwrap();
+        assert!(!range.contains(4));
+        assert!(range.contains(5) && range.contains(20));
+        assert!(!range.contains(21));
+
+        let range = " 1, 3 ,7-9".parse::<PlaylistRange>().unwrap();
+        assert_eq!(range.to_string(), "1,3,7-9");
+        assert!(range.contains(3) && !range.contains(2));
+
+        for invalid in ["", "0-3", "5-2", "1,,2", "a-b", "3-"] {
+            assert!(invalid.parse::<PlaylistRange>().is_err(), "{}", invalid);
+        }
+    }
+}
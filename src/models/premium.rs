@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// The features which can be gated behind premium, see `premium_features` in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    Filters,
+    AlwaysOn,
+    Autoplay,
+    HighBitrate,
+}
+
+impl Display for Feature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Feature::Filters => "filters",
+            Feature::AlwaysOn => "always_on",
+            Feature::Autoplay => "autoplay",
+            Feature::HighBitrate => "high_bitrate",
+        };
+        f.write_str(name)
+    }
+}
@@ -1,11 +1,18 @@
-use super::{playlist_item::PlaylistItem, youtube_playlist::YouTubePlaylist};
-use crate::utils::{get_http_client, url::UrlExt, ytdl::ytdl_playlist};
-use songbird::input::{Compose, YoutubeDl};
+use super::{
+    playlist_item::PlaylistItem, playlist_range::PlaylistRange, youtube_playlist::YouTubePlaylist,
+};
+use crate::utils::{
+    resolver::MusicLink,
+    url::UrlExt,
+    ytdl::{ytdl_playlist, ytdl_track},
+};
 use anyhow::Result;
 use url::Url;
 
 pub struct QueueItem {
-    url: Url,
+    query: String,
+    /// Only these items are queued if the query is a playlist
+    range: Option<PlaylistRange>,
 }
 
 pub enum QueueItemKind {
@@ -14,21 +21,36 @@ pub enum QueueItemKind {
 }
 
 impl QueueItem {
-    pub fn new(url: Url) -> Self {
-        Self { url }
+    /// The query can be a URL or a search query of yt-dlp, like `ytsearch1:keywords`
+    pub fn new(query: String) -> Self {
+        Self { query, range: None }
+    }
+
+    pub fn range(mut self, range: Option<PlaylistRange>) -> Self {
+        self.range = range;
+        self
     }
 
     pub async fn query(self) -> Result<QueueItemKind> {
-        if self.url.is_yt_playlist() {
-            Ok(ytdl_playlist(&self.url)
-                .await
-                .map(QueueItemKind::Playlist)?)
-        } else {
-            Ok(YoutubeDl::new(get_http_client(), self.url.to_string())
-                .aux_metadata()
+        let url = Url::parse(&self.query);
+        if let Some(link) = url.as_ref().ok().and_then(MusicLink::parse) {
+            return match (link.resolve().await?, &self.range) {
+                (QueueItemKind::Playlist(mut playlist), Some(range)) => {
+                    let mut index = 0;
+                    playlist.retain(|_| {
+                        index += 1;
+                        range.contains(index)
+                    });
+                    Ok(QueueItemKind::Playlist(playlist))
+                }
+                (queue_item_kind, _) => Ok(queue_item_kind),
+            };
+        }
+        match url {
+            Ok(url) if url.is_playlist() => Ok(ytdl_playlist(&url, self.range.as_ref())
                 .await
-                .map(PlaylistItem::from)
-                .map(QueueItemKind::Single)?)
+                .map(QueueItemKind::Playlist)?),
+            _ => Ok(QueueItemKind::Single(ytdl_track(&self.query).await?)),
         }
     }
 }
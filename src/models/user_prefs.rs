@@ -0,0 +1,66 @@
+use crate::models::guild::saved_playlist::SavedPlaylist;
+use poise::ChoiceParameter;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// The maximum number of bookmarks kept for each user, older ones are dropped first
+pub const BOOKMARK_LIMIT: usize = 100;
+
+/// The preferences of a user, which apply in every guild
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct UserPrefs {
+    /// Send a direct message when a track queued by the user starts playing
+    #[serde(default)]
+    pub notify_track_start: bool,
+    #[serde(default)]
+    pub search_source: SearchSource,
+    /// Overrides the locale of the user and the guild for the replies to the user
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub bookmarks: VecDeque<Bookmark>,
+    /// The saved playlists of the user, which can be loaded in every guild
+    #[serde(default)]
+    pub saved_playlists: BTreeMap<String, SavedPlaylist>,
+    /// The old names of the renamed commands the user has been told about
+    #[serde(default)]
+    pub seen_deprecations: BTreeSet<String>,
+}
+
+impl UserPrefs {
+    pub fn add_bookmark(&mut self, bookmark: Bookmark) {
+        while self.bookmarks.len() >= BOOKMARK_LIMIT {
+            self.bookmarks.pop_front();
+        }
+        self.bookmarks.push_back(bookmark);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bookmark {
+    pub title: String,
+    pub url: String,
+    pub position: u64,  // position in the track in seconds
+    pub timestamp: u64, // unix timestamp in seconds
+}
+
+/// Where the keywords are searched when they are given instead of a URL
+#[derive(Debug, ChoiceParameter, Serialize, Deserialize, PartialEq, Clone, Copy, Default)]
+pub enum SearchSource {
+    #[default]
+    #[name = "youtube"]
+    YouTube,
+    #[name = "soundcloud"]
+    SoundCloud,
+}
+
+impl SearchSource {
+    /// The query for yt-dlp to search the keywords and take the first result
+    pub fn search_query(&self, keywords: &str) -> String {
+        let prefix = match self {
+            SearchSource::YouTube => "ytsearch1",
+            SearchSource::SoundCloud => "scsearch1",
+        };
+        format!("{}:{}", prefix, keywords)
+    }
+}
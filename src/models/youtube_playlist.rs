@@ -47,6 +47,7 @@ impl IntoIterator for YouTubePlaylist {
 pub struct Output {
     pub id: Option<String>,
     pub title: Option<String>,
+    #[serde(default)]
     pub thumbnails: Vec<Thumbnail>,
     pub channel: Option<String>,
     pub uploader: Option<String>,
@@ -74,15 +75,24 @@ pub struct Entry {
     pub channel_url: Option<String>,
     pub uploader: Option<String>,
     pub uploader_url: Option<String>,
+    pub artist: Option<String>,
+    /// The page of the entry, `url` is the media file when the playlist is fully extracted
+    pub webpage_url: Option<String>,
+    #[serde(default)]
     pub thumbnails: Vec<Thumbnail>,
+    pub thumbnail: Option<String>,
 }
 
 impl From<Entry> for PlaylistItem {
     fn from(value: Entry) -> Self {
         PlaylistItem {
             title: value.title.unwrap_or_default(),
-            url: value.url.unwrap_or_default(),
-            channel: value.channel.or(value.uploader).unwrap_or_default(),
+            url: value.webpage_url.or(value.url).unwrap_or_default(),
+            channel: value
+                .channel
+                .or(value.uploader)
+                .or(value.artist)
+                .unwrap_or_default(),
             duration: value
                 .duration
                 .map(Duration::from_secs_f64)
@@ -92,7 +102,10 @@ impl From<Entry> for PlaylistItem {
                 .last()
                 .cloned()
                 .and_then(|thumbnail| thumbnail.url)
+                .or(value.thumbnail)
                 .unwrap_or_default(),
+            priority: 0,
+            requester: None,
         }
     }
 }
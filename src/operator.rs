@@ -0,0 +1,299 @@
+use crate::{
+    config::{
+        check::{check_files, lint_config, Severity},
+        get_config, load_config, CONFIG_PATH, HELP_PATH, TEMPLATES_PATH,
+    },
+    models::{guild::data::GuildData, playing::Playing, user_prefs::UserPrefs},
+    utils::{
+        fade::fade_out_playing, guild::parse_guild_id, json::write_json, play::play_next,
+        player::refresh_player, state::export_state,
+    },
+};
+use dashmap::{DashMap, DashSet};
+use serde_json::{json, Map, Value};
+use serenity::all::{Cache, GuildId, UserId};
+use songbird::Songbird;
+use std::{
+    collections::HashMap,
+    fs::{self, DirBuilder},
+    io,
+    os::unix::fs::{DirBuilderExt, PermissionsExt},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    sync::RwLock,
+};
+use tracing::{debug, info, warn};
+
+const COMMANDS: &str = "guilds, skip <guild id>, save, export <path>, reload, check, help";
+
+/// A local interface for the operators to manage the running instance without Discord, each line
+/// sent to the socket is a command, and each is answered with a line of JSON, like
+/// `{"ok":true,"result":...}` or `{"ok":false,"error":"..."}`
+pub struct OperatorSocket {
+    listener: UnixListener,
+    state: Arc<OperatorState>,
+}
+
+/// What the commands of the operator socket work on, it's shared with the bot
+pub struct OperatorState {
+    pub cache: Arc<Cache>,
+    pub guild_data: Arc<DashMap<GuildId, GuildData>>,
+    pub data_path: PathBuf,
+    pub blacklist: Arc<DashSet<GuildId>>,
+    pub blacklist_path: PathBuf,
+    pub users: Arc<DashMap<UserId, UserPrefs>>,
+    pub users_path: PathBuf,
+    pub playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+    pub voice_manager: Arc<Songbird>,
+}
+
+impl OperatorSocket {
+    /// Listen on the path, a socket left by the previous run is replaced, and only the user
+    /// running turto can connect to it
+    pub fn bind(path: &Path, state: OperatorState) -> io::Result<Self> {
+        // the socket is made in a private directory and moved to the path once its permissions
+        // are set, it's reachable under the umask until then otherwise
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let dir = path.with_file_name(format!(".{}.{}", file_name, std::process::id()));
+        match fs::remove_dir_all(&dir) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => (),
+        }
+        DirBuilder::new().mode(0o700).create(&dir)?;
+        let staged = dir.join("socket");
+        let listener = UnixListener::bind(&staged).and_then(|listener| {
+            fs::set_permissions(&staged, fs::Permissions::from_mode(0o600))?;
+            fs::rename(&staged, path)?;
+            Ok(listener)
+        });
+        if let Err(err) = fs::remove_dir_all(&dir) {
+            warn!("Failed to remove {}: {}", dir.display(), err);
+        }
+        Ok(Self {
+            listener: listener?,
+            state: Arc::new(state),
+        })
+    }
+
+    pub async fn run(self) {
+        loop {
+            match self.listener.accept().await {
+                Ok((stream, _)) => {
+                    let state = self.state.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = handle_connection(stream, &state).await {
+                            debug!("Failed to handle the operator connection: {}", err);
+                        }
+                    });
+                }
+                Err(err) => warn!("Failed to accept the operator connection: {}", err),
+            }
+        }
+    }
+}
+
+async fn handle_connection(stream: UnixStream, state: &OperatorState) -> io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        info!("Operator command: {}", line);
+        let response = match run_command(line, state).await {
+            Ok(result) => json!({ "ok": true, "result": result }),
+            Err(err) => json!({ "ok": false, "error": err }),
+        };
+        writer
+            .write_all(format!("{}\n", response).as_bytes())
+            .await?;
+    }
+    Ok(())
+}
+
+async fn run_command(line: &str, state: &OperatorState) -> Result<Value, String> {
+    let mut args = line.split_whitespace();
+    match (args.next(), args.next(), args.next()) {
+        (Some("guilds"), None, _) => Ok(guilds(state).await),
+        (Some("skip"), Some(guild_id), None) => {
+            let guild_id = parse_guild_id(guild_id).ok_or("invalid guild id")?;
+            skip(state, guild_id).await
+        }
+        (Some("save"), None, _) => save(state),
+        (Some("export"), Some(path), None) => export(state, Path::new(path)).await,
+        (Some("reload"), None, _) => reload(),
+        (Some("check"), None, _) => Ok(check()),
+        (Some("help"), None, _) => Ok(Value::from(COMMANDS)),
+        _ => Err(format!("unknown command, the commands are {}", COMMANDS)),
+    }
+}
+
+/// The guilds turto is in, with what they're playing
+async fn guilds(state: &OperatorState) -> Value {
+    let playing = state.playing.read().await;
+    let mut guild_ids = state.cache.guilds();
+    guild_ids.sort_unstable();
+    let guilds = guild_ids
+        .into_iter()
+        .map(|guild_id| {
+            let queue_length = state
+                .guild_data
+                .get(&guild_id)
+                .map(|guild_data| guild_data.playlist.len())
+                .unwrap_or_default();
+            json!({
+                "id": guild_id.to_string(),
+                "name": state.cache.guild(guild_id).map(|guild| guild.name.clone()),
+                "playing": playing.get(&guild_id).map(|playing| playing.metadata.title.clone()),
+                "queue_length": queue_length,
+            })
+        })
+        .collect::<Vec<_>>();
+    Value::from(guilds)
+}
+
+/// Skip to the next track of the guild like `/skip`, the title of the next one is returned
+async fn skip(state: &OperatorState, guild_id: GuildId) -> Result<Value, String> {
+    let call = state
+        .voice_manager
+        .get(guild_id)
+        .ok_or("not in a voice channel")?;
+    if !state.playing.read().await.contains_key(&guild_id) {
+        return Err("nothing is playing".to_owned());
+    }
+    fade_out_playing(&state.playing, guild_id).await;
+    call.lock().await.stop();
+    let next = play_next(
+        call,
+        state.guild_data.clone(),
+        state.playing.clone(),
+        guild_id,
+    )
+    .await;
+    refresh_player(guild_id);
+    match next {
+        Some(Ok(meta)) => Ok(json!({ "next": meta.title })),
+        Some(Err(err)) => Err(format!("failed to play the next track: {}", err)),
+        None => Ok(json!({ "next": null })),
+    }
+}
+
+/// Save the data right away, the numbers of the bytes written are returned
+fn save(state: &OperatorState) -> Result<Value, String> {
+    let files = [
+        (
+            "guilds",
+            &state.data_path,
+            write_json(&*state.guild_data, &state.data_path),
+        ),
+        (
+            "blacklist",
+            &state.blacklist_path,
+            write_json(&*state.blacklist, &state.blacklist_path),
+        ),
+        (
+            "users",
+            &state.users_path,
+            write_json(&*state.users, &state.users_path),
+        ),
+    ];
+    let mut saved = Map::new();
+    for (name, path, written) in files {
+        let bytes =
+            written.map_err(|err| format!("failed to write {}: {:#}", path.display(), err))?;
+        saved.insert(name.to_owned(), Value::from(bytes));
+    }
+    Ok(Value::Object(saved))
+}
+
+/// Export the state for another instance to take over with `--import-state`, the number of the
+/// bytes written is returned
+async fn export(state: &OperatorState, path: &Path) -> Result<Value, String> {
+    export_state(
+        path,
+        &state.guild_data,
+        &state.blacklist,
+        &state.users,
+        &state.voice_manager,
+        &state.playing,
+    )
+    .await
+    .map(Value::from)
+    .map_err(|err| format!("{:#}", err))
+}
+
+/// Load the config file again, the warnings about the new config are returned. The settings used
+/// when turto starts, like the logging, the HTTP server and this socket, still need a restart
+fn reload() -> Result<Value, String> {
+    load_config(CONFIG_PATH).map_err(|err| format!("{:#}", err))?;
+    let warnings = lint_config(get_config())
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    Ok(json!({ "warnings": warnings }))
+}
+
+/// Check the config files like `--check-config`, without loading them
+fn check() -> Value {
+    let diagnostics = check_files(CONFIG_PATH, HELP_PATH, TEMPLATES_PATH)
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    Value::from(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_command, OperatorSocket};
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    #[tokio::test]
+    async fn test_unknown_command() {
+        // the commands are parsed before anything of the state is used
+        let state = test_state();
+        assert!(run_command("dance", &state).await.is_err());
+        assert!(run_command("skip", &state).await.is_err());
+        assert_eq!(
+            run_command("skip 0", &state).await.unwrap_err(),
+            "invalid guild id"
+        );
+        assert!(run_command("save now", &state).await.is_err());
+        assert!(run_command("export", &state).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bind() {
+        let dir = std::env::temp_dir().join(format!("turto-operator-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("turto.sock");
+        // the socket left by the previous run is replaced
+        fs::write(&path, "").unwrap();
+        let socket = OperatorSocket::bind(&path, test_state()).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // only the socket is left, the private directory is removed
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        drop(socket);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn test_state() -> super::OperatorState {
+        super::OperatorState {
+            cache: Default::default(),
+            guild_data: Default::default(),
+            data_path: Default::default(),
+            blacklist: Default::default(),
+            blacklist_path: Default::default(),
+            users: Default::default(),
+            users_path: Default::default(),
+            playing: Default::default(),
+            voice_manager: songbird::Songbird::serenity(),
+        }
+    }
+}
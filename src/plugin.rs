@@ -0,0 +1,68 @@
+use crate::{config::get_config, handlers::track_hooks::TrackHook, models::alias::Command};
+use serenity::{
+    all::{Context, FullEvent},
+    async_trait,
+};
+use std::sync::OnceLock;
+use toml::Table;
+use tracing::{error, info};
+
+static PLUGINS: OnceLock<Vec<Box<dyn TurtoPlugin>>> = OnceLock::new();
+
+/// An extension of turto for the forks to add their commands and handlers without changing the
+/// core modules, everything is optional. The plugins are set up with `init_plugins` before
+/// `Turto::new`
+#[async_trait]
+pub trait TurtoPlugin: Send + Sync {
+    /// The name in the logs, the config of the plugin is the `[plugins.<name>]` table
+    fn name(&self) -> &str;
+
+    /// Set up the plugin with its table in the config, it's empty if there's none. The plugin is
+    /// left out if it fails
+    fn configure(&mut self, _config: &Table) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// The slash commands of the plugin, their descriptions are taken from help.toml like the
+    /// others
+    fn commands(&self) -> Vec<Command> {
+        Vec::new()
+    }
+
+    /// Subscribed to the track events after the hooks of turto
+    fn track_hooks(&self) -> Vec<Box<dyn TrackHook>> {
+        Vec::new()
+    }
+
+    /// Every event from Discord, it's handled along with the handlers of turto, so the slow work
+    /// should be spawned
+    async fn event(&self, _ctx: &Context, _event: &FullEvent) {}
+}
+
+/// Configure the plugins and make them available to turto, can only be called once, after the
+/// config is loaded
+pub fn init_plugins(mut plugins: Vec<Box<dyn TurtoPlugin>>) {
+    let empty = Table::new();
+    plugins.retain_mut(|plugin| {
+        let config = match get_config().plugins.get(plugin.name()) {
+            Some(toml::Value::Table(config)) => config,
+            _ => &empty,
+        };
+        match plugin.configure(config) {
+            Ok(()) => {
+                info!("Plugin {} is loaded", plugin.name());
+                true
+            }
+            Err(err) => {
+                error!("Failed to configure plugin {}: {:#}", plugin.name(), err);
+                false
+            }
+        }
+    });
+    let _ = PLUGINS.set(plugins);
+}
+
+/// The plugins set up by `init_plugins`, there's none before it's called
+pub fn plugins() -> &'static [Box<dyn TurtoPlugin>] {
+    PLUGINS.get().map(Vec::as_slice).unwrap_or_default()
+}
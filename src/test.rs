@@ -1,7 +1,115 @@
+use crate::{
+    models::{alias::Error, data::Data, error::TurtoError, playlist_item::PlaylistItem},
+    utils::context::TurtoContext,
+};
+use serenity::{
+    all::{GuildId, UserId},
+    async_trait,
+};
+use songbird::Songbird;
+use std::{
+    sync::{Arc, Mutex, Once},
+    time::Duration,
+};
+
+/// Load the templates shipped with the repository exactly once, since they are stored in a
+/// global `OnceLock` and tests run in parallel
+pub fn setup_templates() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        crate::config::message_template::load_templates("templates.toml.template").unwrap()
+    });
+}
+
+/// Same as `setup_templates`, for the config shipped with the repository
+pub fn setup_config() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| crate::config::load_config("config.toml.template").unwrap());
+}
+
+/// An item whose url and title are `url`, the rest is empty
+pub fn item(url: &str) -> PlaylistItem {
+    PlaylistItem {
+        url: url.to_owned(),
+        title: url.to_owned(),
+        channel: String::new(),
+        duration: Duration::ZERO,
+        thumbnail: String::new(),
+        priority: 0,
+        requester: None,
+    }
+}
+
+/// A command context of a guild which records the replies instead of sending them
+pub struct MockContext {
+    pub data: Data,
+    pub guild_id: GuildId,
+    pub author_id: UserId,
+    pub locale: Option<String>,
+    pub manager: Arc<Songbird>,
+    /// The content of each reply and whether it's ephemeral
+    pub replies: Mutex<Vec<(String, bool)>>,
+}
+
+impl Default for MockContext {
+    fn default() -> Self {
+        setup_templates();
+        setup_config();
+        Self {
+            data: Data::default(),
+            guild_id: GuildId::new(1),
+            author_id: UserId::new(1),
+            locale: None,
+            manager: Songbird::serenity(),
+            replies: Mutex::default(),
+        }
+    }
+}
+
+impl MockContext {
+    pub fn replies(&self) -> Vec<(String, bool)> {
+        self.replies.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl TurtoContext for MockContext {
+    fn data(&self) -> &Data {
+        &self.data
+    }
+
+    fn guild_id(&self) -> Option<GuildId> {
+        Some(self.guild_id)
+    }
+
+    fn author_id(&self) -> UserId {
+        self.author_id
+    }
+
+    fn client_locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    async fn voice_manager(&self) -> Result<Arc<Songbird>, TurtoError> {
+        Ok(self.manager.clone())
+    }
+
+    async fn send(&self, content: String, ephemeral: bool) -> Result<(), Error> {
+        self.replies.lock().unwrap().push((content, ephemeral));
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::utils::misc::ToEmoji;
+    use crate::{
+        models::{playlist::Playlist, playlist_item::PlaylistItem},
+        utils::misc::ToEmoji,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use serenity::all::UserId;
+    use songbird::input::AuxMetadata;
 
     #[test]
     fn test_to_emoji() {
@@ -9,4 +117,94 @@ mod tests {
         assert_eq!(123.to_emoji(), "1️⃣2️⃣3️⃣");
         assert_eq!(56789.to_emoji(), "5️⃣6️⃣7️⃣8️⃣9️⃣");
     }
-}
\ No newline at end of file
+
+    /// The id of the item is its url, the requester is derived from it
+    fn playlist_item(id: usize) -> PlaylistItem {
+        let mut item = PlaylistItem::from(AuxMetadata {
+            source_url: Some(id.to_string()),
+            ..Default::default()
+        });
+        item.requester = Some(requester(id));
+        item
+    }
+
+    fn requester(id: usize) -> UserId {
+        UserId::new(id as u64 % 3 + 1)
+    }
+
+    fn ids(playlist: &Playlist) -> Vec<usize> {
+        playlist
+            .iter()
+            .map(|item| item.url.parse().unwrap())
+            .collect()
+    }
+
+    /// Apply random operations to a playlist and to a list of the ids of its items side by side,
+    /// the playlist must match the list after each of them, the seeds keep the failures repeatable
+    #[test]
+    fn test_playlist_operations() {
+        for seed in 0..256 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let len = rng.gen_range(0..20);
+            let mut playlist = Playlist::from((0..len).map(playlist_item).collect::<Vec<_>>());
+            let mut model = (0..len).collect::<Vec<_>>();
+            let mut next_id = len;
+            for _ in 0..50 {
+                // the indices can be out of bounds, which must leave the playlist untouched
+                let bound = model.len() + 2;
+                let (a, b) = (rng.gen_range(0..bound), rng.gen_range(0..bound));
+                let in_bounds = a < model.len() && b < model.len();
+                match rng.gen_range(0..6) {
+                    0 => {
+                        assert_eq!(playlist.move_item(a, b), in_bounds, "seed {}", seed);
+                        if in_bounds {
+                            let id = model.remove(a);
+                            model.insert(b, id);
+                        }
+                    }
+                    1 => {
+                        assert_eq!(playlist.swap_items(a, b), in_bounds, "seed {}", seed);
+                        if in_bounds {
+                            model.swap(a, b);
+                        }
+                    }
+                    2 => {
+                        let removed = playlist.remove(a).map(|item| item.url);
+                        let expected = (a < model.len()).then(|| model.remove(a).to_string());
+                        assert_eq!(removed, expected, "seed {}", seed);
+                    }
+                    3 => {
+                        let removed = playlist.remove_range(a..=b).map(|items| {
+                            items.into_iter().map(|item| item.url).collect::<Vec<_>>()
+                        });
+                        let expected = (in_bounds && a <= b)
+                            .then(|| model.drain(a..=b).map(|id| id.to_string()).collect());
+                        assert_eq!(removed, expected, "seed {}", seed);
+                    }
+                    4 => {
+                        playlist.shuffle(&mut rng);
+                        let mut shuffled = ids(&playlist);
+                        model = shuffled.clone();
+                        shuffled.sort_unstable();
+                        let mut sorted = model.clone();
+                        sorted.sort_unstable();
+                        assert_eq!(shuffled, sorted, "seed {}", seed);
+                    }
+                    _ => {
+                        playlist.push_back(playlist_item(next_id));
+                        model.push(next_id);
+                        next_id += 1;
+                    }
+                }
+                assert_eq!(ids(&playlist), model, "seed {}", seed);
+                assert!(
+                    playlist
+                        .iter()
+                        .all(|item| item.requester == Some(requester(item.url.parse().unwrap()))),
+                    "seed {}",
+                    seed
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use super::resolve_locale;
+use crate::models::{guild::data::GuildData, user_prefs::UserPrefs};
+use dashmap::DashMap;
+use serenity::all::{Cache, ChannelId, GuildId, Http, UserId};
+use tracing::warn;
+
+/// The release notes of the running version
+pub const RELEASE_NOTES_URL: &str = concat!(
+    env!("CARGO_PKG_REPOSITORY"),
+    "/releases/tag/v",
+    env!("CARGO_PKG_VERSION")
+);
+
+/// Post to the announcement channel of every guild turto is in, except the ones which turned the
+/// announcements off. The content is made with the locale of each guild, the numbers of the guilds
+/// it's posted to and failed to be posted to are returned
+pub async fn broadcast(
+    http: &Http,
+    cache: &Cache,
+    guild_data: &DashMap<GuildId, GuildData>,
+    users: &DashMap<UserId, UserPrefs>,
+    content: impl Fn(Option<&str>) -> String,
+) -> (usize, usize) {
+    let targets = cache
+        .guilds()
+        .into_iter()
+        .filter_map(|guild_id| {
+            let guild = cache.guild(guild_id)?;
+            let channel_id = match guild_data.get(&guild_id) {
+                Some(guild_data) => guild_data
+                    .config
+                    .announcement_channel(guild.system_channel_id)?,
+                None => guild.system_channel_id?,
+            };
+            Some((guild_id, channel_id, guild.preferred_locale.clone()))
+        })
+        .collect::<Vec<(GuildId, ChannelId, String)>>();
+
+    let (mut sent, mut failed) = (0, 0);
+    for (guild_id, channel_id, preferred_locale) in targets {
+        // the default id is nobody, so only the locale of the guild is taken
+        let locale = resolve_locale(
+            users,
+            guild_data,
+            UserId::default(),
+            Some(guild_id),
+            Some(&preferred_locale),
+        );
+        match channel_id.say(http, content(locale)).await {
+            Ok(_) => sent += 1,
+            Err(err) => {
+                warn!(
+                    "Failed to announce to {} in guild {}: {}",
+                    channel_id, guild_id, err
+                );
+                failed += 1;
+            }
+        }
+    }
+    (sent, failed)
+}
@@ -0,0 +1,119 @@
+use super::{is_guild_dj, queue::author_priority, resolving::Placeholder};
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{ApprovalRequest, RequestPending},
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+        guild::{
+            data::GuildData,
+            pending_request::{PendingRequest, PENDING_REQUEST_LIMIT},
+        },
+        queue_item::QueueItemKind,
+    },
+    utils::{context::TurtoContext, queue::QueueType},
+};
+use dashmap::DashMap;
+use serenity::all::{
+    ButtonStyle, ChannelId, CreateActionRow, CreateButton, CreateMessage, GuildId, Http,
+};
+
+/// The custom ids of the buttons on the approval messages, the request is the one kept by the
+/// message
+pub const APPROVE_BUTTON_ID: &str = "request_approve";
+pub const REJECT_BUTTON_ID: &str = "request_reject";
+
+/// Whether the requests of the author wait for a DJ to approve them, see `/approval`
+pub async fn needs_approval(ctx: Context<'_>) -> bool {
+    let Some(guild_id) = ctx.guild_id() else {
+        return false;
+    };
+    let (request_approval, dj_role) = {
+        let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        (
+            guild_data.config.request_approval,
+            guild_data.config.dj_role,
+        )
+    };
+    request_approval && !is_guild_dj(ctx, dj_role).await
+}
+
+/// Keep the resolved request of the author for the DJs to approve, the placeholder is edited into
+/// the notice that it's waiting
+pub async fn pend_request(
+    ctx: Context<'_>,
+    placeholder: Placeholder<'_>,
+    queue_item_kind: QueueItemKind,
+    queue_type: QueueType,
+) -> Result<(), Error> {
+    let priority = author_priority(ctx).await;
+    let request = PendingRequest::new(queue_item_kind, ctx.author().id, priority, queue_type);
+    let title = request.title.clone();
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let posted = request_approval(
+        ctx.http(),
+        &ctx.data().guilds,
+        guild_id,
+        ctx.channel_id(),
+        request,
+    )
+    .await;
+    match posted {
+        Ok(()) => {
+            let pending = TurtoMessage {
+                locale: placeholder.locale(),
+                kind: RequestPending { title: &title },
+            };
+            placeholder.send(pending.to_string(), false).await
+        }
+        Err(err) => placeholder.fail(err).await,
+    }
+}
+
+/// Post the request to the channel with the buttons for the DJs to approve or reject it, it's
+/// kept in the guild until then. It fails if too many requests are waiting already
+pub async fn request_approval(
+    http: &Http,
+    guilds: &DashMap<GuildId, GuildData>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    request: PendingRequest,
+) -> Result<(), Error> {
+    let locale = {
+        let guild_data = guilds.entry(guild_id).or_default();
+        if guild_data.pending_requests.len() >= PENDING_REQUEST_LIMIT {
+            return Err(TurtoError::TooManyPendingRequests {
+                limit: PENDING_REQUEST_LIMIT,
+            }
+            .into());
+        }
+        guild_data.config.locale.clone()
+    };
+    let content = TurtoMessage {
+        locale: locale.as_deref(),
+        kind: ApprovalRequest {
+            requester: request.requester,
+            title: &request.title,
+        },
+    };
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(APPROVE_BUTTON_ID)
+            .emoji('✅')
+            .style(ButtonStyle::Success),
+        CreateButton::new(REJECT_BUTTON_ID)
+            .emoji('❌')
+            .style(ButtonStyle::Danger),
+    ]);
+    let message = CreateMessage::new()
+        .content(content.to_string())
+        .components(vec![buttons]);
+    let message = channel_id.send_message(http, message).await?;
+    guilds
+        .entry(guild_id)
+        .or_default()
+        .pending_requests
+        .insert(message.id, request);
+    Ok(())
+}
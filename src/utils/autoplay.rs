@@ -0,0 +1,161 @@
+use crate::{
+    config::get_config,
+    models::{
+        guild::{data::GuildData, history::HistoryEntry},
+        playlist_item::PlaylistItem,
+        premium::Feature,
+    },
+    utils::{premium::has_feature, url::UrlExt, ytdl::ytdl_playlist},
+};
+use dashmap::DashMap;
+use serenity::model::prelude::GuildId;
+use tracing::warn;
+use url::Url;
+
+/// Pick the next track from the YouTube mix of the last played one when the playlist runs out, the
+/// tracks and the uploaders played recently are avoided
+pub async fn autoplay_next(
+    guild_data: &DashMap<GuildId, GuildData>,
+    guild_id: GuildId,
+) -> Option<PlaylistItem> {
+    let config = get_config();
+    let (enabled, recent) = {
+        let guild_data = guild_data.entry(guild_id).or_default();
+        let window = config
+            .autoplay_track_window
+            .max(config.autoplay_uploader_window);
+        let recent = guild_data
+            .history
+            .latest()
+            .take(window.max(1))
+            .cloned()
+            .collect::<Vec<_>>();
+        (guild_data.config.autoplay, recent)
+    };
+    if !enabled || !has_feature(guild_id, Feature::Autoplay).await {
+        return None;
+    }
+
+    let video_id = Url::parse(&recent.first()?.url)
+        .ok()
+        .as_ref()
+        .and_then(Url::yt_video_id)?;
+    let mix = Url::parse(&format!(
+        "https://www.youtube.com/watch?v={}&list=RD{}",
+        video_id, video_id
+    ))
+    .ok()?;
+    let mut candidates = match ytdl_playlist(&mix, None).await {
+        Ok(playlist) => playlist,
+        Err(err) => {
+            warn!("Failed to get the recommendations of {}: {}", video_id, err);
+            return None;
+        }
+    };
+    {
+        let guild_data = guild_data.entry(guild_id).or_default();
+        candidates.retain(|candidate| !guild_data.never_again.contains(&candidate.url));
+    }
+    pick(
+        candidates.into_iter(),
+        &recent,
+        config.autoplay_track_window,
+        config.autoplay_uploader_window,
+    )
+}
+
+/// The first candidate which is neither one of the latest `track_window` tracks nor from the
+/// uploaders of the latest `uploader_window` tracks, the uploaders are allowed again if every
+/// candidate is filtered out
+fn pick(
+    candidates: impl Iterator<Item = PlaylistItem>,
+    recent: &[HistoryEntry],
+    track_window: usize,
+    uploader_window: usize,
+) -> Option<PlaylistItem> {
+    let recent_ids = recent
+        .iter()
+        .take(track_window)
+        .filter_map(|entry| {
+            Url::parse(&entry.url)
+                .ok()
+                .as_ref()
+                .and_then(Url::yt_video_id)
+        })
+        .collect::<Vec<_>>();
+    let recent_uploaders = recent
+        .iter()
+        .take(uploader_window)
+        .map(|entry| entry.channel.as_str())
+        .collect::<Vec<_>>();
+
+    let mut fallback = None;
+    for candidate in candidates {
+        let id = Url::parse(&candidate.url)
+            .ok()
+            .as_ref()
+            .and_then(Url::yt_video_id);
+        if id.is_some_and(|id| recent_ids.contains(&id)) {
+            continue;
+        }
+        if !recent_uploaders.contains(&candidate.channel.as_str()) {
+            return Some(candidate);
+        }
+        fallback.get_or_insert(candidate);
+    }
+    fallback
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pick;
+    use crate::models::{guild::history::HistoryEntry, playlist_item::PlaylistItem};
+    use std::time::Duration;
+
+    fn item(id: &str, channel: &str) -> PlaylistItem {
+        PlaylistItem {
+            url: format!("https://www.youtube.com/watch?v={}", id),
+            title: id.to_owned(),
+            channel: channel.to_owned(),
+            duration: Duration::ZERO,
+            thumbnail: String::new(),
+            priority: 0,
+            requester: None,
+        }
+    }
+
+    fn entry(id: &str, channel: &str) -> HistoryEntry {
+        HistoryEntry {
+            url: format!("https://youtu.be/{}", id),
+            title: id.to_owned(),
+            channel: channel.to_owned(),
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_pick() {
+        // the latest one goes first
+        let recent = [entry("a", "x"), entry("b", "y"), entry("c", "z")];
+        let candidates = || {
+            vec![
+                item("a", "x"),
+                item("b", "w"),
+                item("d", "x"),
+                item("e", "w"),
+            ]
+        };
+
+        let picked = pick(candidates().into_iter(), &recent, 3, 1);
+        assert_eq!(picked.unwrap().title, "e");
+        // the uploader of `d` isn't recent anymore with a smaller window
+        let picked = pick(candidates().into_iter(), &recent, 3, 0);
+        assert_eq!(picked.unwrap().title, "d");
+        // `a` is allowed again once it's out of the window
+        let picked = pick(candidates().into_iter(), &recent[1..], 2, 0);
+        assert_eq!(picked.unwrap().title, "a");
+        // the uploaders are allowed if nothing else is left
+        let picked = pick(vec![item("d", "x")].into_iter(), &recent, 3, 1);
+        assert_eq!(picked.unwrap().title, "d");
+    }
+}
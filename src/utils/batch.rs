@@ -0,0 +1,160 @@
+use super::{get_locale, resolving::Placeholder};
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{QueueBatch, QueueBatchMore},
+    },
+    models::alias::Context,
+};
+use dashmap::DashMap;
+use serenity::{
+    all::{ChannelId, Http, MessageId, UserId},
+    builder::EditMessage,
+};
+use std::{
+    mem::replace,
+    sync::{Arc, LazyLock},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+/// The tracks queued this soon after the previous one of the same user in the same channel are
+/// added to its confirmation instead of getting their own
+const BATCH_WINDOW: Duration = Duration::from_secs(30);
+/// The summary is edited at most this often, so queueing fast doesn't hit the rate limits
+const BATCH_EDIT_INTERVAL: Duration = Duration::from_secs(3);
+/// The number of the latest titles listed in the summary
+const BATCH_SHOWN: usize = 10;
+/// The titles are cut to this many chars, so the summary fits in a message
+const BATCH_TITLE_LENGTH: usize = 100;
+
+/// The confirmations being coalesced, by the channel and the user who queued the tracks
+static BATCHES: LazyLock<DashMap<(ChannelId, UserId), Batch>> = LazyLock::new(DashMap::new);
+
+struct Batch {
+    /// The confirmation of the first track, it's edited into the summary
+    message_id: MessageId,
+    titles: Vec<String>,
+    last_queued: Instant,
+    /// Whether an edit of the summary is scheduled
+    editing: bool,
+    locale: Option<String>,
+}
+
+/// Whether the author has just queued a track in the channel, the confirmation of the next one is
+/// folded into its summary then
+pub fn is_batching(ctx: Context<'_>) -> bool {
+    BATCHES
+        .get(&(ctx.channel_id(), ctx.author().id))
+        .is_some_and(|batch| batch.last_queued.elapsed() < BATCH_WINDOW)
+}
+
+/// Fold the confirmation of the track into the summary of the tracks the author has just queued in
+/// the channel, the placeholder is sent ephemerally for it, see `is_batching`. Otherwise the
+/// placeholder starts a new summary
+pub async fn coalesce(ctx: Context<'_>, placeholder: &Placeholder<'_>, title: String) {
+    let key = (ctx.channel_id(), ctx.author().id);
+    let now = Instant::now();
+    BATCHES.retain(|_, batch| now.duration_since(batch.last_queued) < BATCH_WINDOW);
+    let folded = BATCHES.get_mut(&key).map(|mut batch| {
+        batch.titles.push(title.clone());
+        batch.last_queued = now;
+        !replace(&mut batch.editing, true)
+    });
+    match folded {
+        Some(schedule) => {
+            if schedule {
+                edit_summary(ctx.serenity_context().http.clone(), key);
+            }
+        }
+        // the ephemeral confirmations can't be edited as messages of the channel
+        None if placeholder.is_ephemeral() => (),
+        None => match placeholder.reply().message().await {
+            Ok(message) => {
+                BATCHES.insert(
+                    key,
+                    Batch {
+                        message_id: message.id,
+                        titles: vec![title],
+                        last_queued: now,
+                        editing: false,
+                        locale: get_locale(ctx).map(str::to_owned),
+                    },
+                );
+            }
+            Err(err) => warn!("Failed to get the confirmation to coalesce: {}", err),
+        },
+    }
+}
+
+/// Edit the summary with the titles queued until then after a while
+fn edit_summary(http: Arc<Http>, key: (ChannelId, UserId)) {
+    tokio::spawn(async move {
+        tokio::time::sleep(BATCH_EDIT_INTERVAL).await;
+        let Some((message_id, content)) = BATCHES.get_mut(&key).map(|mut batch| {
+            batch.editing = false;
+            (
+                batch.message_id,
+                summary(batch.locale.as_deref(), &batch.titles),
+            )
+        }) else {
+            return;
+        };
+        let (channel_id, _) = key;
+        let edited = channel_id
+            .edit_message(&http, message_id, EditMessage::new().content(content))
+            .await;
+        if let Err(err) = edited {
+            warn!("Failed to edit the summary {}: {}", message_id, err);
+            // it may have been deleted, the next track starts a new one
+            BATCHES.remove_if(&key, |_, batch| batch.message_id == message_id);
+        }
+    });
+}
+
+/// The number of the tracks with the latest titles, one per line
+fn summary(locale: Option<&str>, titles: &[String]) -> String {
+    let hidden = titles.len().saturating_sub(BATCH_SHOWN);
+    let mut lines = titles[hidden..]
+        .iter()
+        .map(|title| {
+            format!(
+                "- {}",
+                title.chars().take(BATCH_TITLE_LENGTH).collect::<String>()
+            )
+        })
+        .collect::<Vec<_>>();
+    if hidden > 0 {
+        lines.push(
+            TurtoMessage {
+                locale,
+                kind: QueueBatchMore { count: hidden },
+            }
+            .to_string(),
+        );
+    }
+    TurtoMessage {
+        locale,
+        kind: QueueBatch {
+            count: titles.len(),
+            titles: &lines.join("\n"),
+        },
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::summary;
+
+    #[test]
+    fn test_summary() {
+        let titles = ["a", "b"].map(str::to_owned);
+        assert_eq!(summary(None, &titles), "✅ Queued 2 tracks\n- a\n- b");
+
+        let titles = (1..=12).map(|n| n.to_string()).collect::<Vec<_>>();
+        let summary = summary(None, &titles);
+        assert!(summary.starts_with("✅ Queued 12 tracks\n- 3\n"));
+        assert!(summary.ends_with("- 12\n…and 2 more"));
+    }
+}
@@ -0,0 +1,70 @@
+use super::misc::{format_duration, unix_now};
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{self, BookmarkDm, BookmarkDmFailed, BookmarkSent, NotPlaying},
+    },
+    models::{
+        playing::Playing,
+        user_prefs::{Bookmark, UserPrefs},
+    },
+};
+use dashmap::DashMap;
+use serenity::all::{GuildId, Http, UserId};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// The custom id of the bookmark button on the now playing embed, the button is handled by the
+/// event handler so it keeps working after turto restarts
+pub const BOOKMARK_BUTTON_ID: &str = "turto_bookmark";
+
+/// Bookmark the track playing in the guild for the user and send it to the user's DMs, the
+/// bookmark is stored even if the DM can't be sent, returns the reply to the user
+pub async fn bookmark_playing(
+    http: &Http,
+    playing: &RwLock<HashMap<GuildId, Playing>>,
+    users: &DashMap<UserId, UserPrefs>,
+    guild_id: GuildId,
+    user_id: UserId,
+    locale: Option<&str>,
+) -> TurtoMessageKind<'static> {
+    let playing_map = playing.read().await;
+    let Some(playing) = playing_map.get(&guild_id) else {
+        return NotPlaying;
+    };
+    let position = match playing.track_handle.get_info().await {
+        Ok(track_state) => track_state.position,
+        Err(_) => return NotPlaying,
+    };
+    let bookmark = Bookmark {
+        title: playing.metadata.title.clone().unwrap_or_default(),
+        url: playing.metadata.source_url.clone().unwrap_or_default(),
+        position: position.as_secs(),
+        timestamp: unix_now(),
+    };
+    drop(playing_map);
+
+    let content = TurtoMessage {
+        locale,
+        kind: BookmarkDm {
+            title: &bookmark.title,
+            url: &bookmark.url,
+            position: &format_duration(position),
+        },
+    }
+    .to_string();
+    users.entry(user_id).or_default().add_bookmark(bookmark);
+
+    let result = match user_id.create_dm_channel(http).await {
+        Ok(dm_channel) => dm_channel.say(http, content).await.map(|_| ()),
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(_) => BookmarkSent,
+        Err(err) => {
+            warn!("Failed to send the bookmark to user {}: {}", user_id, err);
+            BookmarkDmFailed
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::models::{alias::Context, cleanup::MessageCleanup};
+use dashmap::DashMap;
+use poise::ReplyHandle;
+use serenity::{
+    all::{ChannelId, GuildId, Http, MessageId},
+    builder::EditMessage,
+};
+use songbird::input::AuxMetadata;
+use std::{
+    sync::{Arc, LazyLock, OnceLock},
+    time::Duration,
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tracing::warn;
+
+/// The messages are cleaned one by one with this interval in between, so a busy guild doesn't
+/// hit the rate limits of Discord
+const CLEANUP_INTERVAL: Duration = Duration::from_millis(500);
+
+static CLEANER: OnceLock<UnboundedSender<CleanupJob>> = OnceLock::new();
+
+/// The replies about the tracks in each guild, they're cleaned when their track ends
+static TRACK_MESSAGES: LazyLock<DashMap<GuildId, Vec<TrackMessage>>> = LazyLock::new(DashMap::new);
+
+struct TrackMessage {
+    /// The metadata of the track, it tells the track apart from the next one
+    track: Arc<AuxMetadata>,
+    channel_id: ChannelId,
+    message_id: MessageId,
+}
+
+struct CleanupJob {
+    channel_id: ChannelId,
+    message_id: MessageId,
+    /// The content the message is edited to, it's deleted if there's none
+    content: Option<String>,
+}
+
+/// Start the background cleaner, must be called once the client is ready
+pub fn init_cleanup(http: Arc<Http>) {
+    let (tx, mut rx) = unbounded_channel::<CleanupJob>();
+    if CLEANER.set(tx).is_err() {
+        return;
+    }
+    tokio::spawn(async move {
+        while let Some(job) = rx.recv().await {
+            let result = match job.content {
+                Some(content) => job
+                    .channel_id
+                    .edit_message(
+                        &http,
+                        job.message_id,
+                        EditMessage::new()
+                            .content(content)
+                            .embeds(Vec::new())
+                            .components(Vec::new()),
+                    )
+                    .await
+                    .map(|_| ()),
+                None => job.channel_id.delete_message(&http, job.message_id).await,
+            };
+            // the message may have been deleted by the users
+            if let Err(err) = result {
+                warn!("Failed to clean up message {}: {}", job.message_id, err);
+            }
+            tokio::time::sleep(CLEANUP_INTERVAL).await;
+        }
+    });
+}
+
+/// Remember the reply about the track playing in the guild, so it's cleaned when the track ends
+pub async fn track_reply(ctx: Context<'_>, reply: &ReplyHandle<'_>) {
+    let Some(guild_id) = ctx.guild_id() else {
+        return;
+    };
+    let track = ctx
+        .data()
+        .playing
+        .read()
+        .await
+        .get(&guild_id)
+        .map(|playing| playing.metadata.clone());
+    let Some(track) = track else {
+        return;
+    };
+    match reply.message().await {
+        Ok(message) => TRACK_MESSAGES
+            .entry(guild_id)
+            .or_default()
+            .push(TrackMessage {
+                track,
+                channel_id: message.channel_id,
+                message_id: message.id,
+            }),
+        Err(err) => warn!("Failed to get the reply to clean up: {}", err),
+    }
+}
+
+/// Clean the replies about the track that just ended in the guild, `finished` is the content the
+/// replies are edited to with [`MessageCleanup::Finished`]
+pub fn clean_track_messages(
+    guild_id: GuildId,
+    track: &Arc<AuxMetadata>,
+    policy: MessageCleanup,
+    finished: impl FnOnce() -> String,
+) {
+    let messages = {
+        let Some(mut messages) = TRACK_MESSAGES.get_mut(&guild_id) else {
+            return;
+        };
+        let (ended, others) = std::mem::take(&mut *messages)
+            .into_iter()
+            .partition::<Vec<_>, _>(|message| Arc::ptr_eq(&message.track, track));
+        *messages = others;
+        ended
+    };
+    let Some(cleaner) = CLEANER.get() else {
+        return;
+    };
+    let content = match policy {
+        MessageCleanup::Keep => return,
+        MessageCleanup::Finished => Some(finished()),
+        MessageCleanup::Delete => None,
+    };
+    for message in messages {
+        let _ = cleaner.send(CleanupJob {
+            channel_id: message.channel_id,
+            message_id: message.message_id,
+            content: content.clone(),
+        });
+    }
+}
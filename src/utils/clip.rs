@@ -0,0 +1,71 @@
+use std::{
+    io::{Error, ErrorKind},
+    process::Stdio,
+    time::Duration,
+};
+use tokio::process::Command;
+
+/// The part of the track ending at `position` which is clipped, it starts at the start of the
+/// track if less than `seconds` has been played
+pub fn clip_range(position: Duration, seconds: u64) -> (Duration, Duration) {
+    let start = position.saturating_sub(Duration::from_secs(seconds));
+    (start, position - start)
+}
+
+/// Cut the part of the track out as an ogg file, the stream is asked from yt-dlp again since the
+/// one playing is already decoded by songbird
+pub async fn extract_clip(url: &str, start: Duration, length: Duration) -> Result<Vec<u8>, Error> {
+    let output = Command::new("yt-dlp")
+        .args(["-f", "bestaudio/best", "--no-playlist", "-g", url])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .await?;
+    let stream_url = String::from_utf8_lossy(&output.stdout);
+    let Some(stream_url) = stream_url
+        .lines()
+        .next()
+        .filter(|_| output.status.success())
+    else {
+        return Err(Error::new(ErrorKind::NotFound, "yt-dlp found no stream"));
+    };
+
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-ss"])
+        .arg(format!("{:.3}", start.as_secs_f64()))
+        .arg("-t")
+        .arg(format!("{:.3}", length.as_secs_f64()))
+        .args([
+            "-i", stream_url, "-vn", "-c:a", "libopus", "-b:a", "96k", "-f", "ogg",
+        ])
+        .arg("pipe:1")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .await?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(Error::other(format!(
+            "ffmpeg exited with {}",
+            output.status
+        )));
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clip_range;
+    use std::time::Duration;
+
+    #[test]
+    fn test_clip_range() {
+        assert_eq!(
+            clip_range(Duration::from_secs(95), 30),
+            (Duration::from_secs(65), Duration::from_secs(30))
+        );
+        assert_eq!(
+            clip_range(Duration::from_secs(10), 30),
+            (Duration::ZERO, Duration::from_secs(10))
+        );
+    }
+}
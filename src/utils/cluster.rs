@@ -0,0 +1,242 @@
+use super::{get_http_client, store::restore_guild};
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use dashmap::DashSet;
+use reqwest::header::CONTENT_TYPE;
+use serde_json::{json, Value};
+use serenity::all::GuildId;
+use songbird::Songbird;
+use std::{
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+static CLUSTER: OnceLock<Cluster> = OnceLock::new();
+
+/// The instances running with the same token share the guilds through etcd, each guild is owned
+/// by the instance which claims it first, and only the owner handles it. The claims are attached
+/// to the lease of the instance, so the guilds of an instance which stops keeping its lease alive
+/// are claimed by the others
+struct Cluster {
+    address: String,
+    instance: String,
+    ttl: u64,
+    /// The lease the claims are attached to, 0 if there's none
+    lease: AtomicI64,
+    owned: DashSet<GuildId>,
+}
+
+/// Join the cluster with the etcd at the address, the guilds are claimed as `instance`, and they're
+/// handed off once the lease isn't kept alive for `ttl` seconds
+pub fn init_cluster(address: &str, instance: String, ttl: u64) {
+    let cluster = Cluster {
+        address: address.trim_end_matches('/').to_owned(),
+        instance,
+        ttl: ttl.max(1),
+        lease: AtomicI64::new(0),
+        owned: DashSet::new(),
+    };
+    if CLUSTER.set(cluster).is_ok() {
+        info!(
+            "Joined the cluster at {} as {}",
+            address,
+            CLUSTER.get().unwrap().instance
+        );
+    }
+}
+
+/// Whether this instance handles the guild, it's claimed if nobody owns it. It's always true
+/// without a cluster, and it's false if etcd can't be reached while the guild isn't owned yet
+pub async fn owns(guild_id: GuildId) -> bool {
+    let Some(cluster) = CLUSTER.get() else {
+        return true;
+    };
+    if cluster.owned.contains(&guild_id) {
+        return true;
+    }
+    match cluster.claim(guild_id).await {
+        Ok(true) => {
+            info!("Claimed guild {}", guild_id);
+            // the previous owner may have left its playlist in the store
+            restore_guild(guild_id).await;
+            cluster.owned.insert(guild_id);
+            true
+        }
+        Ok(false) => false,
+        Err(err) => {
+            warn!("Failed to claim guild {}: {:#}", guild_id, err);
+            false
+        }
+    }
+}
+
+/// Whether this instance handles the guild, unlike `owns`, the guild isn't claimed
+pub fn handles(guild_id: GuildId) -> bool {
+    CLUSTER
+        .get()
+        .map_or(true, |cluster| cluster.owned.contains(&guild_id))
+}
+
+/// Keep the lease of the instance alive, the guilds are given up if it can't be kept alive in
+/// time, since the other instances may have claimed them then
+pub async fn lease_keeper(manager: Arc<Songbird>) {
+    let Some(cluster) = CLUSTER.get() else {
+        return;
+    };
+    let mut interval = tokio::time::interval(Duration::from_secs((cluster.ttl / 3).max(1)));
+    let mut kept_alive = Instant::now();
+    loop {
+        interval.tick().await;
+        let kept = match cluster.lease.load(Ordering::Relaxed) {
+            0 => cluster.grant().await.map(|lease| {
+                cluster.lease.store(lease, Ordering::Relaxed);
+                true
+            }),
+            lease => cluster.keep_alive(lease).await,
+        };
+        match kept {
+            Ok(true) => {
+                kept_alive = Instant::now();
+                continue;
+            }
+            Ok(false) => (),
+            Err(err) if kept_alive.elapsed() < Duration::from_secs(cluster.ttl) => {
+                warn!("Failed to keep the lease alive: {:#}", err);
+                continue;
+            }
+            Err(err) => warn!("Failed to keep the lease alive: {:#}", err),
+        }
+        // the lease has expired, so its claims are gone
+        cluster.lease.store(0, Ordering::Relaxed);
+        let lost = cluster
+            .owned
+            .iter()
+            .map(|guild_id| *guild_id)
+            .collect::<Vec<_>>();
+        cluster.owned.clear();
+        for guild_id in lost {
+            warn!("Gave up guild {}, the lease has expired", guild_id);
+            let _ = manager.remove(guild_id).await;
+        }
+    }
+}
+
+impl Cluster {
+    async fn post(&self, endpoint: &str, body: Value) -> Result<Value> {
+        let url = format!("{}/v3/{}", self.address, endpoint);
+        let body = get_http_client()
+            .post(&url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context(format!("Failed to request {}", url))?
+            .text()
+            .await?;
+        serde_json::from_str(&body).map_err(|err| anyhow!("Invalid response from etcd: {}", err))
+    }
+
+    async fn grant(&self) -> Result<i64> {
+        let response = self.post("lease/grant", json!({ "TTL": self.ttl })).await?;
+        // the 64-bit integers are strings in the json of etcd
+        let lease = response["ID"]
+            .as_str()
+            .and_then(|lease| lease.parse().ok())
+            .ok_or_else(|| anyhow!("No lease in the response of etcd"))?;
+        info!("Granted lease {:x} of {} seconds", lease, self.ttl);
+        Ok(lease)
+    }
+
+    /// False if the lease has expired
+    async fn keep_alive(&self, lease: i64) -> Result<bool> {
+        let response = self
+            .post("lease/keepalive", json!({ "ID": lease.to_string() }))
+            .await?;
+        let ttl = response["result"]["TTL"]
+            .as_str()
+            .and_then(|ttl| ttl.parse::<i64>().ok())
+            .unwrap_or_default();
+        Ok(ttl > 0)
+    }
+
+    /// Put the instance as the owner of the guild unless there's one, true if the owner is this
+    /// instance
+    async fn claim(&self, guild_id: GuildId) -> Result<bool> {
+        let lease = self.lease.load(Ordering::Relaxed);
+        if lease == 0 {
+            return Err(anyhow!("No lease yet"));
+        }
+        let response = self
+            .post("kv/txn", claim_txn(guild_id, &self.instance, lease))
+            .await?;
+        if response["succeeded"].as_bool().unwrap_or_default() {
+            return Ok(true);
+        }
+        if owner(&response).as_deref() != Some(self.instance.as_str()) {
+            return Ok(false);
+        }
+        // it's claimed with the lease of the previous run, which is going to expire
+        self.post("kv/put", put_owner(guild_id, &self.instance, lease))
+            .await?;
+        Ok(true)
+    }
+}
+
+fn put_owner(guild_id: GuildId, instance: &str, lease: i64) -> Value {
+    json!({
+        "key": STANDARD.encode(format!("turto/guilds/{}/owner", guild_id)),
+        "value": STANDARD.encode(instance),
+        "lease": lease.to_string(),
+    })
+}
+
+/// Put the owner if the key doesn't exist, otherwise get the current owner
+fn claim_txn(guild_id: GuildId, instance: &str, lease: i64) -> Value {
+    let put = put_owner(guild_id, instance, lease);
+    let key = &put["key"];
+    json!({
+        "compare": [{ "key": key, "target": "CREATE", "create_revision": "0" }],
+        "failure": [{ "request_range": { "key": key } }],
+        "success": [{ "request_put": put }],
+    })
+}
+
+/// The current owner in the response of a failed claim
+fn owner(response: &Value) -> Option<String> {
+    let owner = response["responses"][0]["response_range"]["kvs"][0]["value"].as_str()?;
+    String::from_utf8(STANDARD.decode(owner).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{claim_txn, owner};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde_json::json;
+    use serenity::all::GuildId;
+
+    #[test]
+    fn test_claim() {
+        let txn = claim_txn(GuildId::new(196), "a", 7);
+        let key = STANDARD.encode("turto/guilds/196/owner");
+        assert_eq!(txn["compare"][0]["key"], key);
+        assert_eq!(txn["failure"][0]["request_range"]["key"], key);
+        assert_eq!(
+            txn["success"][0]["request_put"]["value"],
+            STANDARD.encode("a")
+        );
+        assert_eq!(txn["success"][0]["request_put"]["lease"], "7");
+
+        let response = json!({
+            "responses": [{
+                "response_range": { "kvs": [{ "value": STANDARD.encode("b") }] }
+            }]
+        });
+        assert_eq!(owner(&response).as_deref(), Some("b"));
+        assert_eq!(owner(&json!({})), None);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    config::get_config,
+    messages::TurtoMessage,
+    models::{alias::Error, data::Data, error::TurtoError},
+    utils::resolve_locale,
+};
+use poise::CreateReply;
+use serenity::{
+    all::{GuildId, UserId},
+    async_trait,
+};
+use songbird::Songbird;
+use std::sync::Arc;
+
+/// The pieces of the command context the command logic uses, so the logic can be run against
+/// `MockContext` in the tests without a live gateway
+#[async_trait]
+pub trait TurtoContext: Sync {
+    fn data(&self) -> &Data;
+
+    fn guild_id(&self) -> Option<GuildId>;
+
+    fn author_id(&self) -> UserId;
+
+    /// The locale of the discord client of the author
+    fn client_locale(&self) -> Option<&str>;
+
+    async fn voice_manager(&self) -> Result<Arc<Songbird>, TurtoError>;
+
+    async fn send(&self, content: String, ephemeral: bool) -> Result<(), Error>;
+
+    /// Same as `utils::get_locale`
+    fn locale(&self) -> Option<&str> {
+        let data = self.data();
+        resolve_locale(
+            &data.users,
+            &data.guilds,
+            self.author_id(),
+            self.guild_id(),
+            self.client_locale(),
+        )
+    }
+
+    /// Same as `utils::say`, the errors are only visible to the author if it's configured
+    async fn say(&self, message: TurtoMessage<'_>) -> Result<(), Error> {
+        let ephemeral = get_config().ephemeral_replies && message.kind.is_error();
+        self.send(message.to_string(), ephemeral).await
+    }
+}
+
+#[async_trait]
+impl TurtoContext for poise::Context<'_, Data, Error> {
+    fn data(&self) -> &Data {
+        poise::Context::data(*self)
+    }
+
+    fn guild_id(&self) -> Option<GuildId> {
+        poise::Context::guild_id(*self)
+    }
+
+    fn author_id(&self) -> UserId {
+        self.author().id
+    }
+
+    fn client_locale(&self) -> Option<&str> {
+        poise::Context::locale(*self)
+    }
+
+    async fn voice_manager(&self) -> Result<Arc<Songbird>, TurtoError> {
+        songbird::get(self.serenity_context())
+            .await
+            .ok_or(TurtoError::VoiceUnavailable)
+    }
+
+    async fn send(&self, content: String, ephemeral: bool) -> Result<(), Error> {
+        poise::Context::send(
+            *self,
+            CreateReply::default().content(content).ephemeral(ephemeral),
+        )
+        .await?;
+        Ok(())
+    }
+}
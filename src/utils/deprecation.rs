@@ -0,0 +1,137 @@
+use super::get_locale;
+use crate::{
+    config::get_config,
+    messages::{TurtoMessage, TurtoMessageKind::CommandRenamed},
+    models::{alias::Context, config::DeprecatedCommand},
+};
+use poise::CreateReply;
+use std::borrow::Cow;
+use tracing::warn;
+
+/// The renamed commands whose old names still work in this version, see `deprecated_commands` in
+/// the config
+pub fn active_deprecations() -> impl Iterator<Item = &'static DeprecatedCommand> {
+    get_config()
+        .deprecated_commands
+        .iter()
+        .filter(|deprecation| is_active(env!("CARGO_PKG_VERSION"), deprecation.until.as_deref()))
+}
+
+/// The current name of the command, the old names of the renamed commands are redirected to it.
+/// It takes the qualified names as well, like `queue add`
+pub fn canonical_name(name: &str) -> Cow<'_, str> {
+    rename(name, active_deprecations())
+}
+
+// only the top-level commands are renamed, so the subcommands keep their names
+fn rename<'a, 'b: 'a>(
+    name: &'a str,
+    mut deprecations: impl Iterator<Item = &'b DeprecatedCommand>,
+) -> Cow<'a, str> {
+    let (root, subcommand) = match name.split_once(' ') {
+        Some((root, subcommand)) => (root, Some(subcommand)),
+        None => (name, None),
+    };
+    match (
+        deprecations.find(|deprecation| deprecation.old == root),
+        subcommand,
+    ) {
+        (None, _) => Cow::Borrowed(name),
+        (Some(deprecation), None) => Cow::Borrowed(&deprecation.new),
+        (Some(deprecation), Some(subcommand)) => {
+            Cow::Owned(format!("{} {}", deprecation.new, subcommand))
+        }
+    }
+}
+
+/// Tell the user the command is renamed if it's invoked with the old name, once for each user
+pub async fn notify_deprecation(ctx: Context<'_>) {
+    let root = ctx
+        .parent_commands()
+        .first()
+        .copied()
+        .unwrap_or(ctx.command());
+    let Some(deprecation) = active_deprecations().find(|deprecation| deprecation.old == root.name)
+    else {
+        return;
+    };
+    let first = ctx
+        .data()
+        .users
+        .entry(ctx.author().id)
+        .or_default()
+        .seen_deprecations
+        .insert(deprecation.old.clone());
+    if !first {
+        return;
+    }
+    let notice = CreateReply::default()
+        .content(TurtoMessage {
+            locale: get_locale(ctx),
+            kind: CommandRenamed {
+                old: &deprecation.old,
+                new: &deprecation.new,
+            },
+        })
+        .ephemeral(true);
+    if let Err(err) = ctx.send(notice).await {
+        warn!(
+            "Failed to send the notice about /{}: {}",
+            deprecation.old, err
+        );
+    }
+}
+
+/// Whether the old name still works in the current version, it works up to `until`, or forever
+/// if it's not set
+pub fn is_active(current: &str, until: Option<&str>) -> bool {
+    until.map_or(true, |until| version(current) <= version(until))
+}
+
+/// The numbers of the version, like `[1, 3, 1]` for `v1.3.1-beta`
+fn version(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches('v')
+        .split('.')
+        .map(|part| {
+            let digits = part
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>();
+            digits.parse().unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_active, rename};
+    use crate::models::config::DeprecatedCommand;
+
+    #[test]
+    fn test_rename() {
+        let deprecations = [DeprecatedCommand {
+            old: "list".to_owned(),
+            new: "queue".to_owned(),
+            until: None,
+        }];
+        assert_eq!(rename("list", deprecations.iter()), "queue");
+        assert_eq!(rename("list add", deprecations.iter()), "queue add");
+        assert_eq!(rename("play", deprecations.iter()), "play");
+        // only the top-level commands are renamed
+        assert_eq!(
+            rename("playlist list", deprecations.iter()),
+            "playlist list"
+        );
+    }
+
+    #[test]
+    fn test_is_active() {
+        assert!(is_active("1.3.1", None));
+        assert!(is_active("1.3.1", Some("1.3.1")));
+        assert!(is_active("1.3.1", Some("v1.10.0")));
+        assert!(is_active("1.3.1-beta", Some("2.0")));
+        assert!(!is_active("1.3.1", Some("1.3.0")));
+        assert!(!is_active("2.0.0", Some("1.9.9")));
+    }
+}
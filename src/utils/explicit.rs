@@ -0,0 +1,106 @@
+use crate::{
+    models::{alias::Context, queue_item::QueueItemKind},
+    utils::is_admin,
+};
+use regex::Regex;
+use std::{process::Stdio, sync::OnceLock};
+use tokio::process::Command;
+
+/// YouTube restricts the videos to adults with an age limit of 18
+const ADULT_AGE_LIMIT: u8 = 18;
+
+/// Whether the explicit items queued by the author are rejected, the admins and the members with
+/// the bypass role of the guild aren't filtered
+pub async fn should_filter(ctx: Context<'_>) -> bool {
+    let Some(guild_id) = ctx.guild_id() else {
+        return false;
+    };
+    let (enabled, bypass_role) = {
+        let guild_data = ctx.data().guilds.entry(guild_id).or_default();
+        (
+            guild_data.config.explicit_filter,
+            guild_data.config.explicit_bypass_role,
+        )
+    };
+    if !enabled {
+        return false;
+    }
+    let bypassed = match bypass_role {
+        Some(role_id) => ctx
+            .author_member()
+            .await
+            .is_some_and(|member| member.roles.contains(&role_id)),
+        None => false,
+    };
+    !bypassed && !is_admin(ctx).await
+}
+
+/// Remove the explicit items, and return what's left with the number of the removed ones. Only the
+/// titles of the items in a playlist are checked, looking up the age limit of each one takes too
+/// long
+pub async fn filter_explicit(queue_item_kind: QueueItemKind) -> (Option<QueueItemKind>, usize) {
+    match queue_item_kind {
+        QueueItemKind::Single(playlist_item) => {
+            if is_explicit_title(&playlist_item.title)
+                || is_age_restricted(&playlist_item.url).await
+            {
+                (None, 1)
+            } else {
+                (Some(QueueItemKind::Single(playlist_item)), 0)
+            }
+        }
+        QueueItemKind::Playlist(mut yt_playlist) => {
+            let len = yt_playlist.len();
+            yt_playlist.retain(|playlist_item| !is_explicit_title(&playlist_item.title));
+            let removed = len - yt_playlist.len();
+            (Some(QueueItemKind::Playlist(yt_playlist)), removed)
+        }
+    }
+}
+
+/// The tracks are usually labeled in the titles, like `Song (Explicit)` or `[NSFW]`
+pub fn is_explicit_title(title: &str) -> bool {
+    static EXPLICIT_TITLE: OnceLock<Regex> = OnceLock::new();
+    EXPLICIT_TITLE
+        .get_or_init(|| {
+            Regex::new(r"(?i)(\bexplicit\b|\bnsfw\b|\buncensored\b|\b18\+|\bR-18\b)").unwrap()
+        })
+        .is_match(title)
+}
+
+/// The age limit isn't in the metadata from songbird, so it's asked from yt-dlp again, the item is
+/// taken as not restricted if yt-dlp fails
+async fn is_age_restricted(url: &str) -> bool {
+    let output = Command::new("yt-dlp")
+        .args([
+            "--skip-download",
+            "--no-playlist",
+            "--print",
+            "age_limit",
+            url,
+        ])
+        .stdout(Stdio::piped())
+        // it's stopped if the resolution is canceled
+        .kill_on_drop(true)
+        .output()
+        .await;
+    output
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|age_limit| age_limit.trim().parse::<u8>().ok())
+        .is_some_and(|age_limit| age_limit >= ADULT_AGE_LIMIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_explicit_title;
+
+    #[test]
+    fn test_is_explicit_title() {
+        assert!(is_explicit_title("Song (Explicit)"));
+        assert!(is_explicit_title("[NSFW] clip"));
+        assert!(is_explicit_title("Movie Trailer 18+"));
+        assert!(!is_explicit_title("Explicitly Yours"));
+        assert!(!is_explicit_title("Never Gonna Give You Up"));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{config::get_config, models::playing::Playing};
+use serenity::all::GuildId;
+use songbird::tracks::{TrackHandle, TrackResult};
+use std::{collections::HashMap, time::Duration};
+use tokio::sync::RwLock;
+
+const FADE_STEPS: u32 = 10;
+
+/// The volumes of each step of a fade, the last one is `to`
+fn fade_volumes(from: f32, to: f32, steps: u32) -> impl Iterator<Item = f32> {
+    (1..=steps).map(move |step| from + (to - from) * step as f32 / steps as f32)
+}
+
+/// Ramp the volume of the track over `fade_duration` milliseconds, it's set right away if the
+/// duration is 0
+async fn ramp(track_handle: &TrackHandle, from: f32, to: f32) -> TrackResult<()> {
+    let duration = Duration::from_millis(get_config().fade_duration);
+    if duration.is_zero() {
+        return track_handle.set_volume(to);
+    }
+    let step = duration / FADE_STEPS;
+    for volume in fade_volumes(from, to, FADE_STEPS) {
+        track_handle.set_volume(volume)?;
+        tokio::time::sleep(step).await;
+    }
+    Ok(())
+}
+
+/// Fade the track out, the volume it's faded from is returned
+pub async fn fade_out(track_handle: &TrackHandle) -> TrackResult<f32> {
+    let volume = track_handle.get_info().await?.volume;
+    ramp(track_handle, volume, 0.0).await?;
+    Ok(volume)
+}
+
+/// Fade the track out and pause it, the volume is restored so it's resumed with [`fade_play`]
+pub async fn fade_pause(track_handle: &TrackHandle) -> TrackResult<()> {
+    let volume = fade_out(track_handle).await?;
+    track_handle.pause()?;
+    track_handle.set_volume(volume)
+}
+
+/// Resume the track from silence and fade it in
+pub async fn fade_play(track_handle: &TrackHandle) -> TrackResult<()> {
+    let volume = track_handle.get_info().await?.volume;
+    track_handle.set_volume(0.0)?;
+    track_handle.play()?;
+    ramp(track_handle, 0.0, volume).await
+}
+
+/// Fade the track out and stop it
+pub async fn fade_stop(track_handle: &TrackHandle) -> TrackResult<()> {
+    fade_out(track_handle).await?;
+    track_handle.stop()
+}
+
+/// Fade out the track playing in the guild before it's replaced
+pub async fn fade_out_playing(
+    guild_playing: &RwLock<HashMap<GuildId, Playing>>,
+    guild_id: GuildId,
+) {
+    let track_handle = guild_playing
+        .read()
+        .await
+        .get(&guild_id)
+        .map(|playing| playing.track_handle.clone());
+    // the track may have ended already, there's nothing to fade then
+    if let Some(track_handle) = track_handle {
+        let _ = fade_out(&track_handle).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fade_volumes;
+
+    #[test]
+    fn test_fade_volumes() {
+        let volumes = fade_volumes(1.0, 0.0, 4).collect::<Vec<_>>();
+        assert_eq!(volumes, vec![0.75, 0.5, 0.25, 0.0]);
+        let volumes = fade_volumes(0.0, 0.5, 2).collect::<Vec<_>>();
+        assert_eq!(volumes, vec![0.25, 0.5]);
+    }
+}
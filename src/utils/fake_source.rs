@@ -0,0 +1,134 @@
+//! A generated audio source for the tests, so the playback flows can be run without yt-dlp or
+//! network access. The urls look like `fake:sine?title=a&secs=2`, the kinds are `silence`, `sine`
+//! and `fail`, which fails to be created like an unavailable video.
+
+use serenity::async_trait;
+use songbird::input::{
+    core::io::MediaSource, AudioStream, AudioStreamError, AuxMetadata, Compose, RawAdapter,
+};
+use std::{f32::consts::TAU, io::Cursor, time::Duration};
+use url::Url;
+
+const SAMPLE_RATE: u32 = 48000;
+const CHANNELS: u32 = 2;
+const SINE_FREQUENCY: f32 = 440.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Silence,
+    Sine,
+    Fail,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeSource {
+    url: String,
+    kind: Kind,
+    title: String,
+    duration: Duration,
+}
+
+impl FakeSource {
+    pub fn parse(url: &str) -> Option<Self> {
+        let parsed = Url::parse(url).ok().filter(|url| url.scheme() == "fake")?;
+        let kind = match parsed.path() {
+            "silence" => Kind::Silence,
+            "sine" => Kind::Sine,
+            "fail" => Kind::Fail,
+            _ => return None,
+        };
+        let mut title = url.to_owned();
+        let mut duration = Duration::from_secs(1);
+        for (key, value) in parsed.query_pairs() {
+            match key.as_ref() {
+                "title" => title = value.into_owned(),
+                "secs" => duration = Duration::from_secs(value.parse().ok()?),
+                _ => (),
+            }
+        }
+        Some(Self {
+            url: url.to_owned(),
+            kind,
+            title,
+            duration,
+        })
+    }
+
+    /// The interleaved stereo samples of the whole source
+    fn samples(&self) -> Vec<f32> {
+        let frames = (self.duration.as_secs_f32() * SAMPLE_RATE as f32) as usize;
+        (0..frames)
+            .flat_map(|frame| {
+                let sample = match self.kind {
+                    Kind::Sine => {
+                        0.2 * (TAU * SINE_FREQUENCY * frame as f32 / SAMPLE_RATE as f32).sin()
+                    }
+                    _ => 0.0,
+                };
+                [sample; CHANNELS as usize]
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Compose for FakeSource {
+    fn create(&mut self) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        if self.kind == Kind::Fail {
+            return Err(AudioStreamError::Fail(
+                format!("{} is unavailable", self.url).into(),
+            ));
+        }
+        let bytes = self
+            .samples()
+            .into_iter()
+            .flat_map(f32::to_le_bytes)
+            .collect::<Vec<_>>();
+        Ok(AudioStream {
+            input: Box::new(RawAdapter::new(Cursor::new(bytes), SAMPLE_RATE, CHANNELS)),
+            hint: None,
+        })
+    }
+
+    async fn create_async(
+        &mut self,
+    ) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        self.create()
+    }
+
+    fn should_create_async(&self) -> bool {
+        false
+    }
+
+    async fn aux_metadata(&mut self) -> Result<AuxMetadata, AudioStreamError> {
+        Ok(AuxMetadata {
+            title: Some(self.title.clone()),
+            source_url: Some(self.url.clone()),
+            duration: Some(self.duration),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FakeSource, Kind};
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_fake_source() {
+        let source = FakeSource::parse("fake:sine?title=a&secs=2").unwrap();
+        assert_eq!(source.kind, Kind::Sine);
+        assert_eq!(source.title, "a");
+        assert_eq!(source.duration, Duration::from_secs(2));
+        assert_eq!(source.samples().len(), 2 * 48000 * 2);
+        assert!(source.samples().iter().any(|sample| *sample != 0.0));
+
+        let source = FakeSource::parse("fake:silence").unwrap();
+        assert_eq!(source.title, "fake:silence");
+        assert!(source.samples().iter().all(|sample| *sample == 0.0));
+
+        assert!(FakeSource::parse("fake:noise").is_none());
+        assert!(FakeSource::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ").is_none());
+    }
+}
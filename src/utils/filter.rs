@@ -0,0 +1,80 @@
+use crate::models::guild::config::GuildConfig;
+use songbird::input::{AudioStreamError, ChildContainer, Input};
+use std::process::{Command, Stdio};
+
+/// Remove the silence longer than a second quieter than -50 dB, the trailing silence can only be
+/// detected while streaming, so the long pauses in the middle of the tracks are shortened as well
+const TRIM_SILENCE: &str = "silenceremove=start_periods=1:start_threshold=-50dB:stop_periods=-1:stop_duration=1:stop_threshold=-50dB";
+
+/// The filters of the guild joined in the syntax of the `-af` option of ffmpeg, it's none if no
+/// filter is applied
+pub fn filter_chain(config: &GuildConfig) -> Option<String> {
+    let mut filters = Vec::new();
+    // the silence is trimmed first, so it's detected in the original audio
+    if config.trim_silence {
+        filters.push(TRIM_SILENCE.to_owned());
+    }
+    filters.extend(config.filter.iter().map(|filter| filter.to_ffmpeg()));
+    if filters.is_empty() {
+        None
+    } else {
+        Some(filters.join(","))
+    }
+}
+
+/// The audio downloaded by yt-dlp is piped through ffmpeg with the filters, the input can't be
+/// seeked since it's a live stream of the output of ffmpeg
+pub fn filtered_input(url: &str, filter_chain: &str) -> Result<Input, AudioStreamError> {
+    let mut ytdl = Command::new("yt-dlp")
+        .args([
+            "-f",
+            "bestaudio/best",
+            "--no-playlist",
+            "-q",
+            "-o",
+            "-",
+            url,
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| AudioStreamError::Fail(Box::new(err)))?;
+    let ytdl_stdout = ytdl.stdout.take().unwrap();
+    let ffmpeg = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-i", "pipe:0", "-af"])
+        .arg(filter_chain)
+        .args([
+            "-ac", "2", "-ar", "48000", "-c:a", "libopus", "-f", "ogg", "pipe:1",
+        ])
+        .stdin(Stdio::from(ytdl_stdout))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let ffmpeg = match ffmpeg {
+        Ok(ffmpeg) => ffmpeg,
+        Err(err) => {
+            let _ = ytdl.kill();
+            return Err(AudioStreamError::Fail(Box::new(err)));
+        }
+    };
+    // the output is read from the last child, and both are killed when the track is dropped
+    Ok(ChildContainer::from(vec![ytdl, ffmpeg]).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filter_chain, TRIM_SILENCE};
+    use crate::models::guild::{config::GuildConfig, filter::AudioFilter};
+
+    #[test]
+    fn test_filter_chain() {
+        let mut config = GuildConfig::default();
+        assert_eq!(filter_chain(&config), None);
+        config.trim_silence = true;
+        config.filter = Some(AudioFilter::EightD { speed: 0.5 });
+        assert_eq!(
+            filter_chain(&config),
+            Some(format!("{},apulsator=hz=0.5", TRIM_SILENCE))
+        );
+    }
+}
@@ -1,8 +1,15 @@
-use serenity::model::prelude::{ChannelId, Guild, UserId};
+use serenity::model::prelude::{
+    ChannelId, Guild, GuildChannel, GuildId, Member, Permissions, UserId,
+};
 
 pub trait GuildUtil {
     fn get_user_voice_channel(&self, user: &UserId) -> Option<ChannelId>;
     fn cmp_voice_channel(&self, first_user: &UserId, second_user: &UserId) -> VoiceChannelState;
+    /// The channel, or the channel the thread is in if it's a thread, the permissions and the
+    /// settings of a thread are the ones of its channel
+    fn text_channel(&self, channel_id: ChannelId) -> Option<&GuildChannel>;
+    /// The permissions of the member in the channel, `None` if the channel isn't in the guild
+    fn permissions_in(&self, channel_id: ChannelId, member: &Member) -> Option<Permissions>;
 }
 
 impl GuildUtil for Guild {
@@ -30,6 +37,21 @@ impl GuildUtil for Guild {
             }
         }
     }
+
+    fn text_channel(&self, channel_id: ChannelId) -> Option<&GuildChannel> {
+        let channel_id = self
+            .threads
+            .iter()
+            .find(|thread| thread.id == channel_id)
+            .and_then(|thread| thread.parent_id)
+            .unwrap_or(channel_id);
+        self.channels.get(&channel_id)
+    }
+
+    fn permissions_in(&self, channel_id: ChannelId, member: &Member) -> Option<Permissions> {
+        self.text_channel(channel_id)
+            .map(|channel| self.user_permissions_in(channel, member))
+    }
 }
 
 pub enum VoiceChannelState {
@@ -39,3 +61,47 @@ pub enum VoiceChannelState {
     OnlySecond(ChannelId),
     None,
 }
+
+/// The id of the channel the thread is in, or the id of the channel if it isn't a thread
+pub fn thread_parent(channel: &GuildChannel) -> ChannelId {
+    match channel.thread_metadata {
+        Some(_) => channel.parent_id.unwrap_or(channel.id),
+        None => channel.id,
+    }
+}
+
+/// Parse a guild id provided by users, ids are non-zero integers
+pub fn parse_guild_id(id: &str) -> Option<GuildId> {
+    id.trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|id| *id != 0)
+        .map(GuildId::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GuildUtil;
+    use serenity::all::{ChannelId, Guild, GuildChannel};
+
+    #[test]
+    fn test_text_channel() {
+        let mut guild = Guild::default();
+        let mut channel = GuildChannel::default();
+        channel.id = ChannelId::new(1);
+        guild.channels.insert(channel.id, channel);
+        let mut thread = GuildChannel::default();
+        thread.id = ChannelId::new(2);
+        thread.parent_id = Some(ChannelId::new(1));
+        guild.threads.push(thread);
+
+        let text_channel = |id| {
+            guild
+                .text_channel(ChannelId::new(id))
+                .map(|channel| channel.id)
+        };
+        assert_eq!(text_channel(1), Some(ChannelId::new(1)));
+        assert_eq!(text_channel(2), Some(ChannelId::new(1)));
+        assert_eq!(text_channel(3), None);
+    }
+}
@@ -0,0 +1,151 @@
+use super::premium::has_feature;
+use crate::models::{guild::data::GuildData, playing::Playing, premium::Feature};
+use dashmap::DashMap;
+use serenity::all::{Cache, ChannelId, GuildId};
+use songbird::{tracks::PlayMode, Songbird};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+use tracing::{error, info};
+
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Since when turto has been idle or alone in the voice channel of a guild
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct IdleSince {
+    idle: Option<Instant>,
+    alone: Option<Instant>,
+}
+
+impl IdleSince {
+    fn update(&mut self, idle: bool, alone: bool, now: Instant) {
+        self.idle = if idle { self.idle.or(Some(now)) } else { None };
+        self.alone = if alone {
+            self.alone.or(Some(now))
+        } else {
+            None
+        };
+    }
+}
+
+/// Whether the timeout has passed since then, it never does if the timeout is `None`
+fn expired(since: Option<Instant>, timeout: Option<Duration>, now: Instant) -> bool {
+    match (since, timeout) {
+        (Some(since), Some(timeout)) => now.duration_since(since) >= timeout,
+        _ => false,
+    }
+}
+
+/// Leave the voice channels where nothing has been playing, or nobody else has been in, for longer
+/// than the timeouts of the guilds
+pub async fn idle_tracker(
+    cache: Arc<Cache>,
+    manager: Arc<Songbird>,
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+) {
+    let mut idle_since = HashMap::<GuildId, IdleSince>::new();
+    let mut interval = tokio::time::interval(IDLE_CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let now = Instant::now();
+        let calls = manager
+            .iter()
+            .map(|(guild_id, call)| (GuildId::new(guild_id.0.get()), call))
+            .collect::<Vec<_>>();
+        idle_since.retain(|guild_id, _| calls.iter().any(|(id, _)| id == guild_id));
+
+        for (guild_id, call) in calls {
+            let Some(channel_id) = call.lock().await.current_channel() else {
+                idle_since.remove(&guild_id);
+                continue;
+            };
+            let channel_id = ChannelId::new(channel_id.0.get());
+
+            let playing = match guild_playing.read().await.get(&guild_id) {
+                Some(playing) => playing.track_handle.get_info().await.ok(),
+                None => None,
+            };
+            let idle = !playing
+                .is_some_and(|info| matches!(info.playing, PlayMode::Play | PlayMode::Pause));
+            let alone = is_alone(&cache, guild_id, channel_id);
+
+            let since = idle_since.entry(guild_id).or_default();
+            since.update(idle, alone, now);
+
+            let always_on = has_feature(guild_id, Feature::AlwaysOn).await;
+            let (idle_timeout, alone_timeout) = {
+                let guild_data = guild_data.entry(guild_id).or_default();
+                (
+                    guild_data.config.leave_when_idle(always_on),
+                    guild_data.config.leave_when_alone(always_on),
+                )
+            };
+            if !expired(since.idle, idle_timeout, now) && !expired(since.alone, alone_timeout, now)
+            {
+                continue;
+            }
+
+            idle_since.remove(&guild_id);
+            info!(
+                "Leaving the idle voice channel {} of guild {}",
+                channel_id, guild_id
+            );
+            if let Err(err) = call.lock().await.leave().await {
+                error!("Failed to leave voice channel {}: {}", channel_id, err);
+            }
+        }
+    }
+}
+
+/// Whether nobody but the bots is in the voice channel
+pub fn is_alone(cache: &Cache, guild_id: GuildId, channel_id: ChannelId) -> bool {
+    let Some(guild) = cache.guild(guild_id) else {
+        return false;
+    };
+    let bot_id = cache.current_user().id;
+    !guild.voice_states.values().any(|voice_state| {
+        voice_state.channel_id == Some(channel_id)
+            && voice_state.user_id != bot_id
+            && !voice_state
+                .member
+                .as_ref()
+                .is_some_and(|member| member.user.bot)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expired, IdleSince};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_idle_since() {
+        let start = Instant::now();
+        let later = start + Duration::from_secs(60);
+        let mut since = IdleSince::default();
+
+        since.update(true, false, start);
+        since.update(true, true, later);
+        assert_eq!(since.idle, Some(start));
+        assert_eq!(since.alone, Some(later));
+        since.update(false, true, later + Duration::from_secs(1));
+        assert_eq!(since.idle, None);
+        assert_eq!(since.alone, Some(later));
+    }
+
+    #[test]
+    fn test_expired() {
+        let start = Instant::now();
+        let minute = Duration::from_secs(60);
+        assert!(!expired(Some(start), Some(minute), start + minute / 2));
+        assert!(expired(Some(start), Some(minute), start + minute));
+        assert!(expired(Some(start), Some(Duration::ZERO), start));
+        assert!(!expired(Some(start), None, start + minute * 60));
+        assert!(!expired(None, Some(Duration::ZERO), start));
+    }
+}
@@ -0,0 +1,168 @@
+use dashmap::DashMap;
+use serenity::all::{GuildId, UserId};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    Arc, LazyLock,
+};
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+static JOBS: LazyLock<DashMap<u64, Arc<Job>>> = LazyLock::new(DashMap::new);
+/// The jobs of a guild run while holding a permit, see `guild_jobs` in the config
+static GUILD_SLOTS: LazyLock<DashMap<GuildId, Arc<Semaphore>>> = LazyLock::new(DashMap::new);
+
+/// A long-running operation of a guild, like queueing the links of `/queue bulk`
+#[derive(Debug)]
+pub struct Job {
+    pub id: u64,
+    pub guild_id: GuildId,
+    /// Who started it, they can cancel it along with the administrators
+    pub author: UserId,
+    pub total: usize,
+    done: AtomicUsize,
+    running: AtomicBool,
+    canceled: AtomicBool,
+    cancel: Notify,
+}
+
+impl Job {
+    pub fn done(&self) -> usize {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    pub fn set_done(&self, done: usize) {
+        self.done.store(done, Ordering::Relaxed);
+    }
+
+    /// Whether it has started, otherwise it's waiting for the other jobs of the guild
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// The job stops at the next step once it's canceled
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::Relaxed)
+    }
+}
+
+/// The job is forgotten once this is dropped, along with the permit it runs with
+pub struct JobHandle {
+    pub job: Arc<Job>,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl JobHandle {
+    /// Start right away if fewer than `limit` jobs of the guild are running, which is `guild_jobs`
+    /// in the config
+    pub fn try_start(&mut self, limit: usize) -> bool {
+        if let Ok(permit) = slots(self.job.guild_id, limit).try_acquire_owned() {
+            self.start(permit);
+        }
+        self.job.is_running()
+    }
+
+    /// Wait until fewer than `limit` jobs of the guild are running, false if it's canceled in the
+    /// meantime
+    pub async fn wait_turn(&mut self, limit: usize) -> bool {
+        if self.job.is_running() {
+            return !self.job.is_canceled();
+        }
+        let slots = slots(self.job.guild_id, limit);
+        tokio::select! {
+            Ok(permit) = slots.acquire_owned() => {
+                self.start(permit);
+                !self.job.is_canceled()
+            }
+            _ = self.job.cancel.notified() => false,
+        }
+    }
+
+    fn start(&mut self, permit: OwnedSemaphorePermit) {
+        self._permit = Some(permit);
+        self.job.running.store(true, Ordering::Relaxed);
+    }
+}
+
+fn slots(guild_id: GuildId, limit: usize) -> Arc<Semaphore> {
+    GUILD_SLOTS
+        .entry(guild_id)
+        .or_insert_with(|| Arc::new(Semaphore::new(limit.max(1))))
+        .clone()
+}
+
+impl Drop for JobHandle {
+    fn drop(&mut self) {
+        JOBS.remove(&self.job.id);
+    }
+}
+
+/// Register a job of the guild with `total` steps, run it after `JobHandle::wait_turn`
+pub fn new_job(guild_id: GuildId, author: UserId, total: usize) -> JobHandle {
+    let job = Arc::new(Job {
+        id: NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed),
+        guild_id,
+        author,
+        total,
+        done: AtomicUsize::new(0),
+        running: AtomicBool::new(false),
+        canceled: AtomicBool::new(false),
+        cancel: Notify::new(),
+    });
+    JOBS.insert(job.id, job.clone());
+    JobHandle { job, _permit: None }
+}
+
+/// The jobs of the guild from the oldest
+pub fn guild_jobs(guild_id: GuildId) -> Vec<Arc<Job>> {
+    let mut jobs = JOBS
+        .iter()
+        .filter(|job| job.guild_id == guild_id)
+        .map(|job| job.value().clone())
+        .collect::<Vec<_>>();
+    jobs.sort_unstable_by_key(|job| job.id);
+    jobs
+}
+
+/// The job of the guild with the id, the other guilds' can't be seen
+pub fn find_job(guild_id: GuildId, id: u64) -> Option<Arc<Job>> {
+    JOBS.get(&id)
+        .map(|job| job.value().clone())
+        .filter(|job| job.guild_id == guild_id)
+}
+
+pub fn cancel_job(job: &Job) {
+    job.canceled.store(true, Ordering::Relaxed);
+    // a permit is stored if it's not waiting, so it's never missed
+    job.cancel.notify_one();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cancel_job, find_job, guild_jobs, new_job};
+    use serenity::all::{GuildId, UserId};
+
+    #[tokio::test]
+    async fn test_jobs() {
+        let guild_id = GuildId::new(186);
+        let author = UserId::new(1);
+        let mut first = new_job(guild_id, author, 10);
+        let mut second = new_job(guild_id, author, 5);
+        assert!(first.try_start(1));
+        assert!(!second.try_start(1));
+        assert!(!second.job.is_running());
+        assert_eq!(guild_jobs(guild_id).len(), 2);
+        assert!(find_job(GuildId::new(187), second.job.id).is_none());
+
+        // it's still waiting for the first one, which holds the only slot
+        cancel_job(&second.job);
+        assert!(!second.wait_turn(1).await);
+        let second_id = second.job.id;
+        drop(second);
+        assert!(find_job(guild_id, second_id).is_none());
+
+        drop(first);
+        let mut third = new_job(guild_id, author, 1);
+        assert!(third.wait_turn(1).await);
+        assert_eq!(guild_jobs(guild_id).len(), 1);
+    }
+}
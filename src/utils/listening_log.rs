@@ -0,0 +1,117 @@
+use crate::models::guild::{
+    data::GuildData,
+    listening_log::{LogPeriod, LogThread},
+};
+use chrono::Local;
+use dashmap::DashMap;
+use serenity::{
+    all::{ChannelId, ChannelType, GuildId, Http},
+    builder::{CreateAllowedMentions, CreateForumPost, CreateMessage, CreateThread},
+};
+use std::sync::{Arc, OnceLock};
+use tracing::warn;
+
+static HTTP: OnceLock<Arc<Http>> = OnceLock::new();
+
+/// Set up the listening logs, must be called once the client is ready
+pub fn init_listening_log(http: Arc<Http>) {
+    let _ = HTTP.set(http);
+}
+
+/// Drop the thread of the session when turto joins a voice channel, so the next track starts a
+/// new one if the guild logs by session
+pub fn start_log_session(guild_data: &mut GuildData) {
+    if guild_data.config.listening_log_period == LogPeriod::Session {
+        guild_data.listening_log_thread = None;
+    }
+}
+
+/// Post the entry to the thread of the day or the session in the listening log of the guild, the
+/// thread is created first if there isn't one yet. `thread_name` names the new thread with the
+/// name from `LogPeriod::thread_name`
+pub async fn log_track(
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    guild_id: GuildId,
+    entry: String,
+    thread_name: impl FnOnce(String) -> String,
+) {
+    let Some(http) = HTTP.get() else {
+        return;
+    };
+    let now = Local::now();
+    let (channel_id, period, thread) = {
+        let Some(guild_data) = guild_data.get(&guild_id) else {
+            return;
+        };
+        let Some(channel_id) = guild_data.config.listening_log else {
+            return;
+        };
+        let period = guild_data.config.listening_log_period;
+        let key = period.key(now);
+        let thread = guild_data
+            .listening_log_thread
+            .as_ref()
+            .filter(|thread| thread.key == key)
+            .map(|thread| thread.id);
+        (channel_id, period, thread)
+    };
+    // the requesters are mentioned to show their names, not to notify them
+    let message = CreateMessage::new()
+        .content(entry)
+        .allowed_mentions(CreateAllowedMentions::new());
+
+    if let Some(thread_id) = thread {
+        match thread_id.send_message(http, message.clone()).await {
+            Ok(_) => return,
+            // the thread may have been deleted, a new one is started
+            Err(err) => warn!(
+                "Failed to log the track to thread {} in guild {}: {}",
+                thread_id, guild_id, err
+            ),
+        }
+    }
+    let name = thread_name(period.thread_name(now));
+    let thread_id = match create_thread(http, channel_id, name, message).await {
+        Ok(thread_id) => thread_id,
+        Err(err) => {
+            warn!(
+                "Failed to start a listening log in {} of guild {}: {}",
+                channel_id, guild_id, err
+            );
+            return;
+        }
+    };
+    if let Some(mut guild_data) = guild_data.get_mut(&guild_id) {
+        guild_data.listening_log_thread = Some(LogThread {
+            key: period.key(now),
+            id: thread_id,
+        });
+    }
+}
+
+/// Start a post in the forum or a thread in the text channel, the message is the first one in it
+async fn create_thread(
+    http: &Http,
+    channel_id: ChannelId,
+    name: String,
+    message: CreateMessage,
+) -> Result<ChannelId, serenity::Error> {
+    let channel = channel_id.to_channel(http).await?;
+    let is_forum = channel
+        .guild()
+        .is_some_and(|channel| channel.kind == ChannelType::Forum);
+    if is_forum {
+        let post = channel_id
+            .create_forum_post(http, CreateForumPost::new(name, message))
+            .await?;
+        return Ok(post.id);
+    }
+    let thread = channel_id
+        .create_thread(
+            http,
+            CreateThread::new(name).kind(ChannelType::PublicThread),
+        )
+        .await?;
+    thread.id.send_message(http, message).await?;
+    Ok(thread.id)
+}
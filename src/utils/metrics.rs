@@ -0,0 +1,93 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+/// The counters since turto started, they're exposed at `/metrics` of the HTTP server
+pub static METRICS: Metrics = Metrics {
+    tracks_started: AtomicU64::new(0),
+    tracks_failed: AtomicU64::new(0),
+    tracks_stuck: AtomicU64::new(0),
+    voice_migrations: AtomicU64::new(0),
+    voice_migrations_interrupted: AtomicU64::new(0),
+};
+
+/// Mark the time turto started, the uptime is counted from the first call
+pub fn init_uptime() {
+    STARTED.get_or_init(Instant::now);
+}
+
+pub fn uptime() -> Duration {
+    STARTED.get().map(Instant::elapsed).unwrap_or_default()
+}
+
+pub struct Metrics {
+    pub tracks_started: AtomicU64,
+    pub tracks_failed: AtomicU64,
+    /// The tracks skipped by the watchdog since their position stopped advancing
+    pub tracks_stuck: AtomicU64,
+    /// The voice server changes while playing, e.g. when the region of the channel is changed
+    pub voice_migrations: AtomicU64,
+    /// The voice server changes the playback was lost in, or failed to reconnect after
+    pub voice_migrations_interrupted: AtomicU64,
+}
+
+impl Metrics {
+    pub fn increment(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Render the counters in the Prometheus text format
+    pub fn render(&self) -> String {
+        [
+            ("turto_tracks_started_total", &self.tracks_started),
+            ("turto_tracks_failed_total", &self.tracks_failed),
+            ("turto_tracks_stuck_total", &self.tracks_stuck),
+            ("turto_voice_migrations_total", &self.voice_migrations),
+            (
+                "turto_voice_migrations_interrupted_total",
+                &self.voice_migrations_interrupted,
+            ),
+        ]
+        .into_iter()
+        .map(|(name, counter)| {
+            format!(
+                "# TYPE {} counter\n{} {}\n",
+                name,
+                name,
+                counter.load(Ordering::Relaxed)
+            )
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metrics;
+    use std::sync::atomic::AtomicU64;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics {
+            tracks_started: AtomicU64::new(3),
+            tracks_failed: AtomicU64::new(1),
+            tracks_stuck: AtomicU64::new(0),
+            voice_migrations: AtomicU64::new(2),
+            voice_migrations_interrupted: AtomicU64::new(1),
+        };
+        Metrics::increment(&metrics.tracks_stuck);
+        let rendered = metrics.render();
+        assert!(rendered
+            .contains("# TYPE turto_tracks_started_total counter\nturto_tracks_started_total 3\n"));
+        assert!(rendered.contains("turto_tracks_failed_total 1\n"));
+        assert!(rendered.contains("turto_tracks_stuck_total 1\n"));
+        assert!(rendered.contains("turto_voice_migrations_total 2\n"));
+        assert!(rendered.contains("turto_voice_migrations_interrupted_total 1\n"));
+    }
+}
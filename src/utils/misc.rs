@@ -1,5 +1,5 @@
 use sha256::digest;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub trait ToEmoji {
     fn to_emoji(&self) -> String;
@@ -28,6 +28,14 @@ impl ToEmoji for usize {
     }
 }
 
+/// The seconds since the unix epoch, the timestamps kept in the data are in them
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 pub fn sha256_now() -> String {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -36,3 +44,27 @@ pub fn sha256_now() -> String {
         .to_be_bytes();
     digest(&now)
 }
+
+/// Format the duration like `3:07` or `1:02:03`
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_duration;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(7)), "0:07");
+        assert_eq!(format_duration(Duration::from_secs(187)), "3:07");
+        assert_eq!(format_duration(Duration::from_secs(3723)), "1:02:03");
+    }
+}
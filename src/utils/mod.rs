@@ -1,21 +1,74 @@
 use crate::{
-    messages::{TurtoMessage, TurtoMessageKind::Join},
-    models::alias::{Context, Error},
+    config::{get_config, message_template::get_locales},
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{AdministratorOnly, Join, OwnerOnly},
+    },
+    models::{
+        alias::{Context, Error},
+        guild::data::GuildData,
+        invocation::Phase::Discord,
+        user_prefs::UserPrefs,
+    },
 };
+use context::TurtoContext;
+use dashmap::DashMap;
+use poise::{CreateReply, ReplyHandle};
 use reqwest::Client;
-use serenity::all::{ChannelId, GuildId};
+use serenity::all::{ChannelId, GuildId, Member, Permissions, RoleId, UserId};
 use songbird::Call;
 use std::sync::{Arc, OnceLock};
+use timing::timed;
 use tokio::sync::Mutex;
+use url::{parse_web_url, UrlExt};
 
+pub mod announce;
+pub mod approval;
+pub mod autoplay;
+pub mod batch;
+pub mod bookmark;
+pub mod cleanup;
+pub mod clip;
+pub mod cluster;
+pub mod context;
+pub mod deprecation;
+pub mod explicit;
+pub mod fade;
+#[cfg(test)]
+pub mod fake_source;
+pub mod filter;
 pub mod guild;
+pub mod idle;
+pub mod jobs;
 pub mod json;
+pub mod listening_log;
+pub mod metrics;
 pub mod misc;
+pub mod notify;
 pub mod play;
+pub mod player;
+pub mod premium;
+pub mod presence;
 pub mod queue;
+pub mod raid;
+pub mod redis;
+pub mod report;
+pub mod resolver;
+pub mod resolving;
+pub mod scheduler;
+pub mod script;
+pub mod seek;
+pub mod state;
+pub mod store;
+pub mod suggest;
+pub mod temp_voice;
 pub mod template;
-pub mod ytdl;
+pub mod timing;
+pub mod tts;
 pub mod url;
+pub mod vote;
+pub mod watchdog;
+pub mod ytdl;
 
 pub fn get_http_client() -> Client {
     static HTTP_CLIENT: OnceLock<Client> = OnceLock::new();
@@ -31,12 +84,9 @@ pub async fn join_voice_channel(
     // there is some time limit of a command to be response,
     // joining a voice can take time and cause timeout
     // so use defer to prevent timeout
-    ctx.defer().await?;
-    let success = songbird::get(ctx.serenity_context())
-        .await
-        .unwrap()
-        .join(guild_id, channel_id)
-        .await;
+    timed(ctx, Discord, ctx.defer()).await?;
+    let manager = ctx.voice_manager().await?;
+    let success = timed(ctx, Discord, manager.join(guild_id, channel_id)).await;
     match success {
         Ok(call) => {
             ctx.say(TurtoMessage {
@@ -49,3 +99,179 @@ pub async fn join_voice_channel(
         Err(err) => Err(Box::new(err)),
     }
 }
+
+/// The locale of the replies, the preference of the user goes first, then the locale set for the
+/// guild, and then the locale of the user's Discord client
+pub fn get_locale(ctx: Context<'_>) -> Option<&str> {
+    let data = ctx.data();
+    resolve_locale(
+        &data.users,
+        &data.guilds,
+        ctx.author().id,
+        ctx.guild_id(),
+        ctx.locale(),
+    )
+}
+
+/// Same as `get_locale`, for where there is no command context, e.g. in event handlers
+pub fn resolve_locale<'a>(
+    users: &DashMap<UserId, UserPrefs>,
+    guilds: &DashMap<GuildId, GuildData>,
+    user_id: UserId,
+    guild_id: Option<GuildId>,
+    fallback: Option<&'a str>,
+) -> Option<&'a str> {
+    let supported_locale =
+        |locale: &str| get_locales().find(|supported| supported.eq_ignore_ascii_case(locale));
+    let user_locale = users
+        .get(&user_id)
+        .and_then(|prefs| supported_locale(prefs.locale.as_deref()?));
+    let guild_locale = || {
+        let guild_data = guilds.get(&guild_id?)?;
+        supported_locale(guild_data.config.locale.as_deref()?)
+    };
+    user_locale.or_else(guild_locale).or(fallback)
+}
+
+/// The query to pass to yt-dlp, the keywords are searched with the preferred source of the author
+/// if it's not a URL
+pub fn resolve_query(ctx: Context<'_>, query: &str) -> String {
+    resolve_user_query(&ctx.data().users, ctx.author().id, query)
+}
+
+/// Same as `resolve_query`, for where there is no command context, e.g. in event handlers
+pub fn resolve_user_query(
+    users: &DashMap<UserId, UserPrefs>,
+    user_id: UserId,
+    query: &str,
+) -> String {
+    if let Some(url) = parse_web_url(query) {
+        return url.normalize_yt_music().to_string();
+    }
+    let search_source = users
+        .get(&user_id)
+        .map(|prefs| prefs.search_source)
+        .unwrap_or_default();
+    search_source.search_query(query)
+}
+
+/// Reply with the message, the errors are only shown to the author if `ephemeral_replies` is on
+pub async fn say<'a>(
+    ctx: Context<'a>,
+    message: TurtoMessage<'_>,
+) -> Result<ReplyHandle<'a>, serenity::Error> {
+    let ephemeral = get_config().ephemeral_replies && message.kind.is_error();
+    ctx.send(
+        CreateReply::default()
+            .content(message.to_string())
+            .ephemeral(ephemeral),
+    )
+    .await
+}
+
+/// Check if the author is the owner of this bot, reply with a message if not
+pub async fn check_owner(ctx: Context<'_>) -> Result<bool, Error> {
+    let is_owner = get_config().is_owner(&ctx.author().id);
+    if !is_owner {
+        say(
+            ctx,
+            TurtoMessage {
+                locale: get_locale(ctx),
+                kind: OwnerOnly,
+            },
+        )
+        .await?;
+    }
+    Ok(is_owner)
+}
+
+/// Check if the author is an administrator of the guild or the owner of this bot, reply with a
+/// message if not
+pub async fn check_admin(ctx: Context<'_>) -> Result<bool, Error> {
+    let allowed = is_admin(ctx).await;
+    if !allowed {
+        say(
+            ctx,
+            TurtoMessage {
+                locale: get_locale(ctx),
+                kind: AdministratorOnly,
+            },
+        )
+        .await?;
+    }
+    Ok(allowed)
+}
+
+/// Check if the author is a DJ of the guild, see `is_member_dj`
+pub async fn is_guild_dj(ctx: Context<'_>, dj_role: Option<RoleId>) -> bool {
+    ctx.author_member()
+        .await
+        .is_some_and(|member| is_member_dj(&member, None, dj_role))
+}
+
+/// Check if the member has the DJ role, the administrators and the owner of this bot are DJs as
+/// well, and only they are if the role isn't set. The members of the interactions come with their
+/// permissions, the others need the `permissions` in the channel
+pub fn is_member_dj(
+    member: &Member,
+    permissions: Option<Permissions>,
+    dj_role: Option<RoleId>,
+) -> bool {
+    let has_role = dj_role.is_some_and(|dj_role| member.roles.contains(&dj_role));
+    let is_admin = permissions
+        .or(member.permissions)
+        .is_some_and(|permissions| permissions.administrator());
+    has_role || is_admin || get_config().is_owner(&member.user.id)
+}
+
+/// Check if the author is an administrator of the guild or the owner of this bot
+pub async fn is_admin(ctx: Context<'_>) -> bool {
+    let is_admin = ctx
+        .author_member()
+        .await
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.administrator());
+    is_admin || get_config().is_owner(&ctx.author().id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_member_dj, resolve_user_query};
+    use crate::test::setup_config;
+    use dashmap::DashMap;
+    use serenity::all::{Member, Permissions, RoleId, UserId};
+
+    #[test]
+    fn test_is_member_dj() {
+        setup_config();
+        let dj_role = RoleId::new(1);
+        let mut member = Member::default();
+        assert!(!is_member_dj(&member, None, Some(dj_role)));
+        // only the administrators are DJs without the role
+        assert!(!is_member_dj(&member, None, None));
+        assert!(is_member_dj(
+            &member,
+            Some(Permissions::ADMINISTRATOR),
+            None
+        ));
+
+        member.roles.push(dj_role);
+        assert!(is_member_dj(&member, None, Some(dj_role)));
+        assert!(!is_member_dj(&member, None, None));
+    }
+
+    #[test]
+    fn test_resolve_user_query() {
+        let users = DashMap::new();
+        let user_id = UserId::new(1);
+        assert_eq!(
+            resolve_user_query(&users, user_id, "Tiesto: Red Lights"),
+            "ytsearch1:Tiesto: Red Lights"
+        );
+        assert_eq!(
+            resolve_user_query(&users, user_id, "C:\\music\\a.mp3"),
+            "ytsearch1:C:\\music\\a.mp3"
+        );
+        assert!(resolve_user_query(&users, user_id, "https://youtu.be/abc").starts_with("https://"));
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{
+    config::message_template::get_locales,
+    messages::{TurtoMessage, TurtoMessageKind::TrackStartNotify},
+    models::user_prefs::UserPrefs,
+};
+use dashmap::DashMap;
+use serenity::all::{ChannelId, Http, UserId};
+use std::sync::{Arc, OnceLock};
+use tracing::warn;
+
+static NOTIFIER: OnceLock<Notifier> = OnceLock::new();
+
+struct Notifier {
+    http: Arc<Http>,
+    users: Arc<DashMap<UserId, UserPrefs>>,
+}
+
+/// Set up the direct message notifications, must be called once the client is ready
+pub fn init_notify(http: Arc<Http>, users: Arc<DashMap<UserId, UserPrefs>>) {
+    let _ = NOTIFIER.set(Notifier { http, users });
+}
+
+/// Send a direct message to the requester that the track started playing, if it's turned on in
+/// the preferences of the requester
+pub async fn notify_track_start(requester: UserId, title: String, channel_id: ChannelId) {
+    let Some(notifier) = NOTIFIER.get() else {
+        return;
+    };
+    let locale = match notifier.users.get(&requester) {
+        Some(prefs) if prefs.notify_track_start => prefs.locale.clone(),
+        _ => return,
+    };
+    let locale = locale
+        .and_then(|locale| get_locales().find(|supported| supported.eq_ignore_ascii_case(&locale)));
+    let content = TurtoMessage {
+        locale,
+        kind: TrackStartNotify {
+            title: &title,
+            channel_id,
+        },
+    }
+    .to_string();
+
+    let result = match requester.create_dm_channel(&notifier.http).await {
+        Ok(dm_channel) => dm_channel.say(&notifier.http, content).await.map(|_| ()),
+        Err(err) => Err(err),
+    };
+    // the user may have turned off the direct messages from the guild members
+    if let Err(err) = result {
+        warn!(
+            "Failed to notify user {} of the track start: {}",
+            requester, err
+        );
+    }
+}
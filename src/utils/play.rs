@@ -1,10 +1,19 @@
-use super::get_http_client;
+use super::{
+    autoplay::autoplay_next,
+    filter::{filter_chain, filtered_input},
+    get_http_client,
+    tts::{announce_track, tts_enabled},
+};
 use crate::{
-    handlers::track_end::TrackEndHandler,
+    handlers::{
+        ducking::start_ducking,
+        track_end::TrackEndHandler,
+        track_hooks::{track_hooks, TrackInfo},
+    },
     models::{guild::data::GuildData, playing::Playing},
 };
 use dashmap::DashMap;
-use serenity::model::prelude::GuildId;
+use serenity::model::prelude::{ChannelId, GuildId, UserId};
 use songbird::{
     input::{AudioStreamError, AuxMetadata, Compose, Input, LiveInput, YoutubeDl},
     tracks::Track,
@@ -19,9 +28,60 @@ pub async fn play_url(
     guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
     guild_id: GuildId,
     url: impl AsRef<str>,
+    requester: Option<UserId>,
+) -> Result<Arc<AuxMetadata>, AudioStreamError> {
+    start_track(
+        call,
+        guild_data,
+        guild_playing,
+        guild_id,
+        url.as_ref(),
+        requester,
+        false,
+    )
+    .await
+}
+
+/// The track extracted by yt-dlp with the settings of the guild, nothing is played and nothing
+/// about the guild is changed until it's played with `play_prepared`
+pub struct PreparedTrack {
+    url: String,
+    track: Track,
+    meta: Arc<AuxMetadata>,
+    ducking: bool,
+    announcement: Option<(Option<String>, f32)>,
+}
+
+async fn start_track(
+    call: Arc<Mutex<Call>>,
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+    guild_id: GuildId,
+    url: &str,
+    requester: Option<UserId>,
+    queued: bool,
 ) -> Result<Arc<AuxMetadata>, AudioStreamError> {
-    let mut source = YoutubeDl::new(get_http_client(), url.as_ref().to_string());
-    
+    let prepared = prepare_track(&guild_data, guild_id, url).await?;
+    Ok(play_prepared(
+        call,
+        guild_data,
+        guild_playing,
+        guild_id,
+        prepared,
+        requester,
+        queued,
+    )
+    .await)
+}
+
+/// Extract the track to play in the guild, it can be dropped halfway, see `Placeholder::resolve`
+pub async fn prepare_track(
+    guild_data: &DashMap<GuildId, GuildData>,
+    guild_id: GuildId,
+    url: &str,
+) -> Result<PreparedTrack, AudioStreamError> {
+    let mut source = track_source(url);
+
     // If doing this here it will call `YoutubeDl::query` which invoke yt-dlp
     // https://github.com/serenity-rs/songbird/blob/current/src/input/sources/ytdl.rs#L222
     // And since YoutubeDl is lazily instantiated which will become `Input::Lazy`
@@ -31,40 +91,137 @@ pub async fn play_url(
     // This will cause yt-dlp to be invoke twice
     // let meta = Arc::new(source.aux_metadata().await?);
 
-    // So we do it manually
-    // This will make sure the metadata available
-    let audio = source.create_async().await?;
-    let meta = Arc::new(source.aux_metadata().await.unwrap());
-    let input = Input::Live(LiveInput::Raw(audio), Some(Box::new(source)));
+    let (volume, filter_chain, ducking, announcement) = {
+        let guild_data = guild_data.entry(guild_id).or_default();
+        let config = &guild_data.config;
+        (
+            guild_data.track_volume(url),
+            filter_chain(config),
+            config.ducking,
+            // the locale of the guild, there's no author to take the locale from
+            (config.voice_announcements && tts_enabled())
+                .then(|| (config.locale.clone(), *config.volume)),
+        )
+    };
 
-    let volume = guild_data.entry(guild_id).or_default().config.volume;
-    let track = Track::from(input).volume(*volume);
+    let (input, meta) = match filter_chain {
+        // So we do it manually
+        // This will make sure the metadata available
+        None => {
+            let audio = source.create_async().await?;
+            let meta = Arc::new(source.aux_metadata().await?);
+            (Input::Live(LiveInput::Raw(audio), Some(source)), meta)
+        }
+        // the filtered audio comes from ffmpeg, yt-dlp is only asked for the metadata here
+        Some(filter_chain) => {
+            let meta = Arc::new(source.aux_metadata().await?);
+            (filtered_input(url, &filter_chain)?, meta)
+        }
+    };
+
+    let mut track = Track::from(input).volume(volume);
+    if announcement.is_some() {
+        // it's played after the announcement
+        track = track.pause();
+    }
+    Ok(PreparedTrack {
+        url: url.to_owned(),
+        track,
+        meta,
+        ducking,
+        announcement,
+    })
+}
 
-    let track_handle = {
+/// Stop what's playing and play the prepared track instead
+pub async fn play_prepared(
+    call: Arc<Mutex<Call>>,
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+    guild_id: GuildId,
+    prepared: PreparedTrack,
+    requester: Option<UserId>,
+    queued: bool,
+) -> Arc<AuxMetadata> {
+    let PreparedTrack {
+        url,
+        track,
+        meta,
+        ducking,
+        announcement,
+    } = prepared;
+    let url = url.as_str();
+    let (track_handle, channel_id) = {
         let mut call = call.lock().await;
         call.stop();
-        call.play_only(track)
+        let track_handle = call.play_only(track);
+        if ducking {
+            start_ducking(
+                &mut call,
+                guild_data.clone(),
+                guild_id,
+                track_handle.clone(),
+                url.into(),
+            );
+        }
+        (track_handle, call.current_channel())
     };
 
+    let track_info = Arc::new(TrackInfo {
+        guild_id,
+        url: url.into(),
+        requester,
+        metadata: meta.clone(),
+        channel_id: channel_id.map(|channel_id| ChannelId::new(channel_id.0.get())),
+        queued,
+    });
     let track_end_handler = TrackEndHandler {
         guild_data: guild_data.clone(),
         guild_playing: guild_playing.clone(),
         call: call.clone(),
-        url: url.as_ref().into(),
-        guild_id,
+        track: track_info.clone(),
     };
 
+    if let Some((locale, announcement_volume)) = announcement {
+        tokio::spawn(announce_track(
+            call.clone(),
+            track_handle.clone(),
+            guild_id,
+            meta.title.clone().unwrap_or_default(),
+            requester,
+            locale,
+            announcement_volume,
+        ));
+    }
+
     // This is infallible
-    track_handle.add_event(Event::Track(TrackEvent::End), track_end_handler).unwrap();
+    track_handle
+        .add_event(Event::Track(TrackEvent::End), track_end_handler)
+        .unwrap();
     let playing = Playing {
         track_handle,
         metadata: meta.clone(),
+        url: url.into(),
+        requester,
     };
 
     // Update the current track
     let _playing = guild_playing.write().await.insert(guild_id, playing);
 
-    Ok(meta)
+    if let Some(hooks) = track_hooks() {
+        hooks.track_start(&track_info).await;
+    }
+
+    meta
+}
+
+/// The source to play the url with, the generated ones are played in the tests
+fn track_source(url: &str) -> Box<dyn Compose> {
+    #[cfg(test)]
+    if let Some(source) = super::fake_source::FakeSource::parse(url) {
+        return Box::new(source);
+    }
+    Box::new(YoutubeDl::new(get_http_client(), url.to_string()))
 }
 
 pub async fn play_next(
@@ -73,12 +230,42 @@ pub async fn play_next(
     guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
     guild_id: GuildId,
 ) -> Option<Result<Arc<AuxMetadata>, AudioStreamError>> {
-    let next = guild_data.entry(guild_id).or_default().playlist.pop_front();
+    let next = {
+        let mut guild_data = guild_data.entry(guild_id).or_default();
+        let guild_data = &mut *guild_data;
+        // the items queued before they're never played again are skipped
+        let mut next = guild_data.playlist.pop_front();
+        while next
+            .as_ref()
+            .is_some_and(|next| guild_data.never_again.contains(&next.url))
+        {
+            next = guild_data.playlist.pop_front();
+        }
+        next
+    };
+    let next = match next {
+        Some(next) => Some(next),
+        None => autoplay_next(&guild_data, guild_id).await,
+    };
 
     match next {
-        Some(next) => {
-            Some(play_url(call, guild_data, guild_playing, guild_id, next.url).await)
-        }
+        Some(next) => Some(
+            start_track(
+                call,
+                guild_data,
+                guild_playing,
+                guild_id,
+                &next.url,
+                next.requester,
+                true,
+            )
+            .await,
+        ),
         None => None,
     }
-}
\ No newline at end of file
+}
+
+/// Live streams, like the ones on Twitch, have no duration
+pub fn is_live(metadata: &AuxMetadata) -> bool {
+    metadata.duration.is_none()
+}
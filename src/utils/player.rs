@@ -0,0 +1,275 @@
+use crate::{
+    handlers::reaction::ReactionControl,
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{Live, PlayerIdle, PlayerMore, PlayerUpNext},
+    },
+    models::{guild::data::GuildData, playing::Playing, playlist::Playlist, user_prefs::UserPrefs},
+    utils::{play::is_live, resolve_locale},
+};
+use dashmap::{DashMap, DashSet};
+use serenity::{
+    all::{ChannelId, GuildId, Http, MessageId, UserId},
+    builder::{
+        CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateMessage, EditMessage,
+    },
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// The player of a guild is edited at most once in this interval, the changes in between are
+/// shown together
+const PLAYER_EDIT_INTERVAL: Duration = Duration::from_secs(3);
+/// How many upcoming tracks the player shows
+const PREVIEW_LENGTH: usize = 5;
+const PLAYER_BUTTON_PREFIX: &str = "turto_player_";
+
+/// The guilds whose player is out of date
+static PENDING: LazyLock<DashSet<GuildId>> = LazyLock::new(DashSet::new);
+
+/// The custom id of the button of the control on the player
+pub fn player_button_id(control: ReactionControl) -> String {
+    let index = ReactionControl::ALL
+        .iter()
+        .position(|other| *other == control)
+        .unwrap_or_default();
+    format!("{}{}", PLAYER_BUTTON_PREFIX, index)
+}
+
+/// The control of the button on the player, the buttons are handled by the event handler so they
+/// keep working after turto restarts
+pub fn player_control(custom_id: &str) -> Option<ReactionControl> {
+    let index = custom_id
+        .strip_prefix(PLAYER_BUTTON_PREFIX)?
+        .parse::<usize>()
+        .ok()?;
+    ReactionControl::ALL.get(index).copied()
+}
+
+/// Mark the player of the guild to be edited, the edits are batched by `init_player`
+pub fn refresh_player(guild_id: GuildId) {
+    PENDING.insert(guild_id);
+}
+
+/// Start editing the players in the request channels, must be called once the client is ready.
+/// The players are reconciled at first, the stored ones are put up to date and the ones deleted
+/// while turto was offline are posted again
+pub fn init_player(
+    http: Arc<Http>,
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+    users: Arc<DashMap<UserId, UserPrefs>>,
+) {
+    for guild in guild_data.iter() {
+        if guild.config.request_channel.is_some() {
+            refresh_player(*guild.key());
+        }
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PLAYER_EDIT_INTERVAL);
+        loop {
+            interval.tick().await;
+            let pending = PENDING.iter().map(|guild_id| *guild_id).collect::<Vec<_>>();
+            for guild_id in pending {
+                PENDING.remove(&guild_id);
+                update_player(&http, &guild_data, &guild_playing, &users, guild_id).await;
+            }
+        }
+    });
+}
+
+async fn update_player(
+    http: &Http,
+    guild_data: &DashMap<GuildId, GuildData>,
+    guild_playing: &RwLock<HashMap<GuildId, Playing>>,
+    users: &DashMap<UserId, UserPrefs>,
+    guild_id: GuildId,
+) {
+    let (channel_id, message_id) = match guild_data.get(&guild_id) {
+        Some(guild_data) => match guild_data.config.request_channel {
+            Some(channel_id) => (channel_id, guild_data.player_message),
+            None => return,
+        },
+        None => return,
+    };
+    // nobody has invoked anything, so only the locale of the guild is taken
+    let locale = resolve_locale(users, guild_data, UserId::default(), Some(guild_id), None);
+    let embed = {
+        let playing_map = guild_playing.read().await;
+        let Some(guild_data) = guild_data.get(&guild_id) else {
+            return;
+        };
+        player_embed(locale, playing_map.get(&guild_id), &guild_data.playlist)
+    };
+    let components = vec![CreateActionRow::Buttons(
+        ReactionControl::ALL
+            .into_iter()
+            .map(|control| {
+                CreateButton::new(player_button_id(control)).emoji(control.reaction_type())
+            })
+            .collect(),
+    )];
+
+    if let Some(message_id) = message_id {
+        let edited = channel_id
+            .edit_message(
+                http,
+                message_id,
+                EditMessage::new()
+                    .embed(embed.clone())
+                    .components(components.clone()),
+            )
+            .await;
+        // the player is posted again if it's deleted
+        match edited {
+            Ok(_) => return,
+            Err(err) => warn!("Failed to edit the player of guild {}: {}", guild_id, err),
+        }
+    }
+    post_player(http, guild_data, guild_id, channel_id, embed, components).await;
+}
+
+async fn post_player(
+    http: &Http,
+    guild_data: &DashMap<GuildId, GuildData>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    embed: CreateEmbed,
+    components: Vec<CreateActionRow>,
+) {
+    let message = match channel_id
+        .send_message(
+            http,
+            CreateMessage::new().embed(embed).components(components),
+        )
+        .await
+    {
+        Ok(message) => message,
+        Err(err) => {
+            warn!("Failed to post the player of guild {}: {}", guild_id, err);
+            return;
+        }
+    };
+    // it stays unpinned without the Manage Messages permission
+    if let Err(err) = message.pin(http).await {
+        warn!("Failed to pin the player of guild {}: {}", guild_id, err);
+    }
+    set_player_message(guild_data, guild_id, channel_id, message.id);
+}
+
+/// Store the player unless the request channel is changed while it's posted
+fn set_player_message(
+    guild_data: &DashMap<GuildId, GuildData>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    message_id: MessageId,
+) {
+    if let Some(mut guild_data) = guild_data.get_mut(&guild_id) {
+        if guild_data.config.request_channel == Some(channel_id) {
+            guild_data.player_message = Some(message_id);
+        }
+    }
+}
+
+fn player_embed(
+    locale: Option<&str>,
+    playing: Option<&Playing>,
+    playlist: &Playlist,
+) -> CreateEmbed {
+    let message = |kind| TurtoMessage { locale, kind }.to_string();
+    let mut embed = match playing {
+        Some(playing) => {
+            let metadata = &playing.metadata;
+            let mut embed = CreateEmbed::new().title(metadata.title.as_deref().unwrap_or_default());
+            if let Some(url) = &metadata.source_url {
+                embed = embed.url(url);
+            }
+            if let Some(channel) = metadata.artist.as_deref().or(metadata.channel.as_deref()) {
+                embed = embed.description(channel);
+            }
+            if let Some(thumbnail) = &metadata.thumbnail {
+                embed = embed.thumbnail(thumbnail);
+            }
+            if is_live(metadata) {
+                embed = embed.footer(CreateEmbedFooter::new(message(Live)));
+            }
+            embed
+        }
+        None => CreateEmbed::new().title(message(PlayerIdle)),
+    };
+    if let Some(preview) = queue_preview(locale, playlist) {
+        embed = embed.field(message(PlayerUpNext), preview, false);
+    }
+    embed
+}
+
+/// The upcoming tracks numbered like `/playlist`, the rest are counted
+fn queue_preview(locale: Option<&str>, playlist: &Playlist) -> Option<String> {
+    if playlist.is_empty() {
+        return None;
+    }
+    let mut preview = playlist
+        .iter()
+        .take(PREVIEW_LENGTH)
+        .enumerate()
+        .map(|(index, item)| format!("{}. {}", index + 1, item.title))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let more = playlist.len().saturating_sub(PREVIEW_LENGTH);
+    if more > 0 {
+        preview.push('\n');
+        preview.push_str(
+            &TurtoMessage {
+                locale,
+                kind: PlayerMore { count: more },
+            }
+            .to_string(),
+        );
+    }
+    Some(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{player_button_id, player_control, queue_preview};
+    use crate::{
+        handlers::reaction::ReactionControl,
+        models::{playlist::Playlist, playlist_item::PlaylistItem},
+        test::setup_templates,
+    };
+    use songbird::input::AuxMetadata;
+
+    #[test]
+    fn test_queue_preview() {
+        setup_templates();
+        let mut playlist = Playlist::new();
+        assert_eq!(queue_preview(None, &playlist), None);
+
+        playlist.extend((1..=7).map(|index| {
+            PlaylistItem::from(AuxMetadata {
+                title: Some(format!("track {}", index)),
+                ..Default::default()
+            })
+        }));
+        let preview = queue_preview(None, &playlist).unwrap();
+        let lines = preview.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "1. track 1");
+        assert_eq!(lines[4], "5. track 5");
+        assert!(lines[5].contains('2'));
+    }
+
+    #[test]
+    fn test_player_buttons() {
+        for control in ReactionControl::ALL {
+            assert_eq!(player_control(&player_button_id(control)), Some(control));
+        }
+        assert_eq!(player_control("turto_bookmark"), None);
+        assert_eq!(player_control("turto_player_9"), None);
+    }
+}
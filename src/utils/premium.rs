@@ -0,0 +1,209 @@
+use crate::{
+    config::get_config,
+    messages::{TurtoMessage, TurtoMessageKind::PremiumRequired},
+    models::{
+        alias::{Context, Error},
+        premium::Feature,
+    },
+    utils::{get_http_client, get_locale, say},
+};
+use anyhow::{Context as _, Result};
+use dashmap::DashMap;
+use serde::Deserialize;
+use serenity::{
+    all::{Cache, ChannelId, GuildId},
+    async_trait,
+};
+use songbird::{constants::DEFAULT_BITRATE, driver::Bitrate};
+use std::{
+    collections::HashSet,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+static PROVIDERS: OnceLock<Vec<Box<dyn EntitlementProvider>>> = OnceLock::new();
+
+// how long the entitlements from the external service are reused
+const ENTITLEMENT_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// A source of the premium features a guild is entitled to
+#[async_trait]
+pub trait EntitlementProvider: Send + Sync {
+    async fn features(&self, guild_id: GuildId) -> Result<HashSet<Feature>>;
+}
+
+/// The guilds listed in `premium_guilds` are entitled to every feature
+pub struct ConfigProvider;
+
+#[async_trait]
+impl EntitlementProvider for ConfigProvider {
+    async fn features(&self, guild_id: GuildId) -> Result<HashSet<Feature>> {
+        let config = get_config();
+        if config.premium_guilds.contains(&guild_id) {
+            Ok(config.premium_features.clone())
+        } else {
+            Ok(HashSet::new())
+        }
+    }
+}
+
+/// Ask the external service at `entitlement_url` for the features of a guild
+pub struct HttpProvider {
+    url: String,
+    cache: DashMap<GuildId, (Instant, HashSet<Feature>)>,
+}
+
+#[derive(Deserialize)]
+struct EntitlementResponse {
+    #[serde(default)]
+    features: HashSet<Feature>,
+}
+
+impl HttpProvider {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            cache: DashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl EntitlementProvider for HttpProvider {
+    async fn features(&self, guild_id: GuildId) -> Result<HashSet<Feature>> {
+        if let Some(cached) = self.cache.get(&guild_id) {
+            let (fetched_at, features) = cached.value();
+            if fetched_at.elapsed() < ENTITLEMENT_CACHE_TTL {
+                return Ok(features.clone());
+            }
+        }
+
+        let body = get_http_client()
+            .get(&self.url)
+            .query(&[("guild_id", guild_id.to_string())])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context(format!(
+                "Failed to request the entitlements from {}",
+                self.url
+            ))?
+            .text()
+            .await?;
+        let features = parse_entitlements(&body)?;
+        self.cache
+            .insert(guild_id, (Instant::now(), features.clone()));
+        Ok(features)
+    }
+}
+
+fn parse_entitlements(body: &str) -> Result<HashSet<Feature>> {
+    let response = serde_json::from_str::<EntitlementResponse>(body)
+        .context("Invalid response from the entitlement service")?;
+    Ok(response.features)
+}
+
+fn providers() -> &'static [Box<dyn EntitlementProvider>] {
+    PROVIDERS.get_or_init(|| {
+        let mut providers: Vec<Box<dyn EntitlementProvider>> = vec![Box::new(ConfigProvider)];
+        if let Some(url) = &get_config().entitlement_url {
+            providers.push(Box::new(HttpProvider::new(url.clone())));
+        }
+        providers
+    })
+}
+
+/// Check if the guild can use the feature, the features not listed in `premium_features` are
+/// available to every guild
+pub async fn has_feature(guild_id: GuildId, feature: Feature) -> bool {
+    if !get_config().premium_features.contains(&feature) {
+        return true;
+    }
+    for provider in providers() {
+        match provider.features(guild_id).await {
+            Ok(features) if features.contains(&feature) => return true,
+            Ok(_) => {}
+            // the feature is denied if the entitlements can't be checked
+            Err(err) => warn!(
+                "Failed to check the entitlements of guild {}: {:#}",
+                guild_id, err
+            ),
+        }
+    }
+    false
+}
+
+/// Check if the feature is available in the guild of the context, reply with a message if not
+pub async fn check_feature(ctx: Context<'_>, feature: Feature) -> Result<bool, Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(true);
+    };
+    let available = has_feature(guild_id, feature).await;
+    if !available {
+        say(
+            ctx,
+            TurtoMessage {
+                locale: get_locale(ctx),
+                kind: PremiumRequired(feature),
+            },
+        )
+        .await?;
+    }
+    Ok(available)
+}
+
+/// The bitrate to play at in the voice channel, the guilds with `high_bitrate` match the bitrate
+/// of the channel when it's higher than the default one
+pub async fn voice_bitrate(cache: &Cache, guild_id: GuildId, channel_id: ChannelId) -> Bitrate {
+    if !has_feature(guild_id, Feature::HighBitrate).await {
+        return DEFAULT_BITRATE;
+    }
+    let channel_bitrate = cache.guild(guild_id).and_then(|guild| {
+        guild
+            .channels
+            .get(&channel_id)
+            .and_then(|channel| channel.bitrate)
+    });
+    higher_bitrate(channel_bitrate)
+}
+
+fn higher_bitrate(channel_bitrate: Option<u32>) -> Bitrate {
+    match (channel_bitrate, DEFAULT_BITRATE) {
+        (Some(channel_bitrate), Bitrate::BitsPerSecond(default))
+            if channel_bitrate as i32 > default =>
+        {
+            Bitrate::BitsPerSecond(channel_bitrate as i32)
+        }
+        _ => DEFAULT_BITRATE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{higher_bitrate, parse_entitlements};
+    use crate::models::premium::Feature;
+    use songbird::{constants::DEFAULT_BITRATE, driver::Bitrate};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_parse_entitlements() {
+        let features = parse_entitlements(r#"{"features": ["filters", "always_on"]}"#).unwrap();
+        assert_eq!(
+            features,
+            HashSet::from([Feature::Filters, Feature::AlwaysOn])
+        );
+        assert!(parse_entitlements("{}").unwrap().is_empty());
+        assert!(parse_entitlements(r#"{"features": ["unknown"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_higher_bitrate() {
+        assert_eq!(higher_bitrate(None), DEFAULT_BITRATE);
+        assert_eq!(higher_bitrate(Some(64_000)), DEFAULT_BITRATE);
+        assert_eq!(
+            higher_bitrate(Some(384_000)),
+            Bitrate::BitsPerSecond(384_000)
+        );
+    }
+}
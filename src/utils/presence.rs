@@ -0,0 +1,75 @@
+use super::metrics::METRICS;
+use crate::{config::get_config, models::playing::Playing};
+use serenity::all::{ActivityData, Cache, GuildId, ShardManager};
+use std::{
+    collections::HashMap,
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
+use tokio::sync::RwLock;
+
+/// The statuses in `activities` with the placeholders filled in, `{guilds}` is the number of the
+/// guilds turto is in and `{tracks_played}` is the number of the tracks played since it started
+pub fn render_activity(activity: &str, guilds: usize, tracks_played: u64) -> String {
+    activity
+        .replace("{guilds}", &guilds.to_string())
+        .replace("{tracks_played}", &tracks_played.to_string())
+}
+
+/// The first status of `activities`, which is shown when a shard is ready
+pub fn initial_activity(cache: &Cache) -> Option<ActivityData> {
+    let activity = get_config().activities.first()?;
+    let tracks_played = METRICS.tracks_started.load(Ordering::Relaxed);
+    Some(ActivityData::listening(render_activity(
+        activity,
+        cache.guild_count(),
+        tracks_played,
+    )))
+}
+
+/// Show the next status of `activities` every `activity_interval` seconds on every shard, the
+/// rotation is paused while anything is playing
+pub async fn presence_rotator(
+    shard_manager: Arc<ShardManager>,
+    cache: Arc<Cache>,
+    guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+) {
+    let activities = &get_config().activities;
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(get_config().activity_interval.max(1)));
+    // the first one is set by the ready handler
+    interval.tick().await;
+    let mut next = 1;
+    loop {
+        interval.tick().await;
+        if !guild_playing.read().await.is_empty() {
+            continue;
+        }
+        let tracks_played = METRICS.tracks_started.load(Ordering::Relaxed);
+        let activity = render_activity(
+            &activities[next % activities.len()],
+            cache.guild_count(),
+            tracks_played,
+        );
+        for runner in shard_manager.runners.lock().await.values() {
+            runner
+                .runner_tx
+                .set_activity(Some(ActivityData::listening(activity.clone())));
+        }
+        next = (next + 1) % activities.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_activity;
+
+    #[test]
+    fn test_render_activity() {
+        assert_eq!(
+            render_activity("{tracks_played} tracks in {guilds} guilds", 12, 345),
+            "345 tracks in 12 guilds"
+        );
+        assert_eq!(render_activity("/help", 12, 345), "/help");
+    }
+}
@@ -1,76 +1,396 @@
 use crate::{
+    config::get_config,
     messages::{
         TurtoMessage,
-        TurtoMessageKind::{InvalidUrl, Queue},
+        TurtoMessageKind::{ExplicitFiltered, Queue},
     },
     models::{
         alias::{Context, Error},
+        error::TurtoError,
+        guild::data::GuildData,
+        invocation::Phase::Ytdl,
+        playlist_range::PlaylistRange,
         queue_item::{QueueItem, QueueItemKind},
     },
+    utils::{
+        approval::{needs_approval, pend_request},
+        batch::{coalesce, is_batching},
+        context::TurtoContext,
+        explicit::{filter_explicit, should_filter},
+        raid::detect_raid,
+        resolve_query,
+        resolving::Placeholder,
+        timing::timed,
+    },
 };
-use std::mem::replace;
-use url::Url;
+use dashmap::DashMap;
+use serenity::all::{GuildId, RoleId, UserId};
+use std::{mem::replace, time::Duration};
+use tracing::warn;
 
 pub enum QueueType {
     Front,
     Back,
 }
 
-pub async fn enqueue(ctx: Context<'_>, query: String, queue_type: QueueType) -> Result<(), Error> {
-    let locale = ctx.locale();
-    let Ok(parsed) = Url::parse(&query) else {
-        ctx.say(TurtoMessage {
-            locale,
-            kind: InvalidUrl(None),
-        })
-        .await?;
+/// Resolve the query and add it to the playlist, `items` is the part of a playlist to queue, like
+/// `5-20`. The placeholder replied right away is edited into the result, the confirmations of the
+/// tracks queued in quick succession are coalesced into one summary
+pub async fn enqueue(
+    ctx: Context<'_>,
+    query: String,
+    items: Option<String>,
+    queue_type: QueueType,
+) -> Result<(), Error> {
+    let range = items
+        .map(|items| items.parse::<PlaylistRange>())
+        .transpose()?;
+    let queue_item = QueueItem::new(resolve_query(ctx, &query)).range(range);
+    let filter = should_filter(ctx).await;
+    let approval = needs_approval(ctx).await;
+
+    let resolution = async {
+        let Ok(queue_item_kind) = timed(ctx, Ytdl, queue_item.query()).await else {
+            return Err(TurtoError::ExtractionFailed(query.clone()));
+        };
+        if !filter {
+            return Ok((queue_item_kind, 0));
+        }
+        match timed(ctx, Ytdl, filter_explicit(queue_item_kind)).await {
+            (Some(queue_item_kind), removed) => Ok((queue_item_kind, removed)),
+            (None, _) => Err(TurtoError::ExplicitRejected),
+        }
+    };
+    // the confirmation is folded into the summary, so only the author sees the track is queued
+    let placeholder = Placeholder::send(ctx, &query, is_batching(ctx)).await?;
+    // nothing is queued if it's canceled
+    let Some(resolved) = placeholder.resolve(resolution).await? else {
         return Ok(());
     };
-    ctx.defer().await?;
+    let queued = match resolved {
+        Ok((queue_item_kind, _)) if approval => {
+            return pend_request(ctx, placeholder, queue_item_kind, queue_type).await;
+        }
+        Ok((queue_item_kind, removed)) => {
+            let priority = author_priority(ctx).await;
+            queue_resolved(&placeholder, queue_item_kind, priority, removed, queue_type).await
+        }
+        Err(err) => Err(err.into()),
+    };
+    match queued {
+        Ok(title) => {
+            coalesce(ctx, &placeholder, title).await;
+            Ok(())
+        }
+        Err(err) => placeholder.fail(err).await,
+    }
+}
 
-    let queue_item = QueueItem::new(parsed);
+/// Add the resolved item to the playlist and reply with its title, which is returned, `removed` is
+/// the number of the explicit items filtered out of it
+pub async fn queue_resolved(
+    ctx: &impl TurtoContext,
+    queue_item_kind: QueueItemKind,
+    priority: u32,
+    removed: usize,
+    queue_type: QueueType,
+) -> Result<String, Error> {
+    let locale = ctx.locale();
+    let added = add_to_playlist(
+        &ctx.data().guilds,
+        ctx.guild_id().ok_or(TurtoError::NotInGuild)?,
+        queue_item_kind,
+        priority,
+        ctx.author_id(),
+        queue_type,
+    );
+    let title = added?;
 
-    let Ok(queue_item_kind) = queue_item.query().await else {
-        ctx.say(TurtoMessage {
-            locale,
-            kind: InvalidUrl(Some(&query)),
-        })
-        .await?;
-        return Ok(());
-    };
+    let mut response = TurtoMessage {
+        locale,
+        kind: Queue { title: &title },
+    }
+    .to_string();
+    if removed > 0 {
+        response.push('\n');
+        response.push_str(
+            &TurtoMessage {
+                locale,
+                kind: ExplicitFiltered { count: removed },
+            }
+            .to_string(),
+        );
+    }
+    ctx.send(response, false).await?;
+    Ok(title)
+}
 
-    let guild_id = ctx.guild_id().unwrap();
-    let mut guild_data = ctx.data().guilds.entry(guild_id).or_default();
+/// Add the item to the playlist of the guild with the priority of the requester and return its
+/// title, it fails if the playlist is full or the track is never played again in the guild. The
+/// tracks of a playlist which are never played again are left out, and so are the ones exceeding
+/// the limits of the guild
+pub fn add_to_playlist(
+    guilds: &DashMap<GuildId, GuildData>,
+    guild_id: GuildId,
+    queue_item_kind: QueueItemKind,
+    priority: u32,
+    requester: UserId,
+    queue_type: QueueType,
+) -> Result<String, TurtoError> {
+    let mut guild_data = guilds.entry(guild_id).or_default();
+    let room = match guild_data.config.queue_limit {
+        Some(limit) if guild_data.playlist.len() >= limit => {
+            return Err(TurtoError::QueueFull { limit })
+        }
+        Some(limit) => limit - guild_data.playlist.len(),
+        None => usize::MAX,
+    };
+    // the DJs and the administrators can still queue while it's locked
+    if !guild_data.is_queue_locked() {
+        let (url, longest) = match &queue_item_kind {
+            QueueItemKind::Single(playlist_item) => {
+                (playlist_item.url.clone(), playlist_item.duration)
+            }
+            QueueItemKind::Playlist(yt_playlist) => {
+                let url = yt_playlist
+                    .url
+                    .clone()
+                    .or_else(|| yt_playlist.title.clone());
+                let longest = yt_playlist
+                    .iter()
+                    .map(|playlist_item| playlist_item.duration)
+                    .max();
+                (url.unwrap_or_default(), longest.unwrap_or_default())
+            }
+        };
+        if let Some(queue_lock) = detect_raid(guild_id, requester, &url, longest) {
+            warn!(
+                "Locked the playlist of guild {} for {:?}",
+                guild_id, queue_lock.reason
+            );
+            guild_data.queue_lock = Some(queue_lock);
+            return Err(TurtoError::QueueLocked);
+        }
+    }
 
     let title = match queue_item_kind {
-        QueueItemKind::Single(playlist_item) => {
+        QueueItemKind::Single(playlist_item)
+            if guild_data.never_again.contains(&playlist_item.url) =>
+        {
+            return Err(TurtoError::NeverAgain);
+        }
+        QueueItemKind::Single(mut playlist_item) => {
+            check_duration(&guild_data, requester, playlist_item.duration)?;
             let title = playlist_item.title.clone();
+            playlist_item.priority = priority;
+            playlist_item.requester = Some(requester);
             match queue_type {
                 QueueType::Front => guild_data.playlist.push_front(playlist_item),
-                QueueType::Back => guild_data.playlist.push_back(playlist_item),
+                QueueType::Back => {
+                    let index = guild_data.playlist.priority_index(priority);
+                    guild_data.playlist.insert(index, playlist_item);
+                }
             }
-            drop(guild_data);
             title
         }
         QueueItemKind::Playlist(mut yt_playlist) => {
             let title = yt_playlist.title.take().unwrap_or_default();
+            yt_playlist
+                .retain(|playlist_item| !guild_data.never_again.contains(&playlist_item.url));
+            // the items exceeding the limit of the playlist are dropped
+            yt_playlist.truncate(room);
+            let mut duration = Duration::ZERO;
+            let fitting = yt_playlist
+                .iter()
+                .take_while(|playlist_item| {
+                    duration += playlist_item.duration;
+                    check_duration(&guild_data, requester, duration).is_ok()
+                })
+                .count();
+            if fitting == 0 {
+                if let Some(first) = yt_playlist.first() {
+                    check_duration(&guild_data, requester, first.duration)?;
+                }
+            }
+            yt_playlist.truncate(fitting);
+            for playlist_item in yt_playlist.iter_mut() {
+                playlist_item.priority = priority;
+                playlist_item.requester = Some(requester);
+            }
             match queue_type {
                 QueueType::Front => {
                     let new_playlist = yt_playlist.into_playlist();
                     let tail = replace(&mut guild_data.playlist, new_playlist);
                     guild_data.playlist.extend(tail);
                 }
-                QueueType::Back => guild_data.playlist.extend(yt_playlist),
+                QueueType::Back => {
+                    let index = guild_data.playlist.priority_index(priority);
+                    let tail = guild_data.playlist.split_off(index);
+                    guild_data.playlist.extend(yt_playlist);
+                    guild_data.playlist.extend(tail);
+                }
             }
-            drop(guild_data);
             title
         }
     };
+    Ok(title)
+}
 
-    ctx.say(TurtoMessage {
-        locale,
-        kind: Queue { title: &title },
-    })
-    .await?;
+/// Whether the tracks this long can be queued by the requester under `queue_duration_limit` and
+/// `user_duration_limit` of the guild, the error tells how much room is left. The live streams
+/// and the tracks of unknown durations always fit
+fn check_duration(
+    guild_data: &GuildData,
+    requester: UserId,
+    duration: Duration,
+) -> Result<(), TurtoError> {
+    let config = &guild_data.config;
+    let queued = |of_requester: bool| {
+        guild_data
+            .playlist
+            .iter()
+            .filter(|playlist_item| !of_requester || playlist_item.requester == Some(requester))
+            .map(|playlist_item| playlist_item.duration)
+            .sum::<Duration>()
+    };
+    if let Some(limit) = config.queue_duration_limit {
+        let limit = Duration::from_secs(limit * 60);
+        let left = limit.saturating_sub(queued(false));
+        if duration > left {
+            return Err(TurtoError::QueueDurationFull { limit, left });
+        }
+    }
+    if let Some(limit) = config.user_duration_limit {
+        let limit = Duration::from_secs(limit * 60);
+        let left = limit.saturating_sub(queued(true));
+        if duration > left {
+            return Err(TurtoError::UserDurationFull { limit, left });
+        }
+    }
     Ok(())
 }
+
+/// The priority of the author is the highest one among the roles of the author in `role_priority`
+pub async fn author_priority(ctx: Context<'_>) -> u32 {
+    if get_config().role_priority.is_empty() {
+        return 0;
+    }
+    ctx.author_member()
+        .await
+        .map(|member| roles_priority(&member.roles))
+        .unwrap_or_default()
+}
+
+/// The highest priority among the roles in `role_priority`
+pub fn roles_priority(roles: &[RoleId]) -> u32 {
+    let role_priority = &get_config().role_priority;
+    roles
+        .iter()
+        .filter_map(|role_id| role_priority.get(role_id))
+        .max()
+        .copied()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_to_playlist, queue_resolved, QueueType};
+    use crate::{
+        models::{
+            error::TurtoError, guild::data::GuildData, playlist_item::PlaylistItem,
+            queue_item::QueueItemKind, youtube_playlist::YouTubePlaylist,
+        },
+        test::{setup_config, MockContext},
+    };
+    use dashmap::DashMap;
+    use serenity::all::{GuildId, UserId};
+    use songbird::input::AuxMetadata;
+    use std::time::Duration;
+
+    fn item(title: &str) -> QueueItemKind {
+        QueueItemKind::Single(PlaylistItem::from(AuxMetadata {
+            title: Some(title.to_owned()),
+            ..Default::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_queue_resolved() {
+        let ctx = MockContext::default();
+        ctx.data
+            .guilds
+            .entry(ctx.guild_id)
+            .or_default()
+            .config
+            .queue_limit = Some(2);
+
+        queue_resolved(&ctx, item("back"), 0, 0, QueueType::Back)
+            .await
+            .unwrap();
+        queue_resolved(&ctx, item("front"), 0, 2, QueueType::Front)
+            .await
+            .unwrap();
+        let full = queue_resolved(&ctx, item("full"), 0, 0, QueueType::Back)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            full.downcast_ref::<TurtoError>(),
+            Some(TurtoError::QueueFull { limit: 2 })
+        ));
+
+        let guild_data = ctx.data.guilds.get(&ctx.guild_id).unwrap();
+        let titles = guild_data
+            .playlist
+            .iter()
+            .map(|item| item.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, ["front", "back"]);
+        assert!(guild_data
+            .playlist
+            .iter()
+            .all(|item| item.requester == Some(ctx.author_id)));
+        drop(guild_data);
+        assert_eq!(
+            ctx.replies(),
+            [
+                ("✅ back".to_owned(), false),
+                ("✅ front\n2 explicit items are skipped.".to_owned(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duration_limits() {
+        setup_config();
+        let track = |minutes: u64| {
+            PlaylistItem::from(AuxMetadata {
+                duration: Some(Duration::from_secs(minutes * 60)),
+                ..Default::default()
+            })
+        };
+        let (guilds, guild_id) = (DashMap::new(), GuildId::new(1));
+        let (member, other) = (UserId::new(2), UserId::new(3));
+        let mut guild_data = GuildData::default();
+        guild_data.config.queue_duration_limit = Some(60);
+        guild_data.config.user_duration_limit = Some(30);
+        guilds.insert(guild_id, guild_data);
+        let add = |item, requester| {
+            add_to_playlist(&guilds, guild_id, item, 0, requester, QueueType::Back)
+        };
+
+        assert!(add(QueueItemKind::Single(track(20)), member).is_ok());
+        assert!(matches!(
+            add(QueueItemKind::Single(track(20)), member),
+            Err(TurtoError::UserDurationFull { left, .. }) if left == Duration::from_secs(600)
+        ));
+        // the tracks of the playlist are queued until the room runs out
+        let mut yt_playlist = YouTubePlaylist::default();
+        yt_playlist.extend([track(15), track(15), track(15)]);
+        assert!(add(QueueItemKind::Playlist(yt_playlist), other).is_ok());
+        assert_eq!(guilds.get(&guild_id).unwrap().playlist.len(), 3);
+        assert!(matches!(
+            add(QueueItemKind::Single(track(30)), UserId::new(4)),
+            Err(TurtoError::QueueDurationFull { left, .. }) if left == Duration::from_secs(600)
+        ));
+    }
+}
@@ -0,0 +1,157 @@
+use super::misc::unix_now;
+use crate::{
+    config::get_config,
+    models::{
+        config::AntiRaid,
+        guild::queue_lock::{LockReason, QueueLock},
+    },
+};
+use dashmap::DashMap;
+use serenity::all::{GuildId, UserId};
+use std::{
+    collections::VecDeque,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+/// The tracks queued recently in each guild, the raid protection only looks at the ones in the
+/// window
+static RECENT: OnceLock<DashMap<GuildId, VecDeque<Enqueue>>> = OnceLock::new();
+
+struct Enqueue {
+    at: Instant,
+    url: String,
+    /// The account of the requester is created in `new_account_days`
+    new_account: bool,
+}
+
+/// Record the track queued by the requester, and return the lock of the playlist if it looks like
+/// a raid, see `anti_raid` in the config. `url` is what's queued, `longest` is the duration of the
+/// longest track of it
+pub fn detect_raid(
+    guild_id: GuildId,
+    requester: UserId,
+    url: &str,
+    longest: Duration,
+) -> Option<QueueLock> {
+    let anti_raid = get_config().anti_raid.as_ref()?;
+    let now = unix_now();
+    let age = now.saturating_sub(requester.created_at().unix_timestamp().max(0) as u64);
+    let enqueue = Enqueue {
+        at: Instant::now(),
+        url: url.to_owned(),
+        new_account: age < anti_raid.new_account_days * 24 * 60 * 60,
+    };
+    let recent = RECENT.get_or_init(DashMap::new);
+    let mut recent = recent.entry(guild_id).or_default();
+    let reason = check(anti_raid, &mut recent, enqueue, longest)?;
+    // the enqueues before the lock aren't counted again after it's lifted
+    recent.clear();
+    Some(QueueLock {
+        reason,
+        until: Some(now + anti_raid.lock_minutes * 60),
+    })
+}
+
+fn check(
+    anti_raid: &AntiRaid,
+    recent: &mut VecDeque<Enqueue>,
+    enqueue: Enqueue,
+    longest: Duration,
+) -> Option<LockReason> {
+    let window = Duration::from_secs(anti_raid.window);
+    while recent
+        .front()
+        .is_some_and(|oldest| enqueue.at.duration_since(oldest.at) > window)
+    {
+        recent.pop_front();
+    }
+    recent.push_back(enqueue);
+    let latest = recent.back()?;
+
+    if longest > Duration::from_secs(anti_raid.long_track_hours * 60 * 60) {
+        return Some(LockReason::LongTrack);
+    }
+    let repeated = recent
+        .iter()
+        .filter(|queued| queued.url == latest.url)
+        .count();
+    if repeated >= anti_raid.repeated_enqueues {
+        return Some(LockReason::RepeatedUrl);
+    }
+    let from_new_accounts = recent.iter().filter(|queued| queued.new_account).count();
+    if latest.new_account && from_new_accounts >= anti_raid.new_account_enqueues {
+        return Some(LockReason::NewAccounts);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, Enqueue};
+    use crate::models::{config::AntiRaid, guild::queue_lock::LockReason};
+    use std::{
+        collections::VecDeque,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn test_check() {
+        let anti_raid = toml::from_str::<AntiRaid>("").unwrap();
+        let start = Instant::now();
+        let enqueue = |secs, url: &str, new_account| Enqueue {
+            at: start + Duration::from_secs(secs),
+            url: url.to_owned(),
+            new_account,
+        };
+        let minutes = |minutes: u64| Duration::from_secs(minutes * 60);
+
+        let mut recent = VecDeque::new();
+        assert_eq!(
+            check(&anti_raid, &mut recent, enqueue(0, "a", false), minutes(3)),
+            None
+        );
+        assert_eq!(
+            check(&anti_raid, &mut recent, enqueue(1, "a", false), minutes(3)),
+            None
+        );
+        assert_eq!(
+            check(&anti_raid, &mut recent, enqueue(2, "a", false), minutes(3)),
+            Some(LockReason::RepeatedUrl)
+        );
+        // the enqueues out of the window are forgotten
+        let mut recent = VecDeque::new();
+        for (secs, url) in [(0, "a"), (100, "a"), (200, "a")] {
+            assert_eq!(
+                check(
+                    &anti_raid,
+                    &mut recent,
+                    enqueue(secs, url, false),
+                    minutes(3)
+                ),
+                None
+            );
+        }
+
+        let mut recent = VecDeque::new();
+        for url in ["a", "b", "c", "d"] {
+            assert_eq!(
+                check(&anti_raid, &mut recent, enqueue(0, url, true), minutes(3)),
+                None
+            );
+        }
+        assert_eq!(
+            check(&anti_raid, &mut recent, enqueue(0, "e", true), minutes(3)),
+            Some(LockReason::NewAccounts)
+        );
+        assert_eq!(
+            check(
+                &anti_raid,
+                &mut VecDeque::new(),
+                enqueue(0, "f", false),
+                minutes(11 * 60)
+            ),
+            Some(LockReason::LongTrack)
+        );
+    }
+}
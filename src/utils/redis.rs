@@ -0,0 +1,272 @@
+use super::{guild::parse_guild_id, store::GuildStore};
+use crate::models::guild::data::GuildData;
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use serenity::{all::GuildId, async_trait};
+use std::time::Duration;
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufStream},
+    net::TcpStream,
+    sync::Mutex,
+};
+use tracing::warn;
+
+/// The hash of the data of the guilds, by the guild ids
+const GUILDS_KEY: &str = "turto:guilds";
+const REDIS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The store of the data of the guilds in Redis, see `redis_address` in the config
+pub struct Redis {
+    address: String,
+    /// The arguments of `AUTH`, empty if there's no password
+    auth: Vec<String>,
+    connection: Mutex<Option<BufStream<TcpStream>>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Reply {
+    Nil,
+    Integer(i64),
+    Data(Vec<u8>),
+    Array(Vec<Reply>),
+}
+
+#[async_trait]
+impl GuildStore for Redis {
+    async fn load_all(&self) -> Result<Vec<(GuildId, GuildData)>> {
+        let reply = self.command(&["HGETALL", GUILDS_KEY]).await?;
+        let Reply::Array(fields) = reply else {
+            return Err(anyhow!("Unexpected reply {:?}", reply));
+        };
+        let mut guilds = Vec::new();
+        for field in fields.chunks_exact(2) {
+            let [Reply::Data(guild_id), Reply::Data(json)] = field else {
+                continue;
+            };
+            match parse_guild(guild_id, json) {
+                Some(guild) => guilds.push(guild),
+                None => warn!("Invalid guild data in Redis"),
+            }
+        }
+        Ok(guilds)
+    }
+
+    async fn load(&self, guild_id: GuildId) -> Result<Option<GuildData>> {
+        let field = guild_id.to_string();
+        match self.command(&["HGET", GUILDS_KEY, &field]).await? {
+            Reply::Data(json) => match parse_guild(field.as_bytes(), &json) {
+                Some((_, guild_data)) => Ok(Some(guild_data)),
+                None => Err(anyhow!("Invalid data of guild {} in Redis", guild_id)),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    async fn save(&self, guilds: &[(GuildId, Value)]) -> Result<()> {
+        let fields = guilds
+            .iter()
+            .map(|(guild_id, guild_data)| (guild_id.to_string(), guild_data.to_string()))
+            .collect::<Vec<_>>();
+        let mut args = vec!["HSET", GUILDS_KEY];
+        for (guild_id, json) in &fields {
+            args.push(guild_id);
+            args.push(json);
+        }
+        self.command(&args).await?;
+        Ok(())
+    }
+
+    async fn guild_ids(&self) -> Result<Vec<GuildId>> {
+        let Reply::Array(fields) = self.command(&["HKEYS", GUILDS_KEY]).await? else {
+            return Ok(Vec::new());
+        };
+        Ok(fields
+            .iter()
+            .filter_map(|field| match field {
+                Reply::Data(field) => std::str::from_utf8(field).ok().and_then(parse_guild_id),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn remove(&self, guild_ids: &[GuildId]) -> Result<()> {
+        let fields = guild_ids.iter().map(GuildId::to_string).collect::<Vec<_>>();
+        let mut args = vec!["HDEL", GUILDS_KEY];
+        args.extend(fields.iter().map(String::as_str));
+        self.command(&args).await?;
+        Ok(())
+    }
+}
+
+impl Redis {
+    /// The Redis at the address, like `redis://:password@host:port`
+    pub fn new(address: &str) -> Self {
+        let (address, auth) = parse_address(address);
+        Self {
+            address,
+            auth,
+            connection: Mutex::new(None),
+        }
+    }
+
+    /// Send the command, the connection is made again if it's broken
+    async fn command(&self, args: &[&str]) -> Result<Reply> {
+        let mut connection = self.connection.lock().await;
+        let reply = tokio::time::timeout(REDIS_TIMEOUT, async {
+            if connection.is_none() {
+                *connection = Some(self.connect().await?);
+            }
+            let stream = connection.as_mut().unwrap();
+            stream.write_all(&encode(args)).await?;
+            stream.flush().await?;
+            read_reply(stream).await
+        })
+        .await
+        .unwrap_or_else(|_| Err(anyhow!("Timed out")));
+        if reply.is_err() {
+            // the replies may be out of step now
+            *connection = None;
+        }
+        reply.context(format!("Failed to send {} to Redis", args[0]))
+    }
+
+    async fn connect(&self) -> Result<BufStream<TcpStream>> {
+        let mut stream = BufStream::new(TcpStream::connect(&self.address).await?);
+        if !self.auth.is_empty() {
+            let mut args = vec!["AUTH"];
+            args.extend(self.auth.iter().map(String::as_str));
+            stream.write_all(&encode(&args)).await?;
+            stream.flush().await?;
+            read_reply(&mut stream).await?;
+        }
+        Ok(stream)
+    }
+}
+
+fn parse_guild(guild_id: &[u8], json: &[u8]) -> Option<(GuildId, GuildData)> {
+    let guild_id = parse_guild_id(std::str::from_utf8(guild_id).ok()?)?;
+    Some((guild_id, serde_json::from_slice(json).ok()?))
+}
+
+/// The host and the port, with the arguments of `AUTH` if there's a password
+fn parse_address(address: &str) -> (String, Vec<String>) {
+    let address = address.trim_start_matches("redis://").trim_end_matches('/');
+    let Some((user_info, host)) = address.rsplit_once('@') else {
+        return (address.to_owned(), Vec::new());
+    };
+    let auth = match user_info.split_once(':') {
+        Some(("", password)) => vec![password.to_owned()],
+        Some((user, password)) => vec![user.to_owned(), password.to_owned()],
+        None => vec![user_info.to_owned()],
+    };
+    (host.to_owned(), auth)
+}
+
+/// The command in RESP, the protocol of Redis
+fn encode(args: &[&str]) -> Vec<u8> {
+    let mut command = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        command.extend(format!("${}\r\n", arg.len()).as_bytes());
+        command.extend(arg.as_bytes());
+        command.extend(b"\r\n");
+    }
+    command
+}
+
+/// Read a reply in RESP, the arrays in the arrays aren't needed by the commands used here
+async fn read_reply(reader: &mut (impl AsyncBufRead + Unpin)) -> Result<Reply> {
+    let line = read_line(reader).await?;
+    let Some(count) = line.strip_prefix('*') else {
+        return read_scalar(reader, &line).await;
+    };
+    let count = count.parse::<i64>()?;
+    if count < 0 {
+        return Ok(Reply::Nil);
+    }
+    let mut items = Vec::new();
+    for _ in 0..count {
+        let line = read_line(reader).await?;
+        items.push(read_scalar(reader, &line).await?);
+    }
+    Ok(Reply::Array(items))
+}
+
+async fn read_scalar(reader: &mut (impl AsyncBufRead + Unpin), line: &str) -> Result<Reply> {
+    let value = line.get(1..).unwrap_or_default();
+    match line.chars().next() {
+        Some('+') => Ok(Reply::Data(value.as_bytes().to_vec())),
+        Some('-') => Err(anyhow!("Redis replied {}", value)),
+        Some(':') => Ok(Reply::Integer(value.parse()?)),
+        Some('$') => {
+            let Ok(length) = usize::try_from(value.parse::<i64>()?) else {
+                return Ok(Reply::Nil);
+            };
+            // with the trailing CRLF
+            let mut data = vec![0; length + 2];
+            reader.read_exact(&mut data).await?;
+            data.truncate(length);
+            Ok(Reply::Data(data))
+        }
+        _ => Err(anyhow!("Unexpected reply {}", line)),
+    }
+}
+
+async fn read_line(reader: &mut (impl AsyncBufRead + Unpin)) -> Result<String> {
+    let mut line = Vec::new();
+    if reader.read_until(b'\n', &mut line).await? == 0 {
+        return Err(anyhow!("Redis closed the connection"));
+    }
+    let line = String::from_utf8(line)?;
+    Ok(line.trim_end_matches("\r\n").to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode, parse_address, parse_guild, read_reply, Reply};
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(
+            parse_address("redis://127.0.0.1:6379/"),
+            ("127.0.0.1:6379".to_owned(), vec![])
+        );
+        assert_eq!(
+            parse_address("redis://:secret@redis:6379"),
+            ("redis:6379".to_owned(), vec!["secret".to_owned()])
+        );
+        assert_eq!(
+            parse_address("turto:secret@redis:6379").1,
+            ["turto", "secret"]
+        );
+    }
+
+    #[test]
+    fn test_parse_guild() {
+        assert!(parse_guild(b"1", b"{}").is_none());
+        let json = serde_json::to_vec(&crate::models::guild::data::GuildData::default()).unwrap();
+        assert!(parse_guild(b"1", &json).is_some());
+        // the ids aren't 0, so it's not taken as one
+        assert!(parse_guild(b"0", &json).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resp() {
+        assert_eq!(
+            encode(&["HGET", "turto:guilds", "1"]),
+            b"*3\r\n$4\r\nHGET\r\n$12\r\nturto:guilds\r\n$1\r\n1\r\n"
+        );
+
+        let mut reply: &[u8] = b"*2\r\n$1\r\n1\r\n$-1\r\n:3\r\n+OK\r\n-ERR wrong\r\n";
+        assert_eq!(
+            read_reply(&mut reply).await.unwrap(),
+            Reply::Array(vec![Reply::Data(b"1".to_vec()), Reply::Nil])
+        );
+        assert_eq!(read_reply(&mut reply).await.unwrap(), Reply::Integer(3));
+        assert_eq!(
+            read_reply(&mut reply).await.unwrap(),
+            Reply::Data(b"OK".to_vec())
+        );
+        assert!(read_reply(&mut reply).await.is_err());
+        assert!(read_reply(&mut reply).await.is_err());
+    }
+}
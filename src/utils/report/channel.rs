@@ -0,0 +1,76 @@
+use super::{ErrorReport, ReportSink};
+use serenity::{
+    all::{Cache, ChannelId, Http},
+    async_trait,
+    builder::{CreateEmbed, CreateMessage},
+    model::{Colour, Timestamp},
+    prelude::Mentionable,
+};
+use std::sync::Arc;
+use tracing::warn;
+
+// embed description has a length limitation of 4096 chars
+const MAX_DESCRIPTION_LENGTH: usize = 4000;
+
+/// Post the reports as embeds to a Discord channel
+pub struct ChannelSink {
+    channel: ChannelId,
+    cache: Arc<Cache>,
+    http: Arc<Http>,
+}
+
+impl ChannelSink {
+    pub fn new(channel: ChannelId, cache: Arc<Cache>, http: Arc<Http>) -> Self {
+        Self {
+            channel,
+            cache,
+            http,
+        }
+    }
+}
+
+#[async_trait]
+impl ReportSink for ChannelSink {
+    async fn send(&self, report: &ErrorReport<'_>, message: &str) {
+        let mut embed = CreateEmbed::new()
+            .title(report.title)
+            .colour(Colour::RED)
+            .description(format!("```\n{}\n```", truncate(message)))
+            .timestamp(Timestamp::now());
+        if let Some(guild_id) = report.guild_id {
+            let guild = match guild_id.name(&self.cache) {
+                Some(name) => format!("{} (`{}`)", name, guild_id),
+                None => format!("`{}`", guild_id),
+            };
+            embed = embed.field("Guild", guild, true);
+        }
+        if let Some(command) = report.command {
+            embed = embed.field("Command", format!("`/{}`", command), true);
+        }
+        if let Some(user_id) = report.user_id {
+            embed = embed.field("User", user_id.mention().to_string(), true);
+        }
+
+        if let Err(err) = self
+            .channel
+            .send_message(&self.http, CreateMessage::new().embed(embed))
+            .await
+        {
+            warn!(
+                "Failed to post error report to channel {}: {}",
+                self.channel, err
+            );
+        }
+    }
+}
+
+fn truncate(message: &str) -> String {
+    if message.len() <= MAX_DESCRIPTION_LENGTH {
+        return message.to_owned();
+    }
+    let mut end = MAX_DESCRIPTION_LENGTH;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &message[..end])
+}
@@ -0,0 +1,113 @@
+pub mod channel;
+pub mod sentry;
+
+use crate::config::get_config;
+use channel::ChannelSink;
+use sentry::SentrySink;
+use serenity::{
+    all::{Cache, GuildId, Http, UserId},
+    async_trait,
+};
+use std::{
+    error::Error,
+    fmt::Write,
+    panic,
+    sync::{mpsc, Arc, OnceLock},
+    thread,
+    time::Duration,
+};
+use tokio::runtime::Handle;
+use tracing::warn;
+
+static SINKS: OnceLock<Vec<Box<dyn ReportSink>>> = OnceLock::new();
+
+// how long a panicking thread waits for the report to be sent
+const PANIC_REPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The context of an error, which will be displayed in the report
+#[derive(Default)]
+pub struct ErrorReport<'a> {
+    pub title: &'a str,
+    pub guild_id: Option<GuildId>,
+    pub command: Option<&'a str>,
+    pub user_id: Option<UserId>,
+    /// Whether the error is unrecoverable, e.g. a panic
+    pub fatal: bool,
+}
+
+/// A destination where the error reports are sent to, any failure should be handled (logged) by
+/// the sink itself
+#[async_trait]
+pub trait ReportSink: Send + Sync {
+    async fn send(&self, report: &ErrorReport<'_>, message: &str);
+}
+
+/// Set up the configured report sinks and the panic hook, must be called once the client is ready
+pub fn init_report(cache: Arc<Cache>, http: Arc<Http>) {
+    let config = get_config();
+    let mut sinks: Vec<Box<dyn ReportSink>> = Vec::new();
+    if let Some(channel) = config.error_report_channel {
+        sinks.push(Box::new(ChannelSink::new(channel, cache, http)));
+    }
+    if let Some(dsn) = &config.error_report_dsn {
+        match SentrySink::from_dsn(dsn) {
+            Ok(sink) => sinks.push(Box::new(sink)),
+            Err(err) => warn!(
+                "Invalid error_report_dsn, errors will not be sent to it: {}",
+                err
+            ),
+        }
+    }
+    if SINKS.set(sinks).is_ok() {
+        set_panic_hook();
+    }
+}
+
+/// Send the error to all the configured sinks
+pub async fn report_error(report: ErrorReport<'_>, err: &(dyn Error + Send + Sync + '_)) {
+    report_message(&report, &error_chain(err)).await;
+}
+
+async fn report_message(report: &ErrorReport<'_>, message: &str) {
+    let Some(sinks) = SINKS.get() else {
+        return;
+    };
+    for sink in sinks {
+        sink.send(report, message).await;
+    }
+}
+
+fn set_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        let Ok(handle) = Handle::try_current() else {
+            return;
+        };
+        let message = info.to_string();
+        // the panicking thread may be a runtime worker which can't block on futures, and the
+        // process may abort right after the hook returns, so send it from another thread and wait
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let report = ErrorReport {
+                title: "Panic",
+                fatal: true,
+                ..Default::default()
+            };
+            handle.block_on(report_message(&report, &message));
+            let _ = tx.send(());
+        });
+        let _ = rx.recv_timeout(PANIC_REPORT_TIMEOUT);
+    }));
+}
+
+/// Format the error and all of its sources, one per line
+fn error_chain(err: &(dyn Error + '_)) -> String {
+    let mut res = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        let _ = write!(res, "\ncaused by: {}", err);
+        source = err.source();
+    }
+    res
+}
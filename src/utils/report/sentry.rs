@@ -0,0 +1,125 @@
+use super::{ErrorReport, ReportSink};
+use crate::utils::get_http_client;
+use anyhow::{anyhow, Result};
+use reqwest::{header::CONTENT_TYPE, Client};
+use serde_json::{json, Map, Value};
+use serenity::{async_trait, model::Timestamp};
+use tracing::warn;
+use url::Url;
+
+/// Send the reports as events to a Sentry (or any server compatible with its store endpoint)
+pub struct SentrySink {
+    client: Client,
+    store_url: Url,
+    auth: String,
+}
+
+impl SentrySink {
+    /// Create the sink from a DSN like `https://<public key>@<host>/<project id>`
+    pub fn from_dsn(dsn: &str) -> Result<Self> {
+        let mut url = Url::parse(dsn)?;
+        let key = url.username().to_owned();
+        if key.is_empty() {
+            return Err(anyhow!("the public key is missing"));
+        }
+        let mut segments = url
+            .path_segments()
+            .map(|segments| {
+                segments
+                    .filter(|segment| !segment.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let project_id = segments
+            .pop()
+            .ok_or_else(|| anyhow!("the project id is missing"))?
+            .to_owned();
+
+        let mut path = segments.join("/");
+        if !path.is_empty() {
+            path.push('/');
+        }
+        url.set_path(&format!("{}api/{}/store/", path, project_id));
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+
+        let auth = format!(
+            "Sentry sentry_version=7, sentry_key={}, sentry_client=turto/{}",
+            key,
+            env!("CARGO_PKG_VERSION")
+        );
+        Ok(Self {
+            client: get_http_client(),
+            store_url: url,
+            auth,
+        })
+    }
+}
+
+#[async_trait]
+impl ReportSink for SentrySink {
+    async fn send(&self, report: &ErrorReport<'_>, message: &str) {
+        let mut tags = Map::new();
+        if let Some(guild_id) = report.guild_id {
+            tags.insert("guild_id".to_owned(), guild_id.to_string().into());
+        }
+        if let Some(command) = report.command {
+            tags.insert("command".to_owned(), command.into());
+        }
+        let user = report
+            .user_id
+            .map(|user_id| json!({ "id": user_id.to_string() }));
+
+        let event = json!({
+            "event_id": format!("{:032x}", rand::random::<u128>()),
+            "timestamp": Timestamp::now().to_string(),
+            "level": if report.fatal { "fatal" } else { "error" },
+            "platform": "other",
+            "logger": "turto",
+            "release": concat!("turto@", env!("CARGO_PKG_VERSION")),
+            "message": { "formatted": message },
+            "exception": {
+                "values": [{ "type": report.title, "value": message }],
+            },
+            "tags": Value::Object(tags),
+            "user": user,
+        });
+
+        let response = self
+            .client
+            .post(self.store_url.clone())
+            .header("X-Sentry-Auth", &self.auth)
+            .header(CONTENT_TYPE, "application/json")
+            .body(event.to_string())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(err) = response {
+            warn!("Failed to send error report to Sentry: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SentrySink;
+
+    #[test]
+    fn test_from_dsn() {
+        let sink = SentrySink::from_dsn("https://abc123@o1.ingest.sentry.io/42").unwrap();
+        assert_eq!(
+            sink.store_url.as_str(),
+            "https://o1.ingest.sentry.io/api/42/store/"
+        );
+        assert!(sink.auth.contains("sentry_key=abc123"));
+
+        let sink = SentrySink::from_dsn("http://key@localhost:9000/sentry/7").unwrap();
+        assert_eq!(
+            sink.store_url.as_str(),
+            "http://localhost:9000/sentry/api/7/store/"
+        );
+
+        assert!(SentrySink::from_dsn("https://o1.ingest.sentry.io/42").is_err());
+        assert!(SentrySink::from_dsn("https://key@o1.ingest.sentry.io/").is_err());
+    }
+}
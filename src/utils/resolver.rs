@@ -0,0 +1,311 @@
+use crate::{
+    models::{
+        playlist_item::PlaylistItem, queue_item::QueueItemKind, youtube_playlist::YouTubePlaylist,
+    },
+    utils::{get_http_client, ytdl::ytdl_track},
+};
+use anyhow::{anyhow, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::time::Duration;
+use url::Url;
+
+const ITUNES_LOOKUP_URL: &str = "https://itunes.apple.com/lookup";
+const DEEZER_API_URL: &str = "https://api.deezer.com";
+
+/// A link of the music streaming services which yt-dlp can't play, the tracks are searched on
+/// YouTube with their artists and titles instead
+#[derive(Debug, PartialEq, Eq)]
+pub enum MusicLink {
+    AppleMusicTrack { id: String, country: String },
+    AppleMusicAlbum { id: String, country: String },
+    DeezerTrack(String),
+    DeezerAlbum(String),
+}
+
+/// A track with the metadata from the service
+struct Track {
+    title: String,
+    artist: String,
+    duration: Duration,
+    thumbnail: String,
+}
+
+impl Track {
+    fn search_query(&self) -> String {
+        format!("ytsearch1:{} - {}", self.artist, self.title)
+    }
+}
+
+impl From<Track> for PlaylistItem {
+    /// The search query is taken as the url, it's searched when the item is played
+    fn from(value: Track) -> Self {
+        PlaylistItem {
+            url: value.search_query(),
+            title: value.title,
+            channel: value.artist,
+            duration: value.duration,
+            thumbnail: value.thumbnail,
+            priority: 0,
+            requester: None,
+        }
+    }
+}
+
+impl MusicLink {
+    pub fn parse(url: &Url) -> Option<Self> {
+        let segments = url.path_segments()?.collect::<Vec<_>>();
+        match url.host_str()? {
+            // music.apple.com/us/album/name/1559523357?i=1559523359 is a track of the album
+            "music.apple.com" => {
+                let country = segments.first()?.to_string();
+                let id = segments.last()?.to_string();
+                match *segments.get(1)? {
+                    "album" => match url.query_pairs().find(|(key, _)| key == "i") {
+                        Some((_, track_id)) => Some(Self::AppleMusicTrack {
+                            id: track_id.into_owned(),
+                            country,
+                        }),
+                        None => Some(Self::AppleMusicAlbum { id, country }),
+                    },
+                    "song" => Some(Self::AppleMusicTrack { id, country }),
+                    _ => None,
+                }
+            }
+            // the language segment is optional, like www.deezer.com/en/track/3135556
+            "www.deezer.com" | "deezer.com" => {
+                let mut segments = segments.into_iter().rev();
+                let id = segments.next()?.to_owned();
+                match segments.next()? {
+                    "track" => Some(Self::DeezerTrack(id)),
+                    "album" => Some(Self::DeezerAlbum(id)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub async fn resolve(&self) -> Result<QueueItemKind> {
+        let (title, tracks) = self.tracks().await?;
+        match self {
+            Self::AppleMusicTrack { .. } | Self::DeezerTrack(_) => {
+                let track = tracks
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| anyhow!("{:?} is not found", self))?;
+                search_track(track).await
+            }
+            Self::AppleMusicAlbum { .. } | Self::DeezerAlbum(_) => {
+                let mut playlist = YouTubePlaylist::default();
+                playlist.title = title;
+                playlist.extend(tracks.into_iter().map(PlaylistItem::from));
+                Ok(QueueItemKind::Playlist(playlist))
+            }
+        }
+    }
+
+    /// The search query of the track to play right away, it's the first track of an album
+    pub async fn search_query(&self) -> Result<String> {
+        let (_, tracks) = self.tracks().await?;
+        tracks
+            .first()
+            .map(Track::search_query)
+            .ok_or_else(|| anyhow!("{:?} is not found", self))
+    }
+
+    /// The title of the album and the tracks of the link
+    async fn tracks(&self) -> Result<(Option<String>, Vec<Track>)> {
+        match self {
+            Self::AppleMusicTrack { id, country } => {
+                let results = itunes_lookup(id, country, false).await?;
+                let tracks = results.into_iter().filter_map(ItunesResult::into_track);
+                Ok((None, tracks.collect()))
+            }
+            Self::AppleMusicAlbum { id, country } => {
+                let results = itunes_lookup(id, country, true).await?;
+                let title = results
+                    .iter()
+                    .find(|result| result.wrapper_type == "collection")
+                    .and_then(|result| result.collection_name.clone());
+                let tracks = results.into_iter().filter_map(ItunesResult::into_track);
+                Ok((title, tracks.collect()))
+            }
+            Self::DeezerTrack(id) => {
+                let track = deezer_get::<DeezerTrack>(&format!("track/{}", id)).await?;
+                Ok((None, vec![track.into_track(None)]))
+            }
+            Self::DeezerAlbum(id) => {
+                let album = deezer_get::<DeezerAlbum>(&format!("album/{}", id)).await?;
+                let cover = album.cover_xl;
+                let tracks = album
+                    .tracks
+                    .data
+                    .into_iter()
+                    .map(|track| track.into_track(cover.clone()));
+                Ok((album.title, tracks.collect()))
+            }
+        }
+    }
+}
+
+/// Search the track on YouTube right away, so an invalid one can be reported when it's queued
+async fn search_track(track: Track) -> Result<QueueItemKind> {
+    Ok(QueueItemKind::Single(
+        ytdl_track(&track.search_query()).await?,
+    ))
+}
+
+async fn get_json<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T> {
+    let body = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())?
+        .text()
+        .await?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItunesResponse {
+    results: Vec<ItunesResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItunesResult {
+    wrapper_type: String,
+    collection_name: Option<String>,
+    track_name: Option<String>,
+    artist_name: Option<String>,
+    track_time_millis: Option<u64>,
+    artwork_url100: Option<String>,
+}
+
+impl ItunesResult {
+    fn into_track(self) -> Option<Track> {
+        if self.wrapper_type != "track" {
+            return None;
+        }
+        Some(Track {
+            title: self.track_name?,
+            artist: self.artist_name.unwrap_or_default(),
+            duration: Duration::from_millis(self.track_time_millis.unwrap_or_default()),
+            thumbnail: self.artwork_url100.unwrap_or_default(),
+        })
+    }
+}
+
+async fn itunes_lookup(id: &str, country: &str, album: bool) -> Result<Vec<ItunesResult>> {
+    let mut query = vec![("id", id), ("country", country)];
+    if album {
+        query.push(("entity", "song"));
+    }
+    let request = get_http_client().get(ITUNES_LOOKUP_URL).query(&query);
+    let response = get_json::<ItunesResponse>(request)
+        .await
+        .context("Failed to look up on Apple Music")?;
+    Ok(response.results)
+}
+
+#[derive(Deserialize)]
+struct DeezerArtist {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct DeezerCover {
+    cover_xl: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DeezerTrack {
+    title: String,
+    duration: u64,
+    artist: DeezerArtist,
+    album: Option<DeezerCover>,
+}
+
+impl DeezerTrack {
+    fn into_track(self, cover: Option<String>) -> Track {
+        Track {
+            title: self.title,
+            artist: self.artist.name,
+            duration: Duration::from_secs(self.duration),
+            thumbnail: cover
+                .or(self.album.and_then(|album| album.cover_xl))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DeezerTracks {
+    data: Vec<DeezerTrack>,
+}
+
+#[derive(Deserialize)]
+struct DeezerAlbum {
+    title: Option<String>,
+    cover_xl: Option<String>,
+    tracks: DeezerTracks,
+}
+
+async fn deezer_get<T: DeserializeOwned>(path: &str) -> Result<T> {
+    // deezer responds with 200 and an `error` object for the missing ones, which fails to parse
+    let request = get_http_client().get(format!("{}/{}", DEEZER_API_URL, path));
+    get_json::<T>(request)
+        .await
+        .context(format!("Failed to get {} from Deezer", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MusicLink;
+    use url::Url;
+
+    fn parse(url: &str) -> Option<MusicLink> {
+        MusicLink::parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_parse_music_link() {
+        let us = || "us".to_owned();
+        assert_eq!(
+            parse(
+                "https://music.apple.com/us/album/never-gonna-give-you-up/1559523357?i=1559523359"
+            ),
+            Some(MusicLink::AppleMusicTrack {
+                id: "1559523359".to_owned(),
+                country: us()
+            })
+        );
+        assert_eq!(
+            parse("https://music.apple.com/us/album/whenever-you-need-somebody/1559523357"),
+            Some(MusicLink::AppleMusicAlbum {
+                id: "1559523357".to_owned(),
+                country: us()
+            })
+        );
+        assert_eq!(
+            parse("https://music.apple.com/us/song/never-gonna-give-you-up/1559523359"),
+            Some(MusicLink::AppleMusicTrack {
+                id: "1559523359".to_owned(),
+                country: us()
+            })
+        );
+        assert_eq!(
+            parse("https://www.deezer.com/en/track/3135556"),
+            Some(MusicLink::DeezerTrack("3135556".to_owned()))
+        );
+        assert_eq!(
+            parse("https://deezer.com/album/302127"),
+            Some(MusicLink::DeezerAlbum("302127".to_owned()))
+        );
+        assert_eq!(
+            parse("https://music.apple.com/us/artist/rick-astley/669771"),
+            None
+        );
+        assert_eq!(parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ"), None);
+    }
+}
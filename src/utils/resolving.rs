@@ -0,0 +1,181 @@
+use super::{context::TurtoContext, get_locale, timing::timed};
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{CancelButton, Resolving, ResolvingCanceled},
+    },
+    models::{
+        alias::{Context, Error},
+        data::Data,
+        error::TurtoError,
+        invocation::Phase::Discord,
+    },
+};
+use poise::{CreateReply, ReplyHandle};
+use serenity::{
+    all::{ButtonStyle, GuildId, UserId},
+    async_trait,
+    builder::{
+        CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage,
+    },
+    collector::{collect, ComponentInteractionCollector},
+    futures::StreamExt,
+    model::event::Event,
+};
+use songbird::Songbird;
+use std::{
+    future::{pending, Future},
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+/// The reply saying what's being resolved, which is sent right away so the channel isn't silent
+/// while yt-dlp runs, and edited into the result later. It has a button for the author to cancel
+/// the resolution
+pub struct Placeholder<'a> {
+    ctx: Context<'a>,
+    reply: ReplyHandle<'a>,
+    custom_id: String,
+    ephemeral: bool,
+}
+
+impl<'a> Placeholder<'a> {
+    /// Only the author can see the placeholder if it's `ephemeral`
+    pub async fn send(ctx: Context<'a>, query: &str, ephemeral: bool) -> Result<Self, Error> {
+        let locale = get_locale(ctx);
+        let custom_id = format!("{}_cancel", ctx.id());
+        let button = CreateButton::new(&custom_id)
+            .label(TurtoMessage {
+                locale,
+                kind: CancelButton,
+            })
+            .style(ButtonStyle::Secondary);
+        let resolving = CreateReply::default()
+            .content(TurtoMessage {
+                locale,
+                kind: Resolving { query },
+            })
+            .components(vec![CreateActionRow::Buttons(vec![button])])
+            .ephemeral(ephemeral);
+        let reply = timed(ctx, Discord, ctx.send(resolving)).await?;
+        Ok(Self {
+            ctx,
+            reply,
+            custom_id,
+            ephemeral,
+        })
+    }
+
+    pub fn reply(&self) -> &ReplyHandle<'a> {
+        &self.reply
+    }
+
+    pub fn is_ephemeral(&self) -> bool {
+        self.ephemeral
+    }
+
+    /// Wait for the resolution unless the author cancels it or the placeholder is deleted, `None`
+    /// if it's canceled, the resolution is dropped then, which kills the yt-dlp processes started
+    /// by turto
+    pub async fn resolve<T>(
+        &self,
+        resolution: impl Future<Output = T>,
+    ) -> Result<Option<T>, Error> {
+        let custom_id = self.custom_id.clone();
+        let pressed = ComponentInteractionCollector::new(self.ctx)
+            .author_id(self.ctx.author().id)
+            .channel_id(self.ctx.channel_id())
+            .filter(move |mci| mci.data.custom_id == custom_id);
+        // the deletions are collected before the id of the placeholder is known, so none is missed
+        let channel_id = self.ctx.channel_id();
+        let mut deletions = collect(
+            &self.ctx.serenity_context().shard,
+            move |event| match event {
+                Event::MessageDelete(event) if event.channel_id == channel_id => {
+                    Some(vec![event.message_id])
+                }
+                Event::MessageDeleteBulk(event) if event.channel_id == channel_id => {
+                    Some(event.ids.clone())
+                }
+                _ => None,
+            },
+        );
+        let deleted = async {
+            let Ok(message) = self.reply.message().await else {
+                return pending().await;
+            };
+            while let Some(ids) = deletions.next().await {
+                if ids.contains(&message.id) {
+                    return;
+                }
+            }
+        };
+        tokio::select! {
+            resolved = resolution => Ok(Some(resolved)),
+            () = deleted => Ok(None),
+            Some(mci) = async { pressed.await } => {
+                let canceled = CreateInteractionResponseMessage::new()
+                    .content(TurtoMessage {
+                        locale: get_locale(self.ctx),
+                        kind: ResolvingCanceled,
+                    })
+                    .components(vec![]);
+                mci.create_response(self.ctx, CreateInteractionResponse::UpdateMessage(canceled))
+                    .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Replace the placeholder with the error if it has a message, like the error handler does,
+    /// otherwise it's deleted and the error is returned to be reported
+    pub async fn fail(self, err: Error) -> Result<(), Error> {
+        let message = err
+            .downcast_ref::<TurtoError>()
+            .and_then(|turto_error| turto_error.message(get_locale(self.ctx)));
+        let Some(message) = message else {
+            if let Err(err) = self.reply.delete(self.ctx).await {
+                warn!("Failed to delete the placeholder: {}", err);
+            }
+            return Err(err);
+        };
+        info!(
+            "Command {} of {} ended: {}",
+            self.ctx.command().qualified_name,
+            self.ctx.author().id,
+            err
+        );
+        self.send(message.to_string(), false).await
+    }
+}
+
+/// The replies of the command logic are edited into the placeholder
+#[async_trait]
+impl TurtoContext for Placeholder<'_> {
+    fn data(&self) -> &Data {
+        self.ctx.data()
+    }
+
+    fn guild_id(&self) -> Option<GuildId> {
+        self.ctx.guild_id()
+    }
+
+    fn author_id(&self) -> UserId {
+        self.ctx.author().id
+    }
+
+    fn client_locale(&self) -> Option<&str> {
+        self.ctx.locale()
+    }
+
+    async fn voice_manager(&self) -> Result<Arc<Songbird>, TurtoError> {
+        TurtoContext::voice_manager(&self.ctx).await
+    }
+
+    /// A reply can't be made ephemeral once it's sent
+    async fn send(&self, content: String, _ephemeral: bool) -> Result<(), Error> {
+        let edited = CreateReply::default().content(content).components(vec![]);
+        timed(self.ctx, Discord, self.reply.edit(self.ctx, edited)).await?;
+        Ok(())
+    }
+}
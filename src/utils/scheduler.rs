@@ -0,0 +1,140 @@
+use super::{
+    play::play_next,
+    queue::{add_to_playlist, QueueType},
+};
+use crate::models::{
+    guild::{data::GuildData, scheduled_session::ScheduledSession},
+    playing::Playing,
+    queue_item::QueueItemKind,
+    youtube_playlist::YouTubePlaylist,
+};
+use chrono::Local;
+use dashmap::DashMap;
+use serenity::{
+    all::{GuildId, Http, ScheduledEventId, ScheduledEventStatus},
+    builder::EditScheduledEvent,
+};
+use songbird::Songbird;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Start the listening sessions whose scheduled events begin, see `/event create`
+pub async fn session_scheduler(
+    http: Arc<Http>,
+    manager: Arc<Songbird>,
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+) {
+    let mut interval = tokio::time::interval(SCHEDULE_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        let now = Local::now().timestamp();
+        let mut due = Vec::new();
+        for mut entry in guild_data.iter_mut() {
+            let guild_id = *entry.key();
+            entry.scheduled_sessions.retain(|event_id, session| {
+                if session.start > now {
+                    return true;
+                }
+                due.push((guild_id, *event_id, session.clone()));
+                false
+            });
+        }
+        for (guild_id, event_id, session) in due {
+            let started = start_session(
+                &http,
+                &manager,
+                &guild_data,
+                &guild_playing,
+                (guild_id, event_id),
+                session,
+            )
+            .await;
+            if started {
+                info!("Started the event {} in guild {}", event_id, guild_id);
+            }
+        }
+    }
+}
+
+/// Queue the saved playlist and play it in the voice channel of the event, then begin the event,
+/// nothing is done if the event is gone
+async fn start_session(
+    http: &Http,
+    manager: &Songbird,
+    guild_data: &Arc<DashMap<GuildId, GuildData>>,
+    guild_playing: &Arc<RwLock<HashMap<GuildId, Playing>>>,
+    (guild_id, event_id): (GuildId, ScheduledEventId),
+    session: ScheduledSession,
+) -> bool {
+    // the event may have been canceled or deleted in discord
+    match guild_id.scheduled_event(http, event_id, false).await {
+        Ok(event) if event.status == ScheduledEventStatus::Scheduled => {}
+        Ok(event) if event.status == ScheduledEventStatus::Active => {}
+        _ => {
+            info!("Scheduled event {} of guild {} is gone", event_id, guild_id);
+            return false;
+        }
+    }
+
+    let saved = guild_data
+        .get(&guild_id)
+        .and_then(|guild_data| guild_data.saved_playlists.get(&session.playlist).cloned());
+    let Some(saved) = saved else {
+        warn!(
+            "Saved playlist {} of the event {} in guild {} is deleted",
+            session.playlist, event_id, guild_id
+        );
+        return false;
+    };
+    let mut yt_playlist = YouTubePlaylist::default();
+    yt_playlist.title = Some(session.playlist.clone());
+    yt_playlist.extend(saved.items);
+    if let Err(err) = add_to_playlist(
+        guild_data,
+        guild_id,
+        QueueItemKind::Playlist(yt_playlist),
+        0,
+        session.creator,
+        QueueType::Back,
+    ) {
+        warn!(
+            "Failed to queue the event {} in guild {}: {}",
+            event_id, guild_id, err
+        );
+        return false;
+    }
+
+    let call = match manager.join(guild_id, session.channel).await {
+        Ok(call) => call,
+        Err(err) => {
+            warn!(
+                "Failed to join {} for the event {}: {}",
+                session.channel, event_id, err
+            );
+            return false;
+        }
+    };
+    if !guild_playing.read().await.contains_key(&guild_id) {
+        let played = play_next(call, guild_data.clone(), guild_playing.clone(), guild_id).await;
+        if let Some(Err(err)) = played {
+            warn!(
+                "Failed to play the event {} in guild {}: {}",
+                event_id, guild_id, err
+            );
+        }
+    }
+
+    // discord doesn't begin the events by itself
+    let active = EditScheduledEvent::new().status(ScheduledEventStatus::Active);
+    if let Err(err) = guild_id.edit_scheduled_event(http, event_id, active).await {
+        warn!(
+            "Failed to begin the event {} in guild {}: {}",
+            event_id, guild_id, err
+        );
+    }
+    true
+}
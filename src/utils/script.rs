@@ -0,0 +1,527 @@
+use super::{play::play_next, player::refresh_player, template::Template};
+use crate::{
+    config::get_config,
+    models::{guild::data::GuildData, playing::Playing},
+};
+use dashmap::{mapref::one::Ref, DashMap};
+use regex::{Regex, RegexBuilder};
+use serenity::all::{ChannelId, CreateAllowedMentions, CreateMessage, GuildId, Http};
+use songbird::Songbird;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    str::FromStr,
+    sync::{Arc, LazyLock, OnceLock},
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// The maximum number of the rules of a guild
+pub const SCRIPT_LIMIT: usize = 20;
+/// The patterns of `matches` are kept small, so a rule can't make the regex engine slow
+const PATTERN_SIZE_LIMIT: usize = 1 << 16;
+/// The tracks of a guild are skipped by the rules at most this many times within `SKIP_WINDOW`,
+/// so the rules skipping every track can't keep the playback spinning
+const SKIP_LIMIT: u32 = 5;
+const SKIP_WINDOW: Duration = Duration::from_secs(60);
+
+static RUNTIME: OnceLock<ScriptRuntime> = OnceLock::new();
+/// The time the tracks of the guilds were first skipped by the rules lately, and the numbers of
+/// them skipped since then
+static SKIPS: LazyLock<DashMap<GuildId, (Instant, u32)>> = LazyLock::new(DashMap::new);
+/// The parsed rules of the guilds, the rules loaded with the data are parsed when they're first
+/// run, and the ones added by `/script add` when they're added
+static GUILD_RULES: LazyLock<DashMap<GuildId, Vec<Rule>>> = LazyLock::new(DashMap::new);
+
+/// What the actions of the rules are carried out with, and the rules of the operator, which apply
+/// to every guild
+struct ScriptRuntime {
+    http: Arc<Http>,
+    manager: Arc<Songbird>,
+    rules: Vec<Rule>,
+}
+
+/// The event a rule is run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptEvent {
+    /// A track starts to play, the fields are `title`, `url`, `requester`, `duration` in seconds
+    /// and `channel`, the voice channel
+    TrackStart,
+    /// A command is invoked in a guild, the fields are `command`, the name of the command without
+    /// the subcommand, `user` and `channel`
+    Command,
+}
+
+impl ScriptEvent {
+    fn fields(self) -> &'static [&'static str] {
+        match self {
+            ScriptEvent::TrackStart => &["title", "url", "requester", "duration", "channel"],
+            ScriptEvent::Command => &["command", "user", "channel"],
+        }
+    }
+}
+
+impl Display for ScriptEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptEvent::TrackStart => write!(f, "track_start"),
+            ScriptEvent::Command => write!(f, "command"),
+        }
+    }
+}
+
+/// What a rule does when its conditions are met, the text of the replies are rendered with the
+/// fields of the event, like `{title}`
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    /// Send the text to the channel of the command, or the voice channel of the track
+    Reply(String),
+    /// Reject the command with the text
+    Veto(String),
+    /// Skip the track
+    Skip,
+    /// Clear the playlist
+    Clear,
+}
+
+/// A rule of the scripts, like `on track_start if title contains "nightcore" then skip`. The
+/// conditions are `<field> <is|not|contains|matches|>|<> <value>` joined by `and`, the values
+/// with spaces are quoted, and the actions are `reply <text>`, `veto <text>` for the commands,
+/// `skip` and `clear` for the tracks. That's all a rule can do, so the admins can't do more with
+/// the rules than with the commands
+#[derive(Debug)]
+pub struct Rule {
+    event: ScriptEvent,
+    conditions: Vec<Condition>,
+    action: Action,
+}
+
+#[derive(Debug)]
+struct Condition {
+    field: &'static str,
+    test: Test,
+}
+
+#[derive(Debug)]
+enum Test {
+    Is(String),
+    Not(String),
+    Contains(String),
+    Matches(Regex),
+    Greater(f64),
+    Less(f64),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+}
+
+impl Token {
+    fn word(&self) -> Option<&str> {
+        match self {
+            Token::Word(word) => Some(word),
+            Token::Text(_) => None,
+        }
+    }
+
+    fn into_text(self) -> String {
+        match self {
+            Token::Word(text) | Token::Text(text) => text,
+        }
+    }
+}
+
+/// Set up the runtime of the scripts, the rules of the operator are loaded from `script_file`,
+/// the invalid ones are skipped
+pub fn init_scripts(http: Arc<Http>, manager: Arc<Songbird>) {
+    let mut rules = Vec::new();
+    if let Some(path) = &get_config().script_file {
+        match fs::read_to_string(path) {
+            Ok(script) => {
+                for (number, line) in script.lines().enumerate() {
+                    let line = line.trim();
+                    if line.is_empty() || line.starts_with('#') {
+                        continue;
+                    }
+                    match line.parse() {
+                        Ok(rule) => rules.push(rule),
+                        Err(err) => warn!(
+                            "Invalid rule on line {} of {}: {}",
+                            number + 1,
+                            path.display(),
+                            err
+                        ),
+                    }
+                }
+                info!("Loaded {} rules from {}", rules.len(), path.display());
+            }
+            Err(err) => warn!("Failed to read {}: {}", path.display(), err),
+        }
+    }
+    let _ = RUNTIME.set(ScriptRuntime {
+        http,
+        manager,
+        rules,
+    });
+}
+
+/// The actions of the rules of the operator and the guild run on the event, in the order of the
+/// rules, the replies are rendered with the fields. The rules of the guild are only parsed if
+/// they aren't cached yet
+pub fn run_rules(
+    guild_data: &DashMap<GuildId, GuildData>,
+    guild_id: GuildId,
+    event: ScriptEvent,
+    fields: &[(&str, String)],
+) -> Vec<Action> {
+    let Some(runtime) = RUNTIME.get() else {
+        return Vec::new();
+    };
+    let cached = guild_rules(guild_data, guild_id);
+    runtime
+        .rules
+        .iter()
+        .chain(cached.iter())
+        .filter(|rule| rule.event == event && rule.matches(fields))
+        .map(|rule| match &rule.action {
+            Action::Reply(text) => Action::Reply(render(text, fields)),
+            Action::Veto(text) => Action::Veto(render(text, fields)),
+            Action::Skip => Action::Skip,
+            Action::Clear => Action::Clear,
+        })
+        .collect()
+}
+
+/// The cached rules of the guild, they're parsed from the data of the guild if they aren't cached
+fn guild_rules(
+    guild_data: &DashMap<GuildId, GuildData>,
+    guild_id: GuildId,
+) -> Ref<'static, GuildId, Vec<Rule>> {
+    // the data of the guild is held while the rules are cached, so they can't be changed by
+    // `/script` in the meantime
+    let guild_data = guild_data.get(&guild_id);
+    let cached = GUILD_RULES
+        .entry(guild_id)
+        .or_insert_with(|| {
+            // the rules of the guild are checked when they're added
+            guild_data
+                .iter()
+                .flat_map(|guild_data| &guild_data.config.scripts)
+                .filter_map(|rule| rule.parse::<Rule>().ok())
+                .collect()
+        })
+        .downgrade();
+    cached
+}
+
+/// Add the rule parsed by `/script add` to the cached rules of the guild, the rules are parsed
+/// on the next run if they aren't cached. It's called with the data of the guild held
+pub fn cache_rule(guild_id: GuildId, rule: Rule) {
+    if let Some(mut cached) = GUILD_RULES.get_mut(&guild_id) {
+        cached.push(rule);
+    }
+}
+
+/// Drop the cached rules of the guild, they're parsed again on the next run. It's called with the
+/// data of the guild held, or after it's replaced
+pub fn forget_rules(guild_id: GuildId) {
+    GUILD_RULES.remove(&guild_id);
+}
+
+/// Send the reply of a rule to the channel, the mentions in it don't notify anyone, since the
+/// titles and the requesters are rendered into it
+pub fn reply(channel_id: ChannelId, text: String) {
+    let Some(runtime) = RUNTIME.get() else {
+        return;
+    };
+    let http = runtime.http.clone();
+    let message = CreateMessage::new()
+        .content(text)
+        .allowed_mentions(CreateAllowedMentions::new());
+    tokio::spawn(async move {
+        if let Err(err) = channel_id.send_message(&http, message).await {
+            warn!(
+                "Failed to send the reply of the rule to {}: {}",
+                channel_id, err
+            );
+        }
+    });
+}
+
+/// Skip the track playing in the guild like `/skip` unless the rules have skipped too many lately
+pub fn skip(
+    guild_id: GuildId,
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+) {
+    let Some(runtime) = RUNTIME.get() else {
+        return;
+    };
+    let now = Instant::now();
+    let mut skips = SKIPS.entry(guild_id).or_insert((now, 0));
+    if now.duration_since(skips.0) > SKIP_WINDOW {
+        *skips = (now, 0);
+    }
+    skips.1 += 1;
+    let skipped = skips.1;
+    drop(skips);
+    if skipped > SKIP_LIMIT {
+        warn!(
+            "The rules of guild {} skip too many tracks, ignored",
+            guild_id
+        );
+        return;
+    }
+    let Some(call) = runtime.manager.get(guild_id) else {
+        return;
+    };
+    // it's run after the track is started, which is where the rules are run
+    tokio::spawn(async move {
+        call.lock().await.stop();
+        let _ = play_next(call, guild_data, playing, guild_id).await;
+        refresh_player(guild_id);
+    });
+}
+
+fn render(text: &str, fields: &[(&str, String)]) -> String {
+    let template = Template::parse(text);
+    let mut renderer = template.renderer();
+    for (name, value) in fields {
+        renderer.add_arg(name, value);
+    }
+    renderer.render()
+}
+
+impl Rule {
+    fn matches(&self, fields: &[(&str, String)]) -> bool {
+        self.conditions.iter().all(|condition| {
+            let value = fields
+                .iter()
+                .find(|(name, _)| *name == condition.field)
+                .map(|(_, value)| value.as_str())
+                .unwrap_or_default();
+            condition.test.test(value)
+        })
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let mut tokens = tokenize(rule)?.into_iter();
+        if tokens.next().as_ref().and_then(Token::word) != Some("on") {
+            return Err("a rule starts with `on <event>`".to_owned());
+        }
+        let event = match tokens.next().as_ref().and_then(Token::word) {
+            Some("track_start") => ScriptEvent::TrackStart,
+            Some("command") => ScriptEvent::Command,
+            _ => return Err("the events are `track_start` and `command`".to_owned()),
+        };
+
+        let mut conditions = Vec::new();
+        let mut next = tokens.next();
+        if next.as_ref().and_then(Token::word) == Some("if") {
+            loop {
+                let field = tokens.next().and_then(|token| {
+                    let field = token.word()?;
+                    event.fields().iter().find(|name| **name == field).copied()
+                });
+                let Some(field) = field else {
+                    return Err(format!(
+                        "the fields of `{}` are {}",
+                        event,
+                        event.fields().join(", ")
+                    ));
+                };
+                let (Some(op), Some(value)) = (tokens.next(), tokens.next()) else {
+                    return Err(format!("`{}` needs a comparison and a value", field));
+                };
+                let test = Test::parse(op.word().unwrap_or_default(), value.into_text())?;
+                conditions.push(Condition { field, test });
+                next = tokens.next();
+                if next.as_ref().and_then(Token::word) != Some("and") {
+                    break;
+                }
+            }
+        }
+        if next.as_ref().and_then(Token::word) != Some("then") {
+            return Err("the action goes after `then`".to_owned());
+        }
+
+        let action = match (event, tokens.next().as_ref().and_then(Token::word)) {
+            (_, Some("reply")) => Action::Reply(text(tokens.next())?),
+            (ScriptEvent::Command, Some("veto")) => Action::Veto(text(tokens.next())?),
+            (ScriptEvent::TrackStart, Some("skip")) => Action::Skip,
+            (ScriptEvent::TrackStart, Some("clear")) => Action::Clear,
+            (ScriptEvent::Command, _) => {
+                return Err("the actions of `command` are `reply` and `veto`".to_owned())
+            }
+            (ScriptEvent::TrackStart, _) => {
+                return Err(
+                    "the actions of `track_start` are `reply`, `skip` and `clear`".to_owned(),
+                )
+            }
+        };
+        if tokens.next().is_some() {
+            return Err("a rule has only one action".to_owned());
+        }
+        Ok(Self {
+            event,
+            conditions,
+            action,
+        })
+    }
+}
+
+impl Test {
+    fn parse(op: &str, value: String) -> Result<Self, String> {
+        let number = || {
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("`{}` is compared with a number", op))
+        };
+        Ok(match op {
+            "is" => Test::Is(value),
+            "not" => Test::Not(value),
+            "contains" => Test::Contains(value.to_lowercase()),
+            "matches" => Test::Matches(
+                RegexBuilder::new(&value)
+                    .case_insensitive(true)
+                    .size_limit(PATTERN_SIZE_LIMIT)
+                    .build()
+                    .map_err(|err| format!("invalid pattern: {}", err))?,
+            ),
+            ">" => Test::Greater(number()?),
+            "<" => Test::Less(number()?),
+            _ => return Err("the comparisons are is, not, contains, matches, > and <".to_owned()),
+        })
+    }
+
+    fn test(&self, value: &str) -> bool {
+        match self {
+            Test::Is(expected) => value.eq_ignore_ascii_case(expected),
+            Test::Not(expected) => !value.eq_ignore_ascii_case(expected),
+            Test::Contains(part) => value.to_lowercase().contains(part),
+            Test::Matches(pattern) => pattern.is_match(value),
+            Test::Greater(number) => value.parse::<f64>().is_ok_and(|value| value > *number),
+            Test::Less(number) => value.parse::<f64>().is_ok_and(|value| value < *number),
+        }
+    }
+}
+
+fn text(token: Option<Token>) -> Result<String, String> {
+    token
+        .map(Token::into_text)
+        .ok_or_else(|| "the text goes after the action".to_owned())
+}
+
+/// The words and the quoted texts of the rule
+fn tokenize(rule: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = rule.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '"' {
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => text.push(c),
+                    None => return Err("a quote isn't closed".to_owned()),
+                }
+            }
+            tokens.push(Token::Text(text));
+        } else {
+            let mut word = c.to_string();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                word.push(c);
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cache_rule, forget_rules, guild_rules, render, Action, Rule, ScriptEvent};
+    use crate::models::guild::data::GuildData;
+    use dashmap::DashMap;
+    use serenity::all::GuildId;
+
+    #[test]
+    fn test_parse_rule() {
+        let rule = r#"on track_start if title contains "night core" and duration > 600 then skip"#
+            .parse::<Rule>()
+            .unwrap();
+        assert_eq!(rule.event, ScriptEvent::TrackStart);
+        assert_eq!(rule.action, Action::Skip);
+        let fields = |title: &str, duration: &str| {
+            vec![
+                ("title", title.to_owned()),
+                ("duration", duration.to_owned()),
+            ]
+        };
+        assert!(rule.matches(&fields("Song (Night Core)", "601")));
+        assert!(!rule.matches(&fields("Song (Night Core)", "600")));
+        assert!(!rule.matches(&fields("Song", "601")));
+
+        let rule = r#"on command if command matches "^(play|queue)$" then veto "No {command}""#
+            .parse::<Rule>()
+            .unwrap();
+        let fields = [("command", "play".to_owned())];
+        assert!(rule.matches(&fields));
+        assert_eq!(rule.action, Action::Veto("No {command}".to_owned()));
+        assert_eq!(render("No {command}", &fields), "No play");
+
+        for invalid in [
+            "track_start then skip",
+            "on start then skip",
+            "on command then skip",
+            "on track_start if user is 1 then skip",
+            "on track_start if title is then skip",
+            "on track_start if duration > long then skip",
+            r#"on command then reply "unclosed"#,
+            "on track_start then skip clear",
+        ] {
+            assert!(invalid.parse::<Rule>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_guild_rules() {
+        let (guild_data, guild_id) = (DashMap::new(), GuildId::new(200));
+        let mut data = GuildData::default();
+        data.config.scripts = vec!["on track_start then skip".to_owned(), "invalid".to_owned()];
+        guild_data.insert(guild_id, data);
+        assert_eq!(guild_rules(&guild_data, guild_id).len(), 1);
+
+        // the cached rules are run without parsing the scripts again
+        let rule = "on command then reply hi";
+        guild_data
+            .get_mut(&guild_id)
+            .unwrap()
+            .config
+            .scripts
+            .push(rule.to_owned());
+        assert_eq!(guild_rules(&guild_data, guild_id).len(), 1);
+        cache_rule(guild_id, rule.parse().unwrap());
+        assert_eq!(guild_rules(&guild_data, guild_id).len(), 2);
+
+        guild_data
+            .get_mut(&guild_id)
+            .unwrap()
+            .config
+            .scripts
+            .clear();
+        forget_rules(guild_id);
+        assert!(guild_rules(&guild_data, guild_id).is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    config::get_config,
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            BotNotInVoiceChannel, DifferentVoiceChannel, InvalidSeek, NotPlaying, RewindSuccess,
+            SeekNotAllow, SeekNotLongEnough, SeekSuccess,
+        },
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{
+        get_locale,
+        guild::{GuildUtil, VoiceChannelState},
+        say,
+        vote::vote_move,
+    },
+};
+use songbird::tracks::PlayMode;
+use std::time::Duration;
+use tracing::error;
+
+/// Where to seek the currently playing track to, in seconds
+#[derive(Debug, Clone, Copy)]
+pub enum SeekTo {
+    Absolute(u64),
+    /// Relative to the current position, it's clamped to the end of the track
+    Forward(u64),
+    /// Relative to the current position, it's clamped to the start of the track
+    Backward(u64),
+}
+
+impl SeekTo {
+    /// The target position, `length` is none for the tracks without duration
+    fn target(self, position: u64, length: Option<u64>) -> u64 {
+        match self {
+            SeekTo::Absolute(time) => time,
+            SeekTo::Forward(secs) => {
+                let target = position.saturating_add(secs);
+                length.map_or(target, |length| target.min(length))
+            }
+            SeekTo::Backward(secs) => position.saturating_sub(secs),
+        }
+    }
+}
+
+pub async fn seek_track(ctx: Context<'_>, to: SeekTo) -> Result<(), Error> {
+    let config = get_config();
+    let locale = get_locale(ctx);
+
+    if !config.allow_seek {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: SeekNotAllow { backward: false },
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let bot_id = ctx.cache().current_user().id;
+    let user_id = ctx.author().id;
+    let vc_stat = ctx
+        .guild()
+        .ok_or(TurtoError::NotInGuild)?
+        .cmp_voice_channel(&bot_id, &user_id);
+
+    match vc_stat {
+        VoiceChannelState::Different(bot_vc, user_vc) => {
+            vote_move(ctx, bot_vc, user_vc).await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlyFirst(bot_vc) => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: DifferentVoiceChannel { bot: bot_vc },
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+        VoiceChannelState::OnlySecond(_) | VoiceChannelState::None => {
+            say(
+                ctx,
+                TurtoMessage {
+                    locale,
+                    kind: BotNotInVoiceChannel,
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+        VoiceChannelState::Same(_) => (),
+    }
+
+    let playing_map = ctx.data().playing.read().await;
+    let Some(playing) = playing_map.get(&guild_id) else {
+        return Ok(());
+    };
+    let length = playing.metadata.duration.map(|duration| duration.as_secs());
+    let Ok(track_state) = playing.track_handle.get_info().await else {
+        return Ok(());
+    };
+    if track_state.playing == PlayMode::Stop || track_state.playing == PlayMode::End {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: NotPlaying,
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let position = track_state.position.as_secs();
+    let time = to.target(position, length);
+    if position + config.seek_limit <= time {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: InvalidSeek {
+                    seek_limit: config.seek_limit,
+                },
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+    let backward = position > time;
+    if !config.allow_backward_seek && backward {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: SeekNotAllow { backward: true },
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let title = playing.metadata.title.as_deref().unwrap_or_default();
+    if let Some(length) = length.filter(|length| *length < time) {
+        say(
+            ctx,
+            TurtoMessage {
+                locale,
+                kind: SeekNotLongEnough { title, length },
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    ctx.defer().await?;
+    if let Err(why) = playing
+        .track_handle
+        .seek_async(Duration::from_secs(time))
+        .await
+    {
+        let uuid = playing.track_handle.uuid();
+        error!("Failed to seek track {uuid}: {why}");
+    } else {
+        ctx.say(TurtoMessage {
+            locale,
+            kind: if backward { RewindSuccess } else { SeekSuccess },
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeekTo;
+
+    #[test]
+    fn test_seek_target() {
+        assert_eq!(SeekTo::Absolute(30).target(100, Some(200)), 30);
+        assert_eq!(SeekTo::Forward(30).target(100, Some(200)), 130);
+        assert_eq!(SeekTo::Forward(150).target(100, Some(200)), 200);
+        assert_eq!(SeekTo::Forward(150).target(100, None), 250);
+        assert_eq!(SeekTo::Backward(30).target(100, Some(200)), 70);
+        assert_eq!(SeekTo::Backward(150).target(100, Some(200)), 0);
+    }
+}
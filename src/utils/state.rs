@@ -0,0 +1,150 @@
+use super::{
+    json::{read_json, write_json},
+    misc::unix_now,
+};
+use crate::models::{
+    guild::{data::GuildData, was_playing::WasPlaying},
+    playing::Playing,
+    user_prefs::UserPrefs,
+};
+use anyhow::{Context, Result};
+use dashmap::{DashMap, DashSet};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use serenity::all::{GuildId, UserId};
+use songbird::Songbird;
+use std::{collections::HashMap, path::Path};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// The state written by `export_state`, for another instance to take over with `--import-state`
+#[derive(Deserialize)]
+struct ExportedState {
+    version: String,
+    guilds: DashMap<GuildId, GuildData>,
+    blacklist: DashSet<GuildId>,
+    users: DashMap<UserId, UserPrefs>,
+}
+
+/// The data of the guilds the filter accepts as JSON, with what they're playing now as
+/// `was_playing`, so it's resumed wherever the data is loaded. The data itself isn't changed
+pub async fn snapshot_guilds(
+    guild_data: &DashMap<GuildId, GuildData>,
+    manager: &Songbird,
+    playing: &RwLock<HashMap<GuildId, Playing>>,
+    filter: impl Fn(GuildId) -> bool,
+) -> serde_json::Result<Vec<(GuildId, Value)>> {
+    let mut guilds = guild_data
+        .iter()
+        .filter(|entry| filter(*entry.key()))
+        .map(|entry| Ok((*entry.key(), serde_json::to_value(entry.value())?)))
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    let playing = playing.read().await;
+    for (guild_id, guild_data) in guilds.iter_mut() {
+        if let Some(playing) = playing.get(guild_id) {
+            if let Some(was_playing) = WasPlaying::record(manager, *guild_id, playing).await {
+                guild_data["was_playing"] = serde_json::to_value(was_playing)?;
+            }
+        }
+    }
+    Ok(guilds)
+}
+
+/// Write everything turto keeps about the guilds and the users to the file, with the tracks
+/// playing now and their positions, the number of the bytes written is returned
+pub async fn export_state(
+    path: &Path,
+    guild_data: &DashMap<GuildId, GuildData>,
+    blacklist: &DashSet<GuildId>,
+    users: &DashMap<UserId, UserPrefs>,
+    manager: &Songbird,
+    playing: &RwLock<HashMap<GuildId, Playing>>,
+) -> Result<usize> {
+    let guilds = snapshot_guilds(guild_data, manager, playing, |_| true).await?;
+    let guilds = guilds
+        .into_iter()
+        .map(|(guild_id, guild_data)| (guild_id.to_string(), guild_data))
+        .collect::<Map<_, _>>();
+    let exported_at = unix_now();
+    let state = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "exported_at": exported_at,
+        "guilds": guilds,
+        "blacklist": blacklist,
+        "users": users,
+    });
+    write_json(&state, path).context(format!("Failed to write {}", path.display()))
+}
+
+/// Replace the data with the state exported to the file, the guilds resume what they were
+/// playing when turto is ready. The number of the guilds imported is returned
+pub fn import_state(
+    path: &Path,
+    guild_data: &DashMap<GuildId, GuildData>,
+    blacklist: &DashSet<GuildId>,
+    users: &DashMap<UserId, UserPrefs>,
+) -> Result<usize> {
+    let state: ExportedState =
+        read_json(path).context(format!("Failed to read {}", path.display()))?;
+    if state.version != env!("CARGO_PKG_VERSION") {
+        warn!(
+            "The state is exported by turto {}, this is {}",
+            state.version,
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+    let imported = state.guilds.len();
+    guild_data.clear();
+    for (guild_id, data) in state.guilds {
+        guild_data.insert(guild_id, data);
+    }
+    blacklist.clear();
+    for guild_id in state.blacklist {
+        blacklist.insert(guild_id);
+    }
+    users.clear();
+    for (user_id, prefs) in state.users {
+        users.insert(user_id, prefs);
+    }
+    info!("Imported the state of {} guilds", imported);
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_state, import_state};
+    use dashmap::{DashMap, DashSet};
+    use serenity::all::{GuildId, UserId};
+
+    #[tokio::test]
+    async fn test_export_import() {
+        let path = std::env::temp_dir().join("turto_test_state.json");
+        let guild_data = DashMap::new();
+        guild_data.insert(GuildId::new(1), Default::default());
+        let blacklist = DashSet::from_iter([GuildId::new(2)]);
+        let users = DashMap::new();
+        users.insert(UserId::new(3), Default::default());
+        let manager = songbird::Songbird::serenity();
+        export_state(
+            &path,
+            &guild_data,
+            &blacklist,
+            &users,
+            &manager,
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+
+        let (imported_data, imported_blacklist, imported_users) =
+            (DashMap::new(), DashSet::new(), DashMap::new());
+        imported_data.insert(GuildId::new(4), Default::default());
+        let imported = import_state(&path, &imported_data, &imported_blacklist, &imported_users);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(imported.unwrap(), 1);
+        assert!(imported_data.contains_key(&GuildId::new(1)));
+        assert!(!imported_data.contains_key(&GuildId::new(4)));
+        assert!(imported_blacklist.contains(&GuildId::new(2)));
+        assert!(imported_users.contains_key(&UserId::new(3)));
+    }
+}
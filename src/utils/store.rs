@@ -0,0 +1,138 @@
+use super::{cluster::handles, script::forget_rules, state::snapshot_guilds};
+use crate::{
+    config::get_config,
+    models::{guild::data::GuildData, playing::Playing},
+};
+use anyhow::Result;
+use dashmap::DashMap;
+use serde_json::Value;
+use serenity::{all::GuildId, async_trait};
+use songbird::Songbird;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+static STORE: OnceLock<SharedStore> = OnceLock::new();
+
+/// Where the data of the guilds is kept between the saves of the data file, so it isn't lost when
+/// turto crashes, and the instances of a cluster can take over the playlists of each other. The
+/// data file stays the store they're loaded from on start
+#[async_trait]
+pub trait GuildStore: Send + Sync {
+    /// The data of every guild in the store, the invalid data is skipped
+    async fn load_all(&self) -> Result<Vec<(GuildId, GuildData)>>;
+    async fn load(&self, guild_id: GuildId) -> Result<Option<GuildData>>;
+    /// Write the data of the guilds as JSON, over what's kept of them
+    async fn save(&self, guilds: &[(GuildId, Value)]) -> Result<()>;
+    async fn guild_ids(&self) -> Result<Vec<GuildId>>;
+    async fn remove(&self, guild_ids: &[GuildId]) -> Result<()>;
+}
+
+struct SharedStore {
+    store: Box<dyn GuildStore>,
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+}
+
+/// Keep the data of the guilds in the store as well
+pub fn init_store(store: Box<dyn GuildStore>, guild_data: Arc<DashMap<GuildId, GuildData>>) {
+    let _ = STORE.set(SharedStore { store, guild_data });
+}
+
+/// Load the data of the guilds kept in the store over the data loaded from the file, since it's
+/// newer
+pub async fn load_guilds() {
+    let Some(shared) = STORE.get() else {
+        return;
+    };
+    match shared.store.load_all().await {
+        Ok(guilds) => {
+            let loaded = guilds.len();
+            for (guild_id, guild_data) in guilds {
+                shared.guild_data.insert(guild_id, guild_data);
+            }
+            info!("Loaded the data of {} guilds from the store", loaded);
+        }
+        Err(err) => warn!("Failed to load the data from the store: {:#}", err),
+    }
+}
+
+/// Load the data of the guild kept in the store, for the guilds claimed from another instance.
+/// What it was playing isn't resumed, since that's left to the previous owner when it comes back
+pub async fn restore_guild(guild_id: GuildId) {
+    let Some(shared) = STORE.get() else {
+        return;
+    };
+    match shared.store.load(guild_id).await {
+        Ok(Some(mut guild_data)) => {
+            guild_data.was_playing = None;
+            shared.guild_data.insert(guild_id, guild_data);
+            forget_rules(guild_id);
+            info!("Restored the data of guild {} from the store", guild_id);
+        }
+        Ok(None) => (),
+        Err(err) => warn!(
+            "Failed to restore guild {} from the store: {:#}",
+            guild_id, err
+        ),
+    }
+}
+
+/// Write the data of the guilds to the store every `redis_sync_interval` seconds
+pub async fn store_syncer(manager: Arc<Songbird>, playing: Arc<RwLock<HashMap<GuildId, Playing>>>) {
+    let period = Duration::from_secs(get_config().redis_sync_interval.max(1));
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    loop {
+        interval.tick().await;
+        sync_guilds(&manager, &playing).await;
+    }
+}
+
+/// Write the data of the guilds this instance handles to the store, with what they're playing, so
+/// it's resumed after a crash as well
+pub async fn sync_guilds(manager: &Songbird, playing: &RwLock<HashMap<GuildId, Playing>>) {
+    let Some(shared) = STORE.get() else {
+        return;
+    };
+    if let Err(err) = shared.sync(manager, playing).await {
+        warn!("Failed to write the data to the store: {:#}", err);
+    }
+}
+
+impl SharedStore {
+    async fn sync(
+        &self,
+        manager: &Songbird,
+        playing: &RwLock<HashMap<GuildId, Playing>>,
+    ) -> Result<()> {
+        // what's playing is recorded in the data itself only when shutting down
+        let guilds = snapshot_guilds(&self.guild_data, manager, playing, handles).await?;
+        if !guilds.is_empty() {
+            self.store.save(&guilds).await?;
+        }
+
+        // the data removed by `guild_data_retention` is removed from the store as well
+        let synced = guilds
+            .iter()
+            .map(|(guild_id, _)| *guild_id)
+            .collect::<HashSet<_>>();
+        let removed = self
+            .store
+            .guild_ids()
+            .await?
+            .into_iter()
+            .filter(|guild_id| {
+                !synced.contains(guild_id)
+                    && handles(*guild_id)
+                    && !self.guild_data.contains_key(guild_id)
+            })
+            .collect::<Vec<_>>();
+        if !removed.is_empty() {
+            self.store.remove(&removed).await?;
+        }
+        Ok(())
+    }
+}
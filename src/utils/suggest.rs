@@ -0,0 +1,87 @@
+use crate::{
+    models::alias::Context,
+    utils::{get_http_client, url::parse_web_url},
+};
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+use tracing::debug;
+
+const YOUTUBE_SUGGEST_URL: &str = "https://suggestqueries-clients6.youtube.com/complete/search";
+/// Discord gives up on the autocomplete after 3 seconds
+const SUGGEST_TIMEOUT: Duration = Duration::from_millis(1500);
+/// The limit of the length of a choice of Discord
+const MAX_CHOICE_LEN: usize = 100;
+
+/// Autocomplete the keywords to search with the suggestions, for the commands taking a URL
+pub async fn autocomplete_search(_ctx: Context<'_>, partial: &str) -> impl Iterator<Item = String> {
+    search_suggestions(partial).await.into_iter()
+}
+
+/// The search suggestions of YouTube for what's being typed, nothing is suggested for URLs
+pub async fn search_suggestions(partial: &str) -> Vec<String> {
+    let partial = partial.trim();
+    if partial.is_empty() || parse_web_url(partial).is_some() {
+        return Vec::new();
+    }
+    match youtube_suggestions(partial).await {
+        Ok(suggestions) => suggestions,
+        Err(err) => {
+            // it's only a suggestion, the command works the same without it
+            debug!(
+                "Failed to get the search suggestions of {:?}: {:#}",
+                partial, err
+            );
+            Vec::new()
+        }
+    }
+}
+
+async fn youtube_suggestions(partial: &str) -> Result<Vec<String>> {
+    let body = get_http_client()
+        .get(YOUTUBE_SUGGEST_URL)
+        .query(&[("client", "firefox"), ("ds", "yt"), ("q", partial)])
+        .timeout(SUGGEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    parse_suggestions(&body)
+}
+
+/// The response is like `["query", ["suggestion 1", "suggestion 2"], ...]`
+fn parse_suggestions(body: &str) -> Result<Vec<String>> {
+    let response = serde_json::from_str::<Vec<serde_json::Value>>(body)?;
+    let suggestions = response
+        .get(1)
+        .and_then(serde_json::Value::as_array)
+        .ok_or_else(|| anyhow!("Unexpected response {}", body))?;
+    Ok(suggestions
+        .iter()
+        .filter_map(serde_json::Value::as_str)
+        .filter(|suggestion| suggestion.chars().count() <= MAX_CHOICE_LEN)
+        .map(str::to_owned)
+        // discord allows at most 25 choices
+        .take(25)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_suggestions;
+
+    #[test]
+    fn test_parse_suggestions() {
+        let body = r#"["rick",["rick astley","rick roll"],[],{"google:suggestsubtypes":[[512]]}]"#;
+        assert_eq!(
+            parse_suggestions(body).unwrap(),
+            ["rick astley", "rick roll"]
+        );
+        assert_eq!(
+            parse_suggestions(r#"["rick",[]]"#).unwrap(),
+            Vec::<String>::new()
+        );
+        assert!(parse_suggestions("<html>").is_err());
+        assert!(parse_suggestions(r#"["rick"]"#).is_err());
+    }
+}
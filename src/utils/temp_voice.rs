@@ -0,0 +1,145 @@
+use super::{get_locale, idle::is_alone, misc::unix_now};
+use crate::{
+    messages::{TurtoMessage, TurtoMessageKind::TempVoiceChannelName},
+    models::{
+        alias::Context,
+        guild::{data::GuildData, temp_voice::TempVoiceChannel},
+        playing::Playing,
+    },
+};
+use dashmap::DashMap;
+use serenity::{
+    all::{Cache, ChannelId, ChannelType, GuildId, Http},
+    builder::CreateChannel,
+};
+use songbird::Songbird;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+const TEMP_VOICE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// The temporary voice channel of the guild if it's still there, otherwise a new voice channel
+/// named after the author in the category, with the permissions of the category. It's recorded so
+/// it's deleted by `temp_voice_cleaner` once the session ends
+pub async fn temp_voice_channel(
+    ctx: Context<'_>,
+    guild_id: GuildId,
+    category: ChannelId,
+) -> Result<ChannelId, serenity::Error> {
+    let existing = ctx
+        .data()
+        .guilds
+        .get(&guild_id)
+        .and_then(|guild_data| guild_data.temp_voice_channel);
+    let (existing, permissions) = {
+        let guild = ctx.guild();
+        let channels = guild.as_ref().map(|guild| &guild.channels);
+        (
+            existing.filter(|existing| {
+                channels.is_some_and(|channels| channels.contains_key(&existing.id))
+            }),
+            channels
+                .and_then(|channels| channels.get(&category))
+                .map(|category| category.permission_overwrites.clone())
+                .unwrap_or_default(),
+        )
+    };
+    if let Some(existing) = existing {
+        return Ok(existing.id);
+    }
+    let host = match ctx.author_member().await {
+        Some(member) => member.display_name().to_owned(),
+        None => ctx.author().name.clone(),
+    };
+    let name = TurtoMessage {
+        locale: get_locale(ctx),
+        kind: TempVoiceChannelName { host: &host },
+    }
+    .to_string();
+    // discord allows at most 100 chars
+    let name = name.chars().take(100).collect::<String>();
+    let channel = guild_id
+        .create_channel(
+            ctx,
+            CreateChannel::new(name)
+                .kind(ChannelType::Voice)
+                .category(category)
+                .permissions(permissions),
+        )
+        .await?;
+    ctx.data()
+        .guilds
+        .entry(guild_id)
+        .or_default()
+        .temp_voice_channel = Some(TempVoiceChannel {
+        id: channel.id,
+        created_at: unix_now(),
+    });
+    info!(
+        "Created the temporary voice channel {} in guild {}",
+        channel.id, guild_id
+    );
+    Ok(channel.id)
+}
+
+async fn delete_temp_voice(http: &Http, guild_id: GuildId, channel_id: ChannelId) {
+    match channel_id.delete(http).await {
+        Ok(_) => info!(
+            "Deleted the temporary voice channel {} of guild {}",
+            channel_id, guild_id
+        ),
+        // it may have been deleted by others, it's forgotten either way
+        Err(err) => warn!(
+            "Failed to delete the temporary voice channel {} of guild {}: {}",
+            channel_id, guild_id, err
+        ),
+    }
+}
+
+/// Leave and delete the temporary voice channels where nothing is playing and nobody is in
+pub async fn temp_voice_cleaner(
+    http: Arc<Http>,
+    cache: Arc<Cache>,
+    manager: Arc<Songbird>,
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+) {
+    let mut interval = tokio::time::interval(TEMP_VOICE_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        let channels = guild_data
+            .iter()
+            .filter_map(|guild_data| Some((*guild_data.key(), guild_data.temp_voice_channel?)))
+            .collect::<Vec<_>>();
+        let now = unix_now();
+        for (guild_id, channel) in channels {
+            let call = manager.get(guild_id);
+            let in_channel = match &call {
+                Some(call) => call
+                    .lock()
+                    .await
+                    .current_channel()
+                    .is_some_and(|current| current.0.get() == channel.id.get()),
+                None => false,
+            };
+            // the session may have moved to another voice channel
+            let playing = in_channel && guild_playing.read().await.contains_key(&guild_id);
+            let alone = is_alone(&cache, guild_id, channel.id);
+            if !channel.is_finished(playing, alone, now) {
+                continue;
+            }
+            if let Some(mut guild_data) = guild_data.get_mut(&guild_id) {
+                // a new one may have been created in the meantime
+                if guild_data.temp_voice_channel != Some(channel) {
+                    continue;
+                }
+                guild_data.temp_voice_channel = None;
+            }
+            if in_channel {
+                let _ = manager.remove(guild_id).await;
+            }
+            delete_temp_voice(&http, guild_id, channel.id).await;
+        }
+    }
+}
@@ -32,16 +32,14 @@ impl Template {
                     last_left = pos;
                     has_left = true;
                 }
-                '}' => {
-                    if has_left {
-                        let text = template[start..last_left].to_string();
-                        let arg = template[last_left + 1..pos].to_string();
-                        tokens.push(Token::Text(text));
-                        tokens.push(Token::Arg(arg));
-                        start = pos + 1;
-                        last_left = start;
-                        has_left = false;
-                    }
+                '}' if has_left => {
+                    let text = template[start..last_left].to_string();
+                    let arg = template[last_left + 1..pos].to_string();
+                    tokens.push(Token::Text(text));
+                    tokens.push(Token::Arg(arg));
+                    start = pos + 1;
+                    last_left = start;
+                    has_left = false;
                 }
                 _ => (),
             }
@@ -55,7 +53,15 @@ impl Template {
         }
     }
 
-    pub fn renderer(&self) -> TemplateRenderer {
+    /// The names of the placeholders in this template
+    pub fn args(&self) -> impl Iterator<Item = &str> {
+        self.tokens.iter().filter_map(|token| match token {
+            Token::Arg(arg) => Some(arg.as_str()),
+            Token::Text(_) => None,
+        })
+    }
+
+    pub fn renderer(&self) -> TemplateRenderer<'_> {
         TemplateRenderer {
             template: self,
             args: HashMap::<_, _>::new(),
@@ -0,0 +1,15 @@
+use crate::models::{
+    alias::Context,
+    invocation::{CommandSpan, Phase},
+};
+use std::{future::Future, time::Instant};
+
+/// Run the future and add the time it takes to the phase of the current command invocation
+pub async fn timed<F: Future>(ctx: Context<'_>, phase: Phase, future: F) -> F::Output {
+    let start = Instant::now();
+    let output = future.await;
+    if let Some(mut command_span) = ctx.invocation_data::<CommandSpan>().await {
+        command_span.add(phase, start.elapsed());
+    }
+    output
+}
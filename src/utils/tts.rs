@@ -0,0 +1,168 @@
+use crate::{
+    config::{get_config, message_template::get_locales},
+    messages::{TurtoMessage, TurtoMessageKind::VoiceAnnouncement},
+};
+use serenity::{
+    all::{GuildId, Http, UserId},
+    async_trait,
+};
+use songbird::{
+    events::{Event, EventContext, EventHandler},
+    input::{AudioStreamError, ChildContainer, Input},
+    tracks::{Track, TrackHandle},
+    Call, TrackEvent,
+};
+use std::{
+    process::{Command, Stdio},
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// The announcement is cut off after this long, so a broken backend can't hold the track forever
+const ANNOUNCEMENT_MAX_DURATION: Duration = Duration::from_secs(15);
+
+static HTTP: OnceLock<Arc<Http>> = OnceLock::new();
+
+/// Set up the announcements in voice, the names of the requesters are fetched with it
+pub fn init_tts(http: Arc<Http>) {
+    let _ = HTTP.set(http);
+}
+
+/// Whether `tts_command` is set in the config
+pub fn tts_enabled() -> bool {
+    !get_config().tts_command.is_empty()
+}
+
+/// The program and the arguments of `tts_command` with `{text}` filled in, it's run without a
+/// shell, so nothing in the text is interpreted
+fn tts_args(command: &[String], text: &str) -> Option<(String, Vec<String>)> {
+    let (program, args) = command.split_first()?;
+    let args = args.iter().map(|arg| arg.replace("{text}", text)).collect();
+    Some((program.clone(), args))
+}
+
+/// Speak the text with `tts_command`, what it writes to its stdout is encoded by ffmpeg so any
+/// format ffmpeg reads can be used
+fn tts_input(text: &str) -> Result<Input, AudioStreamError> {
+    let Some((program, args)) = tts_args(&get_config().tts_command, text) else {
+        return Err(AudioStreamError::Fail("tts_command is not set".into()));
+    };
+    let mut tts = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| AudioStreamError::Fail(Box::new(err)))?;
+    let tts_stdout = tts.stdout.take().unwrap();
+    let ffmpeg = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-i", "pipe:0"])
+        .args([
+            "-ac", "2", "-ar", "48000", "-c:a", "libopus", "-f", "ogg", "pipe:1",
+        ])
+        .stdin(Stdio::from(tts_stdout))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let ffmpeg = match ffmpeg {
+        Ok(ffmpeg) => ffmpeg,
+        Err(err) => {
+            let _ = tts.kill();
+            return Err(AudioStreamError::Fail(Box::new(err)));
+        }
+    };
+    Ok(ChildContainer::from(vec![tts, ffmpeg]).into())
+}
+
+/// Say which track is starting and who requested it, the track is added paused and it's played
+/// once the announcement ends
+pub async fn announce_track(
+    call: Arc<Mutex<Call>>,
+    track_handle: TrackHandle,
+    guild_id: GuildId,
+    title: String,
+    requester: Option<UserId>,
+    locale: Option<String>,
+    volume: f32,
+) {
+    let requester = match (requester, HTTP.get()) {
+        (Some(requester), Some(http)) => match guild_id.member(http, requester).await {
+            Ok(member) => Some(member.display_name().to_owned()),
+            Err(_) => None,
+        },
+        _ => None,
+    };
+    let locale = locale
+        .and_then(|locale| get_locales().find(|supported| supported.eq_ignore_ascii_case(&locale)));
+    let text = TurtoMessage {
+        locale,
+        kind: VoiceAnnouncement {
+            title: &title,
+            requester: requester.as_deref(),
+        },
+    }
+    .to_string();
+
+    let input = match tts_input(&text) {
+        Ok(input) => input,
+        Err(err) => {
+            warn!(
+                "Failed to announce the track in guild {}: {}",
+                guild_id, err
+            );
+            let _ = track_handle.play();
+            return;
+        }
+    };
+    let announcement = call.lock().await.play(Track::from(input).volume(volume));
+    let resume = ResumeHandler { track_handle };
+    // the track can't be held up by an announcement that never plays
+    for event in [TrackEvent::End, TrackEvent::Error] {
+        if announcement
+            .add_event(Event::Track(event), resume.clone())
+            .is_err()
+        {
+            let _ = resume.track_handle.play();
+            return;
+        }
+    }
+    tokio::spawn(async move {
+        tokio::time::sleep(ANNOUNCEMENT_MAX_DURATION).await;
+        let _ = announcement.stop();
+    });
+}
+
+/// Play the track after its announcement
+#[derive(Clone)]
+struct ResumeHandler {
+    track_handle: TrackHandle,
+}
+
+#[async_trait]
+impl EventHandler for ResumeHandler {
+    async fn act(&self, _: &EventContext<'_>) -> Option<Event> {
+        // the track may have been skipped during the announcement
+        let _ = self.track_handle.play();
+        Some(Event::Cancel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tts_args;
+
+    #[test]
+    fn test_tts_args() {
+        let command = ["espeak-ng", "--stdout", "{text}"].map(String::from);
+        assert_eq!(
+            tts_args(&command, "Now playing: a; rm -rf ~"),
+            Some((
+                "espeak-ng".to_owned(),
+                vec!["--stdout".to_owned(), "Now playing: a; rm -rf ~".to_owned()]
+            ))
+        );
+        assert_eq!(tts_args(&[], "text"), None);
+    }
+}
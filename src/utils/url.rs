@@ -1,16 +1,143 @@
 use url::Url;
 
+/// The query as a web link, anything starting with a word and a colon parses as a URL otherwise,
+/// like `Tiesto: Red Lights` or `C:\music`
+pub fn parse_web_url(query: &str) -> Option<Url> {
+    Url::parse(query)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
 pub trait UrlExt {
     fn is_yt_playlist(&self) -> bool;
+    /// Convert a YouTube Music link of a track or a playlist to the standard YouTube one
+    fn normalize_yt_music(self) -> Url;
+    fn is_bandcamp_album(&self) -> bool;
+    fn is_mixcloud_playlist(&self) -> bool;
+    /// Whether yt-dlp takes the url as a playlist, every item in it is queued
+    fn is_playlist(&self) -> bool;
+    /// The id of the YouTube video, the links of the same video in different forms share it
+    fn yt_video_id(&self) -> Option<String>;
 }
 
 impl UrlExt for Url {
     fn is_yt_playlist(&self) -> bool {
         match self.host_str() {
-            Some("www.youtube.com") | Some("youtube.com") | Some("youtu.be") | Some("music.youtube.com") => {
+            Some("www.youtube.com") | Some("youtube.com") | Some("youtu.be") => {
                 self.query_pairs().any(|(k, _)| k == "list")
             }
+            // the albums have browse ids like MPREb_..., which only YouTube Music recognizes
+            Some("music.youtube.com") => {
+                self.path().starts_with("/browse/MPREb")
+                    || self.query_pairs().any(|(k, _)| k == "list")
+            }
             _ => false,
         }
     }
+
+    // music.youtube.com/watch?v=ID&list=RDAMVMID becomes www.youtube.com/watch?v=ID, the `RD` lists
+    // are the generated radios following the track rather than what's shared
+    fn normalize_yt_music(self) -> Url {
+        if self.host_str() != Some("music.youtube.com") {
+            return self;
+        }
+        let (path, keys): (_, &[&str]) = match self.path() {
+            "/watch" => ("/watch", &["v", "list"]),
+            "/playlist" => ("/playlist", &["list"]),
+            _ => return self,
+        };
+        let mut url = Url::parse("https://www.youtube.com").unwrap();
+        url.set_path(path);
+        {
+            let mut query = url.query_pairs_mut();
+            for (key, value) in self.query_pairs() {
+                let generated = key == "list" && value.starts_with("RD");
+                if keys.contains(&key.as_ref()) && !generated {
+                    query.append_pair(&key, &value);
+                }
+            }
+        }
+        url
+    }
+
+    // like https://artist.bandcamp.com/album/name, a track is https://artist.bandcamp.com/track/name
+    fn is_bandcamp_album(&self) -> bool {
+        self.host_str()
+            .is_some_and(|host| host.ends_with(".bandcamp.com"))
+            && self.path().starts_with("/album/")
+    }
+
+    // like https://www.mixcloud.com/user/playlists/name/ or https://www.mixcloud.com/user/uploads/,
+    // a show is https://www.mixcloud.com/user/name/
+    fn is_mixcloud_playlist(&self) -> bool {
+        match self.host_str() {
+            Some("www.mixcloud.com") | Some("mixcloud.com") => {
+                let segments = self
+                    .path_segments()
+                    .map(|segments| segments.filter(|s| !s.is_empty()).collect::<Vec<_>>())
+                    .unwrap_or_default();
+                matches!(segments[..], [_, "playlists", _] | [_, "uploads"])
+            }
+            _ => false,
+        }
+    }
+
+    fn is_playlist(&self) -> bool {
+        self.is_yt_playlist() || self.is_bandcamp_album() || self.is_mixcloud_playlist()
+    }
+
+    fn yt_video_id(&self) -> Option<String> {
+        match self.host_str()? {
+            "youtu.be" => self.path_segments()?.next().map(str::to_owned),
+            "www.youtube.com" | "youtube.com" | "music.youtube.com" | "m.youtube.com" => self
+                .query_pairs()
+                .find(|(key, _)| key == "v")
+                .map(|(_, id)| id.into_owned()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UrlExt;
+    use url::Url;
+
+    #[test]
+    fn test_is_playlist() {
+        let is_playlist = |url: &str| Url::parse(url).unwrap().is_playlist();
+        assert!(is_playlist("https://www.youtube.com/playlist?list=PL1234"));
+        assert!(is_playlist("https://artist.bandcamp.com/album/name"));
+        assert!(!is_playlist("https://artist.bandcamp.com/track/name"));
+        assert!(is_playlist("https://www.mixcloud.com/user/playlists/name/"));
+        assert!(is_playlist("https://www.mixcloud.com/user/uploads/"));
+        assert!(!is_playlist("https://www.mixcloud.com/user/show-name/"));
+        assert!(!is_playlist("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+        assert!(is_playlist(
+            "https://music.youtube.com/browse/MPREb_9nqEki4ZDpp"
+        ));
+    }
+
+    #[test]
+    fn test_normalize_yt_music() {
+        let normalize = |url: &str| Url::parse(url).unwrap().normalize_yt_music().to_string();
+        assert_eq!(
+            normalize(
+                "https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=RDAMVMdQw4w9WgXcQ&si=abc"
+            ),
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ"
+        );
+        assert_eq!(
+            normalize("https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=OLAK5uy_abc"),
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=OLAK5uy_abc"
+        );
+        assert_eq!(
+            normalize("https://music.youtube.com/playlist?list=PL1234&si=abc"),
+            "https://www.youtube.com/playlist?list=PL1234"
+        );
+        assert_eq!(
+            normalize("https://music.youtube.com/browse/MPREb_9nqEki4ZDpp"),
+            "https://music.youtube.com/browse/MPREb_9nqEki4ZDpp"
+        );
+    }
 }
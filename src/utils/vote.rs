@@ -0,0 +1,188 @@
+use crate::{
+    messages::{
+        TurtoMessage,
+        TurtoMessageKind::{
+            BusyInVoiceChannel, MovedVoiceChannel, VoteMoveButton, VoteMoveExpired, VoteNotListener,
+        },
+    },
+    models::{
+        alias::{Context, Error},
+        error::TurtoError,
+    },
+    utils::{context::TurtoContext, get_locale},
+};
+use poise::CreateReply;
+use serenity::{
+    all::{ChannelId, UserId},
+    builder::{
+        CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage,
+    },
+    collector::ComponentInteractionCollector,
+};
+use std::{collections::HashSet, time::Duration};
+use tracing::warn;
+
+const VOTE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Reply that turto is busy in `bot` with a vote to move it to `to`, the voice channel of the
+/// author. The listeners in `bot` vote with the button, and turto moves when the majority of them
+/// agree, the playback goes on in the new channel
+pub async fn vote_move(ctx: Context<'_>, bot: ChannelId, to: ChannelId) -> Result<(), Error> {
+    let locale = get_locale(ctx);
+    let button_id = format!("{}_vote_move", ctx.id());
+    let mut votes = HashSet::new();
+    let message = |votes: usize, required: usize| {
+        TurtoMessage {
+            locale,
+            kind: BusyInVoiceChannel {
+                bot,
+                to,
+                votes,
+                required,
+            },
+        }
+        .to_string()
+    };
+    let button = CreateActionRow::Buttons(vec![CreateButton::new(&button_id).emoji('🗳').label(
+        TurtoMessage {
+            locale,
+            kind: VoteMoveButton,
+        }
+        .to_string(),
+    )]);
+
+    let required = required_votes(listeners(ctx, bot).len());
+    let reply = ctx
+        .send(
+            CreateReply::default()
+                .content(message(0, required))
+                .components(vec![button.clone()]),
+        )
+        .await?;
+
+    while let Some(mci) = ComponentInteractionCollector::new(ctx)
+        .channel_id(ctx.channel_id())
+        .timeout(VOTE_TIMEOUT)
+        .filter({
+            let button_id = button_id.clone();
+            move |mci| mci.data.custom_id == button_id
+        })
+        .await
+    {
+        // the listeners may have changed since the vote started
+        let listeners = listeners(ctx, bot);
+        let voter = mci.user.id;
+        // anyone can move turto if nobody is listening
+        if !listeners.is_empty() && !listeners.contains(&voter) {
+            let response = CreateInteractionResponseMessage::new()
+                .content(
+                    TurtoMessage {
+                        locale,
+                        kind: VoteNotListener { bot },
+                    }
+                    .to_string(),
+                )
+                .ephemeral(true);
+            mci.create_response(ctx, CreateInteractionResponse::Message(response))
+                .await?;
+            continue;
+        }
+        votes.insert(voter);
+        votes.retain(|voter| listeners.is_empty() || listeners.contains(voter));
+        let required = required_votes(listeners.len());
+
+        if votes.len() < required {
+            let response = CreateInteractionResponseMessage::new()
+                .content(message(votes.len(), required))
+                .components(vec![button.clone()]);
+            mci.create_response(ctx, CreateInteractionResponse::UpdateMessage(response))
+                .await?;
+            continue;
+        }
+
+        let content = if move_to(ctx, to).await? {
+            TurtoMessage {
+                locale,
+                kind: MovedVoiceChannel(to),
+            }
+            .to_string()
+        } else {
+            message(votes.len(), required)
+        };
+        let response = CreateInteractionResponseMessage::new()
+            .content(content)
+            .components(vec![]);
+        mci.create_response(ctx, CreateInteractionResponse::UpdateMessage(response))
+            .await?;
+        return Ok(());
+    }
+
+    reply
+        .edit(
+            ctx,
+            CreateReply::default()
+                .content(
+                    TurtoMessage {
+                        locale,
+                        kind: VoteMoveExpired { to },
+                    }
+                    .to_string(),
+                )
+                .components(vec![]),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Move turto to another voice channel in the guild, joining another channel with the same call
+/// keeps the track playing. It's `false` if joining the channel fails
+pub async fn move_to(ctx: Context<'_>, to: ChannelId) -> Result<bool, TurtoError> {
+    let guild_id = ctx.guild_id().ok_or(TurtoError::NotInGuild)?;
+    let manager = TurtoContext::voice_manager(&ctx).await?;
+    match manager.join(guild_id, to).await {
+        Ok(_) => Ok(true),
+        Err(err) => {
+            warn!("Failed to move to voice channel {}: {}", to, err);
+            Ok(false)
+        }
+    }
+}
+
+/// The users listening in the voice channel, except the bots
+pub fn listeners(ctx: Context<'_>, channel_id: ChannelId) -> HashSet<UserId> {
+    let Some(guild) = ctx.guild() else {
+        return HashSet::new();
+    };
+    guild
+        .voice_states
+        .values()
+        .filter(|voice_state| voice_state.channel_id == Some(channel_id))
+        .filter(|voice_state| {
+            !voice_state
+                .member
+                .as_ref()
+                .is_some_and(|member| member.user.bot)
+        })
+        .map(|voice_state| voice_state.user_id)
+        .filter(|user_id| *user_id != ctx.cache().current_user().id)
+        .collect()
+}
+
+/// More than half of the listeners have to agree, a single vote is enough if there's no listener
+fn required_votes(listeners: usize) -> usize {
+    listeners / 2 + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::required_votes;
+
+    #[test]
+    fn test_required_votes() {
+        assert_eq!(required_votes(0), 1);
+        assert_eq!(required_votes(1), 1);
+        assert_eq!(required_votes(2), 2);
+        assert_eq!(required_votes(3), 2);
+        assert_eq!(required_votes(4), 3);
+    }
+}
@@ -0,0 +1,192 @@
+use super::{
+    metrics::{Metrics, METRICS},
+    play::play_next,
+    resolve_locale,
+};
+use crate::{
+    config::get_config,
+    messages::{TurtoMessage, TurtoMessageKind::TrackStuck},
+    models::{guild::data::GuildData, playing::Playing, user_prefs::UserPrefs},
+};
+use dashmap::DashMap;
+use serenity::all::{ChannelId, GuildId, Http, UserId};
+use songbird::{input::AuxMetadata, tracks::PlayMode, Songbird};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+use tracing::{error, warn};
+
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The last known position of the track playing in a guild
+struct Progress {
+    metadata: Arc<AuxMetadata>,
+    position: Duration,
+    since: Instant,
+}
+
+/// Keep track of the positions of the playing tracks to find the frozen ones
+#[derive(Default)]
+struct Progresses(HashMap<GuildId, Progress>);
+
+impl Progresses {
+    /// Update the position of the track, it's stuck if the position hasn't changed for `timeout`
+    fn update(
+        &mut self,
+        guild_id: GuildId,
+        metadata: &Arc<AuxMetadata>,
+        position: Duration,
+        now: Instant,
+        timeout: Duration,
+    ) -> bool {
+        match self.0.get(&guild_id) {
+            Some(progress)
+                if Arc::ptr_eq(&progress.metadata, metadata) && progress.position == position =>
+            {
+                now.duration_since(progress.since) >= timeout
+            }
+            _ => {
+                self.0.insert(
+                    guild_id,
+                    Progress {
+                        metadata: metadata.clone(),
+                        position,
+                        since: now,
+                    },
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Skip the tracks whose position hasn't advanced for `watchdog_timeout` seconds, the stream is
+/// probably frozen and the player would be stuck forever otherwise
+pub async fn watchdog(
+    http: Arc<Http>,
+    manager: Arc<Songbird>,
+    guild_data: Arc<DashMap<GuildId, GuildData>>,
+    guild_playing: Arc<RwLock<HashMap<GuildId, Playing>>>,
+    users: Arc<DashMap<UserId, UserPrefs>>,
+) {
+    let timeout = Duration::from_secs(get_config().watchdog_timeout);
+    let mut progresses = Progresses::default();
+    let mut interval = tokio::time::interval(WATCHDOG_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let now = Instant::now();
+        let mut stuck = Vec::new();
+        {
+            let playing_map = guild_playing.read().await;
+            progresses
+                .0
+                .retain(|guild_id, _| playing_map.contains_key(guild_id));
+            for (guild_id, playing) in playing_map.iter() {
+                let Ok(info) = playing.track_handle.get_info().await else {
+                    progresses.0.remove(guild_id);
+                    continue;
+                };
+                // the paused tracks don't advance either
+                if info.playing != PlayMode::Play {
+                    progresses.0.remove(guild_id);
+                    continue;
+                }
+                if progresses.update(*guild_id, &playing.metadata, info.position, now, timeout) {
+                    let title = playing.metadata.title.clone().unwrap_or_default();
+                    stuck.push((*guild_id, title, playing.requester));
+                }
+            }
+        }
+
+        for (guild_id, title, requester) in stuck {
+            progresses.0.remove(&guild_id);
+            Metrics::increment(&METRICS.tracks_stuck);
+            warn!(
+                "Track {} in guild {} is stuck, skipping it",
+                title, guild_id
+            );
+            let Some(call) = manager.get(guild_id) else {
+                continue;
+            };
+            let channel_id = call.lock().await.current_channel();
+
+            match play_next(
+                call.clone(),
+                guild_data.clone(),
+                guild_playing.clone(),
+                guild_id,
+            )
+            .await
+            {
+                Some(Err(err)) => error!("Failed to play the next track in {}: {}", guild_id, err),
+                Some(Ok(_)) => (),
+                None => {
+                    call.lock().await.stop();
+                    guild_playing.write().await.remove(&guild_id);
+                }
+            }
+
+            // the notice goes to the text chat of the voice channel
+            let Some(channel_id) = channel_id else {
+                continue;
+            };
+            // the default id is nobody, so only the locale of the guild is taken without a requester
+            let locale = resolve_locale(
+                &users,
+                &guild_data,
+                requester.unwrap_or_default(),
+                Some(guild_id),
+                None,
+            );
+            let content = TurtoMessage {
+                locale,
+                kind: TrackStuck { title: &title },
+            };
+            let channel_id = ChannelId::new(channel_id.0.get());
+            if let Err(err) = channel_id.say(&http, content).await {
+                warn!("Failed to send the stuck notice to {}: {}", channel_id, err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Progresses;
+    use serenity::all::GuildId;
+    use songbird::input::AuxMetadata;
+    use std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn test_progresses() {
+        let guild_id = GuildId::new(1);
+        let timeout = Duration::from_secs(30);
+        let start = Instant::now();
+        let track = Arc::new(AuxMetadata::default());
+        let mut progresses = Progresses::default();
+
+        assert!(!progresses.update(guild_id, &track, Duration::ZERO, start, timeout));
+        let later = start + Duration::from_secs(20);
+        assert!(!progresses.update(guild_id, &track, Duration::ZERO, later, timeout));
+        let later = start + Duration::from_secs(30);
+        assert!(progresses.update(guild_id, &track, Duration::ZERO, later, timeout));
+
+        // the position has advanced, so the timer starts over
+        let position = Duration::from_secs(1);
+        assert!(!progresses.update(guild_id, &track, position, later, timeout));
+        let much_later = later + Duration::from_secs(29);
+        assert!(!progresses.update(guild_id, &track, position, much_later, timeout));
+
+        // another track at the same position is not stuck
+        let other = Arc::new(AuxMetadata::default());
+        let much_later = later + Duration::from_secs(60);
+        assert!(!progresses.update(guild_id, &other, position, much_later, timeout));
+    }
+}
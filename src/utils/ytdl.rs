@@ -1,14 +1,61 @@
-use crate::models::youtube_playlist::{Output, YouTubePlaylist};
-use std::process::Stdio;
+use crate::{
+    models::{
+        playlist_item::PlaylistItem,
+        playlist_range::PlaylistRange,
+        youtube_playlist::{Entry, Output, YouTubePlaylist},
+    },
+    utils::url::UrlExt,
+};
+use std::{io, process::Stdio};
 use tokio::process::Command;
 use url::Url;
 
-pub async fn ytdl_playlist(url: &Url) -> Result<YouTubePlaylist, std::io::Error> {
-    let args = vec![url.as_str(), "--flat-playlist", "-J"];
+/// Extract the track, the query can be a URL or a search of yt-dlp, like `ytsearch1:keywords`
+pub async fn ytdl_track(query: &str) -> Result<PlaylistItem, io::Error> {
+    let output = Command::new("yt-dlp")
+        .args(["-j", query, "--no-playlist"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // it's stopped if the resolution is canceled
+        .kill_on_drop(true)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "yt-dlp failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    // there's one line of json for each result
+    let entry = output
+        .stdout
+        .split(|byte| *byte == b'\n')
+        .find(|line| !line.is_empty())
+        .ok_or_else(|| io::Error::other(format!("no results found for '{}'", query)))?;
+    Ok(PlaylistItem::from(serde_json::from_slice::<Entry>(entry)?))
+}
+
+/// Extract the playlist, only the items in `range` are extracted if it's given
+pub async fn ytdl_playlist(
+    url: &Url,
+    range: Option<&PlaylistRange>,
+) -> Result<YouTubePlaylist, std::io::Error> {
+    let range = range.map(PlaylistRange::to_string);
+    let mut args = vec![url.as_str(), "-J"];
+    if let Some(range) = &range {
+        args.extend(["--playlist-items", range]);
+    }
+    // the flat entries of bandcamp and mixcloud have neither the durations nor the artworks, the
+    // albums are small enough to be fully extracted
+    if url.is_yt_playlist() {
+        args.push("--flat-playlist");
+    }
 
     let output = Command::new("yt-dlp")
         .args(args)
         .stdout(Stdio::piped())
+        // it's stopped if the resolution is canceled
+        .kill_on_drop(true)
         .output()
         .await?;
 